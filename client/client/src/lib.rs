@@ -33,7 +33,9 @@ use common::{
     },
     event::{EventBus, LocalEvent},
     grid::Grid,
+    guild::{GuildAction, GuildInfo},
     link::Is,
+    mail::{Mail, MailAction},
     mounting::Rider,
     outcome::Outcome,
     recipe::RecipeBook,
@@ -172,6 +174,10 @@ pub struct Client {
     pending_invites: HashSet<Uid>,
     // The pending trade the client is involved in, and it's id
     pending_trade: Option<(TradeId, PendingTrade, Option<SitePrices>)>,
+    // The contents of the character's mailbox, as last sent by the server
+    mailbox: Vec<Mail>,
+    // The character's guild, as last sent by the server
+    guild: Option<GuildInfo>,
 
     network: Option<Network>,
     participant: Option<Participant>,
@@ -613,6 +619,8 @@ impl Client {
             group_members: HashMap::new(),
             pending_invites: HashSet::new(),
             pending_trade: None,
+            mailbox: Vec::new(),
+            guild: None,
 
             network: Some(network),
             participant: Some(participant),
@@ -706,7 +714,9 @@ impl Client {
                     | ClientGeneral::RequestPlayerPhysics { .. }
                     | ClientGeneral::RequestLossyTerrainCompression { .. }
                     | ClientGeneral::AcknowledgePersistenceLoadError
-                    | ClientGeneral::UpdateMapMarker(_) => {
+                    | ClientGeneral::UpdateMapMarker(_)
+                    | ClientGeneral::MailAction(_)
+                    | ClientGeneral::GuildAction(_) => {
                         &mut self.in_game_stream
                     },
                     //Only in game, terrain
@@ -859,6 +869,20 @@ impl Client {
         }
     }
 
+    pub fn mailbox(&self) -> &[Mail] { &self.mailbox }
+
+    pub fn unread_mail_count(&self) -> usize { self.mailbox.iter().filter(|m| !m.read).count() }
+
+    pub fn perform_mail_action(&mut self, action: MailAction) {
+        self.send_msg(ClientGeneral::MailAction(action));
+    }
+
+    pub fn guild(&self) -> Option<&GuildInfo> { self.guild.as_ref() }
+
+    pub fn perform_guild_action(&mut self, action: GuildAction) {
+        self.send_msg(ClientGeneral::GuildAction(action));
+    }
+
     pub fn is_dead(&self) -> bool { self.current::<comp::Health>().map_or(false, |h| h.is_dead) }
 
     pub fn is_gliding(&self) -> bool {
@@ -1116,6 +1140,22 @@ impl Client {
 
     pub fn unmount(&mut self) { self.send_msg(ClientGeneral::ControlEvent(ControlEvent::Unmount)); }
 
+    pub fn command_pet(&mut self, pet: EcsEntity, command: comp::PetCommand) {
+        if let Some(uid) = self.state.read_component_copied(pet) {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::CommandPet(
+                uid, command,
+            )));
+        }
+    }
+
+    pub fn rename_pet(&mut self, pet: EcsEntity, name: String) {
+        if let Some(uid) = self.state.read_component_copied(pet) {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::RenamePet(
+                uid, name,
+            )));
+        }
+    }
+
     pub fn respawn(&mut self) {
         if self
             .state
@@ -1350,6 +1390,12 @@ impl Client {
         )));
     }
 
+    pub fn plant_seed(&mut self, pos: Vec3<i32>, seed: InvSlotId) {
+        self.control_action(ControlAction::InventoryAction(InventoryAction::Plant(
+            pos, seed,
+        )));
+    }
+
     pub fn change_ability(&mut self, slot: usize, new_ability: comp::ability::AuxiliaryAbility) {
         let auxiliary_key = self
             .inventories()
@@ -1993,6 +2039,12 @@ impl Client {
             ServerGeneral::MapMarker(event) => {
                 frontend_events.push(Event::MapMarker(event));
             },
+            ServerGeneral::MailboxUpdate(mailbox) => {
+                self.mailbox = mailbox;
+            },
+            ServerGeneral::GuildUpdate(guild) => {
+                self.guild = guild;
+            },
             _ => unreachable!("Not a in_game message"),
         }
         Ok(())
@@ -2219,6 +2271,9 @@ impl Client {
             .map(|u| u.into())
             .expect("Client doesn't have a Uid!!!");
 
+        self.mailbox.clear();
+        self.guild = None;
+
         // Clear ecs of all entities
         self.state.ecs_mut().delete_all();
         self.state.ecs_mut().maintain();
@@ -2317,6 +2372,7 @@ impl Client {
             comp::ChatType::CommandError => message.to_string(),
             comp::ChatType::CommandInfo => message.to_string(),
             comp::ChatType::FactionMeta(_) => message.to_string(),
+            comp::ChatType::GuildMeta(_) => message.to_string(),
             comp::ChatType::GroupMeta(_) => message.to_string(),
             comp::ChatType::Kill(kill_source, victim) => {
                 // Default message formats if no localized message string is set by hud
@@ -2429,6 +2485,7 @@ impl Client {
             comp::ChatType::Say(uid) => message_format(uid, message, None),
             comp::ChatType::Group(uid, s) => message_format(uid, message, Some(s)),
             comp::ChatType::Faction(uid, s) => message_format(uid, message, Some(s)),
+            comp::ChatType::Guild(uid, s) => message_format(uid, message, Some(s)),
             comp::ChatType::Region(uid) => message_format(uid, message, None),
             comp::ChatType::World(uid) => message_format(uid, message, None),
             // NPCs can't talk. Should be filtered by hud/mod.rs for voxygen and should be filtered
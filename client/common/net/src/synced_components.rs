@@ -56,12 +56,16 @@ macro_rules! synced_components {
             // remove it from that and then see if it's used for anything else and try to move
             // to only being synced for the client's entity.
             skill_set: SkillSet,
+            guild_tag: GuildTag,
+            pet: Pet,
+            pvp_engagement: PvpEngagement,
 
             // Synced to the client only for its own entity
 
             combo: Combo,
             active_abilities: ActiveAbilities,
             can_build: CanBuild,
+            statistics: Statistics,
         }
     };
 }
@@ -221,6 +225,18 @@ impl NetSync for SkillSet {
     const SYNC_FROM: SyncFrom = SyncFrom::AnyEntity;
 }
 
+impl NetSync for GuildTag {
+    const SYNC_FROM: SyncFrom = SyncFrom::AnyEntity;
+}
+
+impl NetSync for Pet {
+    const SYNC_FROM: SyncFrom = SyncFrom::AnyEntity;
+}
+
+impl NetSync for PvpEngagement {
+    const SYNC_FROM: SyncFrom = SyncFrom::AnyEntity;
+}
+
 // These are synced only from the client's own  entity.
 
 impl NetSync for Combo {
@@ -234,3 +250,7 @@ impl NetSync for ActiveAbilities {
 impl NetSync for CanBuild {
     const SYNC_FROM: SyncFrom = SyncFrom::ClientEntity;
 }
+
+impl NetSync for Statistics {
+    const SYNC_FROM: SyncFrom = SyncFrom::ClientEntity;
+}
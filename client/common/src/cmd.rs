@@ -45,6 +45,10 @@ pub enum ChatCommand {
     Airship,
     Alias,
     ApplyBuff,
+    ArenaAdd,
+    ArenaList,
+    ArenaRemove,
+    ArenaScores,
     Ban,
     BattleMode,
     BattleModeForce,
@@ -56,7 +60,10 @@ pub enum ChatCommand {
     DebugColumn,
     DisconnectAllPlayers,
     DropAll,
+    Duel,
+    DuelForfeit,
     Dummy,
+    Edit,
     Explosion,
     Faction,
     GiveItem,
@@ -66,6 +73,14 @@ pub enum ChatCommand {
     GroupKick,
     GroupLeave,
     GroupPromote,
+    Guild,
+    GuildCreate,
+    GuildDemote,
+    GuildDisband,
+    GuildInvite,
+    GuildKick,
+    GuildLeave,
+    GuildPromote,
     Health,
     Help,
     Home,
@@ -84,12 +99,14 @@ pub enum ChatCommand {
     Object,
     PermitBuild,
     Players,
+    Plot,
     Region,
     RemoveLights,
     RevokeBuild,
     RevokeBuildAll,
     Safezone,
     Say,
+    Schematic,
     ServerPhysics,
     SetMotd,
     Ship,
@@ -107,6 +124,7 @@ pub enum ChatCommand {
     Whitelist,
     Wiring,
     World,
+    WorldEvent,
     MakeVolume,
     Location,
     CreateLocation,
@@ -320,6 +338,35 @@ impl ChatCommand {
                 "Cast a buff on player",
                 Some(Admin),
             ),
+            ChatCommand::ArenaAdd => cmd(
+                vec![
+                    Any("name", Required),
+                    Integer("xlo", 0, Required),
+                    Integer("xhi", 10, Required),
+                    Integer("ylo", 0, Required),
+                    Integer("yhi", 10, Required),
+                    Integer("zlo", 0, Required),
+                    Integer("zhi", 10, Required),
+                    Integer("teams", 0, Optional),
+                ],
+                "Adds an arena in which PvP is always enabled, optionally splitting the players \
+                 in it into teams. Add it to the settings to keep it after a restart.",
+                Some(Admin),
+            ),
+            ChatCommand::ArenaList => cmd(vec![], "List all arenas", None),
+            ChatCommand::ArenaRemove => cmd(
+                vec![Any("name", Required)],
+                "Removes the specified arena",
+                Some(Admin),
+            ),
+            ChatCommand::ArenaScores => cmd(
+                vec![
+                    Any("name", Required),
+                    Enum("reset", vec!["reset".to_owned()], Optional),
+                ],
+                "Shows the scoreboard of an arena. Admins can reset it.",
+                None,
+            ),
             ChatCommand::Ban => cmd(
                 vec![
                     PlayerName(Required),
@@ -389,7 +436,37 @@ impl ChatCommand {
                 "Drops all your items on the ground",
                 Some(Moderator),
             ),
+            ChatCommand::Duel => cmd(
+                vec![PlayerName(Required)],
+                "Challenge a player to a duel",
+                None,
+            ),
+            ChatCommand::DuelForfeit => cmd(vec![], "Forfeit your current duel", None),
             ChatCommand::Dummy => cmd(vec![], "Spawns a training dummy", Some(Admin)),
+            ChatCommand::Edit => cmd(
+                vec![
+                    Enum(
+                        "action",
+                        vec![
+                            "copy".to_owned(),
+                            "fill".to_owned(),
+                            "hollow".to_owned(),
+                            "paste".to_owned(),
+                            "pos1".to_owned(),
+                            "pos2".to_owned(),
+                            "replace".to_owned(),
+                            "undo".to_owned(),
+                            "walls".to_owned(),
+                        ],
+                        Required,
+                    ),
+                    Message(Optional),
+                ],
+                "Edit the region of your build area between two corners. Usage: pos1/pos2 [x y \
+                 z], fill/hollow/walls <block> [r g b], replace <from block> <to block> [r g b], \
+                 copy, paste, undo",
+                None,
+            ),
             ChatCommand::Explosion => cmd(
                 vec![Float("radius", 5.0, Required)],
                 "Explodes the ground around you",
@@ -434,6 +511,34 @@ impl ChatCommand {
                 "Promote a player to group leader",
                 None,
             ),
+            ChatCommand::Guild => cmd(vec![Message(Optional)], "Send messages to your guild", None),
+            ChatCommand::GuildCreate => cmd(
+                vec![Any("tag", Required), Message(Required)],
+                "Found a guild with the given tag and name",
+                None,
+            ),
+            ChatCommand::GuildDemote => cmd(
+                vec![PlayerName(Required)],
+                "Demote a member of your guild",
+                None,
+            ),
+            ChatCommand::GuildDisband => cmd(vec![], "Disband the guild you lead", None),
+            ChatCommand::GuildInvite => cmd(
+                vec![PlayerName(Required)],
+                "Invite a player to join your guild",
+                None,
+            ),
+            ChatCommand::GuildKick => cmd(
+                vec![PlayerName(Required)],
+                "Remove a member from your guild",
+                None,
+            ),
+            ChatCommand::GuildLeave => cmd(vec![], "Leave your guild", None),
+            ChatCommand::GuildPromote => cmd(
+                vec![PlayerName(Required)],
+                "Promote a member of your guild, promoting an officer hands over leadership",
+                None,
+            ),
             ChatCommand::Health => cmd(
                 vec![Integer("hp", 100, Required)],
                 "Set your current health",
@@ -529,6 +634,20 @@ impl ChatCommand {
                 Some(Admin),
             ),
             ChatCommand::Players => cmd(vec![], "Lists players currently online", None),
+            ChatCommand::Plot => cmd(
+                vec![Enum(
+                    "action",
+                    vec![
+                        "buy".to_owned(),
+                        "info".to_owned(),
+                        "pay".to_owned(),
+                        "release".to_owned(),
+                    ],
+                    Optional,
+                )],
+                "Buy, inspect, pay rent for or release the town plot you are standing on",
+                None,
+            ),
             ChatCommand::RemoveLights => cmd(
                 vec![Float("radius", 20.0, Optional)],
                 "Removes all lights spawned by players",
@@ -559,6 +678,26 @@ impl ChatCommand {
                 "Send messages to everyone within shouting distance",
                 None,
             ),
+            ChatCommand::Schematic => cmd(
+                vec![
+                    Enum(
+                        "action",
+                        vec![
+                            "import".to_owned(),
+                            "list".to_owned(),
+                            "paste".to_owned(),
+                            "save".to_owned(),
+                        ],
+                        Required,
+                    ),
+                    Any("name", Optional),
+                    Message(Optional),
+                ],
+                "Save the blocks in part of your build area, paste them where you stand or import \
+                 a structure asset. Usage: save <name> <xlo> <xhi> <ylo> <yhi> <zlo> <zhi>, \
+                 paste <name> [rotation] [none/x/y], import <name> <structure group> [index]",
+                None,
+            ),
             ChatCommand::ServerPhysics => cmd(
                 vec![
                     PlayerName(Required),
@@ -649,6 +788,19 @@ impl ChatCommand {
                 "Send messages to everyone on the server",
                 None,
             ),
+            ChatCommand::WorldEvent => cmd(
+                vec![Enum(
+                    "kind",
+                    vec![
+                        "raid".to_owned(),
+                        "world_boss".to_owned(),
+                        "meteor".to_owned(),
+                    ],
+                    Optional,
+                )],
+                "Starts a world event of the given kind, or of a random one",
+                Some(Admin),
+            ),
             ChatCommand::MakeVolume => cmd(vec![], "Create a volume (experimental)", Some(Admin)),
             ChatCommand::Location => {
                 cmd(vec![Any("name", Required)], "Teleport to a location", None)
@@ -673,6 +825,10 @@ impl ChatCommand {
             ChatCommand::Airship => "airship",
             ChatCommand::Alias => "alias",
             ChatCommand::ApplyBuff => "buff",
+            ChatCommand::ArenaAdd => "arena_add",
+            ChatCommand::ArenaList => "arena_list",
+            ChatCommand::ArenaRemove => "arena_remove",
+            ChatCommand::ArenaScores => "arena_scores",
             ChatCommand::Ban => "ban",
             ChatCommand::BattleMode => "battlemode",
             ChatCommand::BattleModeForce => "battlemode_force",
@@ -684,7 +840,10 @@ impl ChatCommand {
             ChatCommand::DebugColumn => "debug_column",
             ChatCommand::DisconnectAllPlayers => "disconnect_all_players",
             ChatCommand::DropAll => "dropall",
+            ChatCommand::Duel => "duel",
+            ChatCommand::DuelForfeit => "forfeit",
             ChatCommand::Dummy => "dummy",
+            ChatCommand::Edit => "edit",
             ChatCommand::Explosion => "explosion",
             ChatCommand::Faction => "faction",
             ChatCommand::GiveItem => "give_item",
@@ -694,6 +853,14 @@ impl ChatCommand {
             ChatCommand::GroupKick => "group_kick",
             ChatCommand::GroupPromote => "group_promote",
             ChatCommand::GroupLeave => "group_leave",
+            ChatCommand::Guild => "guild",
+            ChatCommand::GuildCreate => "guild_create",
+            ChatCommand::GuildDemote => "guild_demote",
+            ChatCommand::GuildDisband => "guild_disband",
+            ChatCommand::GuildInvite => "guild_invite",
+            ChatCommand::GuildKick => "guild_kick",
+            ChatCommand::GuildLeave => "guild_leave",
+            ChatCommand::GuildPromote => "guild_promote",
             ChatCommand::Health => "health",
             ChatCommand::JoinFaction => "join_faction",
             ChatCommand::Help => "help",
@@ -712,12 +879,14 @@ impl ChatCommand {
             ChatCommand::Object => "object",
             ChatCommand::PermitBuild => "permit_build",
             ChatCommand::Players => "players",
+            ChatCommand::Plot => "plot",
             ChatCommand::Region => "region",
             ChatCommand::RemoveLights => "remove_lights",
             ChatCommand::RevokeBuild => "revoke_build",
             ChatCommand::RevokeBuildAll => "revoke_build_all",
             ChatCommand::Safezone => "safezone",
             ChatCommand::Say => "say",
+            ChatCommand::Schematic => "schematic",
             ChatCommand::ServerPhysics => "server_physics",
             ChatCommand::SetMotd => "set_motd",
             ChatCommand::Ship => "ship",
@@ -735,6 +904,7 @@ impl ChatCommand {
             ChatCommand::Wiring => "wiring",
             ChatCommand::Whitelist => "whitelist",
             ChatCommand::World => "world",
+            ChatCommand::WorldEvent => "world_event",
             ChatCommand::MakeVolume => "make_volume",
             ChatCommand::Location => "location",
             ChatCommand::CreateLocation => "create_location",
@@ -748,6 +918,7 @@ impl ChatCommand {
         Some(match self {
             ChatCommand::Faction => "f",
             ChatCommand::Group => "g",
+            ChatCommand::Guild => "gu",
             ChatCommand::Region => "r",
             ChatCommand::Say => "s",
             ChatCommand::Tell => "t",
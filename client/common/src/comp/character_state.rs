@@ -123,6 +123,8 @@ pub enum CharacterState {
     SpriteInteract(sprite_interact::Data),
     /// Runs on the wall
     Wallrun(wallrun::Data),
+    /// Casts a fishing line and waits for a fish to bite
+    Fishing(fishing::Data),
 }
 
 impl CharacterState {
@@ -147,6 +149,7 @@ impl CharacterState {
                 | CharacterState::Blink(_)
                 | CharacterState::BasicSummon(_)
                 | CharacterState::SpriteSummon(_)
+                | CharacterState::Fishing(_)
         )
     }
 
@@ -265,6 +268,7 @@ impl CharacterState {
                 | CharacterState::SpriteSummon(_)
                 | CharacterState::UseItem(_)
                 | CharacterState::SpriteInteract(_)
+                | CharacterState::Fishing(_)
         )
     }
 
@@ -329,6 +333,7 @@ impl CharacterState {
             CharacterState::SpriteSummon(data) => data.behavior(j, output_events),
             CharacterState::UseItem(data) => data.behavior(j, output_events),
             CharacterState::SpriteInteract(data) => data.behavior(j, output_events),
+            CharacterState::Fishing(data) => data.behavior(j, output_events),
         }
     }
 
@@ -375,6 +380,7 @@ impl CharacterState {
             CharacterState::SpriteSummon(data) => data.handle_event(j, output_events, action),
             CharacterState::UseItem(data) => data.handle_event(j, output_events, action),
             CharacterState::SpriteInteract(data) => data.handle_event(j, output_events, action),
+            CharacterState::Fishing(data) => data.handle_event(j, output_events, action),
         }
     }
 }
@@ -3,6 +3,7 @@ use crate::{
         ability,
         inventory::slot::{EquipSlot, InvSlotId, Slot},
         invite::{InviteKind, InviteResponse},
+        pet::PetCommand,
         BuffKind,
     },
     trade::{TradeAction, TradeId},
@@ -36,12 +37,15 @@ pub enum InventoryAction {
     Use(Slot),
     Sort,
     Collect(Vec3<i32>),
+    /// Plant the seed in the given slot in the tilled soil at the position
+    Plant(Vec3<i32>, InvSlotId),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum InventoryManip {
     Pickup(Uid),
     Collect(Vec3<i32>),
+    Plant(Vec3<i32>, InvSlotId),
     Use(Slot),
     Swap(Slot, Slot),
     SplitSwap(Slot, Slot),
@@ -63,6 +67,7 @@ impl From<InventoryAction> for InventoryManip {
             InventoryAction::Drop(equip) => Self::Drop(Slot::Equip(equip)),
             InventoryAction::Sort => Self::Sort,
             InventoryAction::Collect(collect) => Self::Collect(collect),
+            InventoryAction::Plant(pos, seed) => Self::Plant(pos, seed),
         }
     }
 }
@@ -141,6 +146,8 @@ pub enum ControlEvent {
         auxiliary_key: ability::AuxiliaryKey,
         new_ability: ability::AuxiliaryAbility,
     },
+    CommandPet(Uid, PetCommand),
+    RenamePet(Uid, String),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use specs::{Component, DerefFlaggedStorage};
+use specs_idvs::IdvStorage;
+
+/// The tag of the guild a character belongs to, shown on their nameplate
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GuildTag(pub String);
+
+impl Component for GuildTag {
+    type Storage = DerefFlaggedStorage<Self, IdvStorage<Self>>;
+}
@@ -1,3 +1,4 @@
+use crate::comp::buff::Resistances;
 use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, ops::Sub};
 
@@ -46,6 +47,9 @@ pub struct Stats {
     /// Stealth is summed along with the base stealth bonus (2.0), and then
     /// the agent's perception distance is divided by this value
     stealth: Option<f32>,
+    /// Resistances are summed along with those of the body, and then weaken
+    /// the buffs they apply to
+    resistances: Option<Resistances>,
 }
 
 impl Stats {
@@ -66,6 +70,7 @@ impl Stats {
             energy_reward,
             crit_power,
             stealth,
+            resistances: None,
         }
     }
 
@@ -80,6 +85,8 @@ impl Stats {
     pub fn crit_power(&self) -> Option<f32> { self.crit_power }
 
     pub fn stealth(&self) -> Option<f32> { self.stealth }
+
+    pub fn resistances(&self) -> Option<Resistances> { self.resistances }
 }
 
 impl Sub<Stats> for Stats {
@@ -99,6 +106,7 @@ impl Sub<Stats> for Stats {
                 .map(|(a, b)| a - b),
             crit_power: self.crit_power.zip(other.crit_power).map(|(a, b)| a - b),
             stealth: self.stealth.zip(other.stealth).map(|(a, b)| a - b),
+            resistances: self.resistances.zip(other.resistances).map(|(a, b)| a - b),
         }
     }
 }
@@ -159,6 +167,8 @@ impl Armor {
 
     pub fn stealth(&self) -> Option<f32> { self.stats.stealth }
 
+    pub fn resistances(&self) -> Option<Resistances> { self.stats.resistances }
+
     #[cfg(test)]
     pub fn test_armor(
         kind: ArmorKind,
@@ -174,6 +184,7 @@ impl Armor {
                 energy_reward: None,
                 crit_power: None,
                 stealth: None,
+                resistances: None,
             },
         }
     }
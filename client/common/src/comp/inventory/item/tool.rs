@@ -30,6 +30,7 @@ pub enum ToolKind {
     Debug,
     Farming,
     Pick,
+    Fishing,
     // npcs
     /// Intended for invisible weapons (e.g. a creature using its claws or
     /// biting)
@@ -55,6 +56,7 @@ impl ToolKind {
             ToolKind::Debug => "debug",
             ToolKind::Farming => "farming",
             ToolKind::Pick => "pickaxe",
+            ToolKind::Fishing => "fishing",
             ToolKind::Empty => "empty",
         }
     }
@@ -7,6 +7,8 @@ use instant::Instant;
 pub enum InviteKind {
     Group,
     Trade,
+    Guild,
+    Duel,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -66,4 +66,17 @@ pub enum MapMarkerUpdate {
     Owned(MapMarkerChange),
     GroupMember(Uid, MapMarkerChange),
     ClearGroup,
+    /// A world event started or ended, identified by the server
+    WorldEvent(u64, WorldEventKind, MapMarkerChange),
+}
+
+/// Events started by the server which everyone is invited to take part in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WorldEventKind {
+    /// Monsters attacking a town
+    Raid,
+    /// A powerful creature appearing at a point of interest
+    WorldBoss,
+    /// A meteor striking the ground, leaving a crater of velorite
+    Meteor,
 }
@@ -21,6 +21,7 @@ mod energy;
 
 pub mod fluid_dynamics;
 pub mod group;
+pub mod guild;
 mod health;
 mod inputs;
 
@@ -38,10 +39,12 @@ mod player;
 pub mod poise;
 
 pub mod projectile;
+pub mod pvp;
 
 pub mod shockwave;
 
 pub mod skillset;
+pub mod statistics;
 mod stats;
 
 pub mod visual;
@@ -79,6 +82,7 @@ pub use self::{
     energy::Energy,
     fluid_dynamics::Fluid,
     group::Group,
+    guild::GuildTag,
     inputs::CanBuild,
     inventory::{
         item::{
@@ -90,11 +94,13 @@ pub use self::{
         slot, Inventory, InventoryUpdate, InventoryUpdateEvent,
     },
     last::Last,
-    location::{MapMarker, MapMarkerChange, MapMarkerUpdate, Waypoint, WaypointArea},
+    location::{
+        MapMarker, MapMarkerChange, MapMarkerUpdate, Waypoint, WaypointArea, WorldEventKind,
+    },
     melee::{Melee, MeleeConstructor},
     misc::Object,
     ori::Ori,
-    pet::Pet,
+    pet::{Pet, PetCommand},
     phys::{
         Collider, Density, ForceUpdate, Immovable, Mass, PhysicsState, Pos, PosVelOriDefer,
         PreviousPhysCache, Scale, Sticky, Vel,
@@ -103,11 +109,13 @@ pub use self::{
     player::{AliasError, Player, MAX_ALIAS_LEN},
     poise::{Poise, PoiseChange, PoiseState},
     projectile::{Projectile, ProjectileConstructor},
+    pvp::PvpEngagement,
     shockwave::{Shockwave, ShockwaveHitEntities},
     skillset::{
         skills::{self, Skill},
         SkillGroup, SkillGroupKind, SkillSet,
     },
+    statistics::{Stat, Statistics},
     stats::{Stats, StatsModifier},
     visual::{LightAnimation, LightEmitter},
 };
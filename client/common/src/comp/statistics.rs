@@ -0,0 +1,117 @@
+use crate::combat::DamageSource;
+use hashbrown::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use specs::{Component, DerefFlaggedStorage};
+use specs_idvs::IdvStorage;
+
+/// A single statistic tracked for a character
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Stat {
+    /// Creatures killed, by the kind of body they had (e.g. `"Humanoid"`)
+    Kills(String),
+    /// Times the character died, by the cause of death (e.g. `"Falling"`)
+    Deaths(String),
+    /// Distance glided, in blocks
+    DistanceGlided,
+    ItemsCrafted,
+    DungeonsCleared,
+}
+
+impl Stat {
+    /// The string used to identify the statistic in the database
+    pub fn to_key(&self) -> String {
+        match self {
+            Stat::Kills(body) => format!("kills.{}", body),
+            Stat::Deaths(cause) => format!("deaths.{}", cause),
+            Stat::DistanceGlided => String::from("distance_glided"),
+            Stat::ItemsCrafted => String::from("items_crafted"),
+            Stat::DungeonsCleared => String::from("dungeons_cleared"),
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        if let Some(body) = key.strip_prefix("kills.") {
+            Some(Stat::Kills(body.to_owned()))
+        } else if let Some(cause) = key.strip_prefix("deaths.") {
+            Some(Stat::Deaths(cause.to_owned()))
+        } else {
+            match key {
+                "distance_glided" => Some(Stat::DistanceGlided),
+                "items_crafted" => Some(Stat::ItemsCrafted),
+                "dungeons_cleared" => Some(Stat::DungeonsCleared),
+                _ => None,
+            }
+        }
+    }
+
+    /// The name under which a death from the given source is recorded
+    pub fn death_cause(source: Option<DamageSource>) -> &'static str {
+        match source {
+            Some(DamageSource::Buff(_)) => "Buff",
+            Some(DamageSource::Melee) => "Melee",
+            Some(DamageSource::Projectile) => "Projectile",
+            Some(DamageSource::Explosion) => "Explosion",
+            Some(DamageSource::Falling) => "Falling",
+            Some(DamageSource::Shockwave) => "Shockwave",
+            Some(DamageSource::Energy) => "Energy",
+            Some(DamageSource::Other) | None => "Other",
+        }
+    }
+}
+
+/// Lifetime statistics of a character, along with the achievements they have
+/// unlocked
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    stats: HashMap<Stat, u64>,
+    achievements: HashSet<String>,
+}
+
+impl Statistics {
+    pub fn new(stats: HashMap<Stat, u64>, achievements: HashSet<String>) -> Self {
+        Self {
+            stats,
+            achievements,
+        }
+    }
+
+    pub fn get(&self, stat: &Stat) -> u64 { self.stats.get(stat).copied().unwrap_or(0) }
+
+    pub fn increment(&mut self, stat: Stat, amount: u64) {
+        let value = self.stats.entry(stat).or_insert(0);
+        *value = value.saturating_add(amount);
+    }
+
+    pub fn stats(&self) -> impl Iterator<Item = (&Stat, u64)> {
+        self.stats.iter().map(|(stat, value)| (stat, *value))
+    }
+
+    /// Total number of creatures killed, regardless of body type
+    pub fn total_kills(&self) -> u64 {
+        self.stats
+            .iter()
+            .filter(|(stat, _)| matches!(stat, Stat::Kills(_)))
+            .map(|(_, value)| *value)
+            .sum()
+    }
+
+    /// Total number of deaths, regardless of cause
+    pub fn total_deaths(&self) -> u64 {
+        self.stats
+            .iter()
+            .filter(|(stat, _)| matches!(stat, Stat::Deaths(_)))
+            .map(|(_, value)| *value)
+            .sum()
+    }
+
+    pub fn has_achievement(&self, id: &str) -> bool { self.achievements.contains(id) }
+
+    /// Marks an achievement as unlocked, returning false if it already was
+    pub fn unlock_achievement(&mut self, id: String) -> bool { self.achievements.insert(id) }
+
+    pub fn achievements(&self) -> impl Iterator<Item = &String> { self.achievements.iter() }
+}
+
+impl Component for Statistics {
+    type Storage = DerefFlaggedStorage<Self, IdvStorage<Self>>;
+}
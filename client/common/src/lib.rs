@@ -25,6 +25,8 @@ pub use uuid;
 // modules
 
 pub use common_assets as assets;
+
+pub mod achievement;
 pub mod astar;
 
 mod cached_spatial_grid;
@@ -48,9 +50,11 @@ pub mod figure;
 
 pub mod generation;
 pub mod grid;
+pub mod guild;
 pub mod link;
 
 pub mod lottery;
+pub mod mail;
 
 pub mod mounting;
 pub mod npc;
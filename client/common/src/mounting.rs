@@ -28,6 +28,24 @@ pub struct Mounting {
     pub rider: Uid,
 }
 
+/// How well a creature carries a rider, see [`comp::Body::mount_stats`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MountStats {
+    /// Multiplies the movement speed of the mount while it carries a rider
+    pub speed: f32,
+    /// Energy per second the mount spends moving with a rider. Once it runs out
+    /// of energy, it slows down to [`TIRED_MOUNT_SPEED`].
+    pub stamina_drain: f32,
+    /// The fraction of their maximum health a single hit has to deal to the
+    /// mount or its rider to throw the rider off
+    pub dismount_threshold: f32,
+}
+
+/// Speed multiplier of mounts which ran out of energy
+pub const TIRED_MOUNT_SPEED: f32 = 0.6;
+/// Mounts have to be this many times heavier than their rider to carry them
+pub const MOUNT_MASS_RATIO: f32 = 1.5;
+
 pub enum MountingError {
     NoSuchEntity,
     NotMountable,
@@ -142,3 +160,57 @@ impl Link for Mounting {
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        assets::AssetExt,
+        comp::{
+            body::{bird_large, humanoid, quadruped_medium},
+            Body, CharacterAbility, CharacterState,
+        },
+        states::idle,
+    };
+    use rand::thread_rng;
+
+    #[test]
+    fn mount_stats() {
+        let mut rng = thread_rng();
+        let mount_stats = |species| {
+            Body::QuadrupedMedium(quadruped_medium::Body::random_with(
+                &mut thread_rng(),
+                &species,
+            ))
+            .mount_stats()
+            .unwrap()
+        };
+        let horse = mount_stats(quadruped_medium::Species::Horse);
+        let donkey = mount_stats(quadruped_medium::Species::Donkey);
+        assert!(horse.speed > donkey.speed);
+        assert!(horse.stamina_drain > donkey.stamina_drain);
+
+        for species in bird_large::ALL_SPECIES {
+            let body = Body::BirdLarge(bird_large::Body::random_with(&mut rng, &species));
+            assert_eq!(
+                body.mount_stats().is_some(),
+                species == bird_large::Species::Cockatrice
+            );
+        }
+        assert!(Body::Humanoid(humanoid::Body::random())
+            .mount_stats()
+            .is_none());
+    }
+
+    #[test]
+    fn can_perform_mounted() {
+        let ability = |specifier| CharacterAbility::load_expect_cloned(specifier);
+        assert!(ability("common.abilities.bow.charged").can_perform_mounted());
+        assert!(ability("common.abilities.sword.triplestrike").can_perform_mounted());
+        assert!(!ability("common.abilities.sword.dash").can_perform_mounted());
+        assert!(!CharacterAbility::default_roll().can_perform_mounted());
+
+        assert!(CharacterState::Idle(idle::Data { is_sneaking: false }).can_perform_mounted());
+        assert!(CharacterState::Sit.can_perform_mounted());
+    }
+}
@@ -16,6 +16,27 @@ pub struct Time(pub f64);
 #[derive(Default)]
 pub struct DeltaTime(pub f32);
 
+/// A resource that seeds the random rolls of the simulation, such as critical
+/// hits and the decisions of NPCs. Servers pick a random seed when they start
+/// so that the rolls can't be predicted, while the combat simulation fixes it
+/// so that fights can be replayed.
+#[derive(Copy, Clone, Debug)]
+pub struct RngSeed(pub u64);
+
+impl Default for RngSeed {
+    fn default() -> Self { Self(rand::random()) }
+}
+
+impl RngSeed {
+    /// A generator for the rolls made at the given time by whatever `stream`
+    /// identifies, usually the uid of the entity making them
+    pub fn rng<R: rand::SeedableRng>(&self, time: Time, stream: u64) -> R {
+        R::seed_from_u64(
+            self.0 ^ time.0.to_bits().rotate_left(17) ^ stream.wrapping_mul(0x9e37_79b9_7f4a_7c15),
+        )
+    }
+}
+
 
 #[derive(Default)]
 pub struct EntitiesDiedLastTick(pub Vec<(Entity, Pos)>);
@@ -71,3 +92,21 @@ pub enum BattleMode {
     PvP,
     PvE,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng};
+
+    fn roll(seed: u64, time: f64, stream: u64) -> u64 {
+        RngSeed(seed).rng::<StdRng>(Time(time), stream).gen()
+    }
+
+    #[test]
+    fn test_rng_seed() {
+        assert_eq!(roll(1, 2.5, 3), roll(1, 2.5, 3));
+        assert_ne!(roll(1, 2.5, 3), roll(2, 2.5, 3));
+        assert_ne!(roll(1, 2.5, 3), roll(1, 2.55, 3));
+        assert_ne!(roll(1, 2.5, 3), roll(1, 2.5, 4));
+    }
+}
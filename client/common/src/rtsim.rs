@@ -41,6 +41,17 @@ pub enum MemoryItem {
     Mood { state: MoodState },
 }
 
+/// The parts of the daily routine of villagers
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Activity {
+    Sleep,
+    Work,
+    /// Meeting the other villagers at the plaza
+    Gather,
+    /// Spending the evening at home
+    Rest,
+}
+
 /// This type is the map route through which the rtsim (real-time simulation)
 /// aspect of the game communicates with the rest of the game. It is analagous
 /// to `comp::Controller` in that it provides a consistent interface for
@@ -58,6 +69,8 @@ pub struct RtSimController {
     pub travel_to: Option<(Vec3<f32>, String)>,
     /// Proportion of full speed to move
     pub speed_factor: f32,
+    /// What the entity is doing according to its daily routine, if it has one
+    pub activity: Option<Activity>,
     /// Events
     pub events: Vec<RtSimEvent>,
 }
@@ -67,6 +80,7 @@ impl Default for RtSimController {
         Self {
             travel_to: None,
             speed_factor: 1.0,
+            activity: None,
             events: Vec::new(),
         }
     }
@@ -79,6 +93,7 @@ impl RtSimController {
         Self {
             travel_to: Some((pos, format!("{:0.1?}", pos))),
             speed_factor: 0.25,
+            activity: None,
             events: Vec::new(),
         }
     }
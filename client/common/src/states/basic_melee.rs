@@ -92,7 +92,9 @@ impl CharacterBehavior for Data {
                                             self.static_data.ability_info.tool,
                                         )
                                     })
-                                    .filter(|(_, tool)| tool == &Some(ToolKind::Pick)),
+                                    .filter(|(_, tool)| {
+                                        matches!(tool, Some(ToolKind::Pick | ToolKind::Farming))
+                                    }),
                             ),
                     );
                 } else if self.timer < self.static_data.swing_duration {
@@ -11,7 +11,7 @@ use crate::{
     },
     util::Dir,
 };
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use instant::Duration;
 
@@ -85,6 +85,7 @@ impl CharacterBehavior for Data {
                         crit_mult,
                         buff_strength,
                     );
+                    let mut rng = data.rng();
                     // Shoots all projectiles simultaneously
                     for i in 0..self.static_data.num_projectiles {
                         // Gets offsets
@@ -93,7 +94,7 @@ impl CharacterBehavior for Data {
                         // Adds a slight spread to the projectiles. First projectile has no spread,
                         // and spread increases linearly with number of projectiles created.
                         let dir = Dir::from_unnormalized(data.inputs.look_dir.map(|x| {
-                            let offset = (2.0 * thread_rng().gen::<f32>() - 1.0)
+                            let offset = (2.0 * rng.gen::<f32>() - 1.0)
                                 * self.static_data.projectile_spread
                                 * i as f32;
                             x + offset
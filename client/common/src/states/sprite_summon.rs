@@ -10,7 +10,7 @@ use crate::{
     terrain::{Block, SpriteKind},
     vol::ReadVol,
 };
-use rand::{thread_rng, Rng};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use instant::Duration;
 use vek::*;
@@ -80,6 +80,7 @@ impl CharacterBehavior for Data {
                         + self.static_data.summon_distance.0;
                     let summon_distance = summon_distance.round() as i32;
 
+                    let mut rng = data.rng();
                     // Only summons sprites if summon distance is greater than achieved radius
                     for radius in self.achieved_radius..=summon_distance {
                         // 1 added to make range correct, too lazy to add 1 to both variables above
@@ -88,7 +89,7 @@ impl CharacterBehavior for Data {
                         let spiral = Spiral2d::with_edge_radius(radius);
                         for point in spiral {
                             // If square is not sparse, generate sprite
-                            if !thread_rng().gen_bool(self.static_data.sparseness) {
+                            if !rng.gen_bool(self.static_data.sparseness) {
                                 // The coordinates of where the sprite is created
                                 let sprite_pos = Vec3::new(
                                     data.pos.0.x.floor() as i32 + point.x,
@@ -4,7 +4,7 @@ use common::{
         aura::{AuraChange, AuraKey, AuraKind, AuraTarget},
         buff::{Buff, BuffCategory, BuffChange, BuffSource},
         group::Group,
-        Alignment, Aura, Auras, BuffKind, Buffs, CharacterState, Health, Player, Pos,
+        Alignment, Aura, Auras, BuffKind, Buffs, CharacterState, Health, Player, Pos, PvpEngagement,
     },
     event::{Emitter, EventBus, ServerEvent},
     resources::DeltaTime,
@@ -21,6 +21,7 @@ use instant::Duration;
 pub struct ReadData<'a> {
     entities: Entities<'a>,
     players: ReadStorage<'a, Player>,
+    pvp_engagements: ReadStorage<'a, PvpEngagement>,
     dt: Read<'a, DeltaTime>,
     server_bus: Read<'a, EventBus<ServerEvent>>,
     uid_allocator: Read<'a, UidAllocator>,
@@ -206,6 +207,7 @@ fn activate_aura(
                 combat::may_harm(
                     &read_data.alignments,
                     &read_data.players,
+                    &read_data.pvp_engagements,
                     &read_data.uid_allocator,
                     owner,
                     target,
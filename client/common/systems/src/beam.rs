@@ -3,18 +3,18 @@ use common::{
     comp::{
         agent::{Sound, SoundKind},
         Alignment, Beam, BeamSegment, Body, CharacterState, Combo, Energy, Group, Health,
-        Inventory, Ori, Player, Pos, Scale, Stats,
+        Inventory, Ori, Player, Pos, PvpEngagement, Scale, Stats,
     },
     event::{EventBus, ServerEvent},
     outcome::Outcome,
-    resources::{DeltaTime, Time},
+    resources::{DeltaTime, RngSeed, Time},
     terrain::TerrainGrid,
     uid::{Uid, UidAllocator},
     vol::ReadVol,
     GroupTarget,
 };
 use common_ecs::{Job, Origin, ParMode, Phase, System};
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, Rng};
 use rayon::iter::ParallelIterator;
 
 
@@ -29,8 +29,10 @@ use vek::*;
 pub struct ReadData<'a> {
     entities: Entities<'a>,
     players: ReadStorage<'a, Player>,
+    pvp_engagements: ReadStorage<'a, PvpEngagement>,
     server_bus: Read<'a, EventBus<ServerEvent>>,
     time: Read<'a, Time>,
+    rng_seed: Read<'a, RngSeed>,
     dt: Read<'a, DeltaTime>,
     terrain: ReadExpect<'a, TerrainGrid>,
     uid_allocator: Read<'a, UidAllocator>,
@@ -99,7 +101,9 @@ impl<'a> System<'a> for Sys {
                         read_data.uid_allocator.retrieve_entity_internal(uid.into())
                     });
 
-                    let mut rng = thread_rng();
+                    let mut rng: StdRng = read_data
+                        .rng_seed
+                        .rng(*read_data.time, u64::from(entity.id()));
                     if rng.gen_bool(0.005) {
                         server_events.push(ServerEvent::Sound {
                             sound: Sound::new(SoundKind::Beam, pos.0, 7.0, time),
@@ -235,6 +239,7 @@ impl<'a> System<'a> for Sys {
                             let may_harm = combat::may_harm(
                                 &read_data.alignments,
                                 &read_data.players,
+                                &read_data.pvp_engagements,
                                 &read_data.uid_allocator,
                                 beam_owner,
                                 target,
@@ -254,6 +259,7 @@ impl<'a> System<'a> for Sys {
                                 1.0,
                                 AttackSource::Beam,
                                 *read_data.time,
+                                &mut rng,
                                 |e| server_events.push(e),
                                 |o| outcomes.push(o),
                             );
@@ -4,7 +4,7 @@ use common::{
         body::{object, Body},
         buff::{
             Buff, BuffCategory, BuffChange, BuffData, BuffEffect, BuffId, BuffKind, BuffSource,
-            Buffs,
+            BuffStacking, Buffs, BUFF_MANIFEST,
         },
         fluid_dynamics::{Fluid, LiquidKind},
         Energy, Group, Health, HealthChange, Inventory, LightEmitter, ModifierKind, PhysicsState,
@@ -59,6 +59,7 @@ impl<'a> System<'a> for Sys {
     ) {
         let mut server_emitter = read_data.server_bus.emitter();
         let dt = read_data.dt.0;
+        let buff_manifest = BUFF_MANIFEST.read();
         // Set to false to avoid spamming server
         buffs.set_event_emission(false);
         stats.set_event_emission(false);
@@ -100,7 +101,8 @@ impl<'a> System<'a> for Sys {
                     // If on ensnaring vines, apply ensnared debuff
                     server_emitter.emit(ServerEvent::Buff {
                         entity,
-                        buff_change: BuffChange::Add(Buff::new(
+                        buff_change: BuffChange::Add(Buff::with_manifest(
+                            &buff_manifest,
                             BuffKind::Ensnared,
                             BuffData::new(1.0, Some(Duration::from_secs_f32(1.0))),
                             Vec::new(),
@@ -118,7 +120,8 @@ impl<'a> System<'a> for Sys {
                     // If in lava fluid, apply burning debuff
                     server_emitter.emit(ServerEvent::Buff {
                         entity,
-                        buff_change: BuffChange::Add(Buff::new(
+                        buff_change: BuffChange::Add(Buff::with_manifest(
+                            &buff_manifest,
                             BuffKind::Burning,
                             BuffData::new(20.0, None),
                             vec![BuffCategory::Natural],
@@ -131,13 +134,20 @@ impl<'a> System<'a> for Sys {
                         kind: LiquidKind::Water,
                         ..
                     })
-                ) && buff_comp.kinds.contains_key(&BuffKind::Burning)
-                {
-                    // If in water fluid and currently burning, remove burning debuffs
-                    server_emitter.emit(ServerEvent::Buff {
-                        entity,
-                        buff_change: BuffChange::RemoveByKind(BuffKind::Burning),
-                    });
+                ) {
+                    // If in water fluid, remove the debuffs that being wet removes, like burning
+                    if let Some(wet) = buff_manifest.get(BuffKind::Wet) {
+                        for kind in wet
+                            .removes
+                            .iter()
+                            .filter(|kind| buff_comp.kinds.contains_key(*kind))
+                        {
+                            server_emitter.emit(ServerEvent::Buff {
+                                entity,
+                                buff_change: BuffChange::RemoveByKind(*kind),
+                            });
+                        }
+                    }
                 }
             }
 
@@ -150,8 +160,12 @@ impl<'a> System<'a> for Sys {
             // For each buff kind present on entity, if the buff kind queues, only ticks
             // duration of strongest buff of that kind, else it ticks durations of all buffs
             // of that kind. Any buffs whose durations expire are marked expired.
-            for (kind, ids) in buff_comp_kinds.iter() {
-                if kind.queues() {
+            for ids in buff_comp_kinds.values() {
+                let queues = ids
+                    .get(0)
+                    .and_then(|id| buff_comp_buffs.get(id))
+                    .map_or(false, |buff| matches!(buff.stacking, BuffStacking::Queue));
+                if queues {
                     if let Some((Some(buff), id)) =
                         ids.get(0).map(|id| (buff_comp_buffs.get_mut(id), id))
                     {
@@ -183,11 +197,30 @@ impl<'a> System<'a> for Sys {
             // Call to reset stats to base values
             stat.reset_temp_modifiers();
 
-            // Iterator over the lists of buffs by kind
+            // Iterator over the buffs which have an effect, the strongest of each buff kind
+            // or all of them for kinds which stack independently
             let buff_comp = &mut *buff_comp;
-            for buff_ids in buff_comp.kinds.values() {
-                // Get the strongest of this buff kind
-                if let Some(buff) = buff_comp.buffs.get_mut(&buff_ids[0]) {
+            let buffs = &buff_comp.buffs;
+            let active_ids = buff_comp
+                .kinds
+                .values()
+                .flat_map(|buff_ids| {
+                    let independent = buff_ids
+                        .get(0)
+                        .and_then(|id| buffs.get(id))
+                        .map_or(false, |buff| {
+                            matches!(buff.stacking, BuffStacking::Independent)
+                        });
+                    if independent {
+                        &buff_ids[..]
+                    } else {
+                        &buff_ids[..1]
+                    }
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            for buff_id in &active_ids {
+                if let Some(buff) = buff_comp.buffs.get_mut(buff_id) {
                     // Get buff owner?
                     let buff_owner = if let BuffSource::Character { by: owner } = buff.source {
                         Some(owner)
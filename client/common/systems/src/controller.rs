@@ -115,6 +115,30 @@ impl<'a> System<'a> for Sys {
                             new_ability,
                         });
                     },
+                    ControlEvent::CommandPet(pet_uid, command) => {
+                        if let Some(pet_entity) = read_data
+                            .uid_allocator
+                            .retrieve_entity_internal(pet_uid.id())
+                        {
+                            server_emitter.emit(ServerEvent::CommandPet {
+                                owner_entity: entity,
+                                pet_entity,
+                                command,
+                            });
+                        }
+                    },
+                    ControlEvent::RenamePet(pet_uid, name) => {
+                        if let Some(pet_entity) = read_data
+                            .uid_allocator
+                            .retrieve_entity_internal(pet_uid.id())
+                        {
+                            server_emitter.emit(ServerEvent::RenamePet {
+                                owner_entity: entity,
+                                pet_entity,
+                                name,
+                            });
+                        }
+                    },
                 }
             }
         }
@@ -3,17 +3,17 @@ use common::{
     comp::{
         agent::{Sound, SoundKind},
         Alignment, Body, CharacterState, Combo, Energy, Group, Health, Inventory, Ori,
-        PhysicsState, Player, Pos, Scale, Shockwave, ShockwaveHitEntities, Stats,
+        PhysicsState, Player, Pos, PvpEngagement, Scale, Shockwave, ShockwaveHitEntities, Stats,
     },
     event::{EventBus, ServerEvent},
     outcome::Outcome,
-    resources::{DeltaTime, Time},
+    resources::{DeltaTime, RngSeed, Time},
     uid::{Uid, UidAllocator},
     util::Dir,
     GroupTarget,
 };
 use common_ecs::{Job, Origin, Phase, System};
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, Rng};
 use specs::{
     saveload::MarkerAllocator, shred::ResourceId, Entities, Join, Read, ReadStorage, SystemData,
     World, Write, WriteStorage,
@@ -25,7 +25,9 @@ pub struct ReadData<'a> {
     entities: Entities<'a>,
     server_bus: Read<'a, EventBus<ServerEvent>>,
     time: Read<'a, Time>,
+    rng_seed: Read<'a, RngSeed>,
     players: ReadStorage<'a, Player>,
+    pvp_engagements: ReadStorage<'a, PvpEngagement>,
     dt: Read<'a, DeltaTime>,
     uid_allocator: Read<'a, UidAllocator>,
     uids: ReadStorage<'a, Uid>,
@@ -91,7 +93,9 @@ impl<'a> System<'a> for Sys {
                 .owner
                 .and_then(|uid| read_data.uid_allocator.retrieve_entity_internal(uid.into()));
 
-            let mut rng = thread_rng();
+            let mut rng: StdRng = read_data
+                .rng_seed
+                .rng(*read_data.time, u64::from(entity.id()));
             if rng.gen_bool(0.05) {
                 server_emitter.emit(ServerEvent::Sound {
                     sound: Sound::new(SoundKind::Shockwave, pos.0, 16.0, time),
@@ -214,6 +218,7 @@ impl<'a> System<'a> for Sys {
                     let may_harm = combat::may_harm(
                         &read_data.alignments,
                         &read_data.players,
+                        &read_data.pvp_engagements,
                         &read_data.uid_allocator,
                         shockwave_owner,
                         target,
@@ -233,6 +238,7 @@ impl<'a> System<'a> for Sys {
                         1.0,
                         AttackSource::Shockwave,
                         *read_data.time,
+                        &mut rng,
                         |e| server_emitter.emit(e),
                         |o| outcomes.push(o),
                     );
@@ -3,8 +3,8 @@ use common::{
     comp::{
         self,
         skills::{GeneralSkill, Skill},
-        Body, CharacterState, Combo, Energy, Health, Inventory, Poise, PoiseChange, Pos, SkillSet,
-        Stats, StatsModifier,
+        Body, CharacterState, Combo, Energy, Health, Inventory, Pet, Poise, PoiseChange, Pos,
+        SkillSet, Stats, StatsModifier,
     },
     event::{EventBus, ServerEvent},
     resources::{DeltaTime, EntitiesDiedLastTick, Time},
@@ -42,6 +42,7 @@ impl<'a> System<'a> for Sys {
         WriteStorage<'a, Poise>,
         WriteStorage<'a, Energy>,
         WriteStorage<'a, Combo>,
+        WriteStorage<'a, Pet>,
         Write<'a, EntitiesDiedLastTick>,
     );
 
@@ -59,6 +60,7 @@ impl<'a> System<'a> for Sys {
             mut poises,
             mut energies,
             mut combos,
+            mut pets,
             mut entities_died_last_tick,
         ): Self::SystemData,
     ) {
@@ -136,6 +138,17 @@ impl<'a> System<'a> for Sys {
             }
         }
 
+        // Scale the health and energy of pets with their level
+        for (mut pet, mut health, mut energy, body) in
+            (&mut pets, &mut healths, &mut energies, &read_data.bodies).join()
+        {
+            if pet.modify_stats {
+                health.update_max_hp(*body, pet.level());
+                energy.update_max_energy(*body, pet.level());
+                pet.modify_stats = false;
+            }
+        }
+
         // Update energies and poises
         for (character_state, mut energy, mut poise) in
             (&read_data.char_states, &mut energies, &mut poises).join()
@@ -150,6 +163,7 @@ impl<'a> System<'a> for Sys {
                 | CharacterState::GlideWield { .. }
                 | CharacterState::Wielding { .. }
                 | CharacterState::Equipping { .. }
+                | CharacterState::Fishing { .. }
                 | CharacterState::Boost { .. } => {
                     let res = { energy.current() < energy.maximum() };
 
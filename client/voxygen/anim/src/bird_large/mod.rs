@@ -96,15 +96,22 @@ impl Skeleton for BirdLargeSkeleton {
             make_bone(foot_l_mat),
             make_bone(foot_r_mat),
         ];
+
+        // Offset from the mounted bone's origin.
+        // Note: This could be its own bone if we need to animate it independently.
+        let mount_position = (chest_mat * Vec4::from_point(mount_point(&body)))
+            .homogenized()
+            .xyz();
+        // NOTE: We apply the ori from base_mat externally so we don't need to worry
+        // about it here for now.
+        let mount_orientation = self.chest.orientation;
+
         Offsets {
             lantern: None,
-            // TODO: see quadruped_medium for how to animate this
             mount_bone: Transform {
-                position: common::comp::Body::BirdLarge(body)
-                    .mount_offset()
-                    .into_tuple()
-                    .into(),
-                ..Default::default()
+                position: mount_position,
+                orientation: mount_orientation,
+                scale: Vec3::one(),
             },
         }
     }
@@ -245,3 +252,14 @@ impl<'a> From<&'a Body> for SkeletonAttr {
         }
     }
 }
+
+fn mount_point(body: &Body) -> Vec3<f32> {
+    use comp::bird_large::Species::*;
+    match (body.species, body.body_type) {
+        (Phoenix, _) => (0.0, -4.0, 8.0),
+        (Cockatrice, _) => (0.0, -5.0, 7.0),
+        (Roc, _) => (0.0, -6.0, 9.0),
+        (FlameWyvern, _) => (0.0, -4.0, 8.0),
+    }
+    .into()
+}
@@ -0,0 +1,72 @@
+use super::{
+    super::{vek::*, Animation},
+    CharacterSkeleton, SkeletonAttr,
+};
+use common::states::utils::StageSection;
+use core::f32::consts::PI;
+
+pub struct FishingAnimation;
+
+type FishingAnimationDependency = (Option<StageSection>, bool, f32);
+
+impl Animation for FishingAnimation {
+    type Dependency<'a> = FishingAnimationDependency;
+    type Skeleton = CharacterSkeleton;
+
+    #[cfg(feature = "use-dyn-lib")]
+    const UPDATE_FN: &'static [u8] = b"character_fishing\0";
+
+    #[cfg_attr(feature = "be-dyn-lib", export_name = "character_fishing")]
+    fn update_skeleton_inner<'a>(
+        skeleton: &Self::Skeleton,
+        (stage_section, is_biting, global_time): Self::Dependency<'a>,
+        anim_time: f32,
+        rate: &mut f32,
+        s_a: &SkeletonAttr,
+    ) -> Self::Skeleton {
+        *rate = 1.0;
+        let mut next = (*skeleton).clone();
+
+        // Rod is raised behind the head, whipped forward to cast, then held out
+        // over the water until it is reeled back in
+        let (move1, move2, move3) = match stage_section {
+            Some(StageSection::Buildup) => (anim_time.powf(0.5), 0.0, 0.0),
+            Some(StageSection::Action) => (1.0, anim_time, 0.0),
+            Some(StageSection::Recover) => (1.0, 0.0, anim_time.powf(0.25)),
+            _ => (0.0, 0.0, 0.0),
+        };
+        let windup = (move1 * PI).sin();
+        let pullback = 1.0 - move3;
+        let bob = (move2 * 2.0).sin() * 0.03;
+        let tug = if is_biting {
+            (global_time * 35.0).sin() * 0.08
+        } else {
+            0.0
+        };
+
+        next.head.orientation = Quaternion::rotation_x(-0.2 + move1 * pullback * 0.3);
+
+        next.chest.position = Vec3::new(0.0, s_a.chest.0, s_a.chest.1);
+        next.chest.orientation = Quaternion::rotation_x(windup * 0.2 - move3 * 0.1)
+            * Quaternion::rotation_z(windup * 0.3);
+
+        next.hand_l.position = Vec3::new(9.0, 1.0, 1.0);
+        next.hand_l.orientation = Quaternion::rotation_x(PI / 2.0);
+        next.hand_r.position = Vec3::new(9.0, 1.0, 11.0);
+        next.hand_r.orientation = Quaternion::rotation_x(PI / 2.0);
+        next.main.position = Vec3::new(7.5, 7.5, 13.2);
+        next.main.orientation = Quaternion::rotation_y(PI);
+
+        next.control.position = Vec3::new(
+            -11.0 + move1 * pullback * 3.0,
+            1.8 + move1 * pullback * 6.0,
+            4.0 + windup * 6.0 + move3 * 4.0,
+        );
+        next.control.orientation =
+            Quaternion::rotation_x(windup * 1.2 - move1 * pullback * 0.9 + bob + tug + move3 * 0.6)
+                * Quaternion::rotation_y(0.6 - move1 * pullback * 0.4)
+                * Quaternion::rotation_z(tug * 0.5);
+
+        next
+    }
+}
@@ -9,6 +9,7 @@ pub mod consume;
 pub mod dance;
 pub mod dash;
 pub mod equip;
+pub mod fishing;
 pub mod glidewield;
 pub mod gliding;
 pub mod idle;
@@ -40,8 +41,8 @@ pub use self::{
     alpha::AlphaAnimation, beam::BeamAnimation, beta::BetaAnimation, block::BlockAnimation,
     chargeswing::ChargeswingAnimation, climb::ClimbAnimation, collect::CollectAnimation,
     consume::ConsumeAnimation, dance::DanceAnimation, dash::DashAnimation, equip::EquipAnimation,
-    glidewield::GlideWieldAnimation, gliding::GlidingAnimation, idle::IdleAnimation,
-    jump::JumpAnimation, leapmelee::LeapAnimation, mount::MountAnimation,
+    fishing::FishingAnimation, glidewield::GlideWieldAnimation, gliding::GlidingAnimation,
+    idle::IdleAnimation, jump::JumpAnimation, leapmelee::LeapAnimation, mount::MountAnimation,
     repeater::RepeaterAnimation, roll::RollAnimation, run::RunAnimation,
     shockwave::ShockwaveAnimation, shoot::ShootAnimation, sit::SitAnimation, sneak::SneakAnimation,
     sneakequip::SneakEquipAnimation, sneakwield::SneakWieldAnimation, spin::SpinAnimation,
@@ -289,7 +289,7 @@ impl Animation for SneakWieldAnimation {
                     next.main.position = Vec3::new(-5.0, 5.0, 23.0);
                     next.main.orientation = Quaternion::rotation_x(PI);
                 },
-                Some(ToolKind::Farming) => {
+                Some(ToolKind::Farming) | Some(ToolKind::Fishing) => {
                     if speed < 0.5 {
                         next.head.orientation = Quaternion::rotation_z(head_look.x)
                             * Quaternion::rotation_x(-0.2 + head_look.y.abs());
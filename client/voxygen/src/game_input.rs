@@ -95,6 +95,14 @@ pub enum GameInput {
     Trade,
     #[strum(serialize = "gameinput.social")]
     Social,
+    #[strum(serialize = "gameinput.mail")]
+    Mail,
+    #[strum(serialize = "gameinput.achievements")]
+    Achievements,
+    #[strum(serialize = "gameinput.guild")]
+    Guild,
+    #[strum(serialize = "gameinput.pets")]
+    Pets,
     #[strum(serialize = "gameinput.crafting")]
     Crafting,
     #[strum(serialize = "gameinput.spellbook")]
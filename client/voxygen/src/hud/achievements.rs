@@ -0,0 +1,317 @@
+use super::{img_ids::Imgs, TEXT_COLOR, TEXT_GRAY_COLOR, UI_HIGHLIGHT_0, UI_MAIN};
+use crate::ui::fonts::Fonts;
+use client::{self, Client};
+use common::{
+    achievement::default_achievement_manifest,
+    comp::{Stat, Statistics},
+};
+use conrod_core::{
+    color,
+    widget::{self, Button, Image, Rectangle, Scrollbar, Text},
+    widget_ids, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
+};
+use i18n::Localization;
+
+/// Colour of achievements that have been unlocked
+const UNLOCKED_COLOR: color::Color = color::Color::Rgba(0.98, 0.76, 0.19, 1.0);
+
+widget_ids! {
+    pub struct Ids {
+        frame,
+        close,
+        title_align,
+        title,
+        bg,
+        icon,
+        achievements_tab,
+        statistics_tab,
+        list_align,
+        list_scrollbar,
+        entry_titles[],
+        entry_descriptions[],
+        entry_progress[],
+        empty_txt,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AchievementsTab {
+    Achievements,
+    Statistics,
+}
+
+pub struct State {
+    ids: Ids,
+    tab: AchievementsTab,
+}
+
+#[derive(WidgetCommon)]
+pub struct Achievements<'a> {
+    client: &'a Client,
+    imgs: &'a Imgs,
+    fonts: &'a Fonts,
+    localized_strings: &'a Localization,
+
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+}
+
+impl<'a> Achievements<'a> {
+    pub fn new(
+        client: &'a Client,
+        imgs: &'a Imgs,
+        fonts: &'a Fonts,
+        localized_strings: &'a Localization,
+    ) -> Self {
+        Self {
+            client,
+            imgs,
+            fonts,
+            localized_strings,
+            common: widget::CommonBuilder::default(),
+        }
+    }
+}
+
+pub enum Event {
+    Close,
+}
+
+/// A single line in the achievements window
+struct Entry {
+    title: String,
+    description: String,
+    progress: String,
+    highlighted: bool,
+}
+
+impl<'a> Achievements<'a> {
+    fn stat_name(&self, stat: &Stat) -> String {
+        let i18n = self.localized_strings;
+        match stat {
+            Stat::Kills(body) => format!("{} ({})", i18n.get("hud.achievements.kills"), body),
+            Stat::Deaths(cause) => format!("{} ({})", i18n.get("hud.achievements.deaths"), cause),
+            Stat::DistanceGlided => i18n.get("hud.achievements.distance_glided").to_owned(),
+            Stat::ItemsCrafted => i18n.get("hud.achievements.items_crafted").to_owned(),
+            Stat::DungeonsCleared => i18n.get("hud.achievements.dungeons_cleared").to_owned(),
+        }
+    }
+
+    fn achievement_entries(&self, statistics: &Statistics) -> Vec<Entry> {
+        let i18n = self.localized_strings;
+        let manifest = default_achievement_manifest();
+        let manifest = manifest.read();
+        let mut entries = manifest
+            .iter()
+            .map(|achievement| {
+                let unlocked = statistics.has_achievement(&achievement.id);
+                let (current, target) = achievement.requirement.progress(statistics);
+                Entry {
+                    title: achievement.title.clone(),
+                    description: achievement.description.clone(),
+                    progress: if unlocked {
+                        i18n.get("hud.achievements.unlocked").to_owned()
+                    } else {
+                        format!("{} / {}", current.min(target), target)
+                    },
+                    highlighted: unlocked,
+                }
+            })
+            .collect::<Vec<_>>();
+        // Show unlocked achievements first
+        entries.sort_by_key(|entry| !entry.highlighted);
+        entries
+    }
+
+    fn statistic_entries(&self, statistics: &Statistics) -> Vec<Entry> {
+        let mut entries = statistics
+            .stats()
+            .map(|(stat, value)| Entry {
+                title: self.stat_name(stat),
+                description: String::new(),
+                progress: value.to_string(),
+                highlighted: false,
+            })
+            .collect::<Vec<_>>();
+        entries.sort_by(|a, b| a.title.cmp(&b.title));
+        entries
+    }
+}
+
+impl<'a> Widget for Achievements<'a> {
+    type Event = Vec<Event>;
+    type State = State;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        Self::State {
+            ids: Ids::new(id_gen),
+            tab: AchievementsTab::Achievements,
+        }
+    }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { state, ui, .. } = args;
+        let mut events = Vec::new();
+        let i18n = self.localized_strings;
+
+        // Window BG
+        Image::new(self.imgs.social_bg_on)
+            .bottom_left_with_margins_on(ui.window, 308.0, 25.0)
+            .color(Some(UI_MAIN))
+            .w_h(280.0, 460.0)
+            .set(state.ids.bg, ui);
+        // Window frame
+        Image::new(self.imgs.social_frame_on)
+            .middle_of(state.ids.bg)
+            .color(Some(UI_HIGHLIGHT_0))
+            .w_h(280.0, 460.0)
+            .set(state.ids.frame, ui);
+
+        // Icon
+        Image::new(self.imgs.social)
+            .w_h(30.0, 30.0)
+            .top_left_with_margins_on(state.ids.frame, 6.0, 6.0)
+            .set(state.ids.icon, ui);
+        // X-Button
+        if Button::image(self.imgs.close_button)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_button_hover)
+            .press_image(self.imgs.close_button_press)
+            .top_right_with_margins_on(state.ids.frame, 0.0, 0.0)
+            .set(state.ids.close, ui)
+            .was_clicked()
+        {
+            events.push(Event::Close);
+        }
+
+        // Title
+        Rectangle::fill_with([212.0, 42.0], color::TRANSPARENT)
+            .top_left_with_margins_on(state.ids.frame, 2.0, 44.0)
+            .set(state.ids.title_align, ui);
+        Text::new(i18n.get("hud.achievements"))
+            .middle_of(state.ids.title_align)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(TEXT_COLOR)
+            .set(state.ids.title, ui);
+
+        // Tabs
+        for (tab, id, label, left) in [
+            (
+                AchievementsTab::Achievements,
+                state.ids.achievements_tab,
+                "hud.achievements",
+                true,
+            ),
+            (
+                AchievementsTab::Statistics,
+                state.ids.statistics_tab,
+                "hud.achievements.statistics",
+                false,
+            ),
+        ] {
+            let button = Button::image(self.imgs.button)
+                .w_h(106.0, 26.0)
+                .hover_image(self.imgs.button_hover)
+                .press_image(self.imgs.button_press)
+                .label(i18n.get(label))
+                .label_y(conrod_core::position::Relative::Scalar(3.0))
+                .label_color(if state.tab == tab {
+                    TEXT_COLOR
+                } else {
+                    TEXT_GRAY_COLOR
+                })
+                .label_font_size(self.fonts.cyri.scale(15))
+                .label_font_id(self.fonts.cyri.conrod_id);
+            let button = if left {
+                button.top_left_with_margins_on(state.ids.frame, 48.0, 7.0)
+            } else {
+                button.top_right_with_margins_on(state.ids.frame, 48.0, 7.0)
+            };
+            if button.set(id, ui).was_clicked() {
+                state.update(|s| s.tab = tab);
+            }
+        }
+
+        let statistics = self.client.current::<Statistics>().unwrap_or_default();
+        let entries = match state.tab {
+            AchievementsTab::Achievements => self.achievement_entries(&statistics),
+            AchievementsTab::Statistics => self.statistic_entries(&statistics),
+        };
+
+        // List
+        Rectangle::fill_with([270.0, 370.0], color::TRANSPARENT)
+            .mid_top_with_margin_on(state.ids.frame, 80.0)
+            .scroll_kids_vertically()
+            .set(state.ids.list_align, ui);
+        Scrollbar::y_axis(state.ids.list_align)
+            .thickness(4.0)
+            .color(color::rgba(0.79, 1.09, 1.09, 0.0))
+            .set(state.ids.list_scrollbar, ui);
+
+        if entries.is_empty() {
+            Text::new(i18n.get("hud.achievements.no_statistics"))
+                .mid_top_with_margin_on(state.ids.list_align, 10.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(TEXT_GRAY_COLOR)
+                .set(state.ids.empty_txt, ui);
+        }
+
+        if state.ids.entry_titles.len() < entries.len() {
+            state.update(|s| {
+                let mut id_gen = ui.widget_id_generator();
+                s.ids.entry_titles.resize(entries.len(), &mut id_gen);
+                s.ids.entry_descriptions.resize(entries.len(), &mut id_gen);
+                s.ids.entry_progress.resize(entries.len(), &mut id_gen);
+            });
+        }
+
+        for (i, entry) in entries.iter().enumerate() {
+            let title = Text::new(&entry.title)
+                .w(180.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(15))
+                .color(if entry.highlighted {
+                    UNLOCKED_COLOR
+                } else {
+                    TEXT_COLOR
+                });
+            let title = if i == 0 {
+                title.top_left_with_margins_on(state.ids.list_align, 4.0, 6.0)
+            } else if entries[i - 1].description.is_empty() {
+                title.down_from(state.ids.entry_titles[i - 1], 6.0)
+            } else {
+                title.down_from(state.ids.entry_descriptions[i - 1], 8.0)
+            };
+            title.set(state.ids.entry_titles[i], ui);
+
+            Text::new(&entry.progress)
+                .top_right_with_margins_on(state.ids.list_align, 0.0, 10.0)
+                .y_relative_to(state.ids.entry_titles[i], 0.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(if entry.highlighted {
+                    UNLOCKED_COLOR
+                } else {
+                    TEXT_GRAY_COLOR
+                })
+                .set(state.ids.entry_progress[i], ui);
+
+            if !entry.description.is_empty() {
+                Text::new(&entry.description)
+                    .down_from(state.ids.entry_titles[i], 2.0)
+                    .w(250.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(12))
+                    .color(TEXT_GRAY_COLOR)
+                    .set(state.ids.entry_descriptions[i], ui);
+            }
+        }
+
+        events
+    }
+}
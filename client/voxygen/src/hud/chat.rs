@@ -1,6 +1,7 @@
 use super::{
-    img_ids::Imgs, ChatTab, ERROR_COLOR, FACTION_COLOR, GROUP_COLOR, INFO_COLOR, KILL_COLOR,
-    OFFLINE_COLOR, ONLINE_COLOR, REGION_COLOR, SAY_COLOR, TELL_COLOR, TEXT_COLOR, WORLD_COLOR,
+    img_ids::Imgs, ChatTab, ERROR_COLOR, FACTION_COLOR, GROUP_COLOR, GUILD_COLOR, INFO_COLOR,
+    KILL_COLOR, OFFLINE_COLOR, ONLINE_COLOR, REGION_COLOR, SAY_COLOR, TELL_COLOR, TEXT_COLOR,
+    WORLD_COLOR,
 };
 use crate::{settings::chat::MAX_CHAT_TABS, ui::fonts::Fonts, GlobalState};
 use client::{cmd, Client};
@@ -732,6 +733,7 @@ fn render_chat_mode(chat_mode: &ChatMode, imgs: &Imgs) -> (Color, conrod_core::i
         ChatMode::Say => (SAY_COLOR, imgs.chat_say_small),
         ChatMode::Region => (REGION_COLOR, imgs.chat_region_small),
         ChatMode::Faction(_) => (FACTION_COLOR, imgs.chat_faction_small),
+        ChatMode::Guild(_) => (GUILD_COLOR, imgs.chat_faction_small),
         ChatMode::Group(_) => (GROUP_COLOR, imgs.chat_group_small),
         ChatMode::Tell(_) => (TELL_COLOR, imgs.chat_tell_small),
     }
@@ -746,11 +748,13 @@ fn render_chat_line(chat_type: &ChatType<String>, imgs: &Imgs) -> (Color, conrod
         ChatType::CommandInfo => (INFO_COLOR, imgs.chat_command_info_small),
         ChatType::GroupMeta(_) => (GROUP_COLOR, imgs.chat_group_small),
         ChatType::FactionMeta(_) => (FACTION_COLOR, imgs.chat_faction_small),
+        ChatType::GuildMeta(_) => (GUILD_COLOR, imgs.chat_faction_small),
         ChatType::Kill(_, _) => (KILL_COLOR, imgs.chat_kill_small),
         ChatType::Tell(_from, _to) => (TELL_COLOR, imgs.chat_tell_small),
         ChatType::Say(_uid) => (SAY_COLOR, imgs.chat_say_small),
         ChatType::Group(_uid, _s) => (GROUP_COLOR, imgs.chat_group_small),
         ChatType::Faction(_uid, _s) => (FACTION_COLOR, imgs.chat_faction_small),
+        ChatType::Guild(_uid, _s) => (GUILD_COLOR, imgs.chat_faction_small),
         ChatType::Region(_uid) => (REGION_COLOR, imgs.chat_region_small),
         ChatType::World(_uid) => (WORLD_COLOR, imgs.chat_world_small),
         ChatType::Npc(_uid, _r) => panic!("NPCs can't talk!"), // Should be filtered by hud/mod.rs
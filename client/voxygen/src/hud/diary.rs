@@ -2849,6 +2849,7 @@ fn unlock_skill_strings(group: SkillGroupKind, i18n: &Localization) -> (&str, Co
             | ToolKind::Debug
             | ToolKind::Farming
             | ToolKind::Pick
+            | ToolKind::Fishing
             | ToolKind::Natural
             | ToolKind::Empty,
         ) => {
@@ -799,6 +799,14 @@ impl<'a> Widget for Group<'a> {
                     .localized_strings
                     .get("hud.group.invite_to_trade")
                     .replace("{name}", &name),
+                InviteKind::Guild => self
+                    .localized_strings
+                    .get("hud.group.invite_to_guild")
+                    .replace("{name}", &name),
+                InviteKind::Duel => self
+                    .localized_strings
+                    .get("hud.group.invite_to_duel")
+                    .replace("{name}", &name),
             };
             Text::new(&invite_text)
                 .mid_top_with_margin_on(state.ids.bg, 5.0)
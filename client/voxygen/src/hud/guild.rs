@@ -0,0 +1,315 @@
+use super::{
+    img_ids::Imgs, GUILD_COLOR, TEXT_COLOR, TEXT_COLOR_3, TEXT_GRAY_COLOR, UI_HIGHLIGHT_0, UI_MAIN,
+};
+use crate::ui::fonts::Fonts;
+use client::{self, Client};
+use common::guild::GuildRank;
+use conrod_core::{
+    color,
+    widget::{self, button, Button, Image, Rectangle, Scrollbar, Text},
+    widget_ids, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
+};
+use i18n::Localization;
+
+widget_ids! {
+    pub struct Ids {
+        frame,
+        close,
+        title_align,
+        title,
+        bg,
+        icon,
+        members_tab,
+        bank_tab,
+        no_guild_txt,
+        rank_txt,
+        list_align,
+        list_scrollbar,
+        member_entries[],
+        bank_entries[],
+        bank_hint,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum GuildTab {
+    Members,
+    Bank,
+}
+
+pub struct State {
+    ids: Ids,
+    tab: GuildTab,
+}
+
+#[derive(WidgetCommon)]
+pub struct Guild<'a> {
+    client: &'a Client,
+    imgs: &'a Imgs,
+    fonts: &'a Fonts,
+    localized_strings: &'a Localization,
+
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+}
+
+impl<'a> Guild<'a> {
+    pub fn new(
+        client: &'a Client,
+        imgs: &'a Imgs,
+        fonts: &'a Fonts,
+        localized_strings: &'a Localization,
+    ) -> Self {
+        Self {
+            client,
+            imgs,
+            fonts,
+            localized_strings,
+            common: widget::CommonBuilder::default(),
+        }
+    }
+
+    fn tab_button<'b>(&self, selected: bool) -> Button<'b, button::Image> {
+        Button::image(self.imgs.button)
+            .w_h(106.0, 26.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .label_y(conrod_core::position::Relative::Scalar(3.0))
+            .label_color(if selected { TEXT_COLOR } else { TEXT_GRAY_COLOR })
+            .label_font_size(self.fonts.cyri.scale(15))
+            .label_font_id(self.fonts.cyri.conrod_id)
+    }
+}
+
+pub enum Event {
+    Close,
+    Withdraw(usize),
+}
+
+fn rank_key(rank: GuildRank) -> &'static str {
+    match rank {
+        GuildRank::Recruit => "hud.guild.rank.recruit",
+        GuildRank::Member => "hud.guild.rank.member",
+        GuildRank::Officer => "hud.guild.rank.officer",
+        GuildRank::Leader => "hud.guild.rank.leader",
+    }
+}
+
+impl<'a> Widget for Guild<'a> {
+    type Event = Vec<Event>;
+    type State = State;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        Self::State {
+            ids: Ids::new(id_gen),
+            tab: GuildTab::Members,
+        }
+    }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { state, ui, .. } = args;
+        let mut events = Vec::new();
+        let i18n = self.localized_strings;
+
+        // Window BG
+        Image::new(self.imgs.social_bg_on)
+            .bottom_left_with_margins_on(ui.window, 308.0, 25.0)
+            .color(Some(UI_MAIN))
+            .w_h(280.0, 460.0)
+            .set(state.ids.bg, ui);
+        // Window frame
+        Image::new(self.imgs.social_frame_on)
+            .middle_of(state.ids.bg)
+            .color(Some(UI_HIGHLIGHT_0))
+            .w_h(280.0, 460.0)
+            .set(state.ids.frame, ui);
+
+        // Icon
+        Image::new(self.imgs.social)
+            .w_h(30.0, 30.0)
+            .top_left_with_margins_on(state.ids.frame, 6.0, 6.0)
+            .set(state.ids.icon, ui);
+        // X-Button
+        if Button::image(self.imgs.close_button)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_button_hover)
+            .press_image(self.imgs.close_button_press)
+            .top_right_with_margins_on(state.ids.frame, 0.0, 0.0)
+            .set(state.ids.close, ui)
+            .was_clicked()
+        {
+            events.push(Event::Close);
+        }
+
+        let guild = self.client.guild();
+
+        // Title
+        Rectangle::fill_with([212.0, 42.0], color::TRANSPARENT)
+            .top_left_with_margins_on(state.ids.frame, 2.0, 44.0)
+            .set(state.ids.title_align, ui);
+        Text::new(&guild.map_or_else(
+            || i18n.get("hud.guild").to_string(),
+            |guild| format!("{} [{}]", guild.name, guild.tag),
+        ))
+        .middle_of(state.ids.title_align)
+        .font_id(self.fonts.cyri.conrod_id)
+        .font_size(self.fonts.cyri.scale(20))
+        .color(TEXT_COLOR)
+        .set(state.ids.title, ui);
+
+        let guild = match guild {
+            Some(guild) => guild,
+            None => {
+                Text::new(i18n.get("hud.guild.no_guild"))
+                    .mid_top_with_margin_on(state.ids.frame, 60.0)
+                    .w(250.0)
+                    .wrap_by_word()
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_GRAY_COLOR)
+                    .set(state.ids.no_guild_txt, ui);
+                return events;
+            },
+        };
+
+        // Tabs
+        if self
+            .tab_button(state.tab == GuildTab::Members)
+            .label(&format!(
+                "{} ({})",
+                i18n.get("hud.guild.members"),
+                guild.members.len()
+            ))
+            .top_left_with_margins_on(state.ids.frame, 48.0, 7.0)
+            .set(state.ids.members_tab, ui)
+            .was_clicked()
+        {
+            state.update(|s| s.tab = GuildTab::Members);
+        }
+        if self
+            .tab_button(state.tab == GuildTab::Bank)
+            .label(&format!(
+                "{} ({}/{})",
+                i18n.get("hud.guild.bank"),
+                guild.bank.len(),
+                guild.bank_capacity
+            ))
+            .top_right_with_margins_on(state.ids.frame, 48.0, 7.0)
+            .set(state.ids.bank_tab, ui)
+            .was_clicked()
+        {
+            state.update(|s| s.tab = GuildTab::Bank);
+        }
+
+        // Rank of the player
+        Text::new(&format!(
+            "{}: {}",
+            i18n.get("hud.guild.your_rank"),
+            i18n.get(rank_key(guild.rank))
+        ))
+        .bottom_left_with_margins_on(state.ids.frame, 12.0, 10.0)
+        .font_id(self.fonts.cyri.conrod_id)
+        .font_size(self.fonts.cyri.scale(14))
+        .color(GUILD_COLOR)
+        .set(state.ids.rank_txt, ui);
+
+        Rectangle::fill_with([270.0, 330.0], color::TRANSPARENT)
+            .mid_top_with_margin_on(state.ids.frame, 80.0)
+            .scroll_kids_vertically()
+            .set(state.ids.list_align, ui);
+        Scrollbar::y_axis(state.ids.list_align)
+            .thickness(4.0)
+            .color(color::rgba(0.79, 1.09, 1.09, 0.0))
+            .set(state.ids.list_scrollbar, ui);
+
+        match state.tab {
+            GuildTab::Members => {
+                if state.ids.member_entries.len() < guild.members.len() {
+                    state.update(|s| {
+                        s.ids
+                            .member_entries
+                            .resize(guild.members.len(), &mut ui.widget_id_generator())
+                    });
+                }
+                for (i, member) in guild.members.iter().enumerate() {
+                    let text = Text::new(&format!(
+                        "{} - {}",
+                        member.name,
+                        i18n.get(rank_key(member.rank))
+                    ))
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(if member.online {
+                        TEXT_COLOR
+                    } else {
+                        TEXT_GRAY_COLOR
+                    });
+                    if i == 0 {
+                        text.top_left_with_margins_on(state.ids.list_align, 2.0, 5.0)
+                    } else {
+                        text.down_from(state.ids.member_entries[i - 1], 4.0)
+                    }
+                    .set(state.ids.member_entries[i], ui);
+                }
+            },
+            GuildTab::Bank => {
+                if state.ids.bank_entries.len() < guild.bank.len() {
+                    state.update(|s| {
+                        s.ids
+                            .bank_entries
+                            .resize(guild.bank.len(), &mut ui.widget_id_generator())
+                    });
+                }
+                let can_withdraw = guild.rank.can_withdraw();
+                for (i, item) in guild.bank.iter().enumerate() {
+                    let button = Button::image(self.imgs.nothing)
+                        .hover_image(if can_withdraw {
+                            self.imgs.selection_hover
+                        } else {
+                            self.imgs.nothing
+                        })
+                        .press_image(if can_withdraw {
+                            self.imgs.selection_press
+                        } else {
+                            self.imgs.nothing
+                        })
+                        .w_h(260.0, 18.0)
+                        .label(&format!("{}x {}", item.amount(), item.name()))
+                        .label_font_size(self.fonts.cyri.scale(13))
+                        .label_font_id(self.fonts.cyri.conrod_id)
+                        .label_color(if can_withdraw { TEXT_COLOR } else { TEXT_COLOR_3 });
+                    let button = if i == 0 {
+                        button.mid_top_with_margin_on(state.ids.list_align, 1.0)
+                    } else {
+                        button.down_from(state.ids.bank_entries[i - 1], 1.0)
+                    };
+                    if button.set(state.ids.bank_entries[i], ui).was_clicked() && can_withdraw {
+                        events.push(Event::Withdraw(i));
+                    }
+                }
+
+                let hint = if guild.bank.len() >= guild.bank_capacity {
+                    i18n.get("hud.guild.bank_full")
+                } else {
+                    i18n.get("hud.guild.deposit_hint")
+                };
+                let text = Text::new(hint)
+                    .w(250.0)
+                    .wrap_by_word()
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(12))
+                    .color(TEXT_GRAY_COLOR);
+                match guild.bank.len() {
+                    0 => text.mid_top_with_margin_on(state.ids.list_align, 4.0),
+                    n => text.down_from(state.ids.bank_entries[n - 1], 6.0),
+                }
+                .set(state.ids.bank_hint, ui);
+            },
+        }
+
+        events
+    }
+}
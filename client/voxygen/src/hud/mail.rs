@@ -0,0 +1,603 @@
+use super::{
+    img_ids::Imgs, Show, TEXT_COLOR, TEXT_COLOR_3, TEXT_GRAY_COLOR, UI_HIGHLIGHT_0, UI_MAIN,
+};
+use crate::ui::fonts::Fonts;
+use client::{self, Client};
+use common::{
+    comp::inventory::slot::InvSlotId,
+    mail::{MailId, MAX_MAIL_BODY_LEN, MAX_MAIL_SUBJECT_LEN},
+};
+use conrod_core::{
+    color,
+    widget::{self, button, Button, Image, Rectangle, Scrollbar, Text, TextEdit},
+    widget_ids, Colorable, Labelable, Positionable, Sizeable, UiCell, Widget, WidgetCommon,
+};
+use i18n::Localization;
+
+widget_ids! {
+    pub struct Ids {
+        frame,
+        close,
+        title_align,
+        title,
+        bg,
+        icon,
+        inbox_tab,
+        compose_tab,
+        // Inbox
+        mail_align,
+        mail_scrollbar,
+        mail_entries[],
+        empty_txt,
+        read_bg,
+        read_header,
+        read_body,
+        read_attachments[],
+        claim_button,
+        delete_button,
+        // Compose
+        recipient_txt,
+        recipient_input_bg,
+        recipient_input,
+        subject_txt,
+        subject_input_bg,
+        subject_input,
+        body_input_bg,
+        body_input,
+        coins_txt,
+        coins_input_bg,
+        coins_input,
+        attachments_txt,
+        attachment_entries[],
+        attach_hint,
+        send_button,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum MailTab {
+    Inbox,
+    Compose,
+}
+
+pub struct State {
+    ids: Ids,
+    tab: MailTab,
+    selected: Option<MailId>,
+    recipient: String,
+    subject: String,
+    body: String,
+    coins: String,
+}
+
+#[derive(WidgetCommon)]
+pub struct Mail<'a> {
+    show: &'a Show,
+    client: &'a Client,
+    imgs: &'a Imgs,
+    fonts: &'a Fonts,
+    localized_strings: &'a Localization,
+
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+}
+
+impl<'a> Mail<'a> {
+    pub fn new(
+        show: &'a Show,
+        client: &'a Client,
+        imgs: &'a Imgs,
+        fonts: &'a Fonts,
+        localized_strings: &'a Localization,
+    ) -> Self {
+        Self {
+            show,
+            client,
+            imgs,
+            fonts,
+            localized_strings,
+            common: widget::CommonBuilder::default(),
+        }
+    }
+}
+
+pub enum Event {
+    Close,
+    MarkRead(MailId),
+    Claim(MailId),
+    Delete(MailId),
+    RemoveAttachment(InvSlotId),
+    Send {
+        recipient: String,
+        subject: String,
+        body: String,
+        coins: u32,
+    },
+}
+
+/// Draws the dark background behind a text input
+fn set_input_bg(
+    id: widget::Id,
+    input: widget::Id,
+    parent: widget::Id,
+    size: [f64; 2],
+    ui: &mut UiCell,
+) {
+    Rectangle::fill(size)
+        .top_left_with_margins_on(input, -2.0, -2.0)
+        .hsla(0.0, 0.0, 0.0, 0.7)
+        .depth(1.0)
+        .parent(parent)
+        .set(id, ui);
+}
+
+impl<'a> Mail<'a> {
+    fn tab_button<'b>(&self, selected: bool) -> Button<'b, button::Image> {
+        Button::image(self.imgs.button)
+            .w_h(106.0, 26.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .label_y(conrod_core::position::Relative::Scalar(3.0))
+            .label_color(if selected { TEXT_COLOR } else { TEXT_GRAY_COLOR })
+            .label_font_size(self.fonts.cyri.scale(15))
+            .label_font_id(self.fonts.cyri.conrod_id)
+    }
+
+    fn action_button<'b>(&self, enabled: bool) -> Button<'b, button::Image> {
+        Button::image(self.imgs.button)
+            .w_h(106.0, 26.0)
+            .hover_image(if enabled {
+                self.imgs.button_hover
+            } else {
+                self.imgs.button
+            })
+            .press_image(if enabled {
+                self.imgs.button_press
+            } else {
+                self.imgs.button
+            })
+            .label_y(conrod_core::position::Relative::Scalar(3.0))
+            .label_color(if enabled { TEXT_COLOR } else { TEXT_COLOR_3 })
+            .image_color(if enabled { TEXT_COLOR } else { TEXT_COLOR_3 })
+            .label_font_size(self.fonts.cyri.scale(15))
+            .label_font_id(self.fonts.cyri.conrod_id)
+    }
+}
+
+impl<'a> Widget for Mail<'a> {
+    type Event = Vec<Event>;
+    type State = State;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        Self::State {
+            ids: Ids::new(id_gen),
+            tab: MailTab::Inbox,
+            selected: None,
+            recipient: String::new(),
+            subject: String::new(),
+            body: String::new(),
+            coins: String::new(),
+        }
+    }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { state, ui, .. } = args;
+        let mut events = Vec::new();
+        let i18n = self.localized_strings;
+
+        // Window BG
+        Image::new(self.imgs.social_bg_on)
+            .bottom_left_with_margins_on(ui.window, 308.0, 25.0)
+            .color(Some(UI_MAIN))
+            .w_h(280.0, 460.0)
+            .set(state.ids.bg, ui);
+        // Window frame
+        Image::new(self.imgs.social_frame_on)
+            .middle_of(state.ids.bg)
+            .color(Some(UI_HIGHLIGHT_0))
+            .w_h(280.0, 460.0)
+            .set(state.ids.frame, ui);
+
+        // Icon
+        Image::new(self.imgs.social)
+            .w_h(30.0, 30.0)
+            .top_left_with_margins_on(state.ids.frame, 6.0, 6.0)
+            .set(state.ids.icon, ui);
+        // X-Button
+        if Button::image(self.imgs.close_button)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_button_hover)
+            .press_image(self.imgs.close_button_press)
+            .top_right_with_margins_on(state.ids.frame, 0.0, 0.0)
+            .set(state.ids.close, ui)
+            .was_clicked()
+        {
+            events.push(Event::Close);
+        }
+
+        // Title
+        Rectangle::fill_with([212.0, 42.0], color::TRANSPARENT)
+            .top_left_with_margins_on(state.ids.frame, 2.0, 44.0)
+            .set(state.ids.title_align, ui);
+        Text::new(i18n.get("hud.mail"))
+            .middle_of(state.ids.title_align)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(TEXT_COLOR)
+            .set(state.ids.title, ui);
+
+        // Tabs
+        if self
+            .tab_button(state.tab == MailTab::Inbox)
+            .label(&format!(
+                "{} ({})",
+                i18n.get("hud.mail.inbox"),
+                self.client.unread_mail_count()
+            ))
+            .top_left_with_margins_on(state.ids.frame, 48.0, 7.0)
+            .set(state.ids.inbox_tab, ui)
+            .was_clicked()
+        {
+            state.update(|s| s.tab = MailTab::Inbox);
+        }
+        if self
+            .tab_button(state.tab == MailTab::Compose)
+            .label(i18n.get("hud.mail.compose"))
+            .top_right_with_margins_on(state.ids.frame, 48.0, 7.0)
+            .set(state.ids.compose_tab, ui)
+            .was_clicked()
+        {
+            state.update(|s| s.tab = MailTab::Compose);
+        }
+
+        match state.tab {
+            MailTab::Inbox => {
+                let mailbox = self.client.mailbox();
+
+                // Mail list
+                Rectangle::fill_with([270.0, 130.0], color::TRANSPARENT)
+                    .mid_top_with_margin_on(state.ids.frame, 80.0)
+                    .scroll_kids_vertically()
+                    .set(state.ids.mail_align, ui);
+                Scrollbar::y_axis(state.ids.mail_align)
+                    .thickness(4.0)
+                    .color(color::rgba(0.79, 1.09, 1.09, 0.0))
+                    .set(state.ids.mail_scrollbar, ui);
+
+                if mailbox.is_empty() {
+                    Text::new(i18n.get("hud.mail.empty"))
+                        .mid_top_with_margin_on(state.ids.mail_align, 10.0)
+                        .font_id(self.fonts.cyri.conrod_id)
+                        .font_size(self.fonts.cyri.scale(14))
+                        .color(TEXT_GRAY_COLOR)
+                        .set(state.ids.empty_txt, ui);
+                }
+
+                if state.ids.mail_entries.len() < mailbox.len() {
+                    state.update(|s| {
+                        s.ids
+                            .mail_entries
+                            .resize(mailbox.len(), &mut ui.widget_id_generator())
+                    });
+                }
+
+                for (i, mail) in mailbox.iter().enumerate() {
+                    let selected = state.selected == Some(mail.id);
+                    let button = Button::image(if selected {
+                        self.imgs.selection
+                    } else {
+                        self.imgs.nothing
+                    })
+                    .hover_image(if selected {
+                        self.imgs.selection
+                    } else {
+                        self.imgs.selection_hover
+                    })
+                    .press_image(self.imgs.selection_press)
+                    .w_h(260.0, 20.0)
+                    .image_color(color::rgba(1.0, 0.82, 0.27, 1.0));
+                    let button = if i == 0 {
+                        button.mid_top_with_margin_on(state.ids.mail_align, 1.0)
+                    } else {
+                        button.down_from(state.ids.mail_entries[i - 1], 1.0)
+                    };
+                    if button
+                        .label(&format!(
+                            "{}{} - {}",
+                            if mail.has_attachments() { "+ " } else { "" },
+                            mail.subject,
+                            mail.sender
+                        ))
+                        .label_font_size(self.fonts.cyri.scale(14))
+                        .label_y(conrod_core::position::Relative::Scalar(1.0))
+                        .label_font_id(self.fonts.cyri.conrod_id)
+                        .label_color(if mail.read { TEXT_GRAY_COLOR } else { TEXT_COLOR })
+                        .set(state.ids.mail_entries[i], ui)
+                        .was_clicked()
+                    {
+                        state.update(|s| s.selected = Some(mail.id));
+                        if !mail.read {
+                            events.push(Event::MarkRead(mail.id));
+                        }
+                    }
+                }
+
+                // Selected mail
+                if let Some(mail) = state
+                    .selected
+                    .and_then(|id| mailbox.iter().find(|mail| mail.id == id))
+                {
+                    Rectangle::fill([260.0, 190.0])
+                        .mid_top_with_margin_on(state.ids.frame, 220.0)
+                        .hsla(0.0, 0.0, 0.0, 0.7)
+                        .set(state.ids.read_bg, ui);
+                    Text::new(&format!(
+                        "{}: {}\n{}: {}",
+                        i18n.get("hud.mail.from"),
+                        mail.sender,
+                        i18n.get("hud.mail.subject"),
+                        mail.subject
+                    ))
+                    .top_left_with_margins_on(state.ids.read_bg, 5.0, 5.0)
+                    .w(250.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.read_header, ui);
+                    Text::new(&mail.body)
+                        .down_from(state.ids.read_header, 8.0)
+                        .w(250.0)
+                        .h(90.0)
+                        .wrap_by_word()
+                        .font_id(self.fonts.cyri.conrod_id)
+                        .font_size(self.fonts.cyri.scale(12))
+                        .color(TEXT_COLOR)
+                        .set(state.ids.read_body, ui);
+
+                    let attachment_lines = mail
+                        .attachments
+                        .iter()
+                        .map(|item| format!("{}x {}", item.amount(), item.name()))
+                        .chain((mail.coins > 0).then(|| {
+                            format!("{} {}", mail.coins, i18n.get("hud.mail.coins"))
+                        }))
+                        .collect::<Vec<_>>();
+                    if state.ids.read_attachments.len() < attachment_lines.len() {
+                        state.update(|s| {
+                            s.ids
+                                .read_attachments
+                                .resize(attachment_lines.len(), &mut ui.widget_id_generator())
+                        });
+                    }
+                    for (i, line) in attachment_lines.iter().enumerate() {
+                        let text = Text::new(line)
+                            .font_id(self.fonts.cyri.conrod_id)
+                            .font_size(self.fonts.cyri.scale(12))
+                            .color(TEXT_COLOR);
+                        if i == 0 {
+                            text.bottom_left_with_margins_on(
+                                state.ids.read_bg,
+                                5.0 + 14.0 * (attachment_lines.len() - 1) as f64,
+                                5.0,
+                            )
+                        } else {
+                            text.down_from(state.ids.read_attachments[i - 1], 2.0)
+                        }
+                        .set(state.ids.read_attachments[i], ui);
+                    }
+
+                    let can_claim = mail.has_attachments();
+                    if self
+                        .action_button(can_claim)
+                        .label(i18n.get("hud.mail.claim"))
+                        .bottom_left_with_margins_on(state.ids.frame, 9.0, 7.0)
+                        .set(state.ids.claim_button, ui)
+                        .was_clicked()
+                        && can_claim
+                    {
+                        events.push(Event::Claim(mail.id));
+                    }
+                    if self
+                        .action_button(!can_claim)
+                        .label(i18n.get("hud.mail.delete"))
+                        .bottom_right_with_margins_on(state.ids.frame, 9.0, 7.0)
+                        .set(state.ids.delete_button, ui)
+                        .was_clicked()
+                        && !can_claim
+                    {
+                        events.push(Event::Delete(mail.id));
+                        state.update(|s| s.selected = None);
+                    }
+                }
+            },
+            MailTab::Compose => {
+                // Recipient
+                Text::new(i18n.get("hud.mail.to"))
+                    .top_left_with_margins_on(state.ids.frame, 86.0, 10.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.recipient_txt, ui);
+                if let Some(string) = TextEdit::new(&state.recipient)
+                    .top_left_with_margins_on(state.ids.frame, 86.0, 80.0)
+                    .w_h(185.0, 20.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.recipient_input, ui)
+                {
+                    state.update(|s| s.recipient = string);
+                }
+                set_input_bg(
+                    state.ids.recipient_input_bg,
+                    state.ids.recipient_input,
+                    state.ids.bg,
+                    [189.0, 20.0],
+                    ui,
+                );
+
+                // Subject
+                Text::new(i18n.get("hud.mail.subject"))
+                    .down_from(state.ids.recipient_txt, 10.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.subject_txt, ui);
+                if let Some(string) = TextEdit::new(&state.subject)
+                    .down_from(state.ids.recipient_input, 10.0)
+                    .w_h(185.0, 20.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.subject_input, ui)
+                {
+                    let string = string.chars().take(MAX_MAIL_SUBJECT_LEN).collect();
+                    state.update(|s| s.subject = string);
+                }
+                set_input_bg(
+                    state.ids.subject_input_bg,
+                    state.ids.subject_input,
+                    state.ids.bg,
+                    [189.0, 20.0],
+                    ui,
+                );
+
+                // Body
+                if let Some(string) = TextEdit::new(&state.body)
+                    .top_left_with_margins_on(state.ids.frame, 150.0, 10.0)
+                    .w_h(255.0, 120.0)
+                    .wrap_by_word()
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(12))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.body_input, ui)
+                {
+                    let string = string.chars().take(MAX_MAIL_BODY_LEN).collect();
+                    state.update(|s| s.body = string);
+                }
+                set_input_bg(
+                    state.ids.body_input_bg,
+                    state.ids.body_input,
+                    state.ids.bg,
+                    [259.0, 124.0],
+                    ui,
+                );
+
+                // Coins
+                Text::new(i18n.get("hud.mail.coins"))
+                    .top_left_with_margins_on(state.ids.frame, 286.0, 10.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.coins_txt, ui);
+                if let Some(string) = TextEdit::new(&state.coins)
+                    .top_left_with_margins_on(state.ids.frame, 286.0, 80.0)
+                    .w_h(80.0, 20.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.coins_input, ui)
+                {
+                    let string = string.chars().filter(char::is_ascii_digit).collect();
+                    state.update(|s| s.coins = string);
+                }
+                set_input_bg(
+                    state.ids.coins_input_bg,
+                    state.ids.coins_input,
+                    state.ids.bg,
+                    [84.0, 20.0],
+                    ui,
+                );
+
+                // Attachments
+                Text::new(i18n.get("hud.mail.attachments"))
+                    .down_from(state.ids.coins_txt, 10.0)
+                    .font_id(self.fonts.cyri.conrod_id)
+                    .font_size(self.fonts.cyri.scale(14))
+                    .color(TEXT_COLOR)
+                    .set(state.ids.attachments_txt, ui);
+
+                let inventories = self.client.inventories();
+                let attachments = inventories
+                    .get(self.client.entity())
+                    .map(|inventory| {
+                        self.show
+                            .mail_attachments
+                            .iter()
+                            .filter_map(|slot| Some((*slot, inventory.get(*slot)?)))
+                            .map(|(slot, item)| {
+                                (slot, format!("{}x {}", item.amount(), item.name()))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                drop(inventories);
+
+                if state.ids.attachment_entries.len() < attachments.len() {
+                    state.update(|s| {
+                        s.ids
+                            .attachment_entries
+                            .resize(attachments.len(), &mut ui.widget_id_generator())
+                    });
+                }
+                for (i, (slot, label)) in attachments.iter().enumerate() {
+                    let button = Button::image(self.imgs.nothing)
+                        .hover_image(self.imgs.selection_hover)
+                        .press_image(self.imgs.selection_press)
+                        .w_h(255.0, 16.0)
+                        .label(label)
+                        .label_font_size(self.fonts.cyri.scale(12))
+                        .label_font_id(self.fonts.cyri.conrod_id)
+                        .label_color(TEXT_COLOR);
+                    let button = if i == 0 {
+                        button.down_from(state.ids.attachments_txt, 4.0)
+                    } else {
+                        button.down_from(state.ids.attachment_entries[i - 1], 1.0)
+                    };
+                    if button
+                        .set(state.ids.attachment_entries[i], ui)
+                        .was_clicked()
+                    {
+                        events.push(Event::RemoveAttachment(*slot));
+                    }
+                }
+                if attachments.is_empty() {
+                    Text::new(i18n.get("hud.mail.attach_hint"))
+                        .down_from(state.ids.attachments_txt, 4.0)
+                        .font_id(self.fonts.cyri.conrod_id)
+                        .font_size(self.fonts.cyri.scale(12))
+                        .color(TEXT_GRAY_COLOR)
+                        .set(state.ids.attach_hint, ui);
+                }
+
+                let can_send = !state.recipient.trim().is_empty() && !state.subject.is_empty();
+                if self
+                    .action_button(can_send)
+                    .label(i18n.get("hud.mail.send"))
+                    .bottom_right_with_margins_on(state.ids.frame, 9.0, 7.0)
+                    .set(state.ids.send_button, ui)
+                    .was_clicked()
+                    && can_send
+                {
+                    events.push(Event::Send {
+                        recipient: state.recipient.clone(),
+                        subject: state.subject.clone(),
+                        body: state.body.clone(),
+                        coins: state.coins.parse().unwrap_or(0),
+                    });
+                    state.update(|s| {
+                        s.subject.clear();
+                        s.body.clear();
+                        s.coins.clear();
+                        s.tab = MailTab::Inbox;
+                    });
+                }
+            },
+        }
+
+        events
+    }
+}
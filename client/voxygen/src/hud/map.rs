@@ -1,7 +1,8 @@
 use super::{
     img_ids::{Imgs, ImgsRot},
     MapMarkers, QUALITY_COMMON, QUALITY_EPIC, QUALITY_HIGH, QUALITY_LOW, QUALITY_MODERATE, TEXT_BG,
-    TEXT_BLUE_COLOR, TEXT_COLOR, TEXT_GRAY_COLOR, TEXT_VELORITE, UI_HIGHLIGHT_0, UI_MAIN,
+    TEXT_BLUE_COLOR, TEXT_COLOR, TEXT_DULL_RED_COLOR, TEXT_GRAY_COLOR, TEXT_VELORITE,
+    UI_HIGHLIGHT_0, UI_MAIN,
 };
 use crate::{
     game_input::GameInput,
@@ -52,6 +53,7 @@ widget_ids! {
         member_height_indicators[],
         location_marker,
         location_marker_group[],
+        world_event_markers[],
         map_settings_align,
         show_towns_img,
         show_towns_box,
@@ -867,6 +869,9 @@ impl<'a> Widget for Map<'a> {
                 SiteKind::Cave => i18n.get("hud.map.cave"),
                 SiteKind::Tree => i18n.get("hud.map.tree"),
                 SiteKind::Gnarling => i18n.get("hud.map.gnarling"),
+                SiteKind::Mine => i18n.get("hud.map.mine"),
+                SiteKind::Port => i18n.get("hud.map.port"),
+                SiteKind::BanditCamp => i18n.get("hud.map.bandit_camp"),
             });
             let (difficulty, desc) = match &site.kind {
                 SiteKind::Town => (None, i18n.get("hud.map.town").to_string()),
@@ -889,14 +894,17 @@ impl<'a> Widget for Map<'a> {
                 SiteKind::Cave => (None, i18n.get("hud.map.cave").to_string()),
                 SiteKind::Tree => (None, i18n.get("hud.map.tree").to_string()),
                 SiteKind::Gnarling => (Some(0), i18n.get("hud.map.gnarling").to_string()),
+                SiteKind::Mine => (None, i18n.get("hud.map.mine").to_string()),
+                SiteKind::Port => (None, i18n.get("hud.map.port").to_string()),
+                SiteKind::BanditCamp => (Some(1), i18n.get("hud.map.bandit_camp").to_string()),
             };
             let desc = desc + &get_site_economy(site_rich);
             let site_btn = Button::image(match &site.kind {
-                SiteKind::Town => self.imgs.mmap_site_town,
+                SiteKind::Town | SiteKind::Port => self.imgs.mmap_site_town,
                 SiteKind::Castle => self.imgs.mmap_site_castle,
-                SiteKind::Cave => self.imgs.mmap_site_cave,
+                SiteKind::Cave | SiteKind::Mine => self.imgs.mmap_site_cave,
                 SiteKind::Tree => self.imgs.mmap_site_tree,
-                SiteKind::Gnarling => self.imgs.mmap_site_gnarling,
+                SiteKind::Gnarling | SiteKind::BanditCamp => self.imgs.mmap_site_gnarling,
                 SiteKind::Dungeon { difficulty } => match difficulty {
                     4 => self.imgs.mmap_site_minotaur,
                     5 => self.imgs.mmap_site_mindflayer,
@@ -910,11 +918,11 @@ impl<'a> Widget for Map<'a> {
             )
             .w_h(rside as f64, rside as f64)
             .hover_image(match &site.kind {
-                SiteKind::Town => self.imgs.mmap_site_town_hover,
+                SiteKind::Town | SiteKind::Port => self.imgs.mmap_site_town_hover,
                 SiteKind::Castle => self.imgs.mmap_site_castle_hover,
-                SiteKind::Cave => self.imgs.mmap_site_cave_hover,
+                SiteKind::Cave | SiteKind::Mine => self.imgs.mmap_site_cave_hover,
                 SiteKind::Tree => self.imgs.mmap_site_tree_hover,
-                SiteKind::Gnarling => self.imgs.mmap_site_gnarling_hover,
+                SiteKind::Gnarling | SiteKind::BanditCamp => self.imgs.mmap_site_gnarling_hover,
                 SiteKind::Dungeon { difficulty } => match difficulty {
                     4 => self.imgs.mmap_site_minotaur_hover,
                     5 => self.imgs.mmap_site_mindflayer_hover,
@@ -928,17 +936,19 @@ impl<'a> Widget for Map<'a> {
                 &desc,
                 &site_tooltip,
                 match &site.kind {
-                    SiteKind::Town => TEXT_COLOR,
+                    SiteKind::Town | SiteKind::Port => TEXT_COLOR,
                     SiteKind::Castle => TEXT_COLOR,
-                    SiteKind::Dungeon { .. } | SiteKind::Gnarling => match difficulty {
-                        Some(0) => QUALITY_LOW,
-                        Some(1) => QUALITY_COMMON,
-                        Some(2) => QUALITY_MODERATE,
-                        Some(3) => QUALITY_HIGH,
-                        Some(4 | 5) => QUALITY_EPIC,
-                        _ => TEXT_COLOR,
+                    SiteKind::Dungeon { .. } | SiteKind::Gnarling | SiteKind::BanditCamp => {
+                        match difficulty {
+                            Some(0) => QUALITY_LOW,
+                            Some(1) => QUALITY_COMMON,
+                            Some(2) => QUALITY_MODERATE,
+                            Some(3) => QUALITY_HIGH,
+                            Some(4 | 5) => QUALITY_EPIC,
+                            _ => TEXT_COLOR,
+                        }
                     },
-                    SiteKind::Cave => TEXT_COLOR,
+                    SiteKind::Cave | SiteKind::Mine => TEXT_COLOR,
                     SiteKind::Tree => TEXT_COLOR,
                 },
             );
@@ -953,10 +963,12 @@ impl<'a> Widget for Map<'a> {
 
             // Only display sites that are toggled on
             let show_site = match &site.kind {
-                SiteKind::Town => show_towns,
-                SiteKind::Dungeon { .. } | SiteKind::Gnarling => show_dungeons,
+                SiteKind::Town | SiteKind::Port => show_towns,
+                SiteKind::Dungeon { .. } | SiteKind::Gnarling | SiteKind::BanditCamp => {
+                    show_dungeons
+                },
                 SiteKind::Castle => show_castles,
-                SiteKind::Cave => show_caves,
+                SiteKind::Cave | SiteKind::Mine => show_caves,
                 SiteKind::Tree => show_trees,
             };
             if show_site {
@@ -1005,12 +1017,12 @@ impl<'a> Widget for Map<'a> {
                     _ => TEXT_COLOR,
                 }));
                 match &site.kind {
-                    SiteKind::Town => {
+                    SiteKind::Town | SiteKind::Port => {
                         if show_towns {
                             dif_img.set(state.ids.site_difs[i], ui)
                         }
                     },
-                    SiteKind::Dungeon { .. } | SiteKind::Gnarling => {
+                    SiteKind::Dungeon { .. } | SiteKind::Gnarling | SiteKind::BanditCamp => {
                         if show_dungeons {
                             dif_img.set(state.ids.site_difs[i], ui)
                         }
@@ -1020,7 +1032,7 @@ impl<'a> Widget for Map<'a> {
                             dif_img.set(state.ids.site_difs[i], ui)
                         }
                     },
-                    SiteKind::Cave => {
+                    SiteKind::Cave | SiteKind::Mine => {
                         if show_caves {
                             dif_img.set(state.ids.site_difs[i], ui)
                         }
@@ -1269,6 +1281,56 @@ impl<'a> Widget for Map<'a> {
                     .set(state.ids.location_marker_group[i], ui);
             }
         }
+        // World event markers
+        if state.ids.world_event_markers.len() < self.location_markers.world_events.len() {
+            state.update(|s| {
+                s.ids.world_event_markers.resize(
+                    self.location_markers.world_events.len(),
+                    &mut ui.widget_id_generator(),
+                )
+            })
+        };
+        for (i, &(kind, wpos)) in self.location_markers.world_events.values().enumerate() {
+            let wpos = wpos.as_();
+            if let Some((rpos, fade)) =
+                wpos_to_rpos_fade(wpos, Vec2::from(side_length / 2.0), side_length / 2.0)
+            {
+                let (img, title) = match kind {
+                    comp::WorldEventKind::Raid => {
+                        (self.imgs.mmap_site_gnarling, "hud.map.world_event.raid")
+                    },
+                    comp::WorldEventKind::WorldBoss => (
+                        self.imgs.mmap_site_minotaur,
+                        "hud.map.world_event.world_boss",
+                    ),
+                    comp::WorldEventKind::Meteor => {
+                        (self.imgs.mmap_site_cave, "hud.map.world_event.meteor")
+                    },
+                };
+                Button::image(img)
+                    .x_y_position_relative_to(
+                        state.ids.map_layers[0],
+                        position::Relative::Scalar(rpos.x as f64),
+                        position::Relative::Scalar(rpos.y as f64),
+                    )
+                    .w_h(side_length as f64, side_length as f64)
+                    .image_color(Color::Rgba(1.0, 0.45, 0.35, fade))
+                    .floating(true)
+                    .with_tooltip(
+                        self.tooltip_manager,
+                        i18n.get(title),
+                        &format!(
+                            "X: {}, Y: {}\n\n{}",
+                            wpos.x as i32,
+                            wpos.y as i32,
+                            i18n.get("hud.map.world_event.ongoing"),
+                        ),
+                        &site_tooltip,
+                        TEXT_DULL_RED_COLOR,
+                    )
+                    .set(state.ids.world_event_markers[i], ui);
+            }
+        }
         // Location marker
         if let Some((lm, (rpos, fade))) = self.location_markers.owned.and_then(|lm| {
             let lm = lm.as_();
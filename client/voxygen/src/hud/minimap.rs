@@ -695,6 +695,9 @@ impl<'a> Widget for MiniMap<'a> {
                     SiteKind::Cave => None,
                     SiteKind::Tree => None,
                     SiteKind::Gnarling => Some(0),
+                    SiteKind::Mine => None,
+                    SiteKind::Port => None,
+                    SiteKind::BanditCamp => Some(1),
                 };
 
                 Image::new(match &site.kind {
@@ -704,6 +707,9 @@ impl<'a> Widget for MiniMap<'a> {
                     SiteKind::Cave => self.imgs.mmap_site_cave_bg,
                     SiteKind::Tree => self.imgs.mmap_site_tree,
                     SiteKind::Gnarling => self.imgs.mmap_site_gnarling_bg,
+                    SiteKind::Mine => self.imgs.mmap_site_cave_bg,
+                    SiteKind::Port => self.imgs.mmap_site_town_bg,
+                    SiteKind::BanditCamp => self.imgs.mmap_site_gnarling_bg,
                 })
                 .x_y_position_relative_to(
                     state.ids.map_layers[0],
@@ -728,6 +734,9 @@ impl<'a> Widget for MiniMap<'a> {
                     SiteKind::Cave => self.imgs.mmap_site_cave,
                     SiteKind::Tree => self.imgs.mmap_site_tree,
                     SiteKind::Gnarling => self.imgs.mmap_site_gnarling,
+                    SiteKind::Mine => self.imgs.mmap_site_cave,
+                    SiteKind::Port => self.imgs.mmap_site_town,
+                    SiteKind::BanditCamp => self.imgs.mmap_site_gnarling,
                 })
                 .middle_of(state.ids.mmap_site_icons_bgs[i])
                 .w_h(20.0, 20.0)
@@ -1,3 +1,4 @@
+mod achievements;
 mod animation;
 mod bag;
 mod buffs;
@@ -7,14 +8,17 @@ mod crafting;
 mod diary;
 mod esc_menu;
 mod group;
+mod guild;
 mod hotbar;
 pub mod img_ids;
 pub mod item_imgs;
 mod loot_scroller;
+mod mail;
 mod map;
 mod minimap;
 mod overhead;
 mod overitem;
+mod pets;
 mod popup;
 mod prompt_dialog;
 mod settings_window;
@@ -30,6 +34,7 @@ pub use item_imgs::animate_by_pulse;
 pub use loot_scroller::LootMessage;
 pub use settings_window::ScaleChange;
 
+use achievements::Achievements;
 use bag::Bag;
 use buffs::BuffsBar;
 use buttons::Buttons;
@@ -39,11 +44,14 @@ use crafting::Crafting;
 use diary::{Diary, SelectedSkillTree};
 use esc_menu::EscMenu;
 use group::Group;
+use guild::Guild;
 use img_ids::Imgs;
 use item_imgs::ItemImgs;
 use loot_scroller::LootScroller;
+use mail::Mail;
 use map::Map;
 use minimap::{MiniMap, VoxelMinimap};
+use pets::Pets;
 use popup::Popup;
 use prompt_dialog::PromptDialog;
 use serde::{Deserialize, Serialize};
@@ -84,13 +92,16 @@ use common::{
         inventory::{slot::InvSlotId, trade_pricing::TradePricing},
         item::{tool::ToolKind, ItemDesc, MaterialStatManifest, Quality},
         skillset::{skills::Skill, SkillGroupKind},
-        BuffData, BuffKind, Item, MapMarkerChange,
+        BuffData, BuffKind, Item, MapMarkerChange, PetCommand,
     },
     consts::MAX_PICKUP_RANGE,
+    guild::GuildAction,
     link::Is,
+    mail::{MailAction, MAX_MAIL_ATTACHMENTS},
     mounting::Mount,
     outcome::Outcome,
     slowjob::SlowJobPool,
+    states::utils::StageSection,
     terrain::{SpriteKind, TerrainChunk},
     trade::{ReducedInventory, TradeAction},
     uid::Uid,
@@ -169,6 +180,8 @@ const SAY_COLOR: Color = Color::Rgba(1.0, 0.8, 0.8, 1.0);
 const GROUP_COLOR: Color = Color::Rgba(0.47, 0.84, 1.0, 1.0);
 /// Color for factional chat
 const FACTION_COLOR: Color = Color::Rgba(0.24, 1.0, 0.48, 1.0);
+/// Color for guild chat
+const GUILD_COLOR: Color = Color::Rgba(1.0, 0.82, 0.36, 1.0);
 /// Color for regional chat
 const REGION_COLOR: Color = Color::Rgba(0.8, 1.0, 0.8, 1.0);
 /// Color for death messagesw
@@ -296,6 +309,10 @@ widget_ids! {
         esc_menu,
         small_window,
         social_window,
+        mail_window,
+        guild_window,
+        pets_window,
+        achievements_window,
         crafting_window,
         settings_window,
         group_window,
@@ -309,6 +326,10 @@ widget_ids! {
         auto_walk_txt,
         auto_walk_bg,
 
+        // Fishing indicator
+        fishing_txt,
+        fishing_bg,
+
         // Camera clamp indicator
         camera_clamp_txt,
         camera_clamp_bg,
@@ -546,6 +567,10 @@ pub enum Event {
     SettingsChange(SettingsChange),
     AcknowledgePersistenceLoadError,
     MapMarkerEvent(MapMarkerChange),
+    MailAction(MailAction),
+    GuildAction(GuildAction),
+    CommandPet(EcsEntity, PetCommand),
+    RenamePet(EcsEntity, String),
 }
 
 // TODO: Are these the possible layouts we want?
@@ -642,6 +667,7 @@ impl PressBehavior {
 pub struct MapMarkers {
     owned: Option<Vec2<i32>>,
     group: HashMap<Uid, Vec2<i32>>,
+    world_events: HashMap<u64, (comp::WorldEventKind, Vec2<i32>)>,
 }
 
 pub struct Show {
@@ -675,6 +701,16 @@ pub struct Show {
     prompt_dialog: Option<PromptDialogSettings>,
     location_markers: MapMarkers,
     salvage: bool,
+    mail: bool,
+    /// Inventory slots attached to the mail being composed
+    mail_attachments: Vec<InvSlotId>,
+    /// Set when the mail window is opened so that the mailbox is refreshed
+    request_mailbox: bool,
+    achievements: bool,
+    guild: bool,
+    /// Set when the guild window is opened so that its contents are refreshed
+    request_guild_info: bool,
+    pets: bool,
 }
 impl Show {
     fn bag(&mut self, open: bool) {
@@ -706,6 +742,10 @@ impl Show {
             self.crafting = false;
             self.salvage = false;
             self.social = false;
+            self.mail = false;
+            self.achievements = false;
+            self.guild = false;
+            self.pets = false;
             self.diary = false;
             self.want_grab = !open;
         }
@@ -718,11 +758,95 @@ impl Show {
                 self.search_social_players(None);
             }
             self.social = open;
+            self.mail = false;
+            self.achievements = false;
+            self.guild = false;
+            self.pets = false;
             self.diary = false;
             self.want_grab = !open;
         }
     }
 
+    fn mail(&mut self, open: bool) {
+        if !self.esc_menu {
+            if !self.mail && open {
+                // rising edge detector
+                self.request_mailbox = true;
+            }
+            self.mail = open;
+            self.bag = open;
+            self.social = false;
+            self.achievements = false;
+            self.guild = false;
+            self.pets = false;
+            self.diary = false;
+            self.crafting = false;
+            self.salvage = false;
+            self.map = false;
+            self.want_grab = !open;
+
+            if !open {
+                self.mail_attachments.clear();
+            }
+        }
+    }
+
+    fn achievements(&mut self, open: bool) {
+        if !self.esc_menu {
+            self.achievements = open;
+            self.guild = false;
+            self.pets = false;
+            self.social = false;
+            self.mail = false;
+            self.diary = false;
+            self.map = false;
+            self.want_grab = !open;
+        }
+    }
+
+    fn guild(&mut self, open: bool) {
+        if !self.esc_menu {
+            if !self.guild && open {
+                // rising edge detector
+                self.request_guild_info = true;
+            }
+            self.guild = open;
+            self.bag = open;
+            self.social = false;
+            self.mail = false;
+            self.achievements = false;
+            self.pets = false;
+            self.diary = false;
+            self.crafting = false;
+            self.salvage = false;
+            self.map = false;
+            self.want_grab = !open;
+        }
+    }
+
+    fn pets(&mut self, open: bool) {
+        if !self.esc_menu {
+            self.pets = open;
+            self.social = false;
+            self.mail = false;
+            self.achievements = false;
+            self.guild = false;
+            self.diary = false;
+            self.map = false;
+            self.want_grab = !open;
+        }
+    }
+
+    /// Attaches an inventory slot to the mail being composed, or detaches it
+    /// if it is already attached
+    fn toggle_mail_attachment(&mut self, slot: InvSlotId) {
+        if let Some(i) = self.mail_attachments.iter().position(|s| *s == slot) {
+            self.mail_attachments.remove(i);
+        } else if self.mail_attachments.len() < MAX_MAIL_ATTACHMENTS {
+            self.mail_attachments.push(slot);
+        }
+    }
+
     fn crafting(&mut self, open: bool) {
         if !self.esc_menu {
             if !self.crafting && open {
@@ -752,6 +876,10 @@ impl Show {
     fn diary(&mut self, open: bool) {
         if !self.esc_menu {
             self.social = false;
+            self.mail = false;
+            self.achievements = false;
+            self.guild = false;
+            self.pets = false;
             self.crafting = false;
             self.salvage = false;
             self.bag = false;
@@ -771,6 +899,10 @@ impl Show {
             };
             self.bag = false;
             self.social = false;
+            self.mail = false;
+            self.achievements = false;
+            self.guild = false;
+            self.pets = false;
             self.crafting = false;
             self.salvage = false;
             self.diary = false;
@@ -784,6 +916,14 @@ impl Show {
 
     fn toggle_social(&mut self) { self.social(!self.social); }
 
+    fn toggle_mail(&mut self) { self.mail(!self.mail); }
+
+    fn toggle_achievements(&mut self) { self.achievements(!self.achievements); }
+
+    fn toggle_guild(&mut self) { self.guild(!self.guild); }
+
+    fn toggle_pets(&mut self) { self.pets(!self.pets); }
+
     fn toggle_crafting(&mut self) { self.crafting(!self.crafting) }
 
     fn toggle_spell(&mut self) { self.diary(!self.diary) }
@@ -811,6 +951,10 @@ impl Show {
             || self.esc_menu
             || self.map
             || self.social
+            || self.mail
+            || self.achievements
+            || self.guild
+            || self.pets
             || self.crafting
             || self.diary
             || self.help
@@ -824,6 +968,11 @@ impl Show {
             self.intro = false;
             self.map = false;
             self.social = false;
+            self.mail = false;
+            self.achievements = false;
+            self.guild = false;
+            self.pets = false;
+            self.mail_attachments.clear();
             self.diary = false;
             self.crafting = false;
             self.open_windows = Windows::None;
@@ -866,6 +1015,10 @@ impl Show {
             && !self.esc_menu
             && !self.map
             && !self.social
+            && !self.mail
+            && !self.achievements
+            && !self.guild
+            && !self.pets
             && !self.crafting
             && !self.diary
             && !self.help
@@ -896,6 +1049,14 @@ impl Show {
             comp::MapMarkerUpdate::ClearGroup => {
                 self.location_markers.group.clear();
             },
+            comp::MapMarkerUpdate::WorldEvent(id, kind, event) => match event {
+                MapMarkerChange::Update(pos) => {
+                    self.location_markers.world_events.insert(id, (kind, pos));
+                },
+                MapMarkerChange::Remove => {
+                    self.location_markers.world_events.remove(&id);
+                },
+            },
         }
     }
 }
@@ -1072,6 +1233,13 @@ impl Hud {
                 prompt_dialog: None,
                 location_markers: MapMarkers::default(),
                 salvage: false,
+                mail: false,
+                mail_attachments: Vec::new(),
+                request_mailbox: false,
+                achievements: false,
+                guild: false,
+                request_guild_info: false,
+                pets: false,
             },
             to_focus: None,
             //never_show: false,
@@ -1150,6 +1318,7 @@ impl Hud {
             let poises = ecs.read_storage::<comp::Poise>();
             let alignments = ecs.read_storage::<comp::Alignment>();
             let is_mount = ecs.read_storage::<Is<Mount>>();
+            let guild_tags = ecs.read_storage::<comp::GuildTag>();
 
             // Check if there was a persistence load error of the skillset, and if so
             // display a dialog prompt
@@ -1794,6 +1963,12 @@ impl Hud {
                     .set(overitem_id, ui_widgets);
                 } else if let Some(desc) = block.get_sprite().and_then(|s| get_sprite_desc(s, i18n))
                 {
+                    // Seeds are planted by using them from the inventory
+                    let (input, action) = if block.get_sprite() == Some(SpriteKind::TilledSoil) {
+                        (GameInput::Bag, "hud.plant")
+                    } else {
+                        (GameInput::Interact, "hud.use")
+                    };
                     overitem::Overitem::new(
                         desc,
                         overitem::TEXT_COLOR,
@@ -1804,7 +1979,7 @@ impl Hud {
                         overitem_properties,
                         self.pulse,
                         &global_state.window.key_layout,
-                        vec![(GameInput::Interact, i18n.get("hud.use").to_string())],
+                        vec![(input, i18n.get(action).to_string())],
                     )
                     .x_y(0.0, 100.0)
                     .position_ingame(over_pos)
@@ -1888,7 +2063,7 @@ impl Hud {
                 &inventories,
                 players.maybe(),
                 poises.maybe(),
-                (alignments.maybe(), is_mount.maybe()),
+                (alignments.maybe(), is_mount.maybe(), guild_tags.maybe()),
             )
                 .join()
                 .filter(|t| {
@@ -1912,7 +2087,7 @@ impl Hud {
                         inventory,
                         player,
                         poise,
-                        (alignment, is_mount),
+                        (alignment, is_mount, guild_tag),
                     )| {
                         // Use interpolated position if available
                         let pos = interpolated.map_or(pos.0, |i| i.pos);
@@ -1950,6 +2125,7 @@ impl Hud {
 
                         let info = display_overhead_info.then(|| overhead::Info {
                             name: &stats.name,
+                            guild_tag: guild_tag.map(|tag| tag.0.as_str()),
                             health,
                             buffs,
                             energy,
@@ -3162,6 +3338,104 @@ impl Hud {
             }
         }
 
+        // Mail Window
+        if self.show.mail {
+            if std::mem::take(&mut self.show.request_mailbox) {
+                events.push(Event::MailAction(MailAction::RequestMailbox));
+            }
+            for event in Mail::new(&self.show, client, &self.imgs, &self.fonts, i18n)
+                .set(self.ids.mail_window, ui_widgets)
+            {
+                match event {
+                    mail::Event::Close => {
+                        self.show.mail(false);
+                        self.show.want_grab = true;
+                        self.force_ungrab = false;
+                    },
+                    mail::Event::MarkRead(id) => {
+                        events.push(Event::MailAction(MailAction::MarkRead(id)))
+                    },
+                    mail::Event::Claim(id) => events.push(Event::MailAction(MailAction::Claim(id))),
+                    mail::Event::Delete(id) => {
+                        events.push(Event::MailAction(MailAction::Delete(id)))
+                    },
+                    mail::Event::RemoveAttachment(slot) => {
+                        self.show.toggle_mail_attachment(slot)
+                    },
+                    mail::Event::Send {
+                        recipient,
+                        subject,
+                        body,
+                        coins,
+                    } => events.push(Event::MailAction(MailAction::Send {
+                        recipient,
+                        subject,
+                        body,
+                        attachments: std::mem::take(&mut self.show.mail_attachments),
+                        coins,
+                    })),
+                }
+            }
+        }
+
+        // Guild Window
+        if self.show.guild {
+            if std::mem::take(&mut self.show.request_guild_info) {
+                events.push(Event::GuildAction(GuildAction::RequestInfo));
+            }
+            for event in Guild::new(client, &self.imgs, &self.fonts, i18n)
+                .set(self.ids.guild_window, ui_widgets)
+            {
+                match event {
+                    guild::Event::Close => {
+                        self.show.guild(false);
+                        self.show.want_grab = true;
+                        self.force_ungrab = false;
+                    },
+                    guild::Event::Withdraw(index) => {
+                        events.push(Event::GuildAction(GuildAction::Withdraw(index)))
+                    },
+                }
+            }
+        }
+
+        // Pets Window
+        if self.show.pets {
+            let attack_target = info
+                .selected_entity
+                .and_then(|(entity, _)| client.state().read_component_copied::<Uid>(entity));
+            for event in Pets::new(client, &self.imgs, &self.fonts, i18n, attack_target)
+                .set(self.ids.pets_window, ui_widgets)
+            {
+                match event {
+                    pets::Event::Close => {
+                        self.show.pets(false);
+                        self.show.want_grab = true;
+                        self.force_ungrab = false;
+                    },
+                    pets::Event::Command(pet, command) => {
+                        events.push(Event::CommandPet(pet, command))
+                    },
+                    pets::Event::Rename(pet, name) => events.push(Event::RenamePet(pet, name)),
+                }
+            }
+        }
+
+        // Achievements Window
+        if self.show.achievements {
+            for event in Achievements::new(client, &self.imgs, &self.fonts, i18n)
+                .set(self.ids.achievements_window, ui_widgets)
+            {
+                match event {
+                    achievements::Event::Close => {
+                        self.show.achievements(false);
+                        self.show.want_grab = true;
+                        self.force_ungrab = false;
+                    },
+                }
+            }
+        }
+
         // Diary
         if self.show.diary {
             let entity = client.entity();
@@ -3337,6 +3611,39 @@ impl Hud {
                 .set(self.ids.auto_walk_txt, ui_widgets);
         }
 
+        // Fishing indicator
+        let fishing_msg = match client
+            .state()
+            .read_storage::<comp::CharacterState>()
+            .get(client.entity())
+        {
+            Some(comp::CharacterState::Fishing(fishing))
+                if fishing.stage_section == StageSection::Action =>
+            {
+                Some(if fishing.is_biting() {
+                    i18n.get("hud.fishing_bite_indicator")
+                } else {
+                    i18n.get("hud.fishing_wait_indicator")
+                })
+            },
+            _ => None,
+        };
+        if let Some(msg) = fishing_msg {
+            Text::new(msg)
+                .color(TEXT_BG)
+                .mid_top_with_margin_on(ui_widgets.window, indicator_offset)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.fishing_bg, ui_widgets);
+            indicator_offset += 30.0;
+            Text::new(msg)
+                .color(KILL_COLOR)
+                .top_left_with_margins_on(self.ids.fishing_bg, -1.0, -1.0)
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(20))
+                .set(self.ids.fishing_txt, ui_widgets);
+        }
+
         // Camera clamp indicator
         if let Some(cameraclamp_key) = global_state
             .settings
@@ -3566,6 +3873,16 @@ impl Hud {
                             {
                                 events.push(Event::SalvageItem { slot, salvage_pos })
                             }
+                        } else if self.show.mail {
+                            // Using an item while the mail window is open attaches it instead
+                            if let Slot::Inventory(slot) = from {
+                                self.show.toggle_mail_attachment(slot);
+                            }
+                        } else if self.show.guild {
+                            // Using an item while the guild window is open deposits it
+                            if let Slot::Inventory(slot) = from {
+                                events.push(Event::GuildAction(GuildAction::Deposit(slot)));
+                            }
                         } else {
                             events.push(Event::UseSlot {
                                 slot: from,
@@ -3963,6 +4280,22 @@ impl Hud {
                         self.show.toggle_social();
                         true
                     },
+                    GameInput::Mail if state => {
+                        self.show.toggle_mail();
+                        true
+                    },
+                    GameInput::Achievements if state => {
+                        self.show.toggle_achievements();
+                        true
+                    },
+                    GameInput::Guild if state => {
+                        self.show.toggle_guild();
+                        true
+                    },
+                    GameInput::Pets if state => {
+                        self.show.toggle_pets();
+                        true
+                    },
                     GameInput::Crafting if state => {
                         self.show.toggle_crafting();
                         true
@@ -4349,6 +4682,7 @@ pub fn get_sprite_desc(sprite: SpriteKind, localized_strings: &Localization) ->
         | SpriteKind::DungeonChest3
         | SpriteKind::DungeonChest4
         | SpriteKind::DungeonChest5 => "common.sprite.chest",
+        SpriteKind::TilledSoil => "common.sprite.tilled_soil",
         sprite => return Some(Cow::Owned(format!("{:?}", sprite))),
     };
     Some(Cow::Borrowed(localized_strings.get(i18n_key)))
@@ -1,7 +1,7 @@
 use super::{
     cr_color, img_ids::Imgs, DEFAULT_NPC, ENEMY_HP_COLOR, FACTION_COLOR, GROUP_COLOR, GROUP_MEMBER,
-    HP_COLOR, LOW_HP_COLOR, QUALITY_EPIC, REGION_COLOR, SAY_COLOR, STAMINA_COLOR, TELL_COLOR,
-    TEXT_BG, TEXT_COLOR,
+    GUILD_COLOR, HP_COLOR, LOW_HP_COLOR, QUALITY_EPIC, REGION_COLOR, SAY_COLOR, STAMINA_COLOR,
+    TELL_COLOR, TEXT_BG, TEXT_COLOR,
 };
 use crate::{
     game_input::GameInput,
@@ -65,6 +65,8 @@ widget_ids! {
 #[derive(Clone, Copy)]
 pub struct Info<'a> {
     pub name: &'a str,
+    /// Shown in front of the name of guild members
+    pub guild_tag: Option<&'a str>,
     pub health: Option<&'a Health>,
     pub buffs: &'a Buffs,
     pub energy: Option<&'a Energy>,
@@ -198,6 +200,7 @@ impl<'a> Widget for Overhead<'a> {
         const MANA_BAR_Y: f64 = MANA_BAR_HEIGHT / 2.0;
         if let Some(Info {
             name,
+            guild_tag,
             health,
             buffs,
             energy,
@@ -304,6 +307,10 @@ impl<'a> Widget for Overhead<'a> {
                     });
             }
             // Name
+            let name = &match guild_tag {
+                Some(tag) => format!("[{}] {}", tag, name),
+                None => name.to_string(),
+            };
             Text::new(name)
                 //Text::new(&format!("{} [{:?}]", name, combat_rating)) // <- Uncomment to debug combat ratings
                 .font_id(self.fonts.cyri.conrod_id)
@@ -685,6 +692,7 @@ fn bubble_color(bubble: &SpeechBubble, dark_mode: bool) -> (Color, Color) {
         SpeechBubbleType::Region => REGION_COLOR,
         SpeechBubbleType::Group => GROUP_COLOR,
         SpeechBubbleType::Faction => FACTION_COLOR,
+        SpeechBubbleType::Guild => GUILD_COLOR,
         SpeechBubbleType::World
         | SpeechBubbleType::Quest
         | SpeechBubbleType::Trade
@@ -705,6 +713,7 @@ fn bubble_icon(sb: &SpeechBubble, imgs: &Imgs) -> conrod_core::image::Id {
         SpeechBubbleType::Region => imgs.chat_region_small,
         SpeechBubbleType::Group => imgs.chat_group_small,
         SpeechBubbleType::Faction => imgs.chat_faction_small,
+        SpeechBubbleType::Guild => imgs.chat_faction_small,
         SpeechBubbleType::World => imgs.chat_world_small,
         SpeechBubbleType::Quest => imgs.nothing, // TODO not implemented
         SpeechBubbleType::Trade => imgs.nothing, // TODO not implemented
@@ -0,0 +1,385 @@
+use super::{
+    img_ids::Imgs, TEXT_COLOR, TEXT_COLOR_3, TEXT_GRAY_COLOR, UI_HIGHLIGHT_0, UI_MAIN, XP_COLOR,
+};
+use crate::ui::fonts::Fonts;
+use client::{self, Client};
+use common::{
+    character::MAX_NAME_LENGTH,
+    comp::{Alignment, Pet, PetCommand, Stats},
+    uid::Uid,
+};
+use conrod_core::{
+    color,
+    widget::{self, Button, Image, Rectangle, Scrollbar, Text, TextEdit},
+    widget_ids, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
+};
+use i18n::Localization;
+use specs::{Entity as EcsEntity, Join, WorldExt};
+
+widget_ids! {
+    pub struct Ids {
+        frame,
+        close,
+        title_align,
+        title,
+        bg,
+        icon,
+        no_pets_txt,
+        list_align,
+        list_scrollbar,
+        pet_entries[],
+        level_txt,
+        exp_txt,
+        command_txt,
+        follow_button,
+        stay_button,
+        attack_button,
+        passive_button,
+        name_input,
+        name_input_bg,
+        rename_button,
+    }
+}
+
+pub struct State {
+    ids: Ids,
+    selected: Option<EcsEntity>,
+    name: String,
+}
+
+#[derive(WidgetCommon)]
+pub struct Pets<'a> {
+    client: &'a Client,
+    imgs: &'a Imgs,
+    fonts: &'a Fonts,
+    localized_strings: &'a Localization,
+    /// The entity the player selected, which pets can be told to attack
+    attack_target: Option<Uid>,
+
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+}
+
+impl<'a> Pets<'a> {
+    pub fn new(
+        client: &'a Client,
+        imgs: &'a Imgs,
+        fonts: &'a Fonts,
+        localized_strings: &'a Localization,
+        attack_target: Option<Uid>,
+    ) -> Self {
+        Self {
+            client,
+            imgs,
+            fonts,
+            localized_strings,
+            attack_target,
+            common: widget::CommonBuilder::default(),
+        }
+    }
+}
+
+pub enum Event {
+    Close,
+    Command(EcsEntity, PetCommand),
+    Rename(EcsEntity, String),
+}
+
+struct PetInfo {
+    entity: EcsEntity,
+    name: String,
+    level: u16,
+    experience: u32,
+    experience_needed: Option<u32>,
+    command: PetCommand,
+}
+
+fn command_key(command: PetCommand) -> &'static str {
+    match command {
+        PetCommand::Follow => "hud.pets.follow",
+        PetCommand::Stay => "hud.pets.stay",
+        PetCommand::Attack(_) => "hud.pets.attack",
+        PetCommand::Passive => "hud.pets.passive",
+    }
+}
+
+impl<'a> Widget for Pets<'a> {
+    type Event = Vec<Event>;
+    type State = State;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        Self::State {
+            ids: Ids::new(id_gen),
+            selected: None,
+            name: String::new(),
+        }
+    }
+
+    fn style(&self) -> Self::Style {}
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { state, ui, .. } = args;
+        let mut events = Vec::new();
+        let i18n = self.localized_strings;
+
+        // Window BG
+        Image::new(self.imgs.social_bg_on)
+            .bottom_left_with_margins_on(ui.window, 308.0, 25.0)
+            .color(Some(UI_MAIN))
+            .w_h(280.0, 460.0)
+            .set(state.ids.bg, ui);
+        // Window frame
+        Image::new(self.imgs.social_frame_on)
+            .middle_of(state.ids.bg)
+            .color(Some(UI_HIGHLIGHT_0))
+            .w_h(280.0, 460.0)
+            .set(state.ids.frame, ui);
+
+        // Icon
+        Image::new(self.imgs.social)
+            .w_h(30.0, 30.0)
+            .top_left_with_margins_on(state.ids.frame, 6.0, 6.0)
+            .set(state.ids.icon, ui);
+        // X-Button
+        if Button::image(self.imgs.close_button)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_button_hover)
+            .press_image(self.imgs.close_button_press)
+            .top_right_with_margins_on(state.ids.frame, 0.0, 0.0)
+            .set(state.ids.close, ui)
+            .was_clicked()
+        {
+            events.push(Event::Close);
+        }
+
+        // Title
+        Rectangle::fill_with([212.0, 42.0], color::TRANSPARENT)
+            .top_left_with_margins_on(state.ids.frame, 2.0, 44.0)
+            .set(state.ids.title_align, ui);
+        Text::new(i18n.get("hud.pets"))
+            .middle_of(state.ids.title_align)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(TEXT_COLOR)
+            .set(state.ids.title, ui);
+
+        // Pets of the player which are in range
+        let ecs = self.client.state().ecs();
+        let player_uid = ecs.read_storage::<Uid>().get(self.client.entity()).copied();
+        let mut pets = (
+            &ecs.entities(),
+            &ecs.read_storage::<Pet>(),
+            &ecs.read_storage::<Alignment>(),
+            &ecs.read_storage::<Stats>(),
+        )
+            .join()
+            .filter(|(_, _, alignment, _)| {
+                matches!(alignment, Alignment::Owned(owner) if Some(*owner) == player_uid)
+            })
+            .map(|(entity, pet, _, stats)| PetInfo {
+                entity,
+                name: stats.name.clone(),
+                level: pet.level(),
+                experience: pet.experience(),
+                experience_needed: pet.experience_needed(),
+                command: pet.command,
+            })
+            .collect::<Vec<_>>();
+        pets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if pets.is_empty() {
+            Text::new(i18n.get("hud.pets.no_pets"))
+                .mid_top_with_margin_on(state.ids.frame, 60.0)
+                .w(250.0)
+                .wrap_by_word()
+                .font_id(self.fonts.cyri.conrod_id)
+                .font_size(self.fonts.cyri.scale(14))
+                .color(TEXT_GRAY_COLOR)
+                .set(state.ids.no_pets_txt, ui);
+            return events;
+        }
+
+        let selected = match pets.iter().find(|pet| Some(pet.entity) == state.selected) {
+            Some(pet) => pet,
+            None => {
+                state.update(|s| {
+                    s.selected = Some(pets[0].entity);
+                    s.name = pets[0].name.clone();
+                });
+                &pets[0]
+            },
+        };
+
+        // Pet list
+        Rectangle::fill_with([270.0, 190.0], color::TRANSPARENT)
+            .mid_top_with_margin_on(state.ids.frame, 50.0)
+            .scroll_kids_vertically()
+            .set(state.ids.list_align, ui);
+        Scrollbar::y_axis(state.ids.list_align)
+            .thickness(4.0)
+            .color(color::rgba(0.79, 1.09, 1.09, 0.0))
+            .set(state.ids.list_scrollbar, ui);
+
+        if state.ids.pet_entries.len() < pets.len() {
+            state.update(|s| {
+                s.ids
+                    .pet_entries
+                    .resize(pets.len(), &mut ui.widget_id_generator())
+            });
+        }
+        for (i, pet) in pets.iter().enumerate() {
+            let is_selected = pet.entity == selected.entity;
+            let button = Button::image(if is_selected {
+                self.imgs.selection
+            } else {
+                self.imgs.nothing
+            })
+            .hover_image(self.imgs.selection_hover)
+            .press_image(self.imgs.selection_press)
+            .w_h(260.0, 20.0)
+            .label(&format!(
+                "{} - {} {}",
+                pet.name,
+                i18n.get("hud.pets.level"),
+                pet.level
+            ))
+            .label_font_size(self.fonts.cyri.scale(14))
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .label_color(TEXT_COLOR);
+            let button = if i == 0 {
+                button.mid_top_with_margin_on(state.ids.list_align, 1.0)
+            } else {
+                button.down_from(state.ids.pet_entries[i - 1], 1.0)
+            };
+            if button.set(state.ids.pet_entries[i], ui).was_clicked() {
+                let (entity, name) = (pet.entity, pet.name.clone());
+                state.update(|s| {
+                    s.selected = Some(entity);
+                    s.name = name;
+                });
+            }
+        }
+
+        // Details of the selected pet
+        Text::new(&format!(
+            "{} {}",
+            i18n.get("hud.pets.level"),
+            selected.level
+        ))
+        .top_left_with_margins_on(state.ids.frame, 250.0, 10.0)
+        .font_id(self.fonts.cyri.conrod_id)
+        .font_size(self.fonts.cyri.scale(14))
+        .color(TEXT_COLOR)
+        .set(state.ids.level_txt, ui);
+        Text::new(&match selected.experience_needed {
+            Some(needed) => format!(
+                "{}: {}/{}",
+                i18n.get("hud.pets.experience"),
+                selected.experience,
+                needed
+            ),
+            None => i18n.get("hud.pets.max_level").to_string(),
+        })
+        .right_from(state.ids.level_txt, 20.0)
+        .font_id(self.fonts.cyri.conrod_id)
+        .font_size(self.fonts.cyri.scale(14))
+        .color(XP_COLOR)
+        .set(state.ids.exp_txt, ui);
+
+        // Commands
+        Text::new(&format!(
+            "{}: {}",
+            i18n.get("hud.pets.command"),
+            i18n.get(command_key(selected.command))
+        ))
+        .down_from(state.ids.level_txt, 12.0)
+        .font_id(self.fonts.cyri.conrod_id)
+        .font_size(self.fonts.cyri.scale(14))
+        .color(TEXT_COLOR)
+        .set(state.ids.command_txt, ui);
+
+        let commands = [
+            (Some(PetCommand::Follow), state.ids.follow_button),
+            (Some(PetCommand::Stay), state.ids.stay_button),
+            (
+                self.attack_target.map(PetCommand::Attack),
+                state.ids.attack_button,
+            ),
+            (Some(PetCommand::Passive), state.ids.passive_button),
+        ];
+        let labels = [
+            "hud.pets.follow",
+            "hud.pets.stay",
+            "hud.pets.attack",
+            "hud.pets.passive",
+        ];
+        for (i, ((command, id), label)) in commands.iter().zip(labels).enumerate() {
+            // Attacking needs a selected target
+            let enabled = command.is_some();
+            let button = Button::image(self.imgs.button)
+                .w_h(62.0, 26.0)
+                .hover_image(if enabled {
+                    self.imgs.button_hover
+                } else {
+                    self.imgs.button
+                })
+                .press_image(if enabled {
+                    self.imgs.button_press
+                } else {
+                    self.imgs.button
+                })
+                .label(i18n.get(label))
+                .label_y(conrod_core::position::Relative::Scalar(3.0))
+                .label_color(if enabled { TEXT_COLOR } else { TEXT_COLOR_3 })
+                .label_font_size(self.fonts.cyri.scale(13))
+                .label_font_id(self.fonts.cyri.conrod_id);
+            let button = if i == 0 {
+                button.down_from(state.ids.command_txt, 8.0)
+            } else {
+                button.right_from(commands[i - 1].1, 4.0)
+            };
+            if let (true, Some(command)) = (button.set(*id, ui).was_clicked(), command) {
+                events.push(Event::Command(selected.entity, *command));
+            }
+        }
+
+        // Naming
+        if let Some(string) = TextEdit::new(&state.name)
+            .down_from(state.ids.follow_button, 20.0)
+            .w_h(170.0, 20.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(14))
+            .color(TEXT_COLOR)
+            .set(state.ids.name_input, ui)
+        {
+            let string = string.chars().take(MAX_NAME_LENGTH).collect();
+            state.update(|s| s.name = string);
+        }
+        Rectangle::fill([174.0, 24.0])
+            .top_left_with_margins_on(state.ids.name_input, -2.0, -2.0)
+            .hsla(0.0, 0.0, 0.0, 0.7)
+            .depth(1.0)
+            .parent(state.ids.bg)
+            .set(state.ids.name_input_bg, ui);
+        let can_rename = !state.name.trim().is_empty() && state.name != selected.name;
+        if Button::image(self.imgs.button)
+            .w_h(80.0, 26.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .right_from(state.ids.name_input, 8.0)
+            .label(i18n.get("hud.pets.rename"))
+            .label_y(conrod_core::position::Relative::Scalar(3.0))
+            .label_color(if can_rename { TEXT_COLOR } else { TEXT_COLOR_3 })
+            .label_font_size(self.fonts.cyri.scale(13))
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .set(state.ids.rename_button, ui)
+            .was_clicked()
+            && can_rename
+        {
+            events.push(Event::Rename(selected.entity, state.name.clone()));
+        }
+
+        events
+    }
+}
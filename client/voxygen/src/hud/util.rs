@@ -1,6 +1,7 @@
 use super::img_ids;
 use common::{
     comp::{
+        buff::{ResistanceKind, Resistances},
         inventory::trade_pricing::TradePricing,
         item::{
             armor::{Armor, ArmorKind, Protection},
@@ -116,6 +117,7 @@ pub fn stats_count(item: &dyn ItemDesc) -> usize {
                     + armor.stats.stealth().is_some() as usize
                     + armor.stats.crit_power().is_some() as usize
                     + armor.stats.poise_resilience().is_some() as usize
+                    + armor.stats.resistances().is_some() as usize
             }
         },
         ItemKind::Tool(_) => 4,
@@ -253,6 +255,7 @@ fn tool_kind<'a>(tool: &Tool, i18n: &'a Localization) -> &'a str {
         ToolKind::Debug => i18n.get("common.tool.debug"),
         ToolKind::Farming => i18n.get("common.tool.farming"),
         ToolKind::Pick => i18n.get("common.tool.pick"),
+        ToolKind::Fishing => i18n.get("common.tool.fishing"),
         ToolKind::Empty => i18n.get("common.empty"),
     };
     kind
@@ -321,6 +324,23 @@ pub fn protec2string(stat: Protection) -> String {
     }
 }
 
+/// Output the resistances of armor as a list of percentages
+pub fn resistances2string(resistances: Resistances, i18n: &Localization) -> String {
+    [
+        (ResistanceKind::Fire, "common.stats.resistance.fire"),
+        (ResistanceKind::Frost, "common.stats.resistance.frost"),
+        (ResistanceKind::Poison, "common.stats.resistance.poison"),
+        (ResistanceKind::Bleeding, "common.stats.resistance.bleeding"),
+        (ResistanceKind::Curse, "common.stats.resistance.curse"),
+        (ResistanceKind::Entangle, "common.stats.resistance.entangle"),
+    ]
+    .iter()
+    .filter(|(kind, _)| resistances.get(*kind).abs() > f32::EPSILON)
+    .map(|(kind, key)| format!("{} {:.0}%", i18n.get(key), resistances.get(*kind) * 100.0))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
 pub fn ability_image(imgs: &img_ids::Imgs, ability_id: &str) -> image::Id {
     match ability_id {
         // Debug stick
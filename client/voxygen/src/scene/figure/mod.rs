@@ -1222,6 +1222,26 @@ impl FigureMgr {
                                 skeleton_attr,
                             )
                         },
+                        CharacterState::Fishing(s) => {
+                            let stage_time = s.timer.as_secs_f32();
+                            let stage_progress = match s.stage_section {
+                                StageSection::Buildup => {
+                                    stage_time / s.static_data.cast_duration.as_secs_f32()
+                                },
+                                StageSection::Action => stage_time,
+                                StageSection::Recover => {
+                                    stage_time / s.static_data.reel_duration.as_secs_f32()
+                                },
+                                _ => 0.0,
+                            };
+                            anim::character::FishingAnimation::update_skeleton(
+                                &target_base,
+                                (Some(s.stage_section), s.is_biting(), time),
+                                stage_progress,
+                                &mut state_animation_rate,
+                                skeleton_attr,
+                            )
+                        },
                         CharacterState::SpriteInteract(s) => {
                             let stage_time = s.timer.as_secs_f32();
                             let sprite_pos = s.static_data.sprite_pos;
@@ -16,13 +16,14 @@ use common::{
         inventory::slot::{EquipSlot, Slot},
         invite::InviteKind,
         item::{tool::ToolKind, ItemDef, ItemDesc},
+        pet::is_mountable,
         ChatMsg, ChatType, InputKind, InventoryUpdateEvent, Pos, Stats, UtteranceKind, Vel,
     },
     consts::MAX_MOUNT_RANGE,
     link::Is,
     mounting::Mount,
     outcome::Outcome,
-    terrain::{Block, BlockKind},
+    terrain::{Block, BlockKind, SpriteKind},
     trade::TradeResult,
     util::{Dir, Plane},
     vol::ReadVol,
@@ -156,6 +157,8 @@ impl SessionState {
                     let kind_str = match kind {
                         InviteKind::Group => "Group",
                         InviteKind::Trade => "Trade",
+                        InviteKind::Guild => "Guild",
+                        InviteKind::Duel => "Duel",
                     };
                     let target_name = match client.player_list().get(&target) {
                         Some(info) => info.player_alias.clone(),
@@ -357,17 +360,20 @@ impl PlayState for SessionState {
                 .get(player_entity)
                 .map_or_else(|| false, |cb| cb.enabled);
 
-            let is_mining = client
+            // Picks mine blocks and hoes till the soil, both by swinging at them
+            let mine_tool = client
                 .inventories()
                 .get(player_entity)
                 .and_then(|inv| inv.equipped(EquipSlot::ActiveMainhand))
                 .and_then(|item| item.tool())
-                .map_or(false, |tool| tool.kind == ToolKind::Pick)
-                && client.is_wielding() == Some(true);
+                .map(|tool| tool.kind)
+                .filter(|kind| matches!(kind, ToolKind::Pick | ToolKind::Farming))
+                .filter(|_| client.is_wielding() == Some(true));
+            let is_mining = mine_tool.is_some();
 
             // Check to see whether we're aiming at anything
             let (build_target, collect_target, entity_target, mine_target, terrain_target) =
-                targets_under_cursor(&client, cam_pos, cam_dir, can_build, is_mining);
+                targets_under_cursor(&client, cam_pos, cam_dir, can_build, mine_tool);
 
             self.interactable = select_interactable(
                 &client,
@@ -637,23 +643,27 @@ impl PlayState for SessionState {
                                         .copied();
                                     if let Some(player_pos) = player_pos {
                                         // Find closest mountable entity
+                                        let bodies = client.state().ecs().read_storage::<comp::Body>();
+                                        let player_body = bodies.get(client.entity());
                                         let closest_mountable_entity = (
                                             &client.state().ecs().entities(),
                                             &client.state().ecs().read_storage::<comp::Pos>(),
-                                            // TODO: More cleverly filter by things that can actually be mounted
                                             !&client.state().ecs().read_storage::<Is<Mount>>(),
                                             client.state().ecs().read_storage::<comp::Alignment>().maybe(),
+                                            &bodies,
                                         )
                                             .join()
-                                            .filter(|(entity, _, _, _)| *entity != client.entity())
-                                            .filter(|(_, _, _, alignment)| matches!(alignment, Some(comp::Alignment::Owned(owner)) if Some(*owner) == client.uid()))
-                                            .map(|(entity, pos, _, _)| {
+                                            .filter(|(entity, _, _, _, _)| *entity != client.entity())
+                                            .filter(|(_, _, _, alignment, _)| matches!(alignment, Some(comp::Alignment::Owned(owner)) if Some(*owner) == client.uid()))
+                                            .filter(|(_, _, _, _, body)| is_mountable(body, player_body))
+                                            .map(|(entity, pos, _, _, _)| {
                                                 (entity, player_pos.0.distance_squared(pos.0))
                                             })
                                             .filter(|(_, dist_sqr)| {
                                                 *dist_sqr < MAX_MOUNT_RANGE.powi(2)
                                             })
                                             .min_by_key(|(_, dist_sqr)| OrderedFloat(*dist_sqr));
+                                        drop(bodies);
                                         if let Some((mountee_entity, _)) = closest_mountable_entity
                                         {
                                             client.mount(mountee_entity);
@@ -669,7 +679,12 @@ impl PlayState for SessionState {
                                             Interactable::Block(block, pos, interaction) => {
                                                 match interaction {
                                                     Interaction::Collect => {
-                                                        if block.is_collectible() {
+                                                        // Seeds are planted by using them from
+                                                        // the inventory instead
+                                                        if block.is_collectible()
+                                                            && block.get_sprite()
+                                                                != Some(SpriteKind::TilledSoil)
+                                                        {
                                                             client.collect_block(pos);
                                                         }
                                                     },
@@ -1154,7 +1169,27 @@ impl PlayState for SessionState {
                             };
                         }
 
-                        if move_allowed {
+                        // Using a seed while looking at tilled soil plants it there
+                        let plant_at = match (slot, self.interactable) {
+                            (
+                                Slot::Inventory(inv_slot),
+                                Some(Interactable::Block(block, pos, _)),
+                            ) if block.get_sprite() == Some(SpriteKind::TilledSoil) => self
+                                .client
+                                .borrow()
+                                .inventories()
+                                .get(self.client.borrow().entity())
+                                .and_then(|inventory| inventory.get(inv_slot))
+                                .and_then(|item| {
+                                    SpriteKind::from_seed_item(item.item_definition_id())
+                                })
+                                .map(|_| (pos, inv_slot)),
+                            _ => None,
+                        };
+
+                        if let Some((pos, seed)) = plant_at {
+                            self.client.borrow_mut().plant_seed(pos, seed);
+                        } else if move_allowed {
                             self.client.borrow_mut().use_slot(slot);
                         }
                     },
@@ -1375,6 +1410,18 @@ impl PlayState for SessionState {
                     HudEvent::MapMarkerEvent(event) => {
                         self.client.borrow_mut().map_marker_event(event);
                     },
+                    HudEvent::MailAction(action) => {
+                        self.client.borrow_mut().perform_mail_action(action);
+                    },
+                    HudEvent::GuildAction(action) => {
+                        self.client.borrow_mut().perform_guild_action(action);
+                    },
+                    HudEvent::CommandPet(pet, command) => {
+                        self.client.borrow_mut().command_pet(pet, command);
+                    },
+                    HudEvent::RenamePet(pet, name) => {
+                        self.client.borrow_mut().rename_pet(pet, name);
+                    },
                 }
             }
 
@@ -3,7 +3,7 @@ use vek::*;
 
 use client::{self, Client};
 use common::{
-    comp,
+    comp::{self, tool::ToolKind},
     consts::MAX_PICKUP_RANGE,
     link::Is,
     mounting::Mount,
@@ -47,7 +47,7 @@ pub(super) fn targets_under_cursor(
     cam_pos: Vec3<f32>,
     cam_dir: Vec3<f32>,
     can_build: bool,
-    is_mining: bool,
+    mine_tool: Option<ToolKind>,
 ) -> (
     Option<Target<Build>>,
     Option<Target<Collectable>>,
@@ -99,9 +99,11 @@ pub(super) fn targets_under_cursor(
     };
 
     let (collect_pos, _, collect_cam_ray) = find_pos(|b: Block| b.is_collectible());
-    let (mine_pos, _, mine_cam_ray) = is_mining
-        .then(|| find_pos(|b: Block| b.mine_tool().is_some()))
-        .unwrap_or((None, None, None));
+    let (mine_pos, _, mine_cam_ray) = match mine_tool {
+        Some(ToolKind::Pick) => find_pos(|b: Block| b.mine_tool().is_some()),
+        Some(ToolKind::Farming) => find_pos(|b: Block| b.is_tillable()),
+        _ => (None, None, None),
+    };
     let (solid_pos, place_block_pos, solid_cam_ray) = find_pos(|b: Block| b.is_filled());
 
     // See if ray hits entities
@@ -34,10 +34,13 @@ impl ChatFilter {
             ChatType::Kill(_, u) => self.death_all || self.death_group && group_members.contains(u),
             ChatType::GroupMeta(_) => true,   //todo
             ChatType::FactionMeta(_) => true, //todo
+            ChatType::GuildMeta(_) => true,
             ChatType::Tell(..) => true,
             ChatType::Say(_) => self.message_all || self.message_say,
             ChatType::Group(..) => self.message_all || self.message_group,
             ChatType::Faction(..) => self.message_all || self.message_faction,
+            // Guild chat shares the faction filter
+            ChatType::Guild(..) => self.message_all || self.message_faction,
             ChatType::Region(_) => self.message_all || self.message_region,
             ChatType::World(_) => self.message_all || self.message_world,
             ChatType::Npc(..) => true,
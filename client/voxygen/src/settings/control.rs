@@ -149,6 +149,10 @@ impl ControlSettings {
             GameInput::Bag => KeyMouse::Key(VirtualKeyCode::B),
             GameInput::Trade => KeyMouse::Key(VirtualKeyCode::T),
             GameInput::Social => KeyMouse::Key(VirtualKeyCode::O),
+            GameInput::Mail => KeyMouse::Key(VirtualKeyCode::U),
+            GameInput::Achievements => KeyMouse::Key(VirtualKeyCode::V),
+            GameInput::Guild => KeyMouse::Key(VirtualKeyCode::I),
+            GameInput::Pets => KeyMouse::Key(VirtualKeyCode::Z),
             GameInput::Crafting => KeyMouse::Key(VirtualKeyCode::C),
             GameInput::Spellbook => KeyMouse::Key(VirtualKeyCode::P),
             GameInput::Settings => KeyMouse::Key(VirtualKeyCode::F10),
@@ -926,6 +926,43 @@ impl<'a> Widget for ItemTooltip<'a> {
                             );
                         }
 
+                        // Resistances
+                        if let Some(resistances) = armor.stats.resistances() {
+                            widget::Text::new(&format!(
+                                "{} : {}",
+                                i18n.get("common.stats.resistances"),
+                                util::resistances2string(resistances, i18n)
+                            ))
+                            .graphics_for(id)
+                            .parent(id)
+                            .with_style(self.style.desc)
+                            .color(text_color)
+                            .and(|t| {
+                                match armor.stats.poise_resilience().is_some() as usize
+                                    + armor.stats.energy_max().is_some() as usize
+                                    + armor.stats.energy_reward().is_some() as usize
+                                    + armor.stats.crit_power().is_some() as usize
+                                    + armor.stats.stealth().is_some() as usize
+                                {
+                                    0 => t
+                                        .x_align_to(
+                                            state.ids.item_frame,
+                                            conrod_core::position::Align::Start,
+                                        )
+                                        .down_from(state.ids.item_frame, V_PAD),
+                                    x => t.down_from(state.ids.stats[x - 1], V_PAD_STATS),
+                                }
+                            })
+                            .set(
+                                state.ids.stats[armor.stats.poise_resilience().is_some() as usize
+                                    + armor.stats.energy_max().is_some() as usize
+                                    + armor.stats.energy_reward().is_some() as usize
+                                    + armor.stats.crit_power().is_some() as usize
+                                    + armor.stats.stealth().is_some() as usize],
+                                ui,
+                            );
+                        }
+
                         // Slots
                         if item.num_slots() > 0 {
                             widget::Text::new(&format!(
@@ -943,6 +980,7 @@ impl<'a> Widget for ItemTooltip<'a> {
                                     + armor.stats.energy_reward().is_some() as usize
                                     + armor.stats.crit_power().is_some() as usize
                                     + armor.stats.stealth().is_some() as usize
+                                    + armor.stats.resistances().is_some() as usize
                                 {
                                     0 => t
                                         .x_align_to(
@@ -958,7 +996,8 @@ impl<'a> Widget for ItemTooltip<'a> {
                                     + armor.stats.energy_max().is_some() as usize
                                     + armor.stats.energy_reward().is_some() as usize
                                     + armor.stats.crit_power().is_some() as usize
-                                    + armor.stats.stealth().is_some() as usize],
+                                    + armor.stats.stealth().is_some() as usize
+                                    + armor.stats.resistances().is_some() as usize],
                                 ui,
                             );
                         }
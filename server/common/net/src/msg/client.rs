@@ -3,6 +3,8 @@ use common::{
     character::CharacterId,
     comp,
     comp::{Skill, SkillGroupKind},
+    guild::GuildAction,
+    mail::MailAction,
     terrain::block::Block,
 };
 use serde::{Deserialize, Serialize};
@@ -79,6 +81,8 @@ pub enum ClientGeneral {
     UnlockSkillGroup(SkillGroupKind),
     RequestSiteInfo(SiteId),
     UpdateMapMarker(comp::MapMarkerChange),
+    MailAction(MailAction),
+    GuildAction(GuildAction),
     //Only in Game, via terrain stream
     TerrainChunkRequest {
         key: Vec2<i32>,
@@ -134,7 +138,9 @@ impl ClientMsg {
                         | ClientGeneral::RequestPlayerPhysics { .. }
                         | ClientGeneral::RequestLossyTerrainCompression { .. }
                         | ClientGeneral::AcknowledgePersistenceLoadError
-                        | ClientGeneral::UpdateMapMarker(_) => {
+                        | ClientGeneral::UpdateMapMarker(_)
+                        | ClientGeneral::MailAction(_)
+                        | ClientGeneral::GuildAction(_) => {
                             c_type == ClientType::Game && presence.is_some()
                         },
                         //Always possible
@@ -7,6 +7,8 @@ use common::{
     calendar::Calendar,
     character::{self, CharacterItem},
     comp::{self, invite::InviteKind, item::MaterialStatManifest},
+    guild::GuildInfo,
+    mail::Mail,
     outcome::Outcome,
     recipe::RecipeBook,
     resources::TimeOfDay,
@@ -190,6 +192,11 @@ pub enum ServerGeneral {
     /// Economic information about sites
     SiteEconomy(EconomyInfo),
     MapMarker(comp::MapMarkerUpdate),
+    /// The current contents of the character's mailbox
+    MailboxUpdate(Vec<Mail>),
+    /// The current state of the character's guild, or `None` if they are not
+    /// in one
+    GuildUpdate(Option<GuildInfo>),
 }
 
 impl ServerGeneral {
@@ -300,7 +307,9 @@ impl ServerMsg {
                         | ServerGeneral::UpdatePendingTrade(_, _, _)
                         | ServerGeneral::FinishedTrade(_)
                         | ServerGeneral::SiteEconomy(_)
-                        | ServerGeneral::MapMarker(_) => {
+                        | ServerGeneral::MapMarker(_)
+                        | ServerGeneral::MailboxUpdate(_)
+                        | ServerGeneral::GuildUpdate(_) => {
                             c_type == ClientType::Game && presence.is_some()
                         },
                         // Always possible
@@ -145,6 +145,9 @@ pub enum SiteKind {
     Cave,
     Tree,
     Gnarling,
+    Mine,
+    Port,
+    BanditCamp,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,115 @@
+//! Achievements are unlocked by characters once their statistics reach the
+//! requirements set out in the achievement manifest.
+use crate::{
+    assets::{self, AssetExt, AssetHandle},
+    comp::{Stat, Statistics},
+};
+use serde::{Deserialize, Serialize};
+
+/// What a character needs to have done to unlock an achievement
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Requirement {
+    /// A single statistic must reach the given value
+    Stat(Stat, u64),
+    /// The total number of kills, of any body type, must reach the given value
+    TotalKills(u64),
+    /// The total number of deaths, from any cause, must reach the given value
+    TotalDeaths(u64),
+}
+
+impl Requirement {
+    /// Current progress towards the requirement, and the value needed to
+    /// fulfil it
+    pub fn progress(&self, statistics: &Statistics) -> (u64, u64) {
+        match self {
+            Requirement::Stat(stat, target) => (statistics.get(stat), *target),
+            Requirement::TotalKills(target) => (statistics.total_kills(), *target),
+            Requirement::TotalDeaths(target) => (statistics.total_deaths(), *target),
+        }
+    }
+
+    pub fn is_met(&self, statistics: &Statistics) -> bool {
+        let (current, target) = self.progress(statistics);
+        current >= target
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Achievement {
+    /// Unique identifier, stored with the character once unlocked
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub requirement: Requirement,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AchievementManifest(pub Vec<Achievement>);
+
+impl assets::Asset for AchievementManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl AchievementManifest {
+    pub fn iter(&self) -> impl Iterator<Item = &Achievement> { self.0.iter() }
+
+    pub fn get(&self, id: &str) -> Option<&Achievement> {
+        self.0.iter().find(|achievement| achievement.id == id)
+    }
+
+    /// Achievements whose requirements are met by the statistics but which
+    /// have not yet been unlocked
+    pub fn newly_unlocked<'a>(
+        &'a self,
+        statistics: &'a Statistics,
+    ) -> impl Iterator<Item = &'a Achievement> + 'a {
+        self.0.iter().filter(move |achievement| {
+            !statistics.has_achievement(&achievement.id)
+                && achievement.requirement.is_met(statistics)
+        })
+    }
+}
+
+pub fn default_achievement_manifest() -> AssetHandle<AchievementManifest> {
+    AchievementManifest::load_expect("common.achievements")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_achievement_manifest() {
+        let manifest = default_achievement_manifest();
+        let manifest = manifest.read();
+        for (i, achievement) in manifest.iter().enumerate() {
+            assert!(
+                manifest.0[..i]
+                    .iter()
+                    .all(|other| other.id != achievement.id),
+                "Duplicate achievement id: {}",
+                achievement.id
+            );
+        }
+    }
+
+    #[test]
+    fn newly_unlocked_achievements() {
+        let manifest = AchievementManifest(vec![Achievement {
+            id: String::from("glider"),
+            title: String::new(),
+            description: String::new(),
+            requirement: Requirement::Stat(Stat::DistanceGlided, 100),
+        }]);
+        let mut statistics = Statistics::default();
+        statistics.increment(Stat::DistanceGlided, 99);
+        assert_eq!(manifest.newly_unlocked(&statistics).count(), 0);
+        statistics.increment(Stat::DistanceGlided, 1);
+        assert_eq!(manifest.newly_unlocked(&statistics).count(), 1);
+        statistics.unlock_achievement(String::from("glider"));
+        assert_eq!(manifest.newly_unlocked(&statistics).count(), 0);
+    }
+}
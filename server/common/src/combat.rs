@@ -2,6 +2,7 @@ use crate::comp::buff::{Buff, BuffChange, BuffData, BuffKind, BuffSource};
 #[cfg(not(target_arch = "wasm32"))]
 use crate::{
     comp::{
+        buff::BuffManifest,
         inventory::{
             item::{
                 armor::Protection,
@@ -12,7 +13,7 @@ use crate::{
         },
         skillset::SkillGroupKind,
         Alignment, Body, CharacterState, Combo, Energy, Health, HealthChange, Inventory, Ori,
-        Player, Poise, PoiseChange, SkillSet, Stats,
+        Player, Poise, PoiseChange, PvpEngagement, SkillSet, Stats,
     },
     event::ServerEvent,
     outcome::Outcome,
@@ -22,7 +23,7 @@ use crate::{
 };
 
 #[cfg(not(target_arch = "wasm32"))]
-use rand::{thread_rng, Rng};
+use rand::Rng;
 
 use serde::{Deserialize, Serialize};
 
@@ -176,6 +177,7 @@ impl Attack {
         1.0 - (1.0 - damage_reduction) * (1.0 - block_reduction)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn apply_attack(
         &self,
         attacker: Option<AttackerInfo>,
@@ -187,6 +189,7 @@ impl Attack {
         strength_modifier: f32,
         attack_source: AttackSource,
         time: Time,
+        rng: &mut impl Rng,
         mut emit: impl FnMut(ServerEvent),
         mut emit_outcome: impl FnMut(Outcome),
     ) -> bool {
@@ -209,7 +212,7 @@ impl Attack {
             matches!(attack_effect.target, Some(GroupTarget::OutOfGroup))
                 && (target_dodging || !may_harm)
         };
-        let is_crit = thread_rng().gen::<f32>() < self.crit_chance;
+        let is_crit = rng.gen::<f32>() < self.crit_chance;
         let mut is_applied = false;
         let mut accumulated_damage = 0.0;
         for damage in self
@@ -317,7 +320,7 @@ impl Attack {
                             }
                         },
                         CombatEffect::Buff(b) => {
-                            if thread_rng().gen::<f32>() < b.chance {
+                            if rng.gen::<f32>() < b.chance {
                                 emit(ServerEvent::Buff {
                                     entity: target.entity,
                                     buff_change: BuffChange::Add(b.to_buff(
@@ -460,7 +463,7 @@ impl Attack {
                         }
                     },
                     CombatEffect::Buff(b) => {
-                        if thread_rng().gen::<f32>() < b.chance {
+                        if rng.gen::<f32>() < b.chance {
                             emit(ServerEvent::Buff {
                                 entity: target.entity,
                                 buff_change: BuffChange::Add(b.to_buff(
@@ -550,10 +553,13 @@ impl Attack {
 ///
 /// If both players have PvP mode enabled, interact with NPC and
 /// in any other case, this function will return `true`
+///
+/// Duels and arenas take precedence over the battle modes of the players.
 // TODO: add parameter for doing self-harm?
 pub fn may_harm(
     alignments: &ReadStorage<Alignment>,
     players: &ReadStorage<Player>,
+    engagements: &ReadStorage<PvpEngagement>,
     uid_allocator: &UidAllocator,
     attacker: Option<EcsEntity>,
     target: EcsEntity,
@@ -584,6 +590,27 @@ pub fn may_harm(
     let attacker = owner_if_pet(attacker);
     let target = owner_if_pet(target);
 
+    match (engagements.get(attacker), engagements.get(target)) {
+        (Some(PvpEngagement::Duel { opponent, .. }), Some(PvpEngagement::Duel { .. }))
+            if uid_allocator.retrieve_entity_internal((*opponent).into()) == Some(target) =>
+        {
+            return true;
+        },
+        (
+            Some(PvpEngagement::Arena {
+                arena: attacker_arena,
+                team: attacker_team,
+            }),
+            Some(PvpEngagement::Arena {
+                arena: target_arena,
+                team: target_team,
+            }),
+        ) if attacker_arena == target_arena => {
+            return attacker_team.is_none() || attacker_team != target_team;
+        },
+        _ => {},
+    }
+
     // Get player components
     let attacker_info = players.get(attacker);
     let target_info = players.get(target);
@@ -1183,6 +1210,36 @@ pub fn compute_stealth_coefficient(inventory: Option<&Inventory>) -> f32 {
     })
 }
 
+/// Computes how much an entity resists a buff, from the resistances of its body
+/// and its armor. The strength of the buff is reduced by this fraction, and the
+/// buff isn't applied at all if it is 1.0.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn compute_buff_resistance(
+    kind: BuffKind,
+    body: Option<&Body>,
+    inventory: Option<&Inventory>,
+    manifest: &BuffManifest,
+) -> f32 {
+    let resistance = match manifest.get(kind).and_then(|def| def.resisted_by) {
+        Some(resistance) => resistance,
+        None => return 0.0,
+    };
+    let body_resistance = body.map_or(0.0, |body| body.buff_resistances().get(resistance));
+    let armor_resistance = inventory.map_or(0.0, |inv| {
+        inv.equipped_items()
+            .filter_map(|item| {
+                if let ItemKind::Armor(armor) = &item.kind() {
+                    armor.resistances()
+                } else {
+                    None
+                }
+            })
+            .map(|resistances| resistances.get(resistance))
+            .sum()
+    });
+    (body_resistance + armor_resistance).min(1.0)
+}
+
 /// Computes the total protection provided from armor. Is used to determine the
 /// damage reduction applied to damage received by an entity None indicates that
 /// the armor equipped makes the entity invulnerable
@@ -590,6 +590,13 @@ pub enum CharacterAbility {
         summon_distance: (f32, f32),
         sparseness: f64,
     },
+    Fishing {
+        cast_duration: f32,
+        reel_duration: f32,
+        bite_delay: (f32, f32),
+        bite_duration: f32,
+        cast_range: f32,
+    },
 }
 
 impl Default for CharacterAbility {
@@ -664,7 +671,35 @@ impl CharacterAbility {
             | CharacterAbility::BasicBeam { .. }
             | CharacterAbility::Blink { .. }
             | CharacterAbility::BasicSummon { .. }
-            | CharacterAbility::SpriteSummon { .. } => true,
+            | CharacterAbility::SpriteSummon { .. }
+            | CharacterAbility::Fishing { .. } => true,
+        }
+    }
+
+    /// Whether the ability can be used while riding a mount
+    pub fn can_perform_mounted(&self) -> bool {
+        match self {
+            CharacterAbility::BasicMelee { .. }
+            | CharacterAbility::BasicRanged { .. }
+            | CharacterAbility::ComboMelee { .. }
+            | CharacterAbility::ChargedRanged { .. }
+            | CharacterAbility::RepeaterRanged { .. }
+            | CharacterAbility::BasicBeam { .. }
+            | CharacterAbility::BasicAura { .. }
+            | CharacterAbility::BasicSummon { .. }
+            | CharacterAbility::SelfBuff { .. }
+            | CharacterAbility::SpriteSummon { .. }
+            | CharacterAbility::Fishing { .. }
+            | CharacterAbility::BasicBlock { .. } => true,
+            // Abilities which move the rider on their own would throw them off their mount
+            CharacterAbility::Roll { .. }
+            | CharacterAbility::DashMelee { .. }
+            | CharacterAbility::LeapMelee { .. }
+            | CharacterAbility::SpinMelee { .. }
+            | CharacterAbility::ChargedMelee { .. }
+            | CharacterAbility::Shockwave { .. }
+            | CharacterAbility::Boost { .. }
+            | CharacterAbility::Blink { .. } => false,
         }
     }
 
@@ -1046,6 +1081,17 @@ impl CharacterAbility {
                 *inner_dist *= stats.range;
                 *outer_dist *= stats.range;
             },
+            Fishing {
+                ref mut cast_duration,
+                ref mut reel_duration,
+                bite_delay: _,
+                bite_duration: _,
+                ref mut cast_range,
+            } => {
+                *cast_duration /= stats.speed;
+                *reel_duration /= stats.speed;
+                *cast_range *= stats.range;
+            },
         }
         self
     }
@@ -1077,7 +1123,8 @@ impl CharacterAbility {
             | ComboMelee { .. }
             | Blink { .. }
             | BasicSummon { .. }
-            | SpriteSummon { .. } => 0.0,
+            | SpriteSummon { .. }
+            | Fishing { .. } => 0.0,
         }
     }
 
@@ -2190,6 +2237,31 @@ impl From<(&CharacterAbility, AbilityInfo, &JoinData<'_>)> for CharacterState {
                 stage_section: StageSection::Buildup,
                 achieved_radius: summon_distance.0.floor() as i32 - 1,
             }),
+            CharacterAbility::Fishing {
+                cast_duration,
+                reel_duration,
+                bite_delay,
+                bite_duration,
+                cast_range,
+            } => CharacterState::Fishing(fishing::Data {
+                static_data: fishing::StaticData {
+                    cast_duration: Duration::from_secs_f32(*cast_duration),
+                    reel_duration: Duration::from_secs_f32(*reel_duration),
+                    bite_delay: (
+                        Duration::from_secs_f32(bite_delay.0),
+                        Duration::from_secs_f32(bite_delay.1),
+                    ),
+                    bite_duration: Duration::from_secs_f32(*bite_duration),
+                    cast_range: *cast_range,
+                    ability_info,
+                },
+                timer: Duration::default(),
+                stage_section: StageSection::Buildup,
+                bobber_pos: None,
+                bite_delay: Duration::default(),
+                input_released: false,
+                caught: false,
+            }),
         }
     }
 }
@@ -20,6 +20,7 @@ use crate::{
     assets::{self, Asset},
     consts::{HUMAN_DENSITY, WATER_DENSITY},
     make_case_elim,
+    mounting::MountStats,
     npc::NpcKind,
 };
 use serde::{Deserialize, Serialize};
@@ -28,7 +29,7 @@ use specs_idvs::IdvStorage;
 use strum_macros::Display;
 use vek::*;
 
-use super::{BuffKind, Density, Mass};
+use super::{buff::Resistances, BuffKind, Density, Mass};
 
 make_case_elim!(
     body,
@@ -800,6 +801,47 @@ impl Body {
         }
     }
 
+    /// Resistances against buffs which weaken rather than prevent them, see
+    /// [`Body::immune_to`] for the latter
+    pub fn buff_resistances(&self) -> Resistances {
+        match self {
+            Body::QuadrupedMedium(q) => match q.species {
+                quadruped_medium::Species::Frostfang
+                | quadruped_medium::Species::Snowleopard
+                | quadruped_medium::Species::Mammoth => Resistances {
+                    frost: 0.5,
+                    ..Default::default()
+                },
+                _ => Resistances::default(),
+            },
+            Body::BipedLarge(b) => match b.species {
+                biped_large::Species::Yeti | biped_large::Species::Wendigo => Resistances {
+                    frost: 0.5,
+                    ..Default::default()
+                },
+                biped_large::Species::Dullahan
+                | biped_large::Species::Cultistwarlord
+                | biped_large::Species::Cultistwarlock => Resistances {
+                    curse: 0.5,
+                    ..Default::default()
+                },
+                _ => Resistances::default(),
+            },
+            Body::QuadrupedLow(q) => match q.species {
+                quadruped_low::Species::Icedrake => Resistances {
+                    frost: 0.75,
+                    ..Default::default()
+                },
+                quadruped_low::Species::Asp => Resistances {
+                    poison: 0.5,
+                    ..Default::default()
+                },
+                _ => Resistances::default(),
+            },
+            _ => Resistances::default(),
+        }
+    }
+
     /// Returns a multiplier representing increased difficulty not accounted for
     /// due to AI or not using an actual weapon
     // TODO: Match on species
@@ -900,6 +942,12 @@ impl Body {
                     (quadruped_medium::Species::Akhlut, _) => [0.6, 0.6, 2.0],
                 }
             },
+            Body::BirdLarge(bird_large) => match bird_large.species {
+                bird_large::Species::Phoenix => [0.0, 0.0, 2.6],
+                bird_large::Species::Cockatrice => [0.0, -0.3, 2.6],
+                bird_large::Species::Roc => [0.0, 0.0, 3.2],
+                bird_large::Species::FlameWyvern => [0.0, 0.5, 3.0],
+            },
             Body::Ship(ship) => match ship {
                 ship::Body::DefaultAirship => [0.0, 0.0, 10.0],
                 ship::Body::AirBalloon => [0.0, 0.0, 5.0],
@@ -912,6 +960,41 @@ impl Body {
         .into()
     }
 
+    /// How well the body carries a rider, `None` if it can't be ridden
+    pub fn mount_stats(&self) -> Option<MountStats> {
+        let (speed, stamina_drain, dismount_threshold) = match self {
+            Body::QuadrupedMedium(quadruped_medium) => match quadruped_medium.species {
+                quadruped_medium::Species::Horse
+                | quadruped_medium::Species::Zebra
+                | quadruped_medium::Species::Kelpie
+                | quadruped_medium::Species::Antelope => (1.4, 15.0, 0.2),
+                // Pack animals are slow but hardly tire
+                quadruped_medium::Species::Donkey
+                | quadruped_medium::Species::Camel
+                | quadruped_medium::Species::Llama
+                | quadruped_medium::Species::Yak
+                | quadruped_medium::Species::Highland
+                | quadruped_medium::Species::Cattle => (1.1, 6.0, 0.25),
+                quadruped_medium::Species::Tarasque
+                | quadruped_medium::Species::Roshwalr
+                | quadruped_medium::Species::Catoblepas
+                | quadruped_medium::Species::Dreadhorn
+                | quadruped_medium::Species::Mammoth => (1.0, 8.0, 0.35),
+                _ => (1.2, 12.0, 0.15),
+            },
+            Body::BirdLarge(bird_large) => match bird_large.species {
+                bird_large::Species::Cockatrice => (1.2, 20.0, 0.15),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(MountStats {
+            speed,
+            stamina_drain,
+            dismount_threshold,
+        })
+    }
+
     /// Component of the mounting offset specific to the rider
     pub fn rider_offset(&self) -> Vec3<f32> {
         match self {
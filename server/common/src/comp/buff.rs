@@ -1,8 +1,16 @@
 #![allow(clippy::nonstandard_macro_braces)] //tmp as of false positive !?
+#[cfg(not(target_arch = "wasm32"))]
+use crate::assets::{self, AssetExt, AssetHandle};
 use crate::uid::Uid;
-use core::{cmp::Ordering, time::Duration};
+use core::{
+    cmp::Ordering,
+    ops::{Add, Sub},
+    time::Duration,
+};
 #[cfg(not(target_arch = "wasm32"))]
 use hashbrown::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 #[cfg(not(target_arch = "wasm32"))]
 use specs::{Component, DerefFlaggedStorage};
@@ -11,7 +19,8 @@ use specs_idvs::IdvStorage;
 use strum_macros::EnumIter;
 
 /// De/buff Kind.
-/// This is used to determine what effects a buff will have
+/// This is used to look up the effects a buff will have in the
+/// [`BuffManifest`]
 #[derive(
     Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, PartialOrd, Ord, EnumIter,
 )]
@@ -108,9 +117,6 @@ impl BuffKind {
             | BuffKind::Poisoned => false,
         }
     }
-
-    /// Checks if buff should queue
-    pub fn queues(self) -> bool { matches!(self, BuffKind::Saturation) }
 }
 
 // Struct used to store data relevant to a buff
@@ -180,13 +186,253 @@ pub enum BuffEffect {
     GroundFriction(f32),
 }
 
+/// How a buff combines with buffs of the same kind already on an entity
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum BuffStacking {
+    /// All buffs tick down, but only the strongest one has an effect
+    Strongest,
+    /// Only the strongest buff ticks down and has an effect, the others wait
+    /// for their turn
+    Queue,
+    /// There is only ever one buff, reapplying it restarts its duration and
+    /// keeps the higher strength
+    Refresh,
+    /// Reapplying the buff adds to its strength, up to `max_stacks` times the
+    /// strength of a single application, and restarts its duration
+    Intensity { max_stacks: u32 },
+    /// Every buff has an effect of its own
+    Independent,
+}
+
+impl Default for BuffStacking {
+    fn default() -> Self { Self::Strongest }
+}
+
+/// How a value of a buff effect follows the strength of the buff
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Scaling {
+    Constant(f32),
+    /// `base + mult * strength`
+    Linear {
+        #[serde(default)]
+        base: f32,
+        mult: f32,
+    },
+    /// `(base + mult * strength / (strength + 0.5)).powf(exponent)`, which
+    /// lets strength be unbounded while the value stays bounded. A strength of
+    /// 0.5 gives half of `mult` and a strength of 1.0 two thirds of it.
+    Diminishing {
+        #[serde(default)]
+        base: f32,
+        mult: f32,
+        #[serde(default = "default_exponent")]
+        exponent: f32,
+    },
+}
+
+fn default_exponent() -> f32 { 1.0 }
+
+impl Scaling {
+    pub fn compute(self, strength: f32) -> f32 {
+        match self {
+            Scaling::Constant(value) => value,
+            Scaling::Linear { base, mult } => base + mult * strength,
+            Scaling::Diminishing {
+                base,
+                mult,
+                exponent,
+            } => (base + mult * strength / (strength + 0.5)).powf(exponent),
+        }
+    }
+}
+
+/// A [`BuffEffect`] as defined in the [`BuffManifest`], with its values given
+/// in terms of the strength of the buff
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum BuffEffectDef {
+    HealthChangeOverTime {
+        rate: Scaling,
+        kind: ModifierKind,
+    },
+    EnergyChangeOverTime {
+        rate: Scaling,
+        kind: ModifierKind,
+    },
+    MaxHealthModifier {
+        value: Scaling,
+        kind: ModifierKind,
+    },
+    MaxEnergyModifier {
+        value: Scaling,
+        kind: ModifierKind,
+    },
+    DamageReduction(Scaling),
+    MaxHealthChangeOverTime {
+        rate: Scaling,
+        kind: ModifierKind,
+        target_fraction: Scaling,
+    },
+    MovementSpeed(Scaling),
+    AttackSpeed(Scaling),
+    GroundFriction(Scaling),
+}
+
+impl BuffEffectDef {
+    pub fn instantiate(&self, strength: f32) -> BuffEffect {
+        match self {
+            BuffEffectDef::HealthChangeOverTime { rate, kind } => {
+                BuffEffect::HealthChangeOverTime {
+                    rate: rate.compute(strength),
+                    accumulated: 0.0,
+                    kind: kind.clone(),
+                }
+            },
+            BuffEffectDef::EnergyChangeOverTime { rate, kind } => {
+                BuffEffect::EnergyChangeOverTime {
+                    rate: rate.compute(strength),
+                    accumulated: 0.0,
+                    kind: kind.clone(),
+                }
+            },
+            BuffEffectDef::MaxHealthModifier { value, kind } => BuffEffect::MaxHealthModifier {
+                value: value.compute(strength),
+                kind: kind.clone(),
+            },
+            BuffEffectDef::MaxEnergyModifier { value, kind } => BuffEffect::MaxEnergyModifier {
+                value: value.compute(strength),
+                kind: kind.clone(),
+            },
+            BuffEffectDef::DamageReduction(dr) => BuffEffect::DamageReduction(dr.compute(strength)),
+            BuffEffectDef::MaxHealthChangeOverTime {
+                rate,
+                kind,
+                target_fraction,
+            } => BuffEffect::MaxHealthChangeOverTime {
+                rate: rate.compute(strength),
+                kind: kind.clone(),
+                target_fraction: target_fraction.compute(strength),
+                achieved_fraction: None,
+            },
+            BuffEffectDef::MovementSpeed(speed) => {
+                BuffEffect::MovementSpeed(speed.compute(strength))
+            },
+            BuffEffectDef::AttackSpeed(speed) => BuffEffect::AttackSpeed(speed.compute(strength)),
+            BuffEffectDef::GroundFriction(gf) => BuffEffect::GroundFriction(gf.compute(strength)),
+        }
+    }
+}
+
+/// What a buff kind does, loaded from the [`BuffManifest`]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BuffDef {
+    #[serde(default)]
+    pub stacking: BuffStacking,
+    pub effects: Vec<BuffEffectDef>,
+    /// Categories the buff is always in, in addition to those it is applied
+    /// with. Buffs can be cleansed by their category.
+    #[serde(default)]
+    pub categories: Vec<BuffCategory>,
+    /// Which resistance of the target weakens the buff
+    #[serde(default)]
+    pub resisted_by: Option<ResistanceKind>,
+    /// Buffs of these kinds are removed when this buff is applied
+    #[serde(default)]
+    pub removes: Vec<BuffKind>,
+    /// This buff can't be applied while a buff of one of these kinds is active
+    #[serde(default)]
+    pub blocked_by: Vec<BuffKind>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug, Deserialize)]
+pub struct BuffManifest(HashMap<BuffKind, BuffDef>);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BuffManifest {
+    pub fn get(&self, kind: BuffKind) -> Option<&BuffDef> { self.0.get(&kind) }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl assets::Asset for BuffManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+lazy_static! {
+    pub static ref BUFF_MANIFEST: AssetHandle<BuffManifest> =
+        BuffManifest::load_expect("common.buffs");
+}
+
+/// The resistances which weaken buffs, see [`BuffDef::resisted_by`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResistanceKind {
+    Fire,
+    Frost,
+    Poison,
+    Bleeding,
+    Curse,
+    Entangle,
+}
+
+/// Resistances of an entity against buffs, from its body and its armor. A
+/// resistance of 0.5 halves the strength of the buffs it applies to, a
+/// resistance of 1.0 or more makes the entity immune to them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+    pub fire: f32,
+    pub frost: f32,
+    pub poison: f32,
+    pub bleeding: f32,
+    pub curse: f32,
+    pub entangle: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, kind: ResistanceKind) -> f32 {
+        match kind {
+            ResistanceKind::Fire => self.fire,
+            ResistanceKind::Frost => self.frost,
+            ResistanceKind::Poison => self.poison,
+            ResistanceKind::Bleeding => self.bleeding,
+            ResistanceKind::Curse => self.curse,
+            ResistanceKind::Entangle => self.entangle,
+        }
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(f32, f32) -> f32) -> Self {
+        Self {
+            fire: f(self.fire, other.fire),
+            frost: f(self.frost, other.frost),
+            poison: f(self.poison, other.poison),
+            bleeding: f(self.bleeding, other.bleeding),
+            curse: f(self.curse, other.curse),
+            entangle: f(self.entangle, other.entangle),
+        }
+    }
+}
+
+impl Add for Resistances {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output { self.zip_with(other, |a, b| a + b) }
+}
+
+impl Sub for Resistances {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output { self.zip_with(other, |a, b| a - b) }
+}
+
 /// Actual de/buff.
 /// Buff can timeout after some time if `time` is Some. If `time` is None,
 /// Buff will last indefinitely, until removed manually (by some action, like
 /// uncursing).
 ///
-/// Buff has a kind, which is used to determine the effects in a builder
-/// function.
+/// Buff has a kind, which is used to look up its effects in the
+/// [`BuffManifest`].
 ///
 /// To provide more classification info when needed,
 /// buff can be in one or more buff category.
@@ -198,6 +444,8 @@ pub struct Buff {
     pub time: Option<Duration>,
     pub effects: Vec<BuffEffect>,
     pub source: BuffSource,
+    /// How the buff combines with other buffs of its kind
+    pub stacking: BuffStacking,
 }
 
 /// Information about whether buff addition or removal was requested.
@@ -232,134 +480,32 @@ impl Buff {
         cat_ids: Vec<BuffCategory>,
         source: BuffSource,
     ) -> Self {
-        // Normalized nonlinear scaling
-        let nn_scaling = |a| a / (a + 0.5);
-        let (effects, time) = match kind {
-            BuffKind::Bleeding => (
-                vec![BuffEffect::HealthChangeOverTime {
-                    rate: -data.strength,
-                    accumulated: 0.0,
-                    kind: ModifierKind::Additive,
-                }],
-                data.duration,
-            ),
-            BuffKind::Regeneration | BuffKind::Saturation | BuffKind::Potion => (
-                vec![BuffEffect::HealthChangeOverTime {
-                    rate: data.strength,
-                    accumulated: 0.0,
-                    kind: ModifierKind::Additive,
-                }],
-                data.duration,
-            ),
-            BuffKind::CampfireHeal => (
-                vec![BuffEffect::HealthChangeOverTime {
-                    rate: data.strength,
-                    accumulated: 0.0,
-                    kind: ModifierKind::Fractional,
-                }],
-                data.duration,
-            ),
-            BuffKind::Cursed => (
-                vec![
-                    BuffEffect::MaxHealthChangeOverTime {
-                        rate: -1.0,
-                        kind: ModifierKind::Additive,
-                        target_fraction: 1.0 - data.strength,
-                        achieved_fraction: None,
-                    },
-                    BuffEffect::HealthChangeOverTime {
-                        rate: -1.0,
-                        accumulated: 0.0,
-                        kind: ModifierKind::Additive,
-                    },
-                ],
-                data.duration,
-            ),
-            BuffKind::IncreaseMaxEnergy => (
-                vec![BuffEffect::MaxEnergyModifier {
-                    value: data.strength,
-                    kind: ModifierKind::Additive,
-                }],
-                data.duration,
-            ),
-            BuffKind::IncreaseMaxHealth => (
-                vec![BuffEffect::MaxHealthModifier {
-                    value: data.strength,
-                    kind: ModifierKind::Additive,
-                }],
-                data.duration,
-            ),
-            BuffKind::Invulnerability => (vec![BuffEffect::DamageReduction(1.0)], data.duration),
-            BuffKind::ProtectingWard => (
-                vec![BuffEffect::DamageReduction(
-                    // Causes non-linearity in effect strength, but necessary to allow for tool
-                    // power and other things to affect the strength. 0.5 also still provides 50%
-                    // damage reduction.
-                    nn_scaling(data.strength),
-                )],
-                data.duration,
-            ),
-            BuffKind::Burning => (
-                vec![BuffEffect::HealthChangeOverTime {
-                    rate: -data.strength,
-                    accumulated: 0.0,
-                    kind: ModifierKind::Additive,
-                }],
-                data.duration,
-            ),
-            BuffKind::Poisoned => (
-                vec![BuffEffect::EnergyChangeOverTime {
-                    rate: -data.strength,
-                    accumulated: 0.0,
-                    kind: ModifierKind::Additive,
-                }],
-                data.duration,
-            ),
-            BuffKind::Crippled => (
-                vec![
-                    BuffEffect::MovementSpeed(1.0 - nn_scaling(data.strength)),
-                    BuffEffect::HealthChangeOverTime {
-                        rate: -data.strength * 4.0,
-                        accumulated: 0.0,
-                        kind: ModifierKind::Additive,
-                    },
-                ],
-                data.duration,
-            ),
-            BuffKind::Frenzied => (
-                vec![
-                    BuffEffect::MovementSpeed(1.0 + data.strength),
-                    BuffEffect::HealthChangeOverTime {
-                        rate: data.strength * 10.0,
-                        accumulated: 0.0,
-                        kind: ModifierKind::Additive,
-                    },
-                ],
-                data.duration,
-            ),
-            BuffKind::Frozen => (
-                vec![
-                    BuffEffect::MovementSpeed(f32::powf(1.0 - nn_scaling(data.strength), 1.1)),
-                    BuffEffect::AttackSpeed(1.0 - nn_scaling(data.strength)),
-                ],
-                data.duration,
-            ),
-            BuffKind::Wet => (
-                vec![BuffEffect::GroundFriction(1.0 - nn_scaling(data.strength))],
-                data.duration,
-            ),
-            BuffKind::Ensnared => (
-                vec![BuffEffect::MovementSpeed(1.0 - nn_scaling(data.strength))],
-                data.duration,
-            ),
-            BuffKind::Hastened => (
-                vec![
-                    BuffEffect::MovementSpeed(1.0 + data.strength),
-                    BuffEffect::AttackSpeed(1.0 + data.strength),
-                ],
-                data.duration,
-            ),
-        };
+        Self::with_manifest(&BUFF_MANIFEST.read(), kind, data, cat_ids, source)
+    }
+
+    /// Like [`Buff::new`], for systems which create buffs every tick and read
+    /// the manifest once beforehand
+    pub fn with_manifest(
+        manifest: &BuffManifest,
+        kind: BuffKind,
+        data: BuffData,
+        cat_ids: Vec<BuffCategory>,
+        source: BuffSource,
+    ) -> Self {
+        let def = manifest.get(kind);
+        let effects = def.map_or_else(Vec::new, |def| {
+            def.effects
+                .iter()
+                .map(|effect| effect.instantiate(data.strength))
+                .collect()
+        });
+        let mut cat_ids = cat_ids;
+        for cat_id in def.iter().flat_map(|def| &def.categories) {
+            if !cat_ids.contains(cat_id) {
+                cat_ids.push(*cat_id);
+            }
+        }
+        let time = data.duration;
         Buff {
             kind,
             data,
@@ -367,6 +513,26 @@ impl Buff {
             time,
             effects,
             source,
+            stacking: def.map_or_else(BuffStacking::default, |def| def.stacking),
+        }
+    }
+
+    /// Weakens the buff by the resistance of its target, a resistance of 1.0
+    /// or more prevents the buff altogether
+    pub fn resisted(self, resistance: f32, manifest: &BuffManifest) -> Option<Self> {
+        if resistance <= 0.0 {
+            Some(self)
+        } else if resistance < 1.0 {
+            let data = BuffData::new(self.data.strength * (1.0 - resistance), self.data.duration);
+            Some(Buff::with_manifest(
+                manifest,
+                self.kind,
+                data,
+                self.cat_ids,
+                self.source,
+            ))
+        } else {
+            None
         }
     }
 }
@@ -474,9 +640,43 @@ impl Buffs {
         id
     }
 
-    pub fn insert(&mut self, buff: Buff) -> BuffId {
+    /// Adds a buff according to the stacking and interactions of its kind.
+    /// Returns `None` if the buff was blocked by another buff.
+    pub fn insert(&mut self, buff: Buff, manifest: &BuffManifest) -> Option<BuffId> {
+        let kind = buff.kind;
+        if let Some(def) = manifest.get(kind) {
+            if def.blocked_by.iter().any(|blocker| self.contains(*blocker)) {
+                return None;
+            }
+            for removed in &def.removes {
+                self.remove_kind(*removed);
+            }
+        }
+
+        let strongest = self
+            .iter_kind(kind)
+            .next()
+            .map(|(_, buff)| buff.data.strength);
+        let buff = match (buff.stacking, strongest) {
+            (BuffStacking::Refresh, Some(strength)) => {
+                let data = BuffData::new(strength.max(buff.data.strength), buff.data.duration);
+                self.remove_kind(kind);
+                Buff::with_manifest(manifest, kind, data, buff.cat_ids, buff.source)
+            },
+            (BuffStacking::Intensity { max_stacks }, Some(strength)) => {
+                let data = BuffData::new(
+                    (strength + buff.data.strength)
+                        .min(buff.data.strength * max_stacks as f32)
+                        .max(strength),
+                    buff.data.duration,
+                );
+                self.remove_kind(kind);
+                Buff::with_manifest(manifest, kind, data, buff.cat_ids, buff.source)
+            },
+            _ => buff,
+        };
         self.id_counter += 1;
-        self.force_insert(self.id_counter, buff)
+        Some(self.force_insert(self.id_counter, buff))
     }
 
     pub fn contains(&self, kind: BuffKind) -> bool { self.kinds.contains_key(&kind) }
@@ -490,11 +690,19 @@ impl Buffs {
             .map(move |id| (*id, &self.buffs[id]))
     }
 
-    // Iterates through all active buffs (the most powerful buff of each kind)
+    // Iterates through all active buffs (the most powerful buff of each kind, or
+    // every buff of kinds which stack independently)
     pub fn iter_active(&self) -> impl Iterator<Item = &Buff> + '_ {
-        self.kinds
-            .values()
-            .filter_map(move |ids| self.buffs.get(&ids[0]))
+        self.kinds.values().flat_map(move |ids| {
+            let independent = ids
+                .get(0)
+                .and_then(|id| self.buffs.get(id))
+                .map_or(false, |buff| {
+                    matches!(buff.stacking, BuffStacking::Independent)
+                });
+            let active = if independent { &ids[..] } else { &ids[..1] };
+            active.iter().filter_map(move |id| self.buffs.get(id))
+        })
     }
 
     // Gets most powerful buff of a given kind
@@ -522,3 +730,81 @@ pub type BuffId = u64;
 impl Component for Buffs {
     type Storage = DerefFlaggedStorage<Self, IdvStorage<Self>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    fn buff(kind: BuffKind, strength: f32) -> Buff {
+        Buff::new(
+            kind,
+            BuffData::new(strength, Some(Duration::from_secs(10))),
+            Vec::new(),
+            BuffSource::Unknown,
+        )
+    }
+
+    #[test]
+    fn every_buff_kind_is_defined() {
+        let manifest = BUFF_MANIFEST.read();
+        for kind in BuffKind::iter() {
+            assert!(
+                manifest
+                    .get(kind)
+                    .map_or(false, |def| !def.effects.is_empty()),
+                "{:?} has no effects in the buff manifest",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn wet_puts_out_burning() {
+        let manifest = BUFF_MANIFEST.read();
+        let mut buffs = Buffs::default();
+        assert!(buffs
+            .insert(buff(BuffKind::Burning, 5.0), &manifest)
+            .is_some());
+        assert!(buffs.insert(buff(BuffKind::Wet, 1.0), &manifest).is_some());
+        assert!(!buffs.contains(BuffKind::Burning));
+        assert!(buffs
+            .insert(buff(BuffKind::Burning, 5.0), &manifest)
+            .is_none());
+    }
+
+    #[test]
+    fn other_buffs_coexist() {
+        let manifest = BUFF_MANIFEST.read();
+        let mut buffs = Buffs::default();
+        for kind in BuffKind::iter().filter(|kind| *kind != BuffKind::Wet) {
+            assert!(buffs.insert(buff(kind, 1.0), &manifest).is_some());
+        }
+        assert!(BuffKind::iter()
+            .filter(|kind| *kind != BuffKind::Wet)
+            .all(|kind| buffs.contains(kind)));
+    }
+
+    #[test]
+    fn stacking() {
+        let manifest = BUFF_MANIFEST.read();
+        let mut buffs = Buffs::default();
+        for _ in 0..2 {
+            buffs.insert(buff(BuffKind::Saturation, 1.0), &manifest);
+        }
+        assert_eq!(buffs.iter_kind(BuffKind::Saturation).count(), 2);
+        assert_eq!(buffs.iter_active().count(), 1);
+    }
+
+    #[test]
+    fn resistance_weakens_buffs() {
+        let manifest = BUFF_MANIFEST.read();
+        let resisted = buff(BuffKind::Bleeding, 10.0)
+            .resisted(0.25, &manifest)
+            .unwrap();
+        assert!((resisted.data.strength - 7.5).abs() < f32::EPSILON);
+        assert!(buff(BuffKind::Bleeding, 10.0)
+            .resisted(1.0, &manifest)
+            .is_none());
+    }
+}
@@ -21,6 +21,8 @@ pub enum ChatMode {
     Group(Group),
     /// Talk to your faction
     Faction(String),
+    /// Talk to your guild (by name)
+    Guild(String),
     /// Talk to every player on the server
     World,
 }
@@ -38,6 +40,7 @@ impl ChatMode {
             ChatMode::Region => ChatType::Region(from),
             ChatMode::Group(group) => ChatType::Group(from, *group),
             ChatMode::Faction(faction) => ChatType::Faction(from, faction.clone()),
+            ChatMode::Guild(guild) => ChatType::Guild(from, guild.clone()),
             ChatMode::World => ChatType::World(from),
         };
         UnresolvedChatMsg { chat_type, message }
@@ -90,6 +93,8 @@ pub enum ChatType<G> {
     GroupMeta(G),
     /// Server notifications to a faction, such as player join/leave
     FactionMeta(String),
+    /// Server notifications to a guild, such as members joining or leaving
+    GuildMeta(String),
     /// One-on-one chat (from, to)
     Tell(Uid, Uid),
     /// Chat with nearby players
@@ -98,6 +103,8 @@ pub enum ChatType<G> {
     Group(Uid, G),
     /// Factional chat
     Faction(Uid, String),
+    /// Guild chat
+    Guild(Uid, String),
     /// Regional chat
     Region(Uid),
     /// World chat
@@ -165,12 +172,14 @@ impl<G> GenericChatMsg<G> {
             ChatType::CommandInfo => ChatType::CommandInfo,
             ChatType::CommandError => ChatType::CommandError,
             ChatType::FactionMeta(a) => ChatType::FactionMeta(a),
+            ChatType::GuildMeta(a) => ChatType::GuildMeta(a),
             ChatType::GroupMeta(g) => ChatType::GroupMeta(f(g)),
             ChatType::Kill(a, b) => ChatType::Kill(a, b),
             ChatType::Tell(a, b) => ChatType::Tell(a, b),
             ChatType::Say(a) => ChatType::Say(a),
             ChatType::Group(a, g) => ChatType::Group(a, f(g)),
             ChatType::Faction(a, b) => ChatType::Faction(a, b),
+            ChatType::Guild(a, b) => ChatType::Guild(a, b),
             ChatType::Region(a) => ChatType::Region(a),
             ChatType::World(a) => ChatType::World(a),
             ChatType::Npc(a, b) => ChatType::Npc(a, b),
@@ -212,12 +221,14 @@ impl<G> GenericChatMsg<G> {
             ChatType::CommandInfo => SpeechBubbleType::None,
             ChatType::CommandError => SpeechBubbleType::None,
             ChatType::FactionMeta(_) => SpeechBubbleType::None,
+            ChatType::GuildMeta(_) => SpeechBubbleType::None,
             ChatType::GroupMeta(_) => SpeechBubbleType::None,
             ChatType::Kill(_, _) => SpeechBubbleType::None,
             ChatType::Tell(_u, _) => SpeechBubbleType::Tell,
             ChatType::Say(_u) => SpeechBubbleType::Say,
             ChatType::Group(_u, _s) => SpeechBubbleType::Group,
             ChatType::Faction(_u, _s) => SpeechBubbleType::Faction,
+            ChatType::Guild(_u, _s) => SpeechBubbleType::Guild,
             ChatType::Region(_u) => SpeechBubbleType::Region,
             ChatType::World(_u) => SpeechBubbleType::World,
             ChatType::Npc(_u, _r) => SpeechBubbleType::None,
@@ -234,12 +245,14 @@ impl<G> GenericChatMsg<G> {
             ChatType::CommandInfo => None,
             ChatType::CommandError => None,
             ChatType::FactionMeta(_) => None,
+            ChatType::GuildMeta(_) => None,
             ChatType::GroupMeta(_) => None,
             ChatType::Kill(_, _) => None,
             ChatType::Tell(u, _t) => Some(*u),
             ChatType::Say(u) => Some(*u),
             ChatType::Group(u, _s) => Some(*u),
             ChatType::Faction(u, _s) => Some(*u),
+            ChatType::Guild(u, _s) => Some(*u),
             ChatType::Region(u) => Some(*u),
             ChatType::World(u) => Some(*u),
             ChatType::Npc(u, _r) => Some(*u),
@@ -282,6 +295,7 @@ pub enum SpeechBubbleType {
     Region,
     Group,
     Faction,
+    Guild,
     World,
     // For NPCs
     Quest, // TODO not implemented
@@ -871,7 +871,21 @@ impl Item {
     pub fn slot_mut(&mut self, slot: usize) -> Option<&mut InvSlot> { self.slots.get_mut(slot) }
 
     pub fn try_reclaim_from_block(block: Block) -> Option<Self> {
-        block.get_sprite()?.collectible_id()?.to_item()
+        match block.get_crop() {
+            // Crops harvested before they are fully grown yield less, and only give back their
+            // seed if they have barely sprouted
+            Some((crop, growth)) if growth < Block::MAX_CROP_GROWTH / 2 => {
+                Item::new_from_asset(crop.seed_item()?).ok()
+            },
+            Some((crop, growth)) => {
+                let mut item = crop.crop_yield()?.to_item()?;
+                let amount = item.amount() * u32::from(growth) / u32::from(Block::MAX_CROP_GROWTH);
+                // Ignore non-stackable errors
+                let _ = item.set_amount(amount.max(1));
+                Some(item)
+            },
+            None => block.get_sprite()?.collectible_id()?.to_item(),
+        }
     }
 
     pub fn ability_spec(&self) -> Option<&AbilitySpec> { self.item_def.ability_spec.as_ref() }
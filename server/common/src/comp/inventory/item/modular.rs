@@ -50,6 +50,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "debug damage component",
                 ToolKind::Farming => "farming damage component",
                 ToolKind::Pick => "pickaxe head",
+                ToolKind::Fishing => "fishing damage component",
                 ToolKind::Natural => "natural damage component",
                 ToolKind::Empty => "empty damage component",
             },
@@ -69,6 +70,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "debug held component",
                 ToolKind::Farming => "farming held component",
                 ToolKind::Pick => "pickaxe handle",
+                ToolKind::Fishing => "fishing held component",
                 ToolKind::Empty => "empty held component",
             },
         }
@@ -91,6 +93,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "common.items.tag_examples.modular.damage.debug",
                 ToolKind::Farming => "common.items.tag_examples.modular.damage.farming",
                 ToolKind::Pick => "common.items.tag_examples.modular.damage.pick",
+                ToolKind::Fishing => "common.items.tag_examples.modular.damage.fishing",
                 ToolKind::Empty => "common.items.tag_examples.modular.damage.empty",
             },
             ModularComponentKind::Held => match self.toolkind {
@@ -108,6 +111,7 @@ impl TagExampleInfo for ModularComponentTag {
                 ToolKind::Debug => "common.items.tag_examples.modular.held.debug",
                 ToolKind::Farming => "common.items.tag_examples.modular.held.farming",
                 ToolKind::Pick => "common.items.tag_examples.modular.held.pick",
+                ToolKind::Fishing => "common.items.tag_examples.modular.held.fishing",
                 ToolKind::Empty => "common.items.tag_examples.modular.held.empty",
             },
         }
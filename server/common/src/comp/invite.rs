@@ -6,6 +6,8 @@ use specs_idvs::IdvStorage;
 pub enum InviteKind {
     Group,
     Trade,
+    Guild,
+    Duel,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -21,6 +21,7 @@ pub mod dialogue;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod fluid_dynamics;
 #[cfg(not(target_arch = "wasm32"))] pub mod group;
+#[cfg(not(target_arch = "wasm32"))] pub mod guild;
 mod health;
 #[cfg(not(target_arch = "wasm32"))] mod inputs;
 #[cfg(not(target_arch = "wasm32"))]
@@ -38,10 +39,13 @@ pub mod invite;
 #[cfg(not(target_arch = "wasm32"))] pub mod poise;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod projectile;
+#[cfg(not(target_arch = "wasm32"))] pub mod pvp;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod shockwave;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod skillset;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod statistics;
 #[cfg(not(target_arch = "wasm32"))] mod stats;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod visual;
@@ -79,6 +83,7 @@ pub use self::{
     energy::Energy,
     fluid_dynamics::Fluid,
     group::Group,
+    guild::GuildTag,
     inputs::CanBuild,
     inventory::{
         item::{
@@ -90,11 +95,13 @@ pub use self::{
         slot, Inventory, InventoryUpdate, InventoryUpdateEvent,
     },
     last::Last,
-    location::{MapMarker, MapMarkerChange, MapMarkerUpdate, Waypoint, WaypointArea},
+    location::{
+        MapMarker, MapMarkerChange, MapMarkerUpdate, Waypoint, WaypointArea, WorldEventKind,
+    },
     melee::{Melee, MeleeConstructor},
     misc::Object,
     ori::Ori,
-    pet::Pet,
+    pet::{Pet, PetCommand},
     phys::{
         Collider, Density, ForceUpdate, Immovable, Mass, PhysicsState, Pos, PosVelOriDefer,
         PreviousPhysCache, Scale, Sticky, Vel,
@@ -103,11 +110,13 @@ pub use self::{
     player::{AliasError, Player, MAX_ALIAS_LEN},
     poise::{Poise, PoiseChange, PoiseState},
     projectile::{Projectile, ProjectileConstructor},
+    pvp::PvpEngagement,
     shockwave::{Shockwave, ShockwaveHitEntities},
     skillset::{
         skills::{self, Skill},
         SkillGroup, SkillGroupKind, SkillSet,
     },
+    statistics::{Stat, Statistics},
     stats::{Stats, StatsModifier},
     visual::{LightAnimation, LightEmitter},
 };
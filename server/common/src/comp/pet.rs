@@ -1,16 +1,46 @@
-use crate::comp::body::Body;
+use crate::{
+    comp::body::{bird_large, Body},
+    mounting::MOUNT_MASS_RATIO,
+    uid::Uid,
+};
 use crossbeam_utils::atomic::AtomicCell;
 use serde::{Deserialize, Serialize};
-use specs::Component;
+use specs::{Component, DerefFlaggedStorage};
 use specs_idvs::IdvStorage;
 use std::{num::NonZeroU64, sync::Arc};
 
 pub type PetId = AtomicCell<Option<NonZeroU64>>;
 
+pub const MAX_PET_LEVEL: u16 = 20;
+
+/// What a pet has been told to do by its owner
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PetCommand {
+    /// Follow the owner around and defend them
+    Follow,
+    /// Stay in place, only fighting back when attacked
+    Stay,
+    /// Attack the given entity, then go back to following the owner
+    Attack(Uid),
+    /// Follow the owner without ever fighting
+    Passive,
+}
+
+impl Default for PetCommand {
+    fn default() -> Self { PetCommand::Follow }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Pet {
     #[serde(skip)]
     database_id: Arc<PetId>,
+    pub command: PetCommand,
+    level: u16,
+    experience: u32,
+    /// Set when the level of the pet changed, so that its health and energy
+    /// can be scaled accordingly
+    #[serde(skip)]
+    pub modify_stats: bool,
 }
 
 impl Pet {
@@ -20,10 +50,46 @@ impl Pet {
     #[doc(hidden)]
     pub fn get_database_id(&self) -> Arc<PetId> { Arc::clone(&self.database_id) }
 
-    pub fn new_from_database(database_id: NonZeroU64) -> Self {
+    pub fn new_from_database(
+        database_id: NonZeroU64,
+        command: PetCommand,
+        level: u16,
+        experience: u32,
+    ) -> Self {
         Self {
             database_id: Arc::new(AtomicCell::new(Some(database_id))),
+            command,
+            level: level.min(MAX_PET_LEVEL),
+            experience,
+            modify_stats: true,
+        }
+    }
+
+    pub fn level(&self) -> u16 { self.level }
+
+    pub fn experience(&self) -> u32 { self.experience }
+
+    /// Experience needed to reach the next level, `None` at the maximum level
+    pub fn experience_needed(&self) -> Option<u32> {
+        (self.level < MAX_PET_LEVEL).then(|| 100 * (u32::from(self.level) + 1))
+    }
+
+    /// Adds experience to the pet, returns whether it levelled up
+    pub fn add_experience(&mut self, experience: u32) -> bool {
+        let old_level = self.level;
+        self.experience = self.experience.saturating_add(experience);
+        while let Some(needed) = self.experience_needed() {
+            if self.experience < needed {
+                break;
+            }
+            self.experience -= needed;
+            self.level += 1;
+        }
+        if self.level == MAX_PET_LEVEL {
+            self.experience = 0;
         }
+        self.modify_stats |= self.level != old_level;
+        self.level != old_level
     }
 }
 
@@ -31,21 +97,88 @@ impl Default for Pet {
     fn default() -> Self {
         Self {
             database_id: Arc::new(AtomicCell::new(None)),
+            command: PetCommand::default(),
+            level: 0,
+            experience: 0,
+            modify_stats: false,
         }
     }
 }
 
 /// Determines whether an entity of a particular body variant is tameable.
 pub fn is_tameable(body: &Body) -> bool {
-    // Currently only Quadruped animals can be tamed pending further work
-    // on the pets feature (allowing larger animals to be tamed will
-    // require balance issues to be addressed).
+    // Currently only Quadruped animals and cockatrices, which can be ridden,
+    // can be tamed pending further work on the pets feature (allowing larger
+    // animals to be tamed will require balance issues to be addressed). The
+    // other large birds are bosses.
     matches!(
         body,
-        Body::QuadrupedLow(_) | Body::QuadrupedMedium(_) | Body::QuadrupedSmall(_)
+        Body::QuadrupedLow(_)
+            | Body::QuadrupedMedium(_)
+            | Body::QuadrupedSmall(_)
+            | Body::BirdLarge(bird_large::Body {
+                species: bird_large::Species::Cockatrice,
+                ..
+            })
     )
 }
 
+/// Determines whether a tamed creature of a particular body variant can be
+/// ridden, and is strong enough to carry the rider if one is given.
+pub fn is_mountable(mount: &Body, rider: Option<&Body>) -> bool {
+    mount.mount_stats().is_some()
+        && rider.map_or(true, |rider| {
+            rider.mass().0 * MOUNT_MASS_RATIO <= mount.mass().0
+        })
+}
+
 impl Component for Pet {
-    type Storage = IdvStorage<Self>;
+    type Storage = DerefFlaggedStorage<Self, IdvStorage<Self>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comp::body::{humanoid, quadruped_medium};
+    use rand::thread_rng;
+
+    #[test]
+    fn levels_up_with_experience() {
+        let mut pet = Pet::default();
+        assert!(!pet.add_experience(99));
+        assert!(pet.add_experience(1));
+        assert_eq!((pet.level(), pet.experience()), (1, 0));
+
+        // Enough experience for several levels at once
+        assert!(pet.add_experience(700));
+        assert_eq!((pet.level(), pet.experience()), (3, 200));
+
+        pet.add_experience(u32::MAX);
+        assert_eq!(pet.level(), MAX_PET_LEVEL);
+        assert_eq!(pet.experience_needed(), None);
+    }
+
+    #[test]
+    fn large_bird_bosses_are_not_tameable() {
+        for species in bird_large::ALL_SPECIES {
+            let body = Body::BirdLarge(bird_large::Body::random_with(&mut thread_rng(), &species));
+            assert_eq!(
+                is_tameable(&body),
+                species == bird_large::Species::Cockatrice
+            );
+        }
+    }
+
+    #[test]
+    fn mounts_carry_lighter_riders() {
+        let horse = Body::QuadrupedMedium(quadruped_medium::Body::random_with(
+            &mut thread_rng(),
+            &quadruped_medium::Species::Horse,
+        ));
+        let human = Body::Humanoid(humanoid::Body::random());
+        assert!(is_mountable(&horse, None));
+        assert!(is_mountable(&horse, Some(&human)));
+        assert!(!is_mountable(&horse, Some(&horse)));
+        assert!(!is_mountable(&human, None));
+    }
 }
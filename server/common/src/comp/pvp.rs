@@ -0,0 +1,35 @@
+use crate::{resources::Time, uid::Uid};
+use serde::{Deserialize, Serialize};
+use specs::Component;
+use specs_idvs::IdvStorage;
+
+/// Consensual PvP, which allows players to fight each other regardless of
+/// their [`BattleMode`](crate::resources::BattleMode)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PvpEngagement {
+    /// Dueling another player, who has a matching engagement
+    Duel { opponent: Uid, started: Time },
+    /// Inside of an arena, where anyone who is not on the same team can be
+    /// attacked
+    Arena { arena: String, team: Option<u8> },
+}
+
+impl PvpEngagement {
+    pub fn arena(&self) -> Option<&str> {
+        match self {
+            PvpEngagement::Arena { arena, .. } => Some(arena),
+            PvpEngagement::Duel { .. } => None,
+        }
+    }
+
+    pub fn duel_opponent(&self) -> Option<Uid> {
+        match self {
+            PvpEngagement::Duel { opponent, .. } => Some(*opponent),
+            PvpEngagement::Arena { .. } => None,
+        }
+    }
+}
+
+impl Component for PvpEngagement {
+    type Storage = IdvStorage<Self>;
+}
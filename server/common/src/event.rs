@@ -6,7 +6,9 @@ use crate::{
         invite::{InviteKind, InviteResponse},
         DisconnectReason, Ori, Pos,
     },
+    guild::GuildAction,
     lottery::LootSpec,
+    mail::MailAction,
     outcome::Outcome,
     rtsim::RtSimEntity,
     terrain::SpriteKind,
@@ -116,6 +118,7 @@ pub enum ServerEvent {
             Vec<(comp::Pet, comp::Body, comp::Stats)>,
             comp::ActiveAbilities,
             Option<comp::MapMarker>,
+            comp::Statistics,
         ),
     },
     ExitIngame {
@@ -144,6 +147,7 @@ pub enum ServerEvent {
         mountable: bool,
         agent: Option<comp::Agent>,
         rtsim_entity: Option<RtSimEntity>,
+        anchor: Option<comp::Anchor>,
     },
     CreateWaypoint(Vec3<f32>),
     ClientDisconnect(EcsEntity, DisconnectReason),
@@ -181,6 +185,11 @@ pub enum ServerEvent {
         pos: Vec3<i32>,
         tool: Option<comp::tool::ToolKind>,
     },
+    /// A fish was hooked by a line whose bobber is at `pos`
+    CatchFish {
+        entity: EcsEntity,
+        pos: Vec3<f32>,
+    },
     TeleportTo {
         entity: EcsEntity,
         target: Uid,
@@ -201,6 +210,16 @@ pub enum ServerEvent {
         pet_entity: EcsEntity,
         owner_entity: EcsEntity,
     },
+    CommandPet {
+        owner_entity: EcsEntity,
+        pet_entity: EcsEntity,
+        command: comp::PetCommand,
+    },
+    RenamePet {
+        owner_entity: EcsEntity,
+        pet_entity: EcsEntity,
+        name: String,
+    },
     EntityAttackedHook {
         entity: EcsEntity,
     },
@@ -214,6 +233,21 @@ pub enum ServerEvent {
         entity: EcsEntity,
         update: comp::MapMarkerChange,
     },
+    MailAction {
+        entity: EcsEntity,
+        action: MailAction,
+    },
+    GuildAction {
+        entity: EcsEntity,
+        action: GuildAction,
+    },
+    /// Adds to one of a character's statistics, unlocking any achievements
+    /// that it completes
+    UpdateStatistic {
+        entity: EcsEntity,
+        stat: comp::Stat,
+        amount: u64,
+    },
 }
 
 pub struct EventBus<E> {
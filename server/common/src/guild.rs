@@ -0,0 +1,177 @@
+//! Types shared between the client and server for player guilds.
+//!
+//! Unlike groups, guilds are persisted: membership, ranks and the contents of
+//! the shared guild bank survive logging out and server restarts. Members are
+//! characters, so each of a player's characters may belong to a different
+//! guild.
+use crate::comp::{inventory::slot::InvSlotId, Item};
+use serde::{Deserialize, Serialize};
+
+pub type GuildId = i64;
+
+/// The maximum length (in characters) of a guild name
+pub const MAX_GUILD_NAME_LEN: usize = 32;
+/// The minimum length (in characters) of a guild tag
+pub const MIN_GUILD_TAG_LEN: usize = 2;
+/// The maximum length (in characters) of a guild tag
+pub const MAX_GUILD_TAG_LEN: usize = 5;
+
+/// The rank of a guild member, which determines what they are allowed to do.
+/// Ranks are ordered from the least to the most privileged.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum GuildRank {
+    /// Newly invited members, who may not take anything from the bank
+    Recruit,
+    Member,
+    Officer,
+    /// Each guild has exactly one leader
+    Leader,
+}
+
+impl GuildRank {
+    pub fn to_key(self) -> &'static str {
+        match self {
+            GuildRank::Recruit => "recruit",
+            GuildRank::Member => "member",
+            GuildRank::Officer => "officer",
+            GuildRank::Leader => "leader",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "recruit" => Some(GuildRank::Recruit),
+            "member" => Some(GuildRank::Member),
+            "officer" => Some(GuildRank::Officer),
+            "leader" => Some(GuildRank::Leader),
+            _ => None,
+        }
+    }
+
+    pub fn can_invite(self) -> bool { self >= GuildRank::Officer }
+
+    /// Whether a member of this rank may remove, promote or demote a member of
+    /// the `other` rank. Members can never manage someone of equal rank.
+    pub fn can_manage(self, other: GuildRank) -> bool { self >= GuildRank::Officer && self > other }
+
+    pub fn can_deposit(self) -> bool { true }
+
+    pub fn can_withdraw(self) -> bool { self >= GuildRank::Member }
+
+    /// The next rank up, excluding leader which can only be handed over
+    pub fn promoted(self) -> Option<Self> {
+        match self {
+            GuildRank::Recruit => Some(GuildRank::Member),
+            GuildRank::Member => Some(GuildRank::Officer),
+            GuildRank::Officer | GuildRank::Leader => None,
+        }
+    }
+
+    pub fn demoted(self) -> Option<Self> {
+        match self {
+            GuildRank::Member => Some(GuildRank::Recruit),
+            GuildRank::Officer => Some(GuildRank::Member),
+            GuildRank::Recruit | GuildRank::Leader => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuildMember {
+    pub name: String,
+    pub rank: GuildRank,
+    pub online: bool,
+}
+
+/// Everything a member knows about their guild
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GuildInfo {
+    pub id: GuildId,
+    pub name: String,
+    pub tag: String,
+    /// The rank of the character receiving the information
+    pub rank: GuildRank,
+    pub members: Vec<GuildMember>,
+    pub bank: Vec<Item>,
+    /// The number of item stacks the bank can hold
+    pub bank_capacity: usize,
+}
+
+/// Requests made by a client to interact with their guild
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum GuildAction {
+    /// Request the current state of the guild
+    RequestInfo,
+    /// Move the (whole) stack in an inventory slot into the guild bank
+    Deposit(InvSlotId),
+    /// Move the stack at the given index of the guild bank into the inventory
+    Withdraw(usize),
+}
+
+/// Validates the name and tag of a guild before it is created
+pub fn validate_guild_name(name: &str, tag: &str) -> Result<(), GuildNameError> {
+    let name_len = name.chars().count();
+    let tag_len = tag.chars().count();
+    if name.trim().is_empty() {
+        Err(GuildNameError::EmptyName)
+    } else if name_len > MAX_GUILD_NAME_LEN {
+        Err(GuildNameError::NameTooLong)
+    } else if name.trim() != name || name.contains("  ") {
+        Err(GuildNameError::InvalidName)
+    } else if tag_len < MIN_GUILD_TAG_LEN || tag_len > MAX_GUILD_TAG_LEN {
+        Err(GuildNameError::InvalidTagLength)
+    } else if !tag.chars().all(|c| c.is_alphanumeric()) {
+        Err(GuildNameError::InvalidTag)
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GuildNameError {
+    EmptyName,
+    NameTooLong,
+    InvalidName,
+    InvalidTagLength,
+    InvalidTag,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rank_permissions() {
+        assert!(GuildRank::Leader.can_manage(GuildRank::Officer));
+        assert!(GuildRank::Officer.can_manage(GuildRank::Member));
+        assert!(!GuildRank::Officer.can_manage(GuildRank::Officer));
+        assert!(!GuildRank::Member.can_manage(GuildRank::Recruit));
+        assert!(!GuildRank::Recruit.can_withdraw());
+        assert_eq!(GuildRank::Officer.promoted(), None);
+        for rank in [
+            GuildRank::Recruit,
+            GuildRank::Member,
+            GuildRank::Officer,
+            GuildRank::Leader,
+        ] {
+            assert_eq!(GuildRank::from_key(rank.to_key()), Some(rank));
+        }
+    }
+
+    #[test]
+    fn guild_names() {
+        assert_eq!(validate_guild_name("Knights of Veloren", "KoV"), Ok(()));
+        assert_eq!(
+            validate_guild_name(" ", "KoV"),
+            Err(GuildNameError::EmptyName)
+        );
+        assert_eq!(
+            validate_guild_name("Knights", "K"),
+            Err(GuildNameError::InvalidTagLength)
+        );
+        assert_eq!(
+            validate_guild_name("Knights", "K-V"),
+            Err(GuildNameError::InvalidTag)
+        );
+    }
+}
@@ -25,6 +25,8 @@ pub use uuid;
 // modules
 #[cfg(not(target_arch = "wasm32"))]
 pub use common_assets as assets;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod achievement;
 #[cfg(not(target_arch = "wasm32"))] pub mod astar;
 #[cfg(not(target_arch = "wasm32"))]
 mod cached_spatial_grid;
@@ -48,9 +50,11 @@ pub mod figure;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod generation;
 #[cfg(not(target_arch = "wasm32"))] pub mod grid;
+#[cfg(not(target_arch = "wasm32"))] pub mod guild;
 #[cfg(not(target_arch = "wasm32"))] pub mod link;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod lottery;
+#[cfg(not(target_arch = "wasm32"))] pub mod mail;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod mounting;
 #[cfg(not(target_arch = "wasm32"))] pub mod npc;
@@ -0,0 +1,83 @@
+//! Types shared between the client and server for the in-game mail system.
+//!
+//! Mail is addressed to a character by name and is stored server-side, so it
+//! can be delivered while the recipient is offline. Each piece of mail may
+//! carry a small number of item attachments and some coins, which the
+//! recipient claims into their inventory.
+use crate::comp::{inventory::slot::InvSlotId, Item};
+use serde::{Deserialize, Serialize};
+
+pub type MailId = i64;
+
+/// The maximum number of item stacks that can be attached to a single piece
+/// of mail
+pub const MAX_MAIL_ATTACHMENTS: usize = 4;
+/// The maximum length (in characters) of a mail subject
+pub const MAX_MAIL_SUBJECT_LEN: usize = 64;
+/// The maximum length (in characters) of a mail body
+pub const MAX_MAIL_BODY_LEN: usize = 1024;
+
+/// A piece of mail as seen by its recipient
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Mail {
+    pub id: MailId,
+    /// The name of the sending character at the time the mail was sent
+    pub sender: String,
+    pub subject: String,
+    pub body: String,
+    /// Unix timestamp (in seconds) of when the mail was sent
+    pub sent_at: i64,
+    pub read: bool,
+    /// Unclaimed item attachments
+    pub attachments: Vec<Item>,
+    /// Unclaimed coins
+    pub coins: u32,
+}
+
+impl Mail {
+    /// Whether the mail still has anything to claim
+    pub fn has_attachments(&self) -> bool { !self.attachments.is_empty() || self.coins > 0 }
+}
+
+/// Requests made by a client to interact with their mailbox
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum MailAction {
+    /// Request the current contents of the mailbox
+    RequestMailbox,
+    /// Send mail to the character named `recipient`. The items in the
+    /// `attachments` slots (whole stacks) and `coins` are removed from the
+    /// sender's inventory.
+    Send {
+        recipient: String,
+        subject: String,
+        body: String,
+        attachments: Vec<InvSlotId>,
+        coins: u32,
+    },
+    MarkRead(MailId),
+    /// Move the attachments of a piece of mail into the inventory
+    Claim(MailId),
+    /// Delete a piece of mail. Mail with unclaimed attachments cannot be
+    /// deleted.
+    Delete(MailId),
+}
+
+/// Validates the text fields of a piece of mail before it is sent
+pub fn validate_mail_text(subject: &str, body: &str) -> Result<(), MailTextError> {
+    if subject.trim().is_empty() {
+        Err(MailTextError::EmptySubject)
+    } else if subject.chars().count() > MAX_MAIL_SUBJECT_LEN {
+        Err(MailTextError::SubjectTooLong)
+    } else if body.chars().count() > MAX_MAIL_BODY_LEN {
+        Err(MailTextError::BodyTooLong)
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MailTextError {
+    EmptySubject,
+    SubjectTooLong,
+    BodyTooLong,
+}
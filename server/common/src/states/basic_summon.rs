@@ -127,7 +127,7 @@ impl CharacterBehavior for Data {
                         let summon_frac =
                             self.summon_count as f32 / self.static_data.summon_amount as f32;
 
-                        let length = rand::thread_rng().gen_range(
+                        let length = data.rng().gen_range(
                             self.static_data.summon_distance.0..=self.static_data.summon_distance.1,
                         );
 
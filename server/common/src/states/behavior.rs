@@ -5,10 +5,13 @@ use crate::{
         Health, InputAttr, InputKind, Inventory, InventoryAction, Mass, Melee, Ori, PhysicsState,
         Pos, SkillSet, StateUpdate, Stats, Vel,
     },
-    resources::DeltaTime,
+    link::Is,
+    mounting::{Mount, Rider},
+    resources::{DeltaTime, RngSeed, Time, TimeOfDay},
     terrain::TerrainGrid,
     uid::Uid,
 };
+use rand::rngs::StdRng;
 use specs::{storage::FlaggedAccessMut, Entity, LazyUpdate};
 use vek::*;
 
@@ -113,6 +116,9 @@ pub struct JoinData<'a> {
     pub mass: &'a Mass,
     pub density: &'a Density,
     pub dt: &'a DeltaTime,
+    pub time: &'a Time,
+    pub time_of_day: &'a TimeOfDay,
+    pub rng_seed: &'a RngSeed,
     pub controller: &'a Controller,
     pub inputs: &'a ControllerInputs,
     pub health: Option<&'a Health>,
@@ -129,6 +135,8 @@ pub struct JoinData<'a> {
     pub combo: Option<&'a Combo>,
     pub alignment: Option<&'a comp::Alignment>,
     pub terrain: &'a TerrainGrid,
+    pub is_mount: Option<&'a Is<Mount>>,
+    pub is_rider: Option<&'a Is<Rider>>,
 }
 
 pub struct JoinStruct<'a> {
@@ -154,6 +162,8 @@ pub struct JoinStruct<'a> {
     pub combo: Option<&'a Combo>,
     pub alignment: Option<&'a comp::Alignment>,
     pub terrain: &'a TerrainGrid,
+    pub is_mount: Option<&'a Is<Mount>>,
+    pub is_rider: Option<&'a Is<Rider>>,
 }
 
 impl<'a> JoinData<'a> {
@@ -161,6 +171,9 @@ impl<'a> JoinData<'a> {
         j: &'a JoinStruct<'a>,
         updater: &'a LazyUpdate,
         dt: &'a DeltaTime,
+        time: &'a Time,
+        time_of_day: &'a TimeOfDay,
+        rng_seed: &'a RngSeed,
         msm: &'a MaterialStatManifest,
     ) -> Self {
         Self {
@@ -184,11 +197,19 @@ impl<'a> JoinData<'a> {
             skill_set: j.skill_set,
             updater,
             dt,
+            time,
+            time_of_day,
+            rng_seed,
             msm,
             combo: j.combo,
             alignment: j.alignment,
             terrain: j.terrain,
+            is_mount: j.is_mount,
+            is_rider: j.is_rider,
             active_abilities: j.active_abilities,
         }
     }
+
+    /// A generator for the random rolls of this entity's character state
+    pub fn rng(&self) -> StdRng { self.rng_seed.rng(*self.time, u64::from(*self.uid)) }
 }
@@ -0,0 +1,260 @@
+use crate::{
+    comp::{character_state::OutputEvents, CharacterState, StateUpdate},
+    event::ServerEvent,
+    resources::TimeOfDay,
+    states::{
+        behavior::{CharacterBehavior, JoinData},
+        utils::*,
+        wielding,
+    },
+    terrain::BiomeKind,
+    vol::ReadVol,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use vek::*;
+
+/// Separated out to condense update portions of character state
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StaticData {
+    /// How long it takes to cast the line
+    pub cast_duration: Duration,
+    /// How long it takes to reel the line back in
+    pub reel_duration: Duration,
+    /// Range of time it takes for a fish to bite in a typical biome at midday
+    pub bite_delay: (Duration, Duration),
+    /// How long a fish stays on the hook before it gets away
+    pub bite_duration: Duration,
+    /// How far the line can be cast
+    pub cast_range: f32,
+    /// What key is used to press ability
+    pub ability_info: AbilityInfo,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Data {
+    /// Struct containing data that does not change over the course of the
+    /// character state
+    pub static_data: StaticData,
+    /// Timer for each stage
+    pub timer: Duration,
+    /// What section the character stage is in
+    pub stage_section: StageSection,
+    /// Where the bobber landed, if the line was cast into water
+    pub bobber_pos: Option<Vec3<f32>>,
+    /// How long after the line settled until the next fish bites
+    pub bite_delay: Duration,
+    /// Whether the input was released since the line was cast, pressing it
+    /// again reels the line in
+    pub input_released: bool,
+    /// Whether a fish was hooked when the line was reeled in
+    pub caught: bool,
+}
+
+impl Data {
+    /// Whether a fish is currently biting, reeling the line in now will catch
+    /// it
+    pub fn is_biting(&self) -> bool {
+        matches!(self.stage_section, StageSection::Action)
+            && self.timer >= self.bite_delay
+            && self.timer < self.bite_delay + self.static_data.bite_duration
+    }
+
+    /// Finds where the line lands when cast along the look direction, if it
+    /// lands in water
+    fn cast_line(&self, data: &JoinData) -> Option<Vec3<f32>> {
+        let start = data.pos.0 + Vec3::unit_z() * data.body.eye_height();
+        let end = start + *data.inputs.look_dir * self.static_data.cast_range;
+        let (dist, _) = data
+            .terrain
+            .ray(start, end)
+            .until(|b| b.is_solid() || b.is_liquid())
+            .cast();
+        let landing = start + (end - start).normalized() * dist;
+        // Let the bobber sink down onto whatever is below where the line landed
+        let (drop, block) = data
+            .terrain
+            .ray(
+                landing,
+                landing - Vec3::unit_z() * self.static_data.cast_range,
+            )
+            .until(|b| b.is_solid() || b.is_liquid())
+            .cast();
+        block
+            .ok()
+            .flatten()
+            .filter(|b| b.is_liquid())
+            .map(|_| landing - Vec3::unit_z() * drop)
+    }
+
+    /// How long until a fish bites at the bobber, which depends on the biome
+    /// the line was cast into and the time of day
+    fn roll_bite_delay(&self, data: &JoinData, bobber_pos: Vec3<f32>) -> Duration {
+        let biome = data
+            .terrain
+            .get_key(data.terrain.pos_key(bobber_pos.map(|e| e.floor() as i32)))
+            .map_or(BiomeKind::Void, |chunk| chunk.meta().biome());
+        let (min, max) = self.static_data.bite_delay;
+        let delay = data.rng().gen_range(min.as_secs_f32()..=max.as_secs_f32());
+        Duration::from_secs_f32(
+            delay * biome_bite_modifier(biome) * time_of_day_bite_modifier(*data.time_of_day),
+        )
+    }
+}
+
+impl CharacterBehavior for Data {
+    fn behavior(&self, data: &JoinData, output_events: &mut OutputEvents) -> StateUpdate {
+        let mut update = StateUpdate::from(data);
+
+        handle_orientation(data, &mut update, 1.0, None);
+        handle_move(data, &mut update, 0.0);
+
+        let input_pressed = input_is_pressed(data, self.static_data.ability_info.input);
+
+        match self.stage_section {
+            StageSection::Buildup => {
+                if self.timer < self.static_data.cast_duration {
+                    // Casts the line
+                    update.character = CharacterState::Fishing(Data {
+                        timer: tick_attack_or_default(data, self.timer, None),
+                        input_released: self.input_released || !input_pressed,
+                        ..*self
+                    });
+                } else if let Some(bobber_pos) = self.cast_line(data) {
+                    // Line landed in water, waits for a bite
+                    update.character = CharacterState::Fishing(Data {
+                        timer: Duration::default(),
+                        stage_section: StageSection::Action,
+                        bobber_pos: Some(bobber_pos),
+                        bite_delay: self.roll_bite_delay(data, bobber_pos),
+                        input_released: self.input_released || !input_pressed,
+                        ..*self
+                    });
+                } else {
+                    // Nothing to fish in, so the line is reeled straight back in
+                    update.character = CharacterState::Fishing(Data {
+                        timer: Duration::default(),
+                        stage_section: StageSection::Recover,
+                        ..*self
+                    });
+                }
+            },
+            StageSection::Action => {
+                if input_pressed && self.input_released {
+                    // Reels the line in, hooking the fish if one is biting
+                    update.character = CharacterState::Fishing(Data {
+                        timer: Duration::default(),
+                        stage_section: StageSection::Recover,
+                        caught: self.is_biting(),
+                        ..*self
+                    });
+                } else if self.timer < self.bite_delay + self.static_data.bite_duration {
+                    // Waits for a bite
+                    update.character = CharacterState::Fishing(Data {
+                        timer: tick_attack_or_default(data, self.timer, None),
+                        input_released: self.input_released || !input_pressed,
+                        ..*self
+                    });
+                } else if let Some(bobber_pos) = self.bobber_pos {
+                    // The fish got away, waits for the next one
+                    update.character = CharacterState::Fishing(Data {
+                        timer: Duration::default(),
+                        bite_delay: self.roll_bite_delay(data, bobber_pos),
+                        input_released: self.input_released || !input_pressed,
+                        ..*self
+                    });
+                }
+            },
+            StageSection::Recover => {
+                if self.timer < self.static_data.reel_duration {
+                    // Reels the line in
+                    update.character = CharacterState::Fishing(Data {
+                        timer: tick_attack_or_default(data, self.timer, None),
+                        ..*self
+                    });
+                } else {
+                    if let Some(pos) = self.bobber_pos.filter(|_| self.caught) {
+                        output_events.emit_server(ServerEvent::CatchFish {
+                            entity: data.entity,
+                            pos,
+                        });
+                    }
+                    // Done
+                    update.character =
+                        CharacterState::Wielding(wielding::Data { is_sneaking: false });
+                }
+            },
+            _ => {
+                // If it somehow ends up in an incorrect stage section
+                update.character = CharacterState::Wielding(wielding::Data { is_sneaking: false });
+            },
+        }
+
+        // At end of state logic so an interrupt isn't overwritten
+        if !input_pressed {
+            handle_state_interrupt(data, &mut update, false);
+        }
+
+        update
+    }
+}
+
+/// How much longer than usual fish take to bite in a biome. Warm, shallow
+/// waters are full of fish while the cold mountain streams are sparse.
+fn biome_bite_modifier(biome: BiomeKind) -> f32 {
+    match biome {
+        BiomeKind::Ocean => 0.8,
+        BiomeKind::Lake | BiomeKind::Swamp => 0.9,
+        BiomeKind::Jungle
+        | BiomeKind::Grassland
+        | BiomeKind::Forest
+        | BiomeKind::Savannah
+        | BiomeKind::Void => 1.0,
+        BiomeKind::Mountain | BiomeKind::Snowland | BiomeKind::Taiga => 1.4,
+        BiomeKind::Desert => 1.8,
+    }
+}
+
+/// Fish feed around dawn and dusk and are slow to bite at night
+fn time_of_day_bite_modifier(time_of_day: TimeOfDay) -> f32 {
+    let hour = (time_of_day.0 / 3600.0).rem_euclid(24.0);
+    if (5.0..8.0).contains(&hour) || (18.0..21.0).contains(&hour) {
+        0.6
+    } else if (8.0..18.0).contains(&hour) {
+        1.0
+    } else {
+        1.3
+    }
+}
+
+/// The loot table that fish caught in a biome are rolled from
+pub fn loot_table(biome: BiomeKind) -> &'static str {
+    match biome {
+        BiomeKind::Ocean => "common.loot_tables.fishing.ocean",
+        BiomeKind::Swamp | BiomeKind::Jungle => "common.loot_tables.fishing.swamp",
+        BiomeKind::Mountain | BiomeKind::Snowland | BiomeKind::Taiga => {
+            "common.loot_tables.fishing.cold"
+        },
+        BiomeKind::Lake
+        | BiomeKind::Grassland
+        | BiomeKind::Forest
+        | BiomeKind::Savannah
+        | BiomeKind::Desert
+        | BiomeKind::Void => "common.loot_tables.fishing.freshwater",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fish_bite_sooner_at_dawn() {
+        let dawn = TimeOfDay(6.0 * 3600.0);
+        let noon = TimeOfDay(12.0 * 3600.0);
+        let midnight = TimeOfDay(24.0 * 3600.0);
+        assert!(time_of_day_bite_modifier(dawn) < time_of_day_bite_modifier(noon));
+        assert!(time_of_day_bite_modifier(noon) < time_of_day_bite_modifier(midnight));
+    }
+}
@@ -1,6 +1,8 @@
 use super::utils::*;
 use crate::{
-    comp::{character_state::OutputEvents, CharacterState, InventoryManip, StateUpdate},
+    comp::{
+        character_state::OutputEvents, slot::InvSlotId, CharacterState, InventoryManip, StateUpdate,
+    },
     event::ServerEvent,
     states::{
         behavior::{CharacterBehavior, JoinData},
@@ -26,6 +28,8 @@ pub struct StaticData {
     pub sprite_pos: Vec3<i32>,
     /// Kind of sprite interacted with
     pub sprite_kind: SpriteInteractKind,
+    /// The seed being planted, when planting in tilled soil
+    pub seed: Option<InvSlotId>,
     /// Had weapon wielded
     pub was_wielded: bool,
     /// Was sneaking
@@ -95,7 +99,10 @@ impl CharacterBehavior for Data {
                     });
                 } else {
                     // Create inventory manipulation event
-                    let inv_manip = InventoryManip::Collect(self.static_data.sprite_pos);
+                    let inv_manip = match self.static_data.seed {
+                        Some(seed) => InventoryManip::Plant(self.static_data.sprite_pos, seed),
+                        None => InventoryManip::Collect(self.static_data.sprite_pos),
+                    };
                     output_events.emit_server(ServerEvent::InventoryManip(data.entity, inv_manip));
                     // Done
 
@@ -132,6 +139,7 @@ pub enum SpriteInteractKind {
     Chest,
     Harvestable,
     Collectible,
+    Plantable,
     Fallback,
 }
 
@@ -150,7 +158,14 @@ impl From<SpriteKind> for Option<SpriteInteractKind> {
             | SpriteKind::WildFlax
             | SpriteKind::RoundCactus
             | SpriteKind::ShortFlatCactus
-            | SpriteKind::MedFlatCactus => Some(SpriteInteractKind::Harvestable),
+            | SpriteKind::MedFlatCactus
+            | SpriteKind::Carrot
+            | SpriteKind::Tomato
+            | SpriteKind::Cabbage
+            | SpriteKind::Flax
+            | SpriteKind::Seedling
+            | SpriteKind::Sprout => Some(SpriteInteractKind::Harvestable),
+            SpriteKind::TilledSoil => Some(SpriteInteractKind::Plantable),
             SpriteKind::Stones
             | SpriteKind::Twigs
             | SpriteKind::VialEmpty
@@ -188,6 +203,11 @@ impl SpriteInteractKind {
                 Duration::from_secs_f32(0.5),
                 Duration::from_secs_f32(0.2),
             ),
+            Self::Plantable => (
+                Duration::from_secs_f32(0.4),
+                Duration::from_secs_f32(1.0),
+                Duration::from_secs_f32(0.3),
+            ),
             Self::Fallback => (
                 Duration::from_secs_f32(5.0),
                 Duration::from_secs_f32(5.0),
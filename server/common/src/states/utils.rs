@@ -13,6 +13,7 @@ use crate::{
     },
     consts::{FRIC_GROUND, GRAVITY, MAX_PICKUP_RANGE},
     event::{LocalEvent, ServerEvent},
+    mounting::TIRED_MOUNT_SPEED,
     states::{behavior::JoinData, *},
     util::Dir,
     vol::ReadVol,
@@ -300,8 +301,29 @@ impl Body {
     }
 }
 
+/// Mounts carrying a rider move at the speed of their
+/// [`MountStats`](crate::mounting::MountStats) for as long as they have the
+/// energy to
+fn mount_efficiency(data: &JoinData<'_>, update: &mut StateUpdate) -> f32 {
+    match data.is_mount.and(data.body.mount_stats()) {
+        Some(stats) if data.inputs.move_dir.magnitude_squared() > 0.0 => {
+            if update
+                .energy
+                .try_change_by(-stats.stamina_drain * data.dt.0)
+                .is_ok()
+            {
+                stats.speed
+            } else {
+                TIRED_MOUNT_SPEED
+            }
+        },
+        _ => 1.0,
+    }
+}
+
 /// Handles updating `Components` to move player based on state of `JoinData`
 pub fn handle_move(data: &JoinData<'_>, update: &mut StateUpdate, efficiency: f32) {
+    let efficiency = efficiency * mount_efficiency(data, update);
     let submersion = data
         .physics
         .in_liquid()
@@ -727,7 +749,11 @@ pub fn handle_manipulate_loadout(
                     .emit_server(ServerEvent::InventoryManip(data.entity, inv_action.into()));
             }
         },
-        InventoryAction::Collect(sprite_pos) => {
+        InventoryAction::Collect(sprite_pos) | InventoryAction::Plant(sprite_pos, _) => {
+            let seed = match inv_action {
+                InventoryAction::Plant(_, seed) => Some(seed),
+                _ => None,
+            };
             let sprite_pos_f32 = sprite_pos.map(|x| x as f32 + 0.5);
             // Closure to check if distance between a point and the sprite is less than
             // MAX_PICKUP_RANGE and the radius of the body
@@ -750,8 +776,10 @@ pub fn handle_manipulate_loadout(
                     .and_then(|b| b.get_sprite());
 
                 // Checks if position has a collectible sprite as well as what sprite is at the
-                // position
-                let sprite_interact = sprite_at_pos.and_then(Option::<SpriteInteractKind>::from);
+                // position. Seeds can only be planted in tilled soil.
+                let sprite_interact = sprite_at_pos
+                    .and_then(Option::<SpriteInteractKind>::from)
+                    .filter(|kind| (*kind == SpriteInteractKind::Plantable) == seed.is_some());
 
                 if let Some(sprite_interact) = sprite_interact {
                     // Do a check that a path can be found between sprite and entity
@@ -819,6 +847,7 @@ pub fn handle_manipulate_loadout(
                                 recover_duration,
                                 sprite_pos,
                                 sprite_kind: sprite_interact,
+                                seed,
                                 was_wielded: matches!(data.character, CharacterState::Wielding(_)),
                                 was_sneak: data.character.is_stealthy(),
                             },
@@ -885,6 +914,7 @@ fn handle_ability(data: &JoinData<'_>, update: &mut StateUpdate, input: InputKin
                     Some(data.body),
                 )
             })
+            .filter(|(ability, _)| data.is_rider.is_none() || ability.can_perform_mounted())
             .filter(|(ability, _)| ability.requirements_paid(data, update))
         {
             update.character = CharacterState::from((
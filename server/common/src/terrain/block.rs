@@ -123,6 +123,8 @@ impl Deref for Block {
 }
 
 impl Block {
+    /// The growth stage at which a planted crop is fully grown
+    pub const MAX_CROP_GROWTH: u8 = 4;
     pub const MAX_HEIGHT: f32 = 3.0;
 
     #[inline]
@@ -166,6 +168,15 @@ impl Block {
         }
     }
 
+    /// A crop that has just been planted in tilled soil
+    #[inline]
+    pub const fn seedling(crop: SpriteKind) -> Self {
+        Self {
+            kind: BlockKind::Air,
+            attr: [SpriteKind::Seedling as u8, 0, crop as u8],
+        }
+    }
+
     #[inline]
     pub fn get_color(&self) -> Option<Rgb<u8>> {
         if self.has_color() {
@@ -194,6 +205,27 @@ impl Block {
         }
     }
 
+    /// The crop planted in this block and its growth stage, up to
+    /// [`Block::MAX_CROP_GROWTH`]. Crops generated with the world, such as
+    /// those in village fields, aren't planted crops.
+    #[inline]
+    pub fn get_crop(&self) -> Option<(SpriteKind, u8)> {
+        // Planted crops keep the crop they grow into in the last attribute, even once
+        // fully grown
+        let crop = SpriteKind::from_u8(self.attr[2]).filter(|s| s.seed_item().is_some())?;
+        match self.get_sprite()? {
+            SpriteKind::Seedling | SpriteKind::Sprout => {
+                Some((crop, self.attr[1].min(Self::MAX_CROP_GROWTH)))
+            },
+            sprite if sprite == crop => Some((crop, Self::MAX_CROP_GROWTH)),
+            _ => None,
+        }
+    }
+
+    /// Is this a block that soil can be tilled in to plant crops on top of?
+    #[inline]
+    pub fn is_tillable(&self) -> bool { matches!(self.kind(), BlockKind::Grass | BlockKind::Earth) }
+
     #[inline]
     pub fn get_glow(&self) -> Option<u8> {
         match self.kind() {
@@ -292,11 +324,14 @@ impl Block {
         }
     }
 
+    /// Can this block be interacted with by hand, either to collect its sprite
+    /// or to plant seeds in tilled soil?
     #[inline]
     pub fn is_collectible(&self) -> bool {
         self.get_sprite()
-            .map(|s| s.is_collectible())
+            .map(|s| s.is_collectible() || s == SpriteKind::TilledSoil)
             .unwrap_or(false)
+            || self.get_crop().is_some()
     }
 
     #[inline]
@@ -379,6 +414,27 @@ impl Block {
         }
     }
 
+    /// If this block is a planted crop, move it on to the given growth stage.
+    /// Crops turn into their fully grown sprite once they reach
+    /// [`Block::MAX_CROP_GROWTH`].
+    #[inline]
+    #[must_use]
+    pub fn with_crop_growth(mut self, growth: u8) -> Self {
+        if let Some((crop, _)) = self.get_crop() {
+            if growth >= Self::MAX_CROP_GROWTH {
+                self.attr = [crop as u8, 0, crop as u8];
+            } else {
+                let stage = if growth < Self::MAX_CROP_GROWTH / 2 {
+                    SpriteKind::Seedling
+                } else {
+                    SpriteKind::Sprout
+                };
+                self.attr = [stage as u8, growth, crop as u8];
+            }
+        }
+        self
+    }
+
     /// Remove the terrain sprite or solid aspects of a block
     #[inline]
     #[must_use]
@@ -420,6 +476,22 @@ mod tests {
         assert_eq!(std::mem::size_of::<Block>(), 4);
     }
 
+    #[test]
+    fn crops_grow_into_their_sprite() {
+        let mut block = Block::seedling(SpriteKind::Carrot);
+        for growth in 1..=Block::MAX_CROP_GROWTH {
+            assert_eq!(block.get_crop(), Some((SpriteKind::Carrot, growth - 1)));
+            block = block.with_crop_growth(growth);
+        }
+        assert_eq!(block.get_sprite(), Some(SpriteKind::Carrot));
+        assert_eq!(
+            block.get_crop(),
+            Some((SpriteKind::Carrot, Block::MAX_CROP_GROWTH))
+        );
+        // Crops generated with the world can't be harvested
+        assert_eq!(Block::air(SpriteKind::Carrot).get_crop(), None);
+    }
+
     #[test]
     fn convert_u32() {
         for bk in BlockKind::iter() {
@@ -191,6 +191,9 @@ make_case_elim!(
         ChristmasOrnament = 0xA4,
         ChristmasWreath = 0xA5,
         EnsnaringWeb = 0xA6,
+        TilledSoil = 0xA7,
+        Seedling = 0xA8,
+        Sprout = 0xA9,
     }
 );
 
@@ -237,6 +240,7 @@ impl SpriteKind {
             SpriteKind::WardrobeDouble => 3.0,
             SpriteKind::Pot => 0.90,
             SpriteKind::Mud => 0.36,
+            SpriteKind::TilledSoil => 0.1,
             SpriteKind::ChestBuried => 0.91,
             SpriteKind::StonyCoral => 1.4,
             SpriteKind::CraftingBench => 1.18,
@@ -369,6 +373,57 @@ impl SpriteKind {
         matches!(self.collectible_id(), Some(LootSpec::LootTable(_)))
     }
 
+    /// What harvesting this crop yields once fully grown. Only crops planted by
+    /// players can be harvested.
+    #[inline]
+    pub fn crop_yield(&self) -> Option<LootSpec<&'static str>> {
+        Some(match self {
+            SpriteKind::Carrot => LootSpec::ItemQuantity("common.items.food.carrot", 2, 3),
+            SpriteKind::Tomato => LootSpec::ItemQuantity("common.items.food.tomato", 2, 4),
+            SpriteKind::Cabbage => LootSpec::ItemQuantity("common.items.food.lettuce", 1, 2),
+            SpriteKind::Flax => LootSpec::ItemQuantity("common.items.flowers.wild_flax", 2, 4),
+            _ => return None,
+        })
+    }
+
+    /// The seed that can be planted in tilled soil to grow this crop, if it
+    /// is a crop
+    #[inline]
+    pub fn seed_item(&self) -> Option<&'static str> {
+        Some(match self {
+            SpriteKind::Carrot => "common.items.seeds.carrot",
+            SpriteKind::Tomato => "common.items.seeds.tomato",
+            SpriteKind::Cabbage => "common.items.seeds.cabbage",
+            SpriteKind::Flax => "common.items.seeds.flax",
+            _ => return None,
+        })
+    }
+
+    /// The crop that grows from planting the given seed item
+    pub fn from_seed_item(item_definition_id: &str) -> Option<Self> {
+        [
+            SpriteKind::Carrot,
+            SpriteKind::Tomato,
+            SpriteKind::Cabbage,
+            SpriteKind::Flax,
+        ]
+        .into_iter()
+        .find(|crop| crop.seed_item() == Some(item_definition_id))
+    }
+
+    /// How many seconds it takes for a planted crop to grow from one stage to
+    /// the next
+    #[inline]
+    pub fn crop_growth_time(&self) -> Option<f64> {
+        Some(match self {
+            SpriteKind::Carrot => 600.0,
+            SpriteKind::Tomato => 900.0,
+            SpriteKind::Cabbage => 750.0,
+            SpriteKind::Flax => 450.0,
+            _ => return None,
+        })
+    }
+
     /// Which tool (if any) is needed to collect this sprite?
     #[inline]
     pub fn mine_tool(&self) -> Option<ToolKind> {
@@ -13,8 +13,8 @@ use common::{
     outcome::Outcome,
     region::RegionMap,
     resources::{
-        DeltaTime, EntitiesDiedLastTick, GameMode, PlayerEntity, PlayerPhysicsSettings, Time,
-        TimeOfDay,
+        DeltaTime, EntitiesDiedLastTick, GameMode, PlayerEntity, PlayerPhysicsSettings, RngSeed,
+        Time, TimeOfDay,
     },
     slowjob::SlowJobPool,
     terrain::{Block, TerrainChunk, TerrainGrid},
@@ -140,6 +140,10 @@ impl State {
         ecs.register::<comp::Health>();
         ecs.register::<comp::Poise>();
         ecs.register::<comp::CanBuild>();
+        ecs.register::<comp::Statistics>();
+        ecs.register::<comp::GuildTag>();
+        ecs.register::<comp::Pet>();
+        ecs.register::<comp::PvpEngagement>();
         ecs.register::<comp::LightEmitter>();
         ecs.register::<comp::Item>();
         ecs.register::<comp::Scale>();
@@ -208,6 +212,7 @@ impl State {
 
         // Register unsynced resources used by the ECS.
         ecs.insert(Time(0.0));
+        ecs.insert(RngSeed::default());
         ecs.insert(DeltaTime(0.0));
         ecs.insert(PlayerEntity(None));
         ecs.insert(TerrainGrid::new().unwrap());
@@ -12,9 +12,9 @@ use common::{
     },
     event::{EventBus, LocalEvent, ServerEvent},
     link::Is,
-    mounting::Rider,
+    mounting::{Mount, Rider},
     outcome::Outcome,
-    resources::{DeltaTime, Time},
+    resources::{DeltaTime, RngSeed, Time, TimeOfDay},
     states::{
         behavior::{JoinData, JoinStruct},
         idle,
@@ -31,6 +31,8 @@ pub struct ReadData<'a> {
     local_bus: Read<'a, EventBus<LocalEvent>>,
     dt: Read<'a, DeltaTime>,
     time: Read<'a, Time>,
+    rng_seed: Read<'a, RngSeed>,
+    time_of_day: Read<'a, TimeOfDay>,
     lazy_update: Read<'a, LazyUpdate>,
     healths: ReadStorage<'a, Health>,
     bodies: ReadStorage<'a, Body>,
@@ -39,6 +41,7 @@ pub struct ReadData<'a> {
     melee_attacks: ReadStorage<'a, Melee>,
     beams: ReadStorage<'a, Beam>,
     uids: ReadStorage<'a, Uid>,
+    is_mounts: ReadStorage<'a, Is<Mount>>,
     is_riders: ReadStorage<'a, Is<Rider>>,
     stats: ReadStorage<'a, Stats>,
     skill_sets: ReadStorage<'a, SkillSet>,
@@ -194,6 +197,8 @@ impl<'a> System<'a> for Sys {
                 combo,
                 alignment: read_data.alignments.get(entity),
                 terrain: &read_data.terrain,
+                is_mount: read_data.is_mounts.get(entity),
+                is_rider,
             };
 
             for action in actions {
@@ -201,6 +206,9 @@ impl<'a> System<'a> for Sys {
                     &join_struct,
                     &read_data.lazy_update,
                     &read_data.dt,
+                    &read_data.time,
+                    &read_data.time_of_day,
+                    &read_data.rng_seed,
                     &read_data.msm,
                 );
                 let state_update = j.character.handle_event(&j, &mut output_events, action);
@@ -219,6 +227,9 @@ impl<'a> System<'a> for Sys {
                 &join_struct,
                 &read_data.lazy_update,
                 &read_data.dt,
+                &read_data.time,
+                &read_data.time_of_day,
+                &read_data.rng_seed,
                 &read_data.msm,
             );
 
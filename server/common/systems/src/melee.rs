@@ -3,16 +3,17 @@ use common::{
     comp::{
         agent::{Sound, SoundKind},
         Alignment, Body, CharacterState, Combo, Energy, Group, Health, Inventory, Melee, Ori,
-        Player, Pos, Scale, Stats,
+        Player, Pos, PvpEngagement, Scale, Stats,
     },
     event::{EventBus, ServerEvent},
     outcome::Outcome,
-    resources::Time,
+    resources::{RngSeed, Time},
     uid::{Uid, UidAllocator},
     util::Dir,
     GroupTarget,
 };
 use common_ecs::{Job, Origin, Phase, System};
+use rand::rngs::StdRng;
 use specs::{
     shred::ResourceId, Entities, Join, Read, ReadStorage, SystemData, World, Write, WriteStorage,
 };
@@ -21,9 +22,11 @@ use vek::*;
 #[derive(SystemData)]
 pub struct ReadData<'a> {
     time: Read<'a, Time>,
+    rng_seed: Read<'a, RngSeed>,
     uid_allocator: Read<'a, UidAllocator>,
     entities: Entities<'a>,
     players: ReadStorage<'a, Player>,
+    pvp_engagements: ReadStorage<'a, PvpEngagement>,
     uids: ReadStorage<'a, Uid>,
     positions: ReadStorage<'a, Pos>,
     orientations: ReadStorage<'a, Ori>,
@@ -72,6 +75,9 @@ impl<'a> System<'a> for Sys {
             if melee_attack.applied {
                 continue;
             }
+            let mut rng: StdRng = read_data
+                .rng_seed
+                .rng(*read_data.time, u64::from(attacker.id()));
             server_emitter.emit(ServerEvent::Sound {
                 sound: Sound::new(SoundKind::Melee, pos.0, 3.0, read_data.time.0),
             });
@@ -171,6 +177,7 @@ impl<'a> System<'a> for Sys {
                     let may_harm = combat::may_harm(
                         &read_data.alignments,
                         &read_data.players,
+                        &read_data.pvp_engagements,
                         &read_data.uid_allocator,
                         Some(attacker),
                         target,
@@ -190,6 +197,7 @@ impl<'a> System<'a> for Sys {
                         1.0,
                         AttackSource::Melee,
                         *read_data.time,
+                        &mut rng,
                         |e| server_emitter.emit(e),
                         |o| outcomes.push(o),
                     );
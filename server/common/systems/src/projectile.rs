@@ -3,17 +3,17 @@ use common::{
     comp::{
         agent::{Sound, SoundKind},
         projectile, Alignment, Body, CharacterState, Combo, Energy, Group, Health, Inventory, Ori,
-        PhysicsState, Player, Pos, Projectile, Stats, Vel,
+        PhysicsState, Player, Pos, PvpEngagement, Projectile, Stats, Vel,
     },
     event::{Emitter, EventBus, ServerEvent},
     outcome::Outcome,
-    resources::{DeltaTime, Time},
+    resources::{DeltaTime, RngSeed, Time},
     uid::{Uid, UidAllocator},
     util::Dir,
     GroupTarget,
 };
 use common_ecs::{Job, Origin, Phase, System};
-use rand::{thread_rng, Rng};
+use rand::{rngs::StdRng, Rng};
 use specs::{
     saveload::MarkerAllocator, shred::ResourceId, Entities, Entity as EcsEntity, Join, Read,
     ReadStorage, SystemData, World, Write, WriteStorage,
@@ -24,8 +24,10 @@ use vek::*;
 #[derive(SystemData)]
 pub struct ReadData<'a> {
     time: Read<'a, Time>,
+    rng_seed: Read<'a, RngSeed>,
     entities: Entities<'a>,
     players: ReadStorage<'a, Player>,
+    pvp_engagements: ReadStorage<'a, PvpEngagement>,
     dt: Read<'a, DeltaTime>,
     uid_allocator: Read<'a, UidAllocator>,
     server_bus: Read<'a, EventBus<ServerEvent>>,
@@ -78,7 +80,9 @@ impl<'a> System<'a> for Sys {
                 .owner
                 .and_then(|uid| read_data.uid_allocator.retrieve_entity_internal(uid.into()));
 
-            let mut rng = thread_rng();
+            let mut rng: StdRng = read_data
+                .rng_seed
+                .rng(*read_data.time, u64::from(entity.id()));
             if physics.on_surface().is_none() && rng.gen_bool(0.05) {
                 server_emitter.emit(ServerEvent::Sound {
                     sound: Sound::new(SoundKind::Projectile, pos.0, 2.0, read_data.time.0),
@@ -142,6 +146,7 @@ impl<'a> System<'a> for Sys {
                         projectile_info,
                         projectile_target_info,
                         &read_data,
+                        &mut rng,
                         &mut projectile_vanished,
                         &mut outcomes,
                         &mut server_emitter,
@@ -230,6 +235,7 @@ fn dispatch_hit(
     projectile_info: ProjectileInfo,
     projectile_target_info: ProjectileTargetInfo,
     read_data: &ReadData,
+    rng: &mut StdRng,
     projectile_vanished: &mut bool,
     outcomes: &mut Vec<Outcome>,
     server_emitter: &mut Emitter<ServerEvent>,
@@ -300,6 +306,7 @@ fn dispatch_hit(
             let may_harm = combat::may_harm(
                 &read_data.alignments,
                 &read_data.players,
+                &read_data.pvp_engagements,
                 &read_data.uid_allocator,
                 owner,
                 target,
@@ -321,6 +328,7 @@ fn dispatch_hit(
                 1.0,
                 AttackSource::Projectile,
                 *read_data.time,
+                rng,
                 |e| server_emitter.emit(e),
                 |o| outcomes.push(o),
             );
@@ -0,0 +1,115 @@
+use std::collections::BTreeMap;
+use structopt::StructOpt;
+use veloren_server::combat_sim::{SimConfig, SimReport, Simulation, DEFAULT_TICK_RATE};
+
+/// Lets two combatants fight each other without a server and reports how the
+/// fights went. It can be run with
+/// `cargo run --bin combat_sim --features bin_combat_sim -- <first> <second>`.
+#[derive(StructOpt)]
+struct Cli {
+    /// Entity config of the first combatant, e.g. `common.entity.village.guard`
+    first: String,
+    /// Entity config of the second combatant
+    second: String,
+    /// Seed of the first fight, the seeds of further fights count up from it
+    #[structopt(long, default_value = "0")]
+    seed: u64,
+    /// How many fights to run
+    #[structopt(long, default_value = "1")]
+    fights: u64,
+    /// Distance between the combatants at the start of a fight
+    #[structopt(long, default_value = "10")]
+    distance: f32,
+    /// Fights lasting longer than this many seconds are called a draw
+    #[structopt(long, default_value = "120")]
+    max_duration: u64,
+}
+
+fn main() {
+    let cli = Cli::from_args();
+
+    let reports = (cli.seed..cli.seed + cli.fights)
+        .map(|seed| {
+            let mut config = SimConfig::new(&cli.first, &cli.second)
+                .with_seed(seed)
+                .with_max_ticks(cli.max_duration * u64::from(DEFAULT_TICK_RATE));
+            config.distance = cli.distance;
+            let report = Simulation::new(config).run();
+            print_fight(seed, &report);
+            report
+        })
+        .collect::<Vec<_>>();
+
+    if reports.len() > 1 {
+        print_summary(&reports);
+    }
+}
+
+fn print_fight(seed: u64, report: &SimReport) {
+    let outcome = match (report.winner, report.time_to_kill()) {
+        (Some(winner), Some(ttk)) => {
+            format!("{} won after {:.2}s", report.combatants[winner].name, ttk)
+        },
+        _ => format!("draw after {:.2}s", report.duration),
+    };
+    println!("Fight {}: {}", seed, outcome);
+    for combatant in &report.combatants {
+        println!(
+            "  {}: dealt {:.1} ({:.1} DPS), took {:.1}, abilities: {}",
+            combatant.name,
+            combatant.damage_dealt,
+            combatant.dps,
+            combatant.damage_taken,
+            format_abilities(&combatant.abilities_used),
+        );
+    }
+}
+
+fn print_summary(reports: &[SimReport]) {
+    let fights = reports.len() as f32;
+    println!("Summary of {} fights:", reports.len());
+    for i in 0..2 {
+        let wins = reports
+            .iter()
+            .filter(|report| report.winner == Some(i))
+            .collect::<Vec<_>>();
+        let mean_ttk = if wins.is_empty() {
+            "-".to_string()
+        } else {
+            let total = wins
+                .iter()
+                .filter_map(|report| report.time_to_kill())
+                .sum::<f64>();
+            format!("{:.2}s", total / wins.len() as f64)
+        };
+        let mean =
+            |stat: &dyn Fn(&SimReport) -> f32| reports.iter().map(stat).sum::<f32>() / fights;
+        let mut abilities_used = BTreeMap::new();
+        for report in reports {
+            for (ability, uses) in &report.combatants[i].abilities_used {
+                *abilities_used.entry(ability.clone()).or_default() += uses;
+            }
+        }
+        println!(
+            "  {}: {} wins (mean time to kill {}), mean {:.1} DPS, mean damage taken {:.1}, \
+             abilities: {}",
+            reports[0].combatants[i].name,
+            wins.len(),
+            mean_ttk,
+            mean(&|report| report.combatants[i].dps),
+            mean(&|report| report.combatants[i].damage_taken),
+            format_abilities(&abilities_used),
+        );
+    }
+}
+
+fn format_abilities(abilities_used: &BTreeMap<String, u32>) -> String {
+    if abilities_used.is_empty() {
+        return "none".to_string();
+    }
+    abilities_used
+        .iter()
+        .map(|(ability, uses)| format!("{} x{}", ability, uses))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -75,6 +75,7 @@ pub fn create_character(
         pets: Vec::new(),
         active_abilities: Default::default(),
         map_marker,
+        statistics: Default::default(),
     });
     Ok(())
 }
@@ -186,7 +186,9 @@ impl Client {
                     | ServerGeneral::SiteEconomy(_)
                     | ServerGeneral::UpdatePendingTrade(_, _, _)
                     | ServerGeneral::FinishedTrade(_)
-                    | ServerGeneral::MapMarker(_) => {
+                    | ServerGeneral::MapMarker(_)
+                    | ServerGeneral::MailboxUpdate(_)
+                    | ServerGeneral::GuildUpdate(_) => {
                         PreparedMsg::new(2, &g, &self.in_game_stream_params)
                     },
                     //Ingame related, terrain
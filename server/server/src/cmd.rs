@@ -4,15 +4,27 @@
 
 use crate::{
     client::Client,
+    coins::{coin_count, remove_coins},
+    guild::{self, Guilds},
+    housing::{self, HousingPlots},
     location::Locations,
     login_provider::LoginProvider,
+    persistence::{
+        character_updater::CharacterUpdater, guild::GuildRequest, housing::HousingRequest,
+    },
+    presence::Presence,
+    pvp,
+    rtsim::RtSim,
+    schematic::{self, Mirror, Schematic, Transform},
     settings::{
         Ban, BanAction, BanInfo, EditableSetting, SettingError, WhitelistInfo, WhitelistRecord,
     },
+    site_state::SiteStates,
     sys::terrain::NpcData,
-    wiring,
+    time, wiring,
     wiring::{Logic, OutputFormula},
-    Server, Settings, SpawnPoint, StateExt,
+    world_edit::{self, Shape, WorldEdit},
+    world_event, Server, Settings, SpawnPoint, StateExt,
 };
 use assets::AssetExt;
 use authc::Uuid;
@@ -20,13 +32,14 @@ use chrono::{NaiveTime, Timelike, Utc};
 use common::{
     assets,
     calendar::Calendar,
+    character::CharacterId,
     cmd::{
         ChatCommand, BUFF_PACK, BUFF_PARSER, ITEM_SPECS, KIT_MANIFEST_PATH, PRESET_MANIFEST_PATH,
     },
     comp::{
         self,
         aura::{Aura, AuraKind, AuraTarget},
-        buff::{Buff, BuffCategory, BuffData, BuffKind, BuffSource},
+        buff::{Buff, BuffCategory, BuffData, BuffKind, BuffSource, BUFF_MANIFEST},
         inventory::item::{tool::AbilityMap, MaterialStatManifest, Quality},
         invite::InviteKind,
         AdminRole, ChatType, Inventory, Item, LightEmitter, WaypointArea,
@@ -35,17 +48,21 @@ use common::{
     effect::Effect,
     event::{EventBus, ServerEvent},
     generation::{EntityConfig, EntityInfo},
+    guild::{
+        validate_guild_name, GuildId, GuildNameError, GuildRank, MAX_GUILD_NAME_LEN,
+        MAX_GUILD_TAG_LEN, MIN_GUILD_TAG_LEN,
+    },
     link::Is,
     mounting::Rider,
     npc::{self, get_npc_name},
     resources::{BattleMode, PlayerPhysicsSettings, Time, TimeOfDay},
-    terrain::{Block, BlockKind, SpriteKind, TerrainChunkSize},
+    terrain::{Block, BlockKind, SpriteKind, StructuresGroup, TerrainChunkSize},
     uid::{Uid, UidAllocator},
     vol::{ReadVol, RectVolSize},
     Damage, DamageKind, DamageSource, Explosion, LoadoutBuilder, RadiusEffect,
 };
 use common_net::{
-    msg::{DisconnectReason, Notification, PlayerListUpdate, ServerGeneral},
+    msg::{DisconnectReason, Notification, PlayerListUpdate, PresenceKind, ServerGeneral},
     sync::WorldSyncExt,
 };
 use common_state::{BuildAreaError, BuildAreas};
@@ -121,6 +138,10 @@ fn do_command(
         ChatCommand::Airship => handle_spawn_airship,
         ChatCommand::Alias => handle_alias,
         ChatCommand::ApplyBuff => handle_apply_buff,
+        ChatCommand::ArenaAdd => handle_arena_add,
+        ChatCommand::ArenaList => handle_arena_list,
+        ChatCommand::ArenaRemove => handle_arena_remove,
+        ChatCommand::ArenaScores => handle_arena_scores,
         ChatCommand::Ban => handle_ban,
         ChatCommand::BattleMode => handle_battlemode,
         ChatCommand::BattleModeForce => handle_battlemode_force,
@@ -132,7 +153,10 @@ fn do_command(
         ChatCommand::DebugColumn => handle_debug_column,
         ChatCommand::DisconnectAllPlayers => handle_disconnect_all_players,
         ChatCommand::DropAll => handle_drop_all,
+        ChatCommand::Duel => handle_duel,
+        ChatCommand::DuelForfeit => handle_duel_forfeit,
         ChatCommand::Dummy => handle_spawn_training_dummy,
+        ChatCommand::Edit => handle_edit,
         ChatCommand::Explosion => handle_explosion,
         ChatCommand::Faction => handle_faction,
         ChatCommand::GiveItem => handle_give_item,
@@ -142,6 +166,14 @@ fn do_command(
         ChatCommand::GroupKick => handle_group_kick,
         ChatCommand::GroupLeave => handle_group_leave,
         ChatCommand::GroupPromote => handle_group_promote,
+        ChatCommand::Guild => handle_guild,
+        ChatCommand::GuildCreate => handle_guild_create,
+        ChatCommand::GuildDemote => handle_guild_demote,
+        ChatCommand::GuildDisband => handle_guild_disband,
+        ChatCommand::GuildInvite => handle_guild_invite,
+        ChatCommand::GuildKick => handle_guild_kick,
+        ChatCommand::GuildLeave => handle_guild_leave,
+        ChatCommand::GuildPromote => handle_guild_promote,
         ChatCommand::Health => handle_health,
         ChatCommand::Help => handle_help,
         ChatCommand::Home => handle_home,
@@ -160,12 +192,14 @@ fn do_command(
         ChatCommand::Object => handle_object,
         ChatCommand::PermitBuild => handle_permit_build,
         ChatCommand::Players => handle_players,
+        ChatCommand::Plot => handle_plot,
         ChatCommand::Region => handle_region,
         ChatCommand::RemoveLights => handle_remove_lights,
         ChatCommand::RevokeBuild => handle_revoke_build,
         ChatCommand::RevokeBuildAll => handle_revoke_build_all,
         ChatCommand::Safezone => handle_safezone,
         ChatCommand::Say => handle_say,
+        ChatCommand::Schematic => handle_schematic,
         ChatCommand::ServerPhysics => handle_server_physics,
         ChatCommand::SetMotd => handle_set_motd,
         ChatCommand::Ship => handle_spawn_ship,
@@ -183,6 +217,7 @@ fn do_command(
         ChatCommand::Wiring => handle_spawn_wiring,
         ChatCommand::Whitelist => handle_whitelist,
         ChatCommand::World => handle_world,
+        ChatCommand::WorldEvent => handle_world_event,
         ChatCommand::MakeVolume => handle_make_volume,
         ChatCommand::Location => handle_location,
         ChatCommand::CreateLocation => handle_create_location,
@@ -1626,6 +1661,221 @@ fn handle_players(
     Ok(())
 }
 
+fn handle_plot(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let settings = server.settings().housing;
+    if !settings.enabled {
+        return Err("Plots cannot be bought on this server".into());
+    }
+    let character_id = match server
+        .state
+        .ecs()
+        .read_storage::<Presence>()
+        .get(client)
+        .map(|presence| presence.kind)
+    {
+        Some(PresenceKind::Character(character_id)) => character_id,
+        _ => return Err("You must be playing a character to own a plot".into()),
+    };
+    let wpos = position(server, client, "target")?.0.xy().map(|e| e as i32);
+    let owned = server
+        .state
+        .ecs()
+        .read_resource::<HousingPlots>()
+        .owned_by(character_id)
+        .cloned();
+    let format_date = |timestamp| {
+        chrono::NaiveDateTime::from_timestamp(timestamp, 0)
+            .format("%Y-%m-%d %H:%M UTC")
+            .to_string()
+    };
+
+    let msg = match parse_args!(args, String).as_deref().unwrap_or("info") {
+        "buy" => {
+            if owned.is_some() {
+                return Err("You already own a plot, release it before buying another".into());
+            }
+            #[cfg(feature = "worldgen")]
+            let plot = housing::find_plot_for_sale(&server.world, &server.index, &settings, wpos);
+            #[cfg(not(feature = "worldgen"))]
+            let plot: Option<housing::PlotForSale> = None;
+            let plot = plot.ok_or_else(|| "There is no plot for sale here".to_string())?;
+            if let Some(owner) = server
+                .state
+                .ecs()
+                .read_resource::<HousingPlots>()
+                .get_at(wpos)
+            {
+                return Err(format!("This plot is already owned by {}", owner.owner_alias));
+            }
+            if plot.is_house
+                && server
+                    .state
+                    .ecs()
+                    .read_resource::<RtSim>()
+                    .is_lived_in(Aabr {
+                        min: plot.bounds.min.xy(),
+                        max: plot.bounds.max.xy(),
+                    })
+            {
+                return Err("Someone already lives in this house".into());
+            }
+
+            let owner_alias = server
+                .state
+                .ecs()
+                .read_storage::<comp::Stats>()
+                .get(client)
+                .map(|stats| stats.name.clone())
+                .unwrap_or_default();
+            let now = time::now();
+            let plot_key = {
+                let ecs = server.state.ecs();
+                ecs.write_resource::<HousingPlots>()
+                    .claim(
+                        &mut ecs.write_resource::<BuildAreas>(),
+                        character_id,
+                        owner_alias,
+                        plot.bounds,
+                        now + housing::rent_period(&settings),
+                        now,
+                    )?
+                    .key()
+            };
+
+            // The plot is only paid for once it has been claimed, and given up
+            // again if the buyer can't afford it
+            let cost = plot.price(&settings) + settings.rent;
+            let inventory = match take_coins(server, client, u64::from(cost)) {
+                Ok(inventory) => inventory,
+                Err(_) => {
+                    let ecs = server.state.ecs();
+                    ecs.write_resource::<HousingPlots>()
+                        .release(&mut ecs.write_resource::<BuildAreas>(), plot_key);
+                    return Err(format!(
+                        "You need {} coins to buy this plot, including the first rent payment",
+                        cost
+                    ));
+                },
+            };
+
+            let ecs = server.state.ecs();
+            let plot = ecs
+                .read_resource::<HousingPlots>()
+                .get(plot_key)
+                .cloned()
+                .ok_or_else(|| "This plot is no longer for sale".to_string())?;
+            housing::grant_build_permission(ecs, client, plot.build_area);
+            ecs.write_resource::<CharacterUpdater>()
+                .housing(HousingRequest::Pay {
+                    plot: plot.to_data(),
+                    inventory,
+                });
+
+            format!(
+                "You bought the plot from {} to {} for {} coins. Use /build to start building.",
+                plot.bounds.min, plot.bounds.max, cost
+            )
+        },
+        "info" => {
+            let plots = server.state.ecs().read_resource::<HousingPlots>();
+            let here = match plots.get_at(wpos) {
+                Some(plot) if plot.owner == character_id => format!(
+                    "You own this plot, rent is paid until {}",
+                    format_date(plot.paid_until)
+                ),
+                Some(plot) => format!("This plot is owned by {}", plot.owner_alias),
+                None => {
+                    #[cfg(feature = "worldgen")]
+                    let plot =
+                        housing::find_plot_for_sale(&server.world, &server.index, &settings, wpos);
+                    #[cfg(not(feature = "worldgen"))]
+                    let plot: Option<housing::PlotForSale> = None;
+                    match plot {
+                        Some(plot) => format!(
+                            "This plot is for sale for {} coins, plus {} coins of rent every {} \
+                             days",
+                            plot.price(&settings),
+                            settings.rent,
+                            settings.rent_period_days
+                        ),
+                        None => "There is no plot for sale here".to_owned(),
+                    }
+                },
+            };
+            match &owned {
+                Some(plot) if plots.get_at(wpos).map(|p| p.key()) != Some(plot.key()) => format!(
+                    "{}\nYou own the plot at {}, rent is paid until {}",
+                    here,
+                    plot.bounds.min.xy(),
+                    format_date(plot.paid_until)
+                ),
+                _ => here,
+            }
+        },
+        "pay" => {
+            let plot = owned.ok_or_else(|| "You don't own a plot".to_string())?;
+            let inventory = take_coins(server, client, u64::from(settings.rent))
+                .map_err(|_| format!("You need {} coins to pay the rent", settings.rent))?;
+            let mut plots = server.state.ecs().write_resource::<HousingPlots>();
+            let plot = plots
+                .get_mut(plot.key())
+                .expect("The plot was owned a moment ago");
+            plot.paid_until += housing::rent_period(&settings);
+            let paid_until = plot.paid_until;
+            server
+                .state
+                .ecs()
+                .write_resource::<CharacterUpdater>()
+                .housing(HousingRequest::Pay {
+                    plot: plot.to_data(),
+                    inventory,
+                });
+            format!("Rent is now paid until {}", format_date(paid_until))
+        },
+        "release" => {
+            let plot = owned.ok_or_else(|| "You don't own a plot".to_string())?;
+            let ecs = server.state.ecs();
+            ecs.write_resource::<HousingPlots>()
+                .release(&mut ecs.write_resource::<BuildAreas>(), plot.key());
+            housing::revoke_build_permission(ecs, client, plot.build_area);
+            ecs.write_resource::<CharacterUpdater>()
+                .housing(HousingRequest::Delete(plot.key()));
+            "You released your plot".to_owned()
+        },
+        _ => return Err(action.help_string()),
+    };
+
+    server.notify_client(client, ServerGeneral::server_msg(ChatType::CommandInfo, msg));
+    Ok(())
+}
+
+/// Removes coins from an entity's inventory, failing if it doesn't hold enough.
+/// The remaining inventory is returned so that it can be persisted along with
+/// whatever the coins paid for.
+fn take_coins(server: &mut Server, entity: EcsEntity, amount: u64) -> CmdResult<Inventory> {
+    let mut inventories = server.state.ecs().write_storage::<Inventory>();
+    let mut inventory = inventories
+        .get_mut(entity)
+        .filter(|inventory| coin_count(inventory) >= amount)
+        .ok_or_else(|| "Not enough coins".to_string())?;
+    remove_coins(&mut inventory, amount);
+    let remaining = inventory.clone();
+    drop(inventories);
+    insert_or_replace_component(
+        server,
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Gave),
+        "target",
+    )?;
+    Ok(remaining)
+}
+
 fn handle_build(
     server: &mut Server,
     client: EcsEntity,
@@ -1750,6 +2000,557 @@ fn handle_build_area_remove(
     }
 }
 
+/// Whether `entity` is in build mode and may build in the whole of `aabb`.
+fn can_build_in(server: &Server, entity: EcsEntity, aabb: Aabb<i32>) -> bool {
+    let build_areas = server.state.ecs().read_resource::<BuildAreas>();
+    server
+        .state
+        .ecs()
+        .read_storage::<comp::CanBuild>()
+        .get(entity)
+        .filter(|can_build| can_build.enabled)
+        .map_or(false, |can_build| {
+            can_build.build_areas.iter().any(|id| {
+                build_areas.areas().get(*id).map_or(false, |area| {
+                    area.contains_point(aabb.min) && area.contains_point(aabb.max)
+                })
+            })
+        })
+}
+
+/// Whether `entity` has been given any build areas, whether or not it is in
+/// build mode.
+fn is_builder(server: &Server, entity: EcsEntity) -> bool {
+    server
+        .state
+        .ecs()
+        .read_storage::<comp::CanBuild>()
+        .get(entity)
+        .map_or(false, |can_build| !can_build.build_areas.is_empty())
+}
+
+/// Sets blocks, saving them through terrain persistence and recording the
+/// changes to sites.
+fn set_blocks(server: &Server, blocks: &[(Vec3<i32>, Block)]) {
+    #[cfg(feature = "persistent_world")]
+    let mut terrain_persistence = server
+        .state
+        .ecs()
+        .try_fetch_mut::<crate::TerrainPersistence>();
+    let mut site_states = server.state.ecs().write_resource::<SiteStates>();
+    for &(pos, block) in blocks {
+        // Edits are only made inside build areas, so the blocks they remove are
+        // not damage for the sites to repair
+        site_states.record_build(pos, block);
+        server.state.set_block(pos, block);
+        #[cfg(feature = "persistent_world")]
+        if let Some(terrain_persistence) = terrain_persistence.as_mut() {
+            terrain_persistence.set_block(pos, block);
+        }
+    }
+}
+
+/// Sets blocks on behalf of `entity`, remembering the blocks they replace so
+/// that the edit can be undone. Returns the number of blocks set.
+fn edit_blocks(
+    server: &mut Server,
+    entity: EcsEntity,
+    blocks: impl IntoIterator<Item = (Vec3<i32>, Block)>,
+) -> CmdResult<(usize, bool)> {
+    let blocks = blocks.into_iter().collect::<Vec<_>>();
+    let replaced = {
+        let terrain = server.state.terrain();
+        blocks
+            .iter()
+            .map(|&(pos, _)| {
+                terrain
+                    .get(pos)
+                    .map(|old| (pos, *old))
+                    .map_err(|_| format!("The terrain at {} isn't loaded", pos))
+            })
+            .collect::<CmdResult<Vec<_>>>()?
+    };
+    set_blocks(server, &blocks);
+    let undoable = server
+        .state
+        .ecs()
+        .write_storage::<WorldEdit>()
+        .entry(entity)
+        .map_err(|_| "Cannot find target entity!".to_string())?
+        .or_insert_with(WorldEdit::default)
+        .push_history(replaced);
+    Ok((blocks.len(), undoable))
+}
+
+/// Warns that an edit made with [`edit_blocks`] can't be undone.
+fn edit_message(msg: String, undoable: bool) -> String {
+    if undoable {
+        msg
+    } else {
+        format!("{}. The edit was too large to be undone", msg)
+    }
+}
+
+/// The region selected by `entity`, if it may edit all of it.
+fn edit_selection(server: &Server, entity: EcsEntity) -> CmdResult<Aabb<i32>> {
+    let aabb = server
+        .state
+        .ecs()
+        .read_storage::<WorldEdit>()
+        .get(entity)
+        .and_then(WorldEdit::selection)
+        .ok_or("Select a region with /edit pos1 and /edit pos2 first")?;
+    if !can_build_in(server, entity, aabb) {
+        return Err("The selection must be inside your build areas".into());
+    }
+    // The corners can be anywhere, so the size may not even fit in a usize
+    let blocks = (aabb.max - aabb.min)
+        .map(|e| i64::from(e) + 1)
+        .iter()
+        .try_fold(1i64, |blocks, e| blocks.checked_mul(*e));
+    match blocks {
+        Some(blocks) if blocks <= schematic::MAX_BLOCKS as i64 => Ok(aabb),
+        _ => Err(format!(
+            "The selection is too large, it may contain at most {} blocks",
+            schematic::MAX_BLOCKS
+        )),
+    }
+}
+
+fn parse_block_kind(name: Option<String>, action: &ChatCommand) -> CmdResult<BlockKind> {
+    let name = name.ok_or_else(|| action.help_string())?;
+    BlockKind::from_str(&name).map_err(|_| format!("Invalid block kind: {}", name))
+}
+
+fn handle_edit(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let (subcommand, args) = parse_args!(args, String, ..Vec<String>);
+    let pos = position(server, target, "target")?
+        .0
+        .map(|e| e.floor() as i32);
+    let msg = match subcommand.as_deref() {
+        Some(corner @ ("pos1" | "pos2")) => {
+            let corner_pos = match parse_args!(args, i32, i32, i32) {
+                (Some(x), Some(y), Some(z)) => Vec3::new(x, y, z),
+                (None, None, None) => pos,
+                _ => return Err(action.help_string()),
+            };
+            let index = if corner == "pos1" { 0 } else { 1 };
+            let mut world_edits = server.state.ecs().write_storage::<WorldEdit>();
+            let world_edit = world_edits
+                .entry(target)
+                .map_err(|_| "Cannot find target entity!".to_string())?
+                .or_insert_with(WorldEdit::default);
+            world_edit.corners[index] = Some(corner_pos);
+            match world_edit.selection() {
+                Some(aabb) => format!(
+                    "Corner {} set to {}, selected {} to {}",
+                    index + 1,
+                    corner_pos,
+                    aabb.min,
+                    aabb.max
+                ),
+                None => format!("Corner {} set to {}", index + 1, corner_pos),
+            }
+        },
+        Some(shape @ ("fill" | "hollow" | "walls")) => {
+            let shape = match shape {
+                "fill" => Shape::Fill,
+                "hollow" => Shape::Hollow,
+                _ => Shape::Walls,
+            };
+            let (kind, r, g, b) = parse_args!(args, String, u8, u8, u8);
+            let block = Block::new(
+                parse_block_kind(kind, action)?,
+                Rgb::new(r, g, b).map(|e| e.unwrap_or(255)),
+            );
+            let aabb = edit_selection(server, target)?;
+            let blocks = world_edit::positions(aabb)
+                .filter_map(|pos| Some((pos, shape.block_at(aabb, pos, block)?)));
+            let (count, undoable) = edit_blocks(server, target, blocks)?;
+            edit_message(format!("Set {} blocks", count), undoable)
+        },
+        Some("replace") => {
+            let (from, to, r, g, b) = parse_args!(args, String, String, u8, u8, u8);
+            let from = parse_block_kind(from, action)?;
+            let to = parse_block_kind(to, action)?;
+            let aabb = edit_selection(server, target)?;
+            let blocks = {
+                let terrain = server.state.terrain();
+                world_edit::positions(aabb)
+                    .filter_map(|pos| {
+                        let old = terrain.get(pos).ok().filter(|old| old.kind() == from)?;
+                        // Keep the old color unless a new one is given
+                        let color = Rgb::new(r, g, b).map2(
+                            old.get_color().unwrap_or_else(|| Rgb::broadcast(255)),
+                            |new, old| new.unwrap_or(old),
+                        );
+                        Some((pos, Block::new(to, color)))
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let (count, undoable) = edit_blocks(server, target, blocks)?;
+            edit_message(format!("Replaced {} blocks", count), undoable)
+        },
+        Some("copy") => {
+            let aabb = edit_selection(server, target)?;
+            let schematic =
+                Schematic::capture(&server.state.terrain(), aabb).map_err(|err| err.to_string())?;
+            let count = schematic.size().product();
+            if let Some(world_edit) = server
+                .state
+                .ecs()
+                .write_storage::<WorldEdit>()
+                .get_mut(target)
+            {
+                world_edit.clipboard = Some((schematic, aabb.min - pos));
+            }
+            format!("Copied {} blocks", count)
+        },
+        Some("paste") => {
+            let (aabb, blocks) = {
+                let world_edits = server.state.ecs().read_storage::<WorldEdit>();
+                let (schematic, offset) = world_edits
+                    .get(target)
+                    .and_then(|world_edit| world_edit.clipboard.as_ref())
+                    .ok_or("Nothing has been copied, use /edit copy first")?;
+                let origin = pos + *offset;
+                let aabb = Aabb {
+                    min: origin,
+                    max: origin + schematic.size().map(|e| e as i32) - 1,
+                };
+                let blocks = schematic
+                    .blocks(origin, Transform::default())
+                    .collect::<Vec<_>>();
+                (aabb, blocks)
+            };
+            if !can_build_in(server, target, aabb) {
+                return Err(format!(
+                    "The copied blocks, pasted from {} to {}, don't fit inside your build areas",
+                    aabb.min, aabb.max
+                ));
+            }
+            let (count, undoable) = edit_blocks(server, target, blocks)?;
+            edit_message(format!("Pasted {} blocks", count), undoable)
+        },
+        Some("undo") => {
+            let replaced = server
+                .state
+                .ecs()
+                .write_storage::<WorldEdit>()
+                .get_mut(target)
+                .and_then(WorldEdit::pop_history)
+                .ok_or("There is nothing to undo")?;
+            // Permissions may have been revoked since the edit
+            if let Some((first, _)) = replaced.first() {
+                let bounds = replaced
+                    .iter()
+                    .fold(Aabb::new_empty(*first), |bounds, (pos, _)| {
+                        bounds.expanded_to_contain_point(*pos)
+                    });
+                if !can_build_in(server, target, bounds) {
+                    if let Some(world_edit) = server
+                        .state
+                        .ecs()
+                        .write_storage::<WorldEdit>()
+                        .get_mut(target)
+                    {
+                        // It was in the history already, so it still fits
+                        world_edit.push_history(replaced);
+                    }
+                    return Err("You can no longer build where this edit was made".into());
+                }
+            }
+            set_blocks(server, &replaced);
+            format!("Undid the edit of {} blocks", replaced.len())
+        },
+        _ => return Err(action.help_string()),
+    };
+
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(ChatType::CommandInfo, msg),
+    );
+    Ok(())
+}
+
+/// Where a schematic of the given size is pasted: centred on the target,
+/// standing on its floor. Fails unless it fits inside the target's build areas.
+fn paste_aabb(server: &Server, target: EcsEntity, size: Vec3<u32>) -> CmdResult<Aabb<i32>> {
+    let size = size.map(|e| e as i32);
+    let pos = position(server, target, "target")?
+        .0
+        .map(|e| e.floor() as i32);
+    let origin = pos - Vec3::new(size.x / 2, size.y / 2, 0);
+    let aabb = Aabb {
+        min: origin,
+        max: origin + size - 1,
+    };
+    if can_build_in(server, target, aabb) {
+        Ok(aabb)
+    } else {
+        Err(format!(
+            "The schematic, from {} to {}, doesn't fit inside your build areas",
+            aabb.min, aabb.max
+        ))
+    }
+}
+
+fn handle_schematic(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let data_dir = server.data_dir().path.clone();
+    let owner = uuid(server, client, "client")?;
+    let (subcommand, name, args) = parse_args!(args, String, String, ..Vec<String>);
+    let msg = match (subcommand.as_deref(), name) {
+        (Some("list"), _) => {
+            let names = schematic::list(&data_dir, owner).map_err(|err| err.to_string())?;
+            if names.is_empty() {
+                "No schematics have been saved".to_owned()
+            } else {
+                format!("Schematics: {}", names.join(", "))
+            }
+        },
+        (Some("save"), Some(name)) => {
+            let aabb = if let (Some(xlo), Some(xhi), Some(ylo), Some(yhi), Some(zlo), Some(zhi)) =
+                parse_args!(args, i32, i32, i32, i32, i32, i32)
+            {
+                Aabb {
+                    min: Vec3::new(xlo, ylo, zlo),
+                    max: Vec3::new(xhi, yhi, zhi),
+                }
+                .made_valid()
+            } else {
+                return Err(action.help_string());
+            };
+            if !can_build_in(server, target, aabb) {
+                return Err("You can only save blocks from inside your build areas".into());
+            }
+            let schematic =
+                Schematic::capture(&server.state.terrain(), aabb).map_err(|err| err.to_string())?;
+            schematic
+                .save(&data_dir, owner, &name)
+                .map_err(|err| err.to_string())?;
+            format!("Saved {} blocks as {}", schematic.size().product(), name)
+        },
+        (Some("paste"), Some(name)) => {
+            let (rotation, mirror) = parse_args!(args, u32, String);
+            let transform = Transform {
+                rotation: match rotation.unwrap_or(0) {
+                    0 => 0,
+                    90 => 1,
+                    180 => 2,
+                    270 => 3,
+                    _ => return Err("Rotation must be 0, 90, 180 or 270 degrees".into()),
+                },
+                mirror: match mirror.as_deref().unwrap_or("none") {
+                    "none" => Mirror::None,
+                    "x" => Mirror::X,
+                    "y" => Mirror::Y,
+                    _ => return Err("Mirror must be none, x or y".into()),
+                },
+            };
+            let schematic =
+                Schematic::load(&data_dir, owner, &name).map_err(|err| err.to_string())?;
+            let aabb = paste_aabb(server, target, transform.size(schematic.size()))?;
+            let (_, undoable) = edit_blocks(server, target, schematic.blocks(aabb.min, transform))?;
+            edit_message(
+                format!("Pasted {} from {} to {}", name, aabb.min, aabb.max),
+                undoable,
+            )
+        },
+        (Some("import"), Some(name)) => {
+            if !is_builder(server, client) {
+                return Err("Only builders can import structures".into());
+            }
+            let (group, index) = parse_args!(args, String, usize);
+            let group = group.ok_or_else(|| action.help_string())?;
+            let structures = StructuresGroup::load(&["world.manifests.", &group].concat())
+                .map_err(|_| format!("No such structure group: {}", group))?
+                .read();
+            let index = index.unwrap_or(0);
+            let structure = structures.get(index).ok_or_else(|| {
+                format!(
+                    "{} only has {} structures, numbered from 0",
+                    group,
+                    structures.len()
+                )
+            })?;
+            let schematic = Schematic::from_structure(structure).map_err(|err| err.to_string())?;
+            schematic
+                .save(&data_dir, owner, &name)
+                .map_err(|err| err.to_string())?;
+            format!("Imported structure {} of {} as {}", index, group, name)
+        },
+        _ => return Err(action.help_string()),
+    };
+
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(ChatType::CommandInfo, msg),
+    );
+    Ok(())
+}
+
+fn handle_arena_add(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let (Some(name), Some(xlo), Some(xhi), Some(ylo), Some(yhi), Some(zlo), Some(zhi), teams) =
+        parse_args!(args, String, i32, i32, i32, i32, i32, i32, u8)
+    {
+        let bounds = Aabb {
+            min: Vec3::new(xlo, ylo, zlo),
+            max: Vec3::new(xhi, yhi, zhi),
+        };
+        if !server
+            .state
+            .mut_resource::<pvp::Arenas>()
+            .add(name.clone(), bounds, teams.unwrap_or(0))
+        {
+            return Err(format!("Arena {} already exists!", name));
+        }
+        server.notify_client(
+            client,
+            ServerGeneral::server_msg(ChatType::CommandInfo, format!("Created arena {}", name)),
+        );
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
+fn handle_arena_list(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    _args: Vec<String>,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    let arenas = server.state.ecs().read_resource::<pvp::Arenas>();
+    let msg = arenas.iter().fold("Arenas:".to_string(), |acc, arena| {
+        let teams = match arena.teams {
+            0 => "free-for-all".to_string(),
+            teams => format!("{} teams", teams),
+        };
+        format!(
+            "{}\n{}: {} to {} ({})",
+            acc, arena.name, arena.bounds.min, arena.bounds.max, teams
+        )
+    });
+    drop(arenas);
+
+    server.notify_client(client, ServerGeneral::server_msg(ChatType::CommandInfo, msg));
+    Ok(())
+}
+
+fn handle_arena_remove(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let Some(name) = parse_args!(args, String) {
+        server
+            .state
+            .mut_resource::<pvp::Arenas>()
+            .remove(&name)
+            .ok_or_else(|| format!("No such arena {}", name))?;
+        server.notify_client(
+            client,
+            ServerGeneral::server_msg(ChatType::CommandInfo, format!("Removed arena {}", name)),
+        );
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
+fn handle_arena_scores(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let (Some(name), reset) = parse_args!(args, String, String) {
+        if reset.is_some() && server.entity_admin_role(client) < Some(AdminRole::Admin) {
+            return Err("Only admins can reset the scores of an arena.".into());
+        }
+        let arena = server
+            .state
+            .mut_resource::<pvp::Arenas>()
+            .get_mut(&name)
+            .ok_or_else(|| format!("No such arena {}", name))?;
+        let msg = if reset.is_some() {
+            arena.scores.clear();
+            format!("Reset the scores of arena {}", name)
+        } else {
+            arena.scoreboard()
+        };
+        server.notify_client(client, ServerGeneral::server_msg(ChatType::CommandInfo, msg));
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
+fn handle_duel(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let Some(opponent_alias) = parse_args!(args, String) {
+        let opponent = find_alias(server.state.ecs(), &opponent_alias)?.0;
+        let uid = uid(server, opponent, "player")?;
+
+        server
+            .state
+            .mut_resource::<EventBus<ServerEvent>>()
+            .emit_now(ServerEvent::InitiateInvite(target, uid, InviteKind::Duel));
+
+        server.notify_client(
+            client,
+            ServerGeneral::server_msg(
+                ChatType::CommandInfo,
+                format!("Challenged {} to a duel.", opponent_alias),
+            ),
+        );
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
+fn handle_duel_forfeit(
+    server: &mut Server,
+    _client: EcsEntity,
+    target: EcsEntity,
+    _args: Vec<String>,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    if pvp::forfeit_duel(server.state.ecs(), target) {
+        Ok(())
+    } else {
+        Err("You are not in a duel.".into())
+    }
+}
+
 fn handle_help(
     server: &mut Server,
     client: EcsEntity,
@@ -2662,6 +3463,378 @@ fn handle_group_promote(
     }
 }
 
+/// The guild the character of an entity belongs to, along with their rank
+fn guild_membership(
+    server: &Server,
+    entity: EcsEntity,
+) -> CmdResult<(CharacterId, GuildId, GuildRank)> {
+    let character_id = guild::character_id(server.state.ecs(), entity)
+        .ok_or_else(|| "You must be playing a character to be in a guild".to_string())?;
+    server
+        .state
+        .ecs()
+        .read_resource::<Guilds>()
+        .guild_of(character_id)
+        .and_then(|guild| Some((character_id, guild.id, guild.rank_of(character_id)?)))
+        .ok_or_else(|| "You are not in a guild".to_string())
+}
+
+/// Finds a member of a guild by their character name
+fn guild_member(
+    server: &Server,
+    guild_id: GuildId,
+    alias: &str,
+) -> CmdResult<(CharacterId, GuildRank)> {
+    server
+        .state
+        .ecs()
+        .read_resource::<Guilds>()
+        .get(guild_id)
+        .and_then(|guild| guild.member_by_alias(alias))
+        .map(|(character_id, member)| (character_id, member.rank))
+        .ok_or_else(|| format!("{} is not a member of your guild", alias))
+}
+
+/// Sends a notice to every online member of a guild
+fn notify_guild(server: &Server, guild_id: GuildId, msg: String) {
+    let name = server
+        .state
+        .ecs()
+        .read_resource::<Guilds>()
+        .get(guild_id)
+        .map(|guild| guild.name.clone());
+    if let Some(name) = name {
+        server.state.send_chat(ChatType::GuildMeta(name).chat_msg(msg));
+    }
+}
+
+fn set_guild_rank(
+    server: &Server,
+    guild_id: GuildId,
+    character_id: CharacterId,
+    rank: GuildRank,
+) {
+    let ecs = server.state.ecs();
+    ecs.write_resource::<Guilds>().set_rank(character_id, rank);
+    ecs.write_resource::<CharacterUpdater>()
+        .guild(GuildRequest::SetMember {
+            guild_id,
+            character_id,
+            rank,
+        });
+}
+
+/// Sends the current state of a guild to its members, and to characters who
+/// were just removed from it
+fn refresh_guild(server: &Server, guild_id: GuildId, removed: &[CharacterId]) {
+    let ecs = server.state.ecs();
+    let settings = server.settings().guild;
+    let guilds = ecs.read_resource::<Guilds>();
+    guild::refresh_guild(ecs, &guilds, &settings, guild_id);
+    for character_id in removed {
+        guild::refresh_member(ecs, &guilds, &settings, *character_id);
+    }
+}
+
+fn handle_guild(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: Vec<String>,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    no_sudo(client, target)?;
+
+    let (_, guild_id, _) =
+        guild_membership(server, target).map_err(|_| "Please join a guild first".to_string())?;
+    let name = server
+        .state
+        .ecs()
+        .read_resource::<Guilds>()
+        .get(guild_id)
+        .map(|guild| guild.name.clone())
+        .unwrap_or_default();
+    let mode = comp::ChatMode::Guild(name);
+    insert_or_replace_component(server, target, mode.clone(), "target")?;
+    let msg = args.join(" ");
+    if !msg.is_empty() {
+        if let Some(uid) = server.state.ecs().read_storage().get(target) {
+            server.state.send_chat(mode.new_message(*uid, msg));
+        }
+    }
+    server.notify_client(target, ServerGeneral::ChatMode(mode));
+    Ok(())
+}
+
+fn handle_guild_create(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let mut args = args.into_iter();
+    let tag = args.next().ok_or_else(|| action.help_string())?;
+    let name = args.collect::<Vec<_>>().join(" ");
+    validate_guild_name(&name, &tag).map_err(|error| match error {
+        GuildNameError::EmptyName => action.help_string(),
+        GuildNameError::NameTooLong => format!(
+            "Guild names can be at most {} characters long",
+            MAX_GUILD_NAME_LEN
+        ),
+        GuildNameError::InvalidName => {
+            "Guild names cannot start or end with spaces, or contain double spaces".to_string()
+        },
+        GuildNameError::InvalidTagLength => format!(
+            "Guild tags must be between {} and {} characters long",
+            MIN_GUILD_TAG_LEN, MAX_GUILD_TAG_LEN
+        ),
+        GuildNameError::InvalidTag => "Guild tags can only contain letters and numbers".to_string(),
+    })?;
+
+    let character_id = guild::character_id(server.state.ecs(), client)
+        .ok_or_else(|| "You must be playing a character to found a guild".to_string())?;
+    {
+        let guilds = server.state.ecs().read_resource::<Guilds>();
+        if guilds.guild_of(character_id).is_some() {
+            return Err("You are already in a guild".into());
+        }
+        if guilds.is_taken(&name, &tag) {
+            return Err("A guild with that name or tag already exists".into());
+        }
+    }
+    let cost = server.settings().guild.creation_cost;
+    take_coins(server, client, u64::from(cost))
+        .map_err(|_| format!("Founding a guild costs {} coins", cost))?;
+
+    let alias = server
+        .state
+        .ecs()
+        .read_storage::<comp::Stats>()
+        .get(client)
+        .map(|stats| stats.name.clone())
+        .unwrap_or_default();
+    let guild_id = server
+        .state
+        .ecs()
+        .write_resource::<Guilds>()
+        .create(name.clone(), tag.clone(), character_id, alias)
+        .id;
+    let mut character_updater = server.state.ecs().write_resource::<CharacterUpdater>();
+    character_updater.guild(GuildRequest::Create {
+        id: guild_id,
+        name: name.clone(),
+        tag: tag.clone(),
+    });
+    character_updater.guild(GuildRequest::SetMember {
+        guild_id,
+        character_id,
+        rank: GuildRank::Leader,
+    });
+    drop(character_updater);
+
+    refresh_guild(server, guild_id, &[]);
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(
+            ChatType::CommandInfo,
+            format!("You founded the guild {} [{}]", name, tag),
+        ),
+    );
+    Ok(())
+}
+
+fn handle_guild_demote(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let alias = parse_args!(args, String).ok_or_else(|| action.help_string())?;
+    let (_, guild_id, rank) = guild_membership(server, client)?;
+    let (member_id, member_rank) = guild_member(server, guild_id, &alias)?;
+    let new_rank = member_rank
+        .demoted()
+        .filter(|_| rank.can_manage(member_rank))
+        .ok_or_else(|| format!("You cannot demote {}", alias))?;
+
+    set_guild_rank(server, guild_id, member_id, new_rank);
+    refresh_guild(server, guild_id, &[]);
+    notify_guild(server, guild_id, format!("[{}] was demoted to {}", alias, new_rank.to_key()));
+    Ok(())
+}
+
+fn handle_guild_disband(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    _args: Vec<String>,
+    _action: &ChatCommand,
+) -> CmdResult<()> {
+    let (_, guild_id, rank) = guild_membership(server, client)?;
+    if rank != GuildRank::Leader {
+        return Err("Only the leader can disband the guild".into());
+    }
+
+    notify_guild(server, guild_id, "The guild has been disbanded".to_string());
+    let members = server
+        .state
+        .ecs()
+        .write_resource::<Guilds>()
+        .disband(guild_id)
+        .map(|guild| guild.members.keys().copied().collect::<Vec<_>>())
+        .unwrap_or_default();
+    server
+        .state
+        .ecs()
+        .write_resource::<CharacterUpdater>()
+        .guild(GuildRequest::Delete(guild_id));
+    refresh_guild(server, guild_id, &members);
+    Ok(())
+}
+
+fn handle_guild_invite(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    if let Some(target_alias) = parse_args!(args, String) {
+        let target_player = find_alias(server.state.ecs(), &target_alias)?.0;
+        let uid = uid(server, target_player, "player")?;
+
+        server
+            .state
+            .mut_resource::<EventBus<ServerEvent>>()
+            .emit_now(ServerEvent::InitiateInvite(client, uid, InviteKind::Guild));
+        Ok(())
+    } else {
+        Err(action.help_string())
+    }
+}
+
+fn handle_guild_kick(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let alias = parse_args!(args, String).ok_or_else(|| action.help_string())?;
+    let (_, guild_id, rank) = guild_membership(server, client)?;
+    let (member_id, member_rank) = guild_member(server, guild_id, &alias)?;
+    if !rank.can_manage(member_rank) {
+        return Err(format!("You cannot remove {} from the guild", alias));
+    }
+
+    server
+        .state
+        .ecs()
+        .write_resource::<Guilds>()
+        .remove_member(member_id);
+    server
+        .state
+        .ecs()
+        .write_resource::<CharacterUpdater>()
+        .guild(GuildRequest::RemoveMember(member_id));
+    refresh_guild(server, guild_id, &[member_id]);
+    notify_guild(server, guild_id, format!("[{}] was removed from the guild", alias));
+    if let Some(member) = guild::character_entity(server.state.ecs(), member_id) {
+        server.notify_client(
+            member,
+            ServerGeneral::server_msg(
+                ChatType::CommandInfo,
+                "You have been removed from your guild",
+            ),
+        );
+    }
+    Ok(())
+}
+
+fn handle_guild_leave(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    _args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let (character_id, guild_id, rank) = guild_membership(server, client)?;
+    if rank == GuildRank::Leader {
+        let successor = server
+            .state
+            .ecs()
+            .read_resource::<Guilds>()
+            .get(guild_id)
+            .and_then(|guild| guild.successor(character_id));
+        match successor {
+            Some(successor) => {
+                set_guild_rank(server, guild_id, successor, GuildRank::Leader);
+            },
+            // The last member leaving disbands the guild
+            None => return handle_guild_disband(server, client, client, Vec::new(), action),
+        }
+    }
+
+    let alias = server
+        .state
+        .ecs()
+        .read_resource::<Guilds>()
+        .get(guild_id)
+        .and_then(|guild| guild.members.get(&character_id))
+        .map(|member| member.alias.clone())
+        .unwrap_or_default();
+    server
+        .state
+        .ecs()
+        .write_resource::<Guilds>()
+        .remove_member(character_id);
+    server
+        .state
+        .ecs()
+        .write_resource::<CharacterUpdater>()
+        .guild(GuildRequest::RemoveMember(character_id));
+    refresh_guild(server, guild_id, &[character_id]);
+    notify_guild(server, guild_id, format!("[{}] left the guild", alias));
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(ChatType::CommandInfo, "You left your guild"),
+    );
+    Ok(())
+}
+
+fn handle_guild_promote(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let alias = parse_args!(args, String).ok_or_else(|| action.help_string())?;
+    let (character_id, guild_id, rank) = guild_membership(server, client)?;
+    let (member_id, member_rank) = guild_member(server, guild_id, &alias)?;
+    if !rank.can_manage(member_rank) {
+        return Err(format!("You cannot promote {}", alias));
+    }
+
+    let new_rank = if rank == GuildRank::Leader && member_rank == GuildRank::Officer {
+        // Promoting an officer hands over leadership of the guild
+        set_guild_rank(server, guild_id, character_id, GuildRank::Officer);
+        GuildRank::Leader
+    } else {
+        member_rank
+            .promoted()
+            .filter(|new_rank| *new_rank < rank)
+            .ok_or_else(|| format!("You cannot promote {}", alias))?
+    };
+
+    set_guild_rank(server, guild_id, member_id, new_rank);
+    refresh_guild(server, guild_id, &[]);
+    notify_guild(server, guild_id, format!("[{}] was promoted to {}", alias, new_rank.to_key()));
+    Ok(())
+}
+
 fn handle_region(
     server: &mut Server,
     client: EcsEntity,
@@ -2725,6 +3898,25 @@ fn handle_world(
     Ok(())
 }
 
+fn handle_world_event(
+    server: &mut Server,
+    client: EcsEntity,
+    _target: EcsEntity,
+    args: Vec<String>,
+    action: &ChatCommand,
+) -> CmdResult<()> {
+    let kind = match parse_args!(args, String) {
+        Some(kind) => Some(world_event::parse_kind(&kind).ok_or_else(|| action.help_string())?),
+        None => None,
+    };
+    let name = world_event::start(server, kind)?;
+    server.notify_client(
+        client,
+        ServerGeneral::server_msg(ChatType::CommandInfo, format!("Started {}", name)),
+    );
+    Ok(())
+}
+
 fn handle_join_faction(
     server: &mut Server,
     _client: EcsEntity,
@@ -3499,7 +4691,11 @@ fn cast_buff(kind: &str, data: BuffData, server: &mut Server, target: EcsEntity)
         let ecs = &server.state.ecs();
         let mut buffs_all = ecs.write_storage::<comp::Buffs>();
         if let Some(mut buffs) = buffs_all.get_mut(target) {
-            buffs.insert(Buff::new(buffkind, data, vec![], BuffSource::Command));
+            let manifest = BUFF_MANIFEST.read();
+            buffs.insert(
+                Buff::with_manifest(&manifest, buffkind, data, vec![], BuffSource::Command),
+                &manifest,
+            );
         }
         Ok(())
     } else {
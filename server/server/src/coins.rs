@@ -0,0 +1,50 @@
+//! Helpers for paying with the coins in a character's inventory
+
+use common::comp::{Inventory, Item};
+
+pub const COINS_ITEM: &str = "common.items.utility.coins";
+
+/// A single stack of `amount` coins
+pub fn coin_stack(amount: u32) -> Item {
+    let mut coins = Item::new_from_asset_expect(COINS_ITEM);
+    coins.set_amount(amount).expect("coins should be stackable");
+    coins
+}
+
+/// The total number of coins in an inventory
+pub fn coin_count(inventory: &Inventory) -> u64 {
+    inventory
+        .slots()
+        .flatten()
+        .filter(|item| item.item_definition_id() == COINS_ITEM)
+        .map(|item| u64::from(item.amount()))
+        .sum()
+}
+
+/// Removes up to `amount` coins from an inventory. Callers should check that
+/// the inventory holds enough coins beforehand using [`coin_count`].
+pub fn remove_coins(inventory: &mut Inventory, mut amount: u64) {
+    let coin_slots = inventory
+        .slots_with_id()
+        .filter(|(_, slot)| {
+            slot.as_ref()
+                .map_or(false, |item| item.item_definition_id() == COINS_ITEM)
+        })
+        .map(|(slot, _)| slot)
+        .collect::<Vec<_>>();
+
+    for slot in coin_slots {
+        if amount == 0 {
+            break;
+        }
+        let stack = inventory.get(slot).map_or(0, |item| u64::from(item.amount()));
+        if stack <= amount {
+            inventory.remove(slot);
+            amount -= stack;
+        } else if let Some(Some(item)) = inventory.slot_mut(slot) {
+            // `amount` is less than `stack`, so it fits in a u32
+            let _ = item.decrease_amount(amount as u32);
+            amount = 0;
+        }
+    }
+}
@@ -0,0 +1,473 @@
+//! Headless combat simulation for balancing
+//!
+//! Two combatants, spawned from entity configs, fight each other on a flat
+//! patch of terrain while the same systems as on the server run tick by tick.
+//! The rolls of attacks and the decisions of agents are derived from the seed,
+//! the time and the entities involved, so a fight started from the same seed
+//! always plays out the same way. This makes the reports usable in
+//! regression tests for balance changes. The `combat_sim` binary runs fights
+//! from the command line.
+
+use crate::{
+    client::Client,
+    events::{
+        entity_creation::{handle_beam, handle_shockwave, handle_shoot},
+        entity_manipulation::{
+            handle_buff, handle_combo_change, handle_energy_change, handle_health_change,
+            handle_knockback, handle_parry, handle_poise,
+        },
+    },
+    pack::PackMember,
+    rtsim::RtSim,
+    state_ext::StateExt,
+    sys::{agent, terrain::NpcData},
+};
+use common::{
+    assets::AssetExt,
+    comp::{
+        self, agent::Target, inventory::item::MaterialStatManifest, item::tool::AbilityMap,
+        Alignment, CharacterState, Health,
+    },
+    event::{EventBus, ServerEvent},
+    generation::EntityInfo,
+    outcome::Outcome,
+    resources::{RngSeed, Time},
+    rtsim::RtSimEntity,
+    terrain::{Block, BlockKind, SpriteKind, TerrainChunk, TerrainChunkMeta, TerrainGrid},
+    uid::Uid,
+};
+use common_ecs::{dispatch, System};
+use common_state::State;
+use common_systems::{add_local_systems, melee, projectile};
+use rand::{rngs::StdRng, SeedableRng};
+use specs::{Builder, Entity as EcsEntity, WorldExt};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use vek::*;
+
+/// The altitude of the floor of the arena
+const FLOOR_ALT: i32 = 256;
+/// The arena spans this many chunks in every direction from its center
+const ARENA_CHUNK_RADIUS: i32 = 2;
+pub const DEFAULT_TICK_RATE: u32 = 30;
+
+#[derive(Clone, Debug)]
+pub struct SimConfig {
+    /// Entity configs of both combatants, e.g. `common.entity.village.guard`
+    pub combatants: [String; 2],
+    /// How far apart the combatants start
+    pub distance: f32,
+    /// The fight is called a draw after this many ticks
+    pub max_ticks: u64,
+    pub dt: Duration,
+    pub seed: u64,
+}
+
+impl SimConfig {
+    pub fn new(first: impl Into<String>, second: impl Into<String>) -> Self {
+        Self {
+            combatants: [first.into(), second.into()],
+            distance: 10.0,
+            max_ticks: u64::from(DEFAULT_TICK_RATE) * 120,
+            dt: Duration::from_secs_f64(1.0 / f64::from(DEFAULT_TICK_RATE)),
+            seed: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_ticks(mut self, max_ticks: u64) -> Self {
+        self.max_ticks = max_ticks;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CombatantReport {
+    pub name: String,
+    pub damage_dealt: f32,
+    pub damage_taken: f32,
+    /// Damage dealt per second over the whole fight
+    pub dps: f32,
+    /// Seconds into the fight at which the combatant died
+    pub died_at: Option<f64>,
+    /// How often each ability was used, by the name of its character state
+    pub abilities_used: BTreeMap<String, u32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimReport {
+    pub combatants: [CombatantReport; 2],
+    /// Length of the fight in seconds
+    pub duration: f64,
+    /// Index of the surviving combatant, `None` for a draw
+    pub winner: Option<usize>,
+}
+
+impl SimReport {
+    /// Seconds it took the winner to kill the other combatant
+    pub fn time_to_kill(&self) -> Option<f64> {
+        self.winner
+            .and_then(|winner| self.combatants[1 - winner].died_at)
+    }
+}
+
+pub struct Simulation {
+    state: State,
+    config: SimConfig,
+    combatants: [EcsEntity; 2],
+    reports: [CombatantReport; 2],
+    /// The character state of each combatant in the last tick, to tell when a
+    /// new ability is used
+    last_character_states: [Option<CharacterState>; 2],
+    start_time: f64,
+    ticks: u64,
+}
+
+impl Simulation {
+    pub fn new(config: SimConfig) -> Self {
+        let mut state = State::server();
+        // Resources and components the server adds on top of the common ones
+        state
+            .ecs_mut()
+            .insert(AbilityMap::<comp::AbilityItem>::load_expect_cloned(
+                "common.abilities.ability_set_manifest",
+            ));
+        state.ecs_mut().insert(MaterialStatManifest::default());
+        state.ecs_mut().insert(RtSim::new(Vec2::one()));
+        state.ecs_mut().register::<Client>();
+        state.ecs_mut().register::<RtSimEntity>();
+        state.ecs_mut().register::<PackMember>();
+        state.ecs_mut().insert(RngSeed(config.seed));
+        let start_time = state.ecs().read_resource::<Time>().0;
+
+        build_arena(&mut state);
+
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let sides = [
+            (-1.0, Alignment::Npc, comp::group::NPC),
+            (1.0, Alignment::Enemy, comp::group::ENEMY),
+        ];
+        let mut names = [String::new(), String::new()];
+        let mut combatants = Vec::with_capacity(2);
+        for (i, (side, alignment, group)) in sides.into_iter().enumerate() {
+            let pos = Vec3::new(side * config.distance / 2.0, 0.0, FLOOR_ALT as f32);
+            let entity_info = EntityInfo::at(pos)
+                .with_asset_expect(&config.combatants[i], &mut rng)
+                .with_alignment(alignment)
+                .with_agency(true)
+                .with_no_flee();
+            let (pos, stats, skill_set, health, poise, inventory, agent, body, scale) =
+                match NpcData::from_entity_info(entity_info) {
+                    NpcData::Data {
+                        pos,
+                        stats,
+                        skill_set,
+                        health,
+                        poise,
+                        inventory,
+                        agent,
+                        body,
+                        scale,
+                        ..
+                    } => (
+                        pos, stats, skill_set, health, poise, inventory, agent, body, scale,
+                    ),
+                    NpcData::Waypoint(_) => panic!(
+                        "Entity config {} doesn't describe a combatant",
+                        config.combatants[i]
+                    ),
+                };
+            names[i] = stats.name.clone();
+            // The combatants face each other
+            let ori = comp::Ori::from_unnormalized_vec(Vec3::new(-side, 0.0, 0.0));
+            let entity = state
+                .create_npc(pos, stats, skill_set, health, poise, inventory, body)
+                .with(ori.unwrap_or_default())
+                .with(scale)
+                .with(alignment)
+                .with(group)
+                .maybe_with(agent)
+                .build();
+            combatants.push(entity);
+        }
+        let combatants = [combatants[0], combatants[1]];
+
+        // Skip the search for a target, the combatants know who they are here for
+        {
+            let mut agents = state.ecs().write_storage::<comp::Agent>();
+            for (i, entity) in combatants.iter().enumerate() {
+                if let Some(agent) = agents.get_mut(*entity) {
+                    agent.target = Some(Target::new(combatants[1 - i], true, start_time, true));
+                }
+            }
+        }
+
+        let [first_name, second_name] = names;
+        Self {
+            state,
+            config,
+            combatants,
+            reports: [
+                CombatantReport {
+                    name: first_name,
+                    ..Default::default()
+                },
+                CombatantReport {
+                    name: second_name,
+                    ..Default::default()
+                },
+            ],
+            last_character_states: [None, None],
+            start_time,
+            ticks: 0,
+        }
+    }
+
+    /// Runs the fight until one of the combatants died or the time is up
+    pub fn run(mut self) -> SimReport {
+        while !self.is_finished() {
+            self.tick();
+        }
+        self.report()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.ticks >= self.config.max_ticks
+            || self.reports.iter().any(|report| report.died_at.is_some())
+    }
+
+    /// Seconds since the start of the fight
+    pub fn elapsed(&self) -> f64 { self.state.ecs().read_resource::<Time>().0 - self.start_time }
+
+    pub fn tick(&mut self) {
+        self.state.tick(
+            self.config.dt,
+            |dispatch_builder| {
+                add_local_systems(dispatch_builder);
+                dispatch::<melee::Sys>(dispatch_builder, &[&projectile::Sys::sys_name()]);
+                dispatch::<agent::Sys>(dispatch_builder, &[]);
+            },
+            false,
+        );
+        self.handle_events();
+        self.state.ecs_mut().maintain();
+        self.record_abilities();
+        self.record_deaths();
+        self.state.ecs().write_resource::<Vec<Outcome>>().clear();
+        self.state.cleanup();
+        self.ticks += 1;
+    }
+
+    pub fn report(&self) -> SimReport {
+        let duration = self.elapsed();
+        let mut combatants = self.reports.clone();
+        for report in combatants.iter_mut() {
+            report.dps = report.damage_dealt / duration.max(self.config.dt.as_secs_f64()) as f32;
+        }
+        let winner = match (combatants[0].died_at, combatants[1].died_at) {
+            (None, Some(_)) => Some(0),
+            (Some(_), None) => Some(1),
+            _ => None,
+        };
+        SimReport {
+            combatants,
+            duration,
+            winner,
+        }
+    }
+
+    /// Applies the events of the fight the same way as the server does, events
+    /// which don't matter to the fight are dropped
+    fn handle_events(&mut self) {
+        let events = self
+            .state
+            .ecs()
+            .read_resource::<EventBus<ServerEvent>>()
+            .recv_all();
+        for event in events {
+            let ecs = self.state.ecs();
+            match event {
+                ServerEvent::HealthChange { entity, change } => {
+                    let current_health = |ecs: &specs::World| {
+                        ecs.read_storage::<Health>()
+                            .get(entity)
+                            .map_or(0.0, Health::current)
+                    };
+                    let attacker = change.by.map(|by| by.uid());
+                    let before = current_health(ecs);
+                    handle_health_change(ecs, entity, change);
+                    let damage = before - current_health(ecs);
+                    if damage > 0.0 {
+                        self.record_damage(entity, attacker, damage);
+                    }
+                },
+                ServerEvent::PoiseChange { entity, change } => handle_poise(ecs, entity, change),
+                ServerEvent::Knockback { entity, impulse } => {
+                    handle_knockback(ecs, entity, impulse)
+                },
+                ServerEvent::Buff {
+                    entity,
+                    buff_change,
+                } => handle_buff(ecs, entity, buff_change),
+                ServerEvent::EnergyChange { entity, change } => {
+                    handle_energy_change(ecs, entity, change)
+                },
+                ServerEvent::ComboChange { entity, change } => {
+                    handle_combo_change(ecs, entity, change)
+                },
+                ServerEvent::Parry {
+                    entity,
+                    energy_cost,
+                } => handle_parry(ecs, entity, energy_cost),
+                ServerEvent::Shoot {
+                    entity,
+                    pos,
+                    dir,
+                    body,
+                    light,
+                    projectile,
+                    speed,
+                    object,
+                } => handle_shoot(
+                    &mut self.state,
+                    entity,
+                    pos,
+                    dir,
+                    body,
+                    light,
+                    projectile,
+                    speed,
+                    object,
+                ),
+                ServerEvent::Shockwave {
+                    properties,
+                    pos,
+                    ori,
+                } => handle_shockwave(&mut self.state, properties, pos, ori),
+                ServerEvent::BeamSegment {
+                    properties,
+                    pos,
+                    ori,
+                } => handle_beam(&mut self.state, properties, pos, ori),
+                ServerEvent::Delete(entity) => {
+                    let _ = self.state.ecs_mut().delete_entity(entity);
+                },
+                // Deaths are read from the health of the combatants
+                _ => {},
+            }
+        }
+    }
+
+    fn record_damage(&mut self, target: EcsEntity, attacker: Option<Uid>, damage: f32) {
+        if let Some(target) = self.combatants.iter().position(|e| *e == target) {
+            self.reports[target].damage_taken += damage;
+        }
+        let uids = self.state.ecs().read_storage::<Uid>();
+        if let Some(attacker) = attacker.and_then(|attacker| {
+            self.combatants
+                .iter()
+                .position(|e| uids.get(*e) == Some(&attacker))
+        }) {
+            self.reports[attacker].damage_dealt += damage;
+        }
+    }
+
+    fn record_abilities(&mut self) {
+        let character_states = self.state.ecs().read_storage::<CharacterState>();
+        for (i, entity) in self.combatants.iter().enumerate() {
+            if let Some(character_state) = character_states.get(*entity) {
+                let is_new = self.last_character_states[i]
+                    .as_ref()
+                    .map_or(true, |last| !last.same_variant(character_state));
+                if is_new
+                    && (character_state.is_attack()
+                        || character_state.is_block()
+                        || character_state.is_dodge())
+                {
+                    *self.reports[i]
+                        .abilities_used
+                        .entry(character_state.to_string())
+                        .or_default() += 1;
+                }
+                self.last_character_states[i] = Some(character_state.clone());
+            }
+        }
+    }
+
+    fn record_deaths(&mut self) {
+        let elapsed = self.elapsed();
+        let healths = self.state.ecs().read_storage::<Health>();
+        for (i, entity) in self.combatants.iter().enumerate() {
+            if self.reports[i].died_at.is_none()
+                && healths.get(*entity).map_or(true, |health| health.is_dead)
+            {
+                self.reports[i].died_at = Some(elapsed);
+            }
+        }
+    }
+}
+
+/// Lays out the flat, empty floor the combatants fight on
+fn build_arena(state: &mut State) {
+    let mut terrain = state.ecs().write_resource::<TerrainGrid>();
+    for x in -ARENA_CHUNK_RADIUS..ARENA_CHUNK_RADIUS {
+        for y in -ARENA_CHUNK_RADIUS..ARENA_CHUNK_RADIUS {
+            terrain.insert(
+                Vec2::new(x, y),
+                Arc::new(TerrainChunk::new(
+                    FLOOR_ALT,
+                    Block::new(BlockKind::Rock, Rgb::new(120, 120, 120)),
+                    Block::air(SpriteKind::Empty),
+                    TerrainChunkMeta::void(),
+                )),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fights_are_deterministic() {
+        let config = SimConfig::new(
+            "common.entity.village.guard",
+            "common.entity.wild.aggressive.wolf",
+        )
+        .with_seed(7)
+        .with_max_ticks(u64::from(DEFAULT_TICK_RATE) * 30);
+        let report = Simulation::new(config.clone()).run();
+        assert_eq!(report, Simulation::new(config).run());
+        assert!(report.combatants.iter().any(|c| c.damage_dealt > 0.0));
+    }
+
+    #[test]
+    fn ranged_fights_are_deterministic() {
+        let config = SimConfig::new(
+            "common.entity.village.guard",
+            "common.entity.wild.aggressive.lavadrake",
+        )
+        .with_seed(3)
+        .with_max_ticks(u64::from(DEFAULT_TICK_RATE) * 30);
+        let report = Simulation::new(config.clone()).run();
+        assert_eq!(report, Simulation::new(config).run());
+        assert!(report.combatants.iter().any(|c| c.damage_dealt > 0.0));
+    }
+
+    #[test]
+    fn seed_is_used() {
+        let config = SimConfig::new(
+            "common.entity.village.guard",
+            "common.entity.wild.aggressive.wolf",
+        );
+        let sim = Simulation::new(config.with_seed(7));
+        assert_eq!(sim.state.ecs().read_resource::<RngSeed>().0, 7);
+        assert_eq!(sim.elapsed(), 0.0);
+    }
+}
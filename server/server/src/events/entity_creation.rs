@@ -1,4 +1,12 @@
-use crate::{client::Client, persistence::PersistedComponents, sys, Server, StateExt};
+use crate::{
+    client::Client,
+    guild::{self, Guilds},
+    housing::{self, HousingPlots},
+    persistence::PersistedComponents,
+    presence::Presence,
+    statistics::DungeonBoss,
+    sys, Server, StateExt,
+};
 use common::{
     character::CharacterId,
     comp::{
@@ -17,7 +25,11 @@ use common::{
     uid::Uid,
     util::Dir,
 };
-use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+use common_net::{
+    msg::{PresenceKind, ServerGeneral},
+    sync::WorldSyncExt,
+};
+use common_state::State;
 use specs::{Builder, Entity as EcsEntity, WorldExt};
 use std::time::Duration;
 use vek::{Rgb, Vec3};
@@ -49,6 +61,30 @@ pub fn handle_loaded_character_data(
         .state
         .update_character_data(entity, loaded_components);
     sys::subscription::initialize_region_subscription(server.state.ecs(), entity);
+    crate::world_event::notify_joined(server, entity);
+
+    // Characters that own a plot may build on it
+    let ecs = server.state.ecs();
+    let character_id = match ecs.read_storage::<Presence>().get(entity).map(|p| p.kind) {
+        Some(PresenceKind::Character(character_id)) => Some(character_id),
+        _ => None,
+    };
+    let build_area = character_id.and_then(|character_id| {
+        ecs.read_resource::<HousingPlots>()
+            .owned_by(character_id)
+            .map(|plot| plot.build_area)
+    });
+    if let Some(build_area) = build_area {
+        housing::grant_build_permission(ecs, entity, build_area);
+    }
+
+    // Show the guild tag, and let the rest of the guild know the member is online
+    if let Some(character_id) = character_id {
+        let guilds = ecs.read_resource::<Guilds>();
+        if let Some(guild) = guilds.guild_of(character_id) {
+            guild::refresh_guild(ecs, &guilds, &server.settings().guild, guild.id);
+        }
+    }
 }
 
 pub fn handle_create_npc(
@@ -80,6 +116,12 @@ pub fn handle_create_npc(
         entity
     };
 
+    let entity = if DungeonBoss::is_boss_loot(&loot) {
+        entity.with(DungeonBoss)
+    } else {
+        entity
+    };
+
     let entity = if let Some(drop_item) = loot.to_item() {
         entity.with(ItemDrop(drop_item))
     } else {
@@ -156,6 +198,7 @@ pub fn handle_create_ship(
     mountable: bool,
     agent: Option<Agent>,
     rtsim_entity: Option<RtSimEntity>,
+    anchor: Option<Anchor>,
 ) {
     let mut entity = server
         .state
@@ -170,11 +213,14 @@ pub fn handle_create_ship(
     if let Some(rtsim_entity) = rtsim_entity {
         entity = entity.with(rtsim_entity);
     }
+    if let Some(anchor) = anchor {
+        entity = entity.with(anchor);
+    }
     entity.build();
 }
 
 pub fn handle_shoot(
-    server: &mut Server,
+    state: &mut State,
     entity: EcsEntity,
     pos: Pos,
     dir: Dir,
@@ -184,8 +230,6 @@ pub fn handle_shoot(
     speed: f32,
     object: Option<Object>,
 ) {
-    let state = server.state_mut();
-
     let pos = pos.0;
 
     let vel = *dir * speed
@@ -212,18 +256,11 @@ pub fn handle_shoot(
     builder.build();
 }
 
-pub fn handle_shockwave(
-    server: &mut Server,
-    properties: shockwave::Properties,
-    pos: Pos,
-    ori: Ori,
-) {
-    let state = server.state_mut();
+pub fn handle_shockwave(state: &mut State, properties: shockwave::Properties, pos: Pos, ori: Ori) {
     state.create_shockwave(properties, pos, ori).build();
 }
 
-pub fn handle_beam(server: &mut Server, properties: beam::Properties, pos: Pos, ori: Ori) {
-    let state = server.state_mut();
+pub fn handle_beam(state: &mut State, properties: beam::Properties, pos: Pos, ori: Ori) {
     let ecs = state.ecs();
     ecs.write_resource::<Vec<Outcome>>().push(Outcome::Beam {
         pos: pos.0,
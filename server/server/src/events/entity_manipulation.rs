@@ -7,6 +7,8 @@ use crate::{
         BuffKind, BuffSource, PhysicsState,
     },
     rtsim::RtSim,
+    site_state::SiteStates,
+    statistics::DungeonBoss,
     sys::terrain::SAFE_ZONE_RADIUS,
     Server, SpawnPoint, StateExt,
 };
@@ -18,11 +20,13 @@ use common::{
         chat::{KillSource, KillType},
         inventory::item::MaterialStatManifest,
         Alignment, Auras, Body, CharacterState, Energy, Group, Health, HealthChange, Inventory,
-        Player, Poise, Pos, SkillSet, Stats,
+        Player, Poise, Pos, SkillSet, Stat, Stats,
     },
     event::{EventBus, ServerEvent},
+    link::Is,
+    mounting::{Mount, Rider},
     outcome::Outcome,
-    resources::Time,
+    resources::{RngSeed, Time},
     rtsim::RtSimEntity,
     terrain::{Block, BlockKind, TerrainGrid},
     uid::{Uid, UidAllocator},
@@ -34,7 +38,7 @@ use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
 use common_state::BlockChange;
 use comp::chat::GenericChatMsg;
 use hashbrown::HashSet;
-use rand::Rng;
+use rand::{rngs::StdRng, Rng};
 use specs::{
     join::Join, saveload::MarkerAllocator, Builder, Entity as EcsEntity, Entity, WorldExt,
 };
@@ -49,8 +53,7 @@ enum DamageContrib {
     NotFound,
 }
 
-pub fn handle_poise(server: &Server, entity: EcsEntity, change: comp::PoiseChange) {
-    let ecs = &server.state.ecs();
+pub fn handle_poise(ecs: &specs::World, entity: EcsEntity, change: comp::PoiseChange) {
     if let Some(character_state) = ecs.read_storage::<CharacterState>().get(entity) {
         // Entity is invincible to poise change during stunned/staggered character
         // state, but the mitigated poise damage is converted to health damage instead
@@ -74,8 +77,7 @@ pub fn handle_poise(server: &Server, entity: EcsEntity, change: comp::PoiseChang
     }
 }
 
-pub fn handle_health_change(server: &Server, entity: EcsEntity, change: HealthChange) {
-    let ecs = &server.state.ecs();
+pub fn handle_health_change(ecs: &specs::World, entity: EcsEntity, change: HealthChange) {
     if let Some(mut health) = ecs.write_storage::<Health>().get_mut(entity) {
         health.change_by(change);
     }
@@ -87,10 +89,46 @@ pub fn handle_health_change(server: &Server, entity: EcsEntity, change: HealthCh
             agent.inbox.push_front(AgentEvent::Hurt);
         }
     }
+    if damage > 0.0 {
+        dismount_on_hit(ecs, entity, damage);
+    }
 }
 
-pub fn handle_knockback(server: &Server, entity: EcsEntity, impulse: Vec3<f32>) {
-    let ecs = &server.state.ecs();
+/// Throws the rider off their mount when either of them takes a hit heavier
+/// than the mount's dismount threshold
+fn dismount_on_hit(ecs: &specs::World, entity: EcsEntity, damage: f32) {
+    let uid_allocator = ecs.read_resource::<UidAllocator>();
+    let mut is_riders = ecs.write_storage::<Is<Rider>>();
+    let (rider, mount) = if let Some(is_rider) = is_riders.get(entity) {
+        (
+            entity,
+            uid_allocator.retrieve_entity_internal(is_rider.mount.into()),
+        )
+    } else if let Some(is_mount) = ecs.read_storage::<Is<Mount>>().get(entity) {
+        match uid_allocator.retrieve_entity_internal(is_mount.rider.into()) {
+            Some(rider) => (rider, Some(entity)),
+            None => return,
+        }
+    } else {
+        return;
+    };
+    let threshold = match mount
+        .and_then(|mount| ecs.read_storage::<Body>().get(mount).copied())
+        .and_then(|body| body.mount_stats())
+    {
+        Some(stats) => stats.dismount_threshold,
+        None => return,
+    };
+    let max_health = ecs
+        .read_storage::<Health>()
+        .get(entity)
+        .map_or(0.0, Health::maximum);
+    if damage > max_health * threshold {
+        is_riders.remove(rider);
+    }
+}
+
+pub fn handle_knockback(ecs: &specs::World, entity: EcsEntity, impulse: Vec3<f32>) {
     let clients = ecs.read_storage::<Client>();
 
     if let Some(physics) = ecs.read_storage::<PhysicsState>().get(entity) {
@@ -203,6 +241,59 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, last_change: Healt
         }
     }
 
+    // Update the statistics of the victim and whoever landed the killing blow
+    {
+        let server_eventbus = state.ecs().read_resource::<EventBus<ServerEvent>>();
+        server_eventbus.emit_now(ServerEvent::UpdateStatistic {
+            entity,
+            stat: Stat::Deaths(Stat::death_cause(last_change.cause).to_owned()),
+            amount: 1,
+        });
+        let killer = last_change
+            .by
+            .and_then(|by| state.ecs().entity_from_uid(by.uid().into()))
+            .filter(|killer| *killer != entity);
+        if let (Some(killer), Some(body)) = (killer, state.ecs().read_storage::<Body>().get(entity))
+        {
+            server_eventbus.emit_now(ServerEvent::UpdateStatistic {
+                entity: killer,
+                stat: Stat::Kills(body.to_string()),
+                amount: 1,
+            });
+        }
+
+        // Everyone who helped defeat a dungeon boss is credited with clearing the
+        // dungeon
+        if state.ecs().read_storage::<DungeonBoss>().get(entity).is_some() {
+            if let Some(health) = state.ecs().read_storage::<Health>().get(entity) {
+                let contributors = health
+                    .damage_contributions()
+                    .map(|(contributor, _)| contributor.uid())
+                    .collect::<HashSet<_>>();
+                for contributor in contributors {
+                    if let Some(contributor) = state.ecs().entity_from_uid(contributor.into()) {
+                        server_eventbus.emit_now(ServerEvent::UpdateStatistic {
+                            entity: contributor,
+                            stat: Stat::DungeonsCleared,
+                            amount: 1,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    // Settle the duel or arena fight the player died in, or count the enemy of a
+    // world event as defeated
+    if state.ecs().read_storage::<Player>().contains(entity) {
+        let killer = last_change
+            .by
+            .and_then(|by| state.ecs().entity_from_uid(by.uid().into()));
+        crate::pvp::handle_player_death(state.ecs(), entity, killer);
+    } else {
+        crate::world_event::handle_npc_death(state.ecs(), entity);
+    }
+
     // Award EXP to damage contributors
     //
     // NOTE: Debug logging is disabled by default for this module - to enable it add
@@ -383,6 +474,21 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, last_change: Healt
                 );
             }
         });
+
+        // Pets level up from the kills of their group, without taking away from the
+        // EXP of its members
+        let mut pets = state.ecs().write_storage::<comp::Pet>();
+        for (damage_contributor, (_, damage_percent)) in damage_contributors.iter() {
+            let group = match damage_contributor {
+                DamageContrib::Group(group) if destroyed_group != Some(group) => group,
+                _ => continue,
+            };
+            for (mut pet, pet_group, pet_pos) in (&mut pets, &groups, &positions).join() {
+                if pet_group == group && within_range(pet_pos) {
+                    pet.add_experience((exp_reward * damage_percent) as u32);
+                }
+            }
+        }
     })();
 
     let should_delete = if state
@@ -713,6 +819,7 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
 
                 let terrain = ecs.read_resource::<TerrainGrid>();
                 let mut block_change = ecs.write_resource::<BlockChange>();
+                let mut site_states = ecs.write_resource::<SiteStates>();
                 for block_pos in touched_blocks {
                     if let Ok(block) = terrain.get(block_pos) {
                         if !matches!(block.kind(), BlockKind::Lava | BlockKind::GlowingRock) {
@@ -776,6 +883,7 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                         .for_each(|block: &Block, pos| {
                             if block.explode_power().is_some() {
                                 block_change.set(pos, block.into_vacant());
+                                site_states.record_damage(pos, *block);
                             }
                         })
                         .cast();
@@ -788,6 +896,13 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                 let alignments = &ecs.read_storage::<Alignment>();
                 let uid_allocator = &ecs.read_resource::<UidAllocator>();
                 let players = &ecs.read_storage::<comp::Player>();
+                let pvp_engagements = &ecs.read_storage::<comp::PvpEngagement>();
+                // Explosions without an owner are told apart by where they happen
+                let stream = owner.map_or_else(
+                    || u64::from(pos.x.to_bits()) << 32 | u64::from(pos.y.to_bits()),
+                    u64::from,
+                );
+                let mut attack_rng: StdRng = ecs.read_resource::<RngSeed>().rng(*time, stream);
                 for (
                     entity_b,
                     pos_b,
@@ -868,6 +983,7 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                         let may_harm = combat::may_harm(
                             alignments,
                             players,
+                            pvp_engagements,
                             uid_allocator,
                             owner_entity,
                             entity_b,
@@ -880,7 +996,6 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                             target_group,
                         };
 
-                        let time = server.state.ecs().read_resource::<Time>();
                         attack.apply_attack(
                             attacker_info,
                             target_info,
@@ -889,6 +1004,7 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                             strength,
                             combat::AttackSource::Explosion,
                             *time,
+                            &mut attack_rng,
                             |e| emitter.emit(e),
                             |o| outcomes.push(o),
                         );
@@ -899,6 +1015,7 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                 let alignments = &ecs.read_storage::<Alignment>();
                 let uid_allocator = &ecs.read_resource::<UidAllocator>();
                 let players = &ecs.read_storage::<comp::Player>();
+                let pvp_engagements = &ecs.read_storage::<comp::PvpEngagement>();
                 for (entity_b, pos_b, body_b_maybe) in (
                     &ecs.entities(),
                     &ecs.read_storage::<comp::Pos>(),
@@ -929,8 +1046,14 @@ pub fn handle_explosion(server: &Server, pos: Vec3<f32>, explosion: Explosion, o
                     //
                     // This can be changed later.
                     let may_harm = || {
-                        combat::may_harm(alignments, players, uid_allocator, owner_entity, entity_b)
-                            || owner_entity.map_or(true, |entity_a| entity_a == entity_b)
+                        combat::may_harm(
+                            alignments,
+                            players,
+                            pvp_engagements,
+                            uid_allocator,
+                            owner_entity,
+                            entity_b,
+                        ) || owner_entity.map_or(true, |entity_a| entity_a == entity_b)
                     };
                     if strength > 0.0 {
                         let is_alive = ecs
@@ -1010,8 +1133,7 @@ pub fn handle_aura(server: &mut Server, entity: EcsEntity, aura_change: aura::Au
     }
 }
 
-pub fn handle_buff(server: &mut Server, entity: EcsEntity, buff_change: buff::BuffChange) {
-    let ecs = &server.state.ecs();
+pub fn handle_buff(ecs: &specs::World, entity: EcsEntity, buff_change: buff::BuffChange) {
     let mut buffs_all = ecs.write_storage::<comp::Buffs>();
     let bodies = ecs.read_storage::<comp::Body>();
     if let Some(mut buffs) = buffs_all.get_mut(entity) {
@@ -1026,7 +1148,16 @@ pub fn handle_buff(server: &mut Server, entity: EcsEntity, buff_change: buff::Bu
                         .get(entity)
                         .map_or(true, |h| !h.is_dead)
                 {
-                    buffs.insert(new_buff);
+                    let manifest = buff::BUFF_MANIFEST.read();
+                    let resistance = combat::compute_buff_resistance(
+                        new_buff.kind,
+                        bodies.get(entity),
+                        ecs.read_storage::<Inventory>().get(entity),
+                        &manifest,
+                    );
+                    if let Some(new_buff) = new_buff.resisted(resistance, &manifest) {
+                        buffs.insert(new_buff, &manifest);
+                    }
                 }
             },
             BuffChange::RemoveById(ids) => {
@@ -1082,8 +1213,7 @@ pub fn handle_buff(server: &mut Server, entity: EcsEntity, buff_change: buff::Bu
     }
 }
 
-pub fn handle_energy_change(server: &Server, entity: EcsEntity, change: f32) {
-    let ecs = &server.state.ecs();
+pub fn handle_energy_change(ecs: &specs::World, entity: EcsEntity, change: f32) {
     if let Some(mut energy) = ecs.write_storage::<Energy>().get_mut(entity) {
         energy.change_by(change);
     }
@@ -1143,8 +1273,7 @@ fn handle_exp_gain(
     });
 }
 
-pub fn handle_combo_change(server: &Server, entity: EcsEntity, change: i32) {
-    let ecs = &server.state.ecs();
+pub fn handle_combo_change(ecs: &specs::World, entity: EcsEntity, change: i32) {
     if let Some(mut combo) = ecs.write_storage::<comp::Combo>().get_mut(entity) {
         let time = ecs.read_resource::<Time>();
         let mut outcomes = ecs.write_resource::<Vec<Outcome>>();
@@ -1158,8 +1287,7 @@ pub fn handle_combo_change(server: &Server, entity: EcsEntity, change: i32) {
     }
 }
 
-pub fn handle_parry(server: &Server, entity: EcsEntity, energy_cost: f32) {
-    let ecs = &server.state.ecs();
+pub fn handle_parry(ecs: &specs::World, entity: EcsEntity, energy_cost: f32) {
     if let Some(mut character) = ecs.write_storage::<comp::CharacterState>().get_mut(entity) {
         *character =
             CharacterState::Wielding(common::states::wielding::Data { is_sneaking: false });
@@ -0,0 +1,136 @@
+use crate::{
+    client::Client,
+    guild::{self, Guilds},
+    persistence::{character_updater::CharacterUpdater, guild::GuildRequest},
+    Server,
+};
+use common::{
+    character::CharacterId,
+    comp::{self, inventory::slot::InvSlotId, ChatType, Inventory},
+    guild::GuildAction,
+};
+use common_net::msg::ServerGeneral;
+use specs::{world::WorldExt, Entity as EcsEntity};
+
+pub fn handle_guild_action(server: &mut Server, entity: EcsEntity, action: GuildAction) {
+    let ecs = server.state.ecs();
+    let settings = server.settings().guild;
+    let character_id = match guild::character_id(ecs, entity) {
+        Some(character_id) => character_id,
+        None => return,
+    };
+
+    let result = match action {
+        GuildAction::RequestInfo => Ok(()),
+        GuildAction::Deposit(slot) => deposit(server, entity, character_id, slot),
+        GuildAction::Withdraw(index) => withdraw(server, entity, character_id, index),
+    };
+
+    if let Err(error) = result {
+        if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+            client.send_fallible(ServerGeneral::server_msg(ChatType::CommandError, error));
+        }
+    }
+
+    let guilds = ecs.read_resource::<Guilds>();
+    match action {
+        // Other members need to see the new contents of the bank
+        GuildAction::Deposit(_) | GuildAction::Withdraw(_) => {
+            if let Some(guild) = guilds.guild_of(character_id) {
+                guild::refresh_guild(ecs, &guilds, &settings, guild.id);
+            }
+        },
+        GuildAction::RequestInfo => guild::refresh_member(ecs, &guilds, &settings, character_id),
+    }
+}
+
+fn deposit(
+    server: &Server,
+    entity: EcsEntity,
+    character_id: CharacterId,
+    slot: InvSlotId,
+) -> Result<(), &'static str> {
+    let ecs = server.state.ecs();
+    let mut guilds = ecs.write_resource::<Guilds>();
+    let guild = guilds
+        .guild_of_mut(character_id)
+        .ok_or("You are not in a guild.")?;
+    if !guild
+        .rank_of(character_id)
+        .map_or(false, |rank| rank.can_deposit())
+    {
+        return Err("You are not allowed to deposit items in the guild bank.");
+    }
+    if guild.bank.len() >= server.settings().guild.bank_slots {
+        return Err("The guild bank is full.");
+    }
+
+    let mut inventories = ecs.write_storage::<Inventory>();
+    let mut inventory = inventories
+        .get_mut(entity)
+        .ok_or("You have no inventory to deposit items from.")?;
+    let item = inventory.remove(slot).ok_or("That item could not be found.")?;
+
+    guild.bank.push(item);
+    ecs.write_resource::<CharacterUpdater>()
+        .guild(GuildRequest::SaveBank {
+            guild_id: guild.id,
+            bank: guild.bank.clone(),
+            character_id,
+            inventory: inventory.clone(),
+        });
+    drop(inventories);
+    let _ = ecs.write_storage().insert(
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Gave),
+    );
+
+    Ok(())
+}
+
+fn withdraw(
+    server: &Server,
+    entity: EcsEntity,
+    character_id: CharacterId,
+    index: usize,
+) -> Result<(), &'static str> {
+    let ecs = server.state.ecs();
+    let mut guilds = ecs.write_resource::<Guilds>();
+    let guild = guilds
+        .guild_of_mut(character_id)
+        .ok_or("You are not in a guild.")?;
+    if !guild
+        .rank_of(character_id)
+        .map_or(false, |rank| rank.can_withdraw())
+    {
+        return Err("Recruits are not allowed to take items from the guild bank.");
+    }
+    if index >= guild.bank.len() {
+        return Err("That item is no longer in the guild bank.");
+    }
+
+    let mut inventories = ecs.write_storage::<Inventory>();
+    let mut inventory = inventories
+        .get_mut(entity)
+        .ok_or("You have no inventory to withdraw items into.")?;
+    let item = guild.bank.remove(index);
+    if let Err(item) = inventory.push(item) {
+        guild.bank.insert(index, item);
+        return Err("Your inventory is full.");
+    }
+
+    ecs.write_resource::<CharacterUpdater>()
+        .guild(GuildRequest::SaveBank {
+            guild_id: guild.id,
+            bank: guild.bank.clone(),
+            character_id,
+            inventory: inventory.clone(),
+        });
+    drop(inventories);
+    let _ = ecs.write_storage().insert(
+        entity,
+        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Given),
+    );
+
+    Ok(())
+}
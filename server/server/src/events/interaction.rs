@@ -9,16 +9,19 @@ use common::{
         agent::{AgentEvent, Sound, SoundKind},
         dialogue::Subject,
         inventory::slot::EquipSlot,
-        item,
+        item::{self, tool::AbilityMap, MaterialStatManifest},
+        pet::is_mountable,
         slot::Slot,
         tool::ToolKind,
         Inventory, Pos, SkillGroupKind,
     },
     consts::{MAX_MOUNT_RANGE, SOUND_TRAVEL_DIST_PER_VOLUME},
     link::Is,
+    lottery::LootSpec,
     mounting::{Mount, Mounting, Rider},
     outcome::Outcome,
-    terrain::{Block, SpriteKind},
+    states::fishing,
+    terrain::{BiomeKind, Block, SpriteKind},
     uid::Uid,
     vol::ReadVol,
 };
@@ -27,11 +30,12 @@ use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
 use crate::{
     client::Client,
     presence::{Presence, RegionSubscription},
+    site_state::SiteStates,
     state_ext::StateExt,
     Server,
 };
 
-use crate::pet::tame_pet;
+use crate::pet::{command_pet, rename_pet, tame_pet};
 use hashbrown::{HashMap, HashSet};
 use lazy_static::lazy_static;
 use serde::Deserialize;
@@ -124,8 +128,13 @@ pub fn handle_mount(server: &mut Server, rider: EcsEntity, mount: EcsEntity) {
                         .get(mount),
                     Some(comp::Alignment::Owned(owner)) if *owner == rider_uid,
                 );
+                let bodies = state.ecs().read_storage::<comp::Body>();
+                let can_carry_rider = bodies
+                    .get(mount)
+                    .map_or(false, |mount| is_mountable(mount, bodies.get(rider)));
 
-                if is_pet {
+                if is_pet && can_carry_rider {
+                    drop(bodies);
                     drop(uids);
                     drop(healths);
                     let _ = state.link(Mounting {
@@ -386,6 +395,10 @@ pub fn handle_mine_block(
             }
 
             state.set_block(pos, block.into_vacant());
+            state
+                .ecs()
+                .write_resource::<SiteStates>()
+                .record_damage(pos, block);
             state
                 .ecs()
                 .write_resource::<Vec<Outcome>>()
@@ -397,6 +410,49 @@ pub fn handle_mine_block(
     }
 }
 
+pub fn handle_catch_fish(server: &mut Server, entity: EcsEntity, pos: Vec3<f32>) {
+    let state = server.state_mut();
+    let biome = {
+        let terrain = state.terrain();
+        terrain
+            .get_key(terrain.pos_key(pos.map(|e| e.floor() as i32)))
+            .map_or(BiomeKind::Void, |chunk| chunk.meta().biome())
+    };
+    let item = match LootSpec::LootTable(fishing::loot_table(biome)).to_item() {
+        Some(item) => item,
+        None => return,
+    };
+    // NOTE: We dup the item for message purposes.
+    let item_msg = item.duplicate(
+        &state.ecs().read_resource::<AbilityMap>(),
+        &state.ecs().read_resource::<MaterialStatManifest>(),
+    );
+    let leftover = match state
+        .ecs()
+        .write_storage::<comp::Inventory>()
+        .get_mut(entity)
+    {
+        Some(inventory) => inventory.push(item).err(),
+        None => Some(item),
+    };
+    if let Some(item) = leftover {
+        // No room for the catch, so it lands at the feet of whoever caught it
+        let drop_pos = state.ecs().read_storage::<Pos>().get(entity).copied();
+        if let Some(drop_pos) = drop_pos {
+            state.create_item_drop(drop_pos, &item).with(item).build();
+        }
+    } else {
+        state
+            .ecs()
+            .write_storage()
+            .insert(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Collected(item_msg)),
+            )
+            .expect("We know entity exists since we got its inventory.");
+    }
+}
+
 pub fn handle_sound(server: &mut Server, sound: &Sound) {
     let ecs = &server.state.ecs();
     let positions = &ecs.read_storage::<comp::Pos>();
@@ -454,3 +510,21 @@ pub fn handle_tame_pet(server: &mut Server, pet_entity: EcsEntity, owner_entity:
     // showing taming success?
     tame_pet(server.state.ecs(), pet_entity, owner_entity);
 }
+
+pub fn handle_command_pet(
+    server: &mut Server,
+    owner_entity: EcsEntity,
+    pet_entity: EcsEntity,
+    command: comp::PetCommand,
+) {
+    command_pet(server.state.ecs(), pet_entity, owner_entity, command);
+}
+
+pub fn handle_rename_pet(
+    server: &mut Server,
+    owner_entity: EcsEntity,
+    pet_entity: EcsEntity,
+    name: String,
+) {
+    rename_pet(server.state.ecs(), pet_entity, owner_entity, &name);
+}
@@ -22,7 +22,7 @@ use common_net::sync::WorldSyncExt;
 use common_state::State;
 use comp::LightEmitter;
 
-use crate::{client::Client, Server, StateExt};
+use crate::{client::Client, farming, Server, StateExt};
 use common::{
     comp::{pet::is_tameable, ChatType, Group},
     event::{EventBus, ServerEvent},
@@ -192,7 +192,7 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
         },
         comp::InventoryManip::Collect(pos) => {
             let block = state.terrain().get(pos).ok().copied();
-            let mut drop_item = None;
+            let mut drop_items = Vec::new();
 
             if let Some(block) = block {
                 if block.is_collectible() && state.can_set_block(pos) {
@@ -217,7 +217,7 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                             // The item we created was in some sense "fake" so it's safe to
                             // drop it.
                             Err(_) => {
-                                drop_item = Some(item_msg);
+                                drop_items.push(item_msg);
                                 comp::InventoryUpdate::new(
                                     comp::InventoryUpdateEvent::BlockCollectFailed(pos),
                                 )
@@ -229,7 +229,16 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                             .insert(entity, event)
                             .expect("We know entity exists since we got its inventory.");
                         // we made sure earlier the block was not already modified this tick
-                        state.set_block(pos, block.into_vacant());
+                        if let Some((crop, growth)) = block.get_crop() {
+                            // Harvested crops leave their tilled soil behind
+                            if let Some(seed) = farming::harvest_crop(state, pos, crop, growth) {
+                                if let Err(seed) = inventory.push(seed) {
+                                    drop_items.push(seed);
+                                }
+                            }
+                        } else {
+                            state.set_block(pos, block.into_vacant());
+                        }
                     } else {
                         debug!(
                             "Failed to reclaim item from block at pos={} or entity had no \
@@ -246,7 +255,7 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                 }
             }
             drop(inventories);
-            if let Some(item) = drop_item {
+            for item in drop_items {
                 state
                     .create_item_drop(Default::default(), &item)
                     .with(comp::Pos(
@@ -257,6 +266,14 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                     .build();
             }
         },
+        comp::InventoryManip::Plant(pos, seed) => {
+            let is_soil = state.terrain().get(pos).map_or(false, |block| {
+                block.get_sprite() == Some(SpriteKind::TilledSoil)
+            });
+            if is_soil && state.can_set_block(pos) {
+                farming::plant_seed(state, inventory, seed, pos);
+            }
+        },
         comp::InventoryManip::Use(slot) => {
             let mut maybe_effect = None;
 
@@ -587,6 +604,8 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
             let recipe_book = default_recipe_book().read();
             let ability_map = &state.ecs().read_resource::<AbilityMap>();
             let msm = state.ecs().read_resource::<MaterialStatManifest>();
+            // Salvaging doesn't count towards the items crafted by the character
+            let is_salvage = matches!(craft_event, CraftEvent::Salvage(_));
 
             let crafted_items = match craft_event {
                 CraftEvent::Simple { recipe, slots } => recipe_book
@@ -663,6 +682,17 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
             // Attempt to insert items into inventory, dropping them if there is not enough
             // space
             let items_were_crafted = if let Some(crafted_items) = crafted_items {
+                if !is_salvage {
+                    let amount = crafted_items.iter().map(|item| item.amount() as u64).sum();
+                    state
+                        .ecs()
+                        .read_resource::<EventBus<ServerEvent>>()
+                        .emit_now(ServerEvent::UpdateStatistic {
+                            entity,
+                            stat: comp::Stat::ItemsCrafted,
+                            amount,
+                        });
+                }
                 for item in crafted_items {
                     if let Err(item) = inventory.push(item) {
                         dropped_items.push((
@@ -1,5 +1,5 @@
 use super::group_manip::{self, update_map_markers};
-use crate::{client::Client, Server};
+use crate::{client::Client, guild, pvp, Server, StateExt};
 use common::{
     comp::{
         self,
@@ -33,6 +33,8 @@ pub fn handle_invite(
     kind: InviteKind,
 ) {
     let max_group_size = server.settings().max_player_group_size;
+    let guild_settings = server.settings().guild;
+    let duel_max_distance = server.settings().pvp.duel_max_distance;
     let state = server.state_mut();
     let clients = state.ecs().read_storage::<Client>();
     let invitee = match state.ecs().entity_from_uid(invitee_uid.into()) {
@@ -72,38 +74,59 @@ pub fn handle_invite(
         }
     }
 
-    if let InviteKind::Group = kind {
-        if !group_manip::can_invite(
-            state,
-            &clients,
-            &mut pending_invites,
-            max_group_size,
-            inviter,
-            invitee,
-        ) {
-            return;
-        }
-    } else {
-        // cancel current trades for inviter before inviting someone else to trade
-        let mut trades = state.ecs().write_resource::<Trades>();
-        if let Some(inviter_uid) = uids.get(inviter).copied() {
-            if let Some(active_trade) = trades.entity_trades.get(&inviter_uid).copied() {
-                trades
-                    .decline_trade(active_trade, inviter_uid)
-                    .and_then(|u| state.ecs().entity_from_uid(u.0))
-                    .map(|e| {
-                        if let Some(client) = clients.get(e) {
-                            client
-                                .send_fallible(ServerGeneral::FinishedTrade(TradeResult::Declined));
-                        }
-                        if let Some(agent) = agents.get_mut(e) {
-                            agent
-                                .inbox
-                                .push_back(AgentEvent::FinishedTrade(TradeResult::Declined));
-                        }
-                    });
+    match kind {
+        InviteKind::Group => {
+            if !group_manip::can_invite(
+                state,
+                &clients,
+                &mut pending_invites,
+                max_group_size,
+                inviter,
+                invitee,
+            ) {
+                return;
             }
-        };
+        },
+        InviteKind::Guild => {
+            if let Err(error) = guild::check_invite(state.ecs(), &guild_settings, inviter, invitee)
+            {
+                if let Some(client) = clients.get(inviter) {
+                    client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, error));
+                }
+                return;
+            }
+        },
+        InviteKind::Duel => {
+            if let Err(error) = pvp::check_duel(state.ecs(), duel_max_distance, inviter, invitee) {
+                if let Some(client) = clients.get(inviter) {
+                    client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, error));
+                }
+                return;
+            }
+        },
+        InviteKind::Trade => {
+            // cancel current trades for inviter before inviting someone else to trade
+            let mut trades = state.ecs().write_resource::<Trades>();
+            if let Some(inviter_uid) = uids.get(inviter).copied() {
+                if let Some(active_trade) = trades.entity_trades.get(&inviter_uid).copied() {
+                    trades
+                        .decline_trade(active_trade, inviter_uid)
+                        .and_then(|u| state.ecs().entity_from_uid(u.0))
+                        .map(|e| {
+                            if let Some(client) = clients.get(e) {
+                                client.send_fallible(ServerGeneral::FinishedTrade(
+                                    TradeResult::Declined,
+                                ));
+                            }
+                            if let Some(agent) = agents.get_mut(e) {
+                                agent
+                                    .inbox
+                                    .push_back(AgentEvent::FinishedTrade(TradeResult::Declined));
+                            }
+                        });
+                }
+            };
+        },
     }
 
     if invites.contains(invitee) {
@@ -193,6 +216,8 @@ pub fn handle_invite_response(
 
 pub fn handle_invite_accept(server: &mut Server, entity: specs::Entity) {
     let index = server.index.clone();
+    let guild_settings = server.settings().guild;
+    let duel_max_distance = server.settings().pvp.duel_max_distance;
     let state = server.state_mut();
     if let Some((inviter, kind)) = get_inviter_and_kind(entity, state) {
         handle_invite_answer(state, inviter, entity, InviteAnswer::Accepted, kind);
@@ -280,6 +305,42 @@ pub fn handle_invite_accept(server: &mut Server, entity: specs::Entity) {
                         .map(|c| c.send(ServerGeneral::UpdatePendingTrade(id, trade, pricing)));
                 }
             },
+            InviteKind::Guild => {
+                match guild::accept_invite(state.ecs(), &guild_settings, inviter, entity) {
+                    Ok(guild_name) => {
+                        let alias = state
+                            .ecs()
+                            .read_storage::<comp::Stats>()
+                            .get(entity)
+                            .map(|stats| stats.name.clone())
+                            .unwrap_or_default();
+                        state.send_chat(
+                            ChatType::GuildMeta(guild_name.clone())
+                                .chat_msg(format!("[{}] joined {}", alias, guild_name)),
+                        );
+                    },
+                    Err(error) => {
+                        for client in clients.get(entity).into_iter().chain(clients.get(inviter)) {
+                            client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, error));
+                        }
+                    },
+                }
+            },
+            InviteKind::Duel => {
+                let stats = state.ecs().read_storage::<comp::Stats>();
+                let message = match pvp::start_duel(state.ecs(), duel_max_distance, inviter, entity)
+                {
+                    Ok(()) => format!(
+                        "The duel between {} and {} has begun!",
+                        stats.get(inviter).map_or("", |s| s.name.as_str()),
+                        stats.get(entity).map_or("", |s| s.name.as_str()),
+                    ),
+                    Err(error) => error.to_owned(),
+                };
+                for client in clients.get(entity).into_iter().chain(clients.get(inviter)) {
+                    client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, &message));
+                }
+            },
         }
     }
 }
@@ -0,0 +1,307 @@
+use crate::{
+    client::Client,
+    coins::{coin_count, coin_stack, remove_coins, COINS_ITEM},
+    persistence::{
+        character_updater::CharacterUpdater,
+        mail::{MailRequest, MailResponse},
+    },
+    presence::Presence,
+    Server,
+};
+use common::{
+    character::CharacterId,
+    comp::{self, inventory::slot::InvSlotId, ChatType, Inventory, Item},
+    mail::{validate_mail_text, MailAction, MailTextError, MAX_MAIL_ATTACHMENTS},
+};
+use common_net::msg::{PresenceKind, ServerGeneral};
+use specs::{join::Join, world::WorldExt, Entity as EcsEntity};
+use tracing::warn;
+
+pub fn handle_mail_action(server: &mut Server, entity: EcsEntity, action: MailAction) {
+    let character_id = match character_id(server, entity) {
+        Some(character_id) => character_id,
+        None => return,
+    };
+
+    let request = match action {
+        MailAction::RequestMailbox => MailRequest::LoadMailbox,
+        MailAction::MarkRead(mail_id) => MailRequest::MarkRead(mail_id),
+        MailAction::Delete(mail_id) => MailRequest::Delete(mail_id),
+        MailAction::Claim(mail_id) => {
+            match server
+                .state
+                .ecs()
+                .read_storage::<Inventory>()
+                .get(entity)
+                .cloned()
+            {
+                Some(inventory) => MailRequest::Claim { mail_id, inventory },
+                None => return,
+            }
+        },
+        MailAction::Send {
+            recipient,
+            subject,
+            body,
+            attachments,
+            coins,
+        } => {
+            let recipient = recipient.trim();
+            let result = if recipient.is_empty() {
+                Err(String::from("You must specify who to send the mail to"))
+            } else {
+                validate_mail_text(&subject, &body).map_err(|error| {
+                    String::from(match error {
+                        MailTextError::EmptySubject => "The mail needs a subject",
+                        MailTextError::SubjectTooLong => "The subject of the mail is too long",
+                        MailTextError::BodyTooLong => "The mail is too long",
+                    })
+                })
+            }
+            .and_then(|_| take_attachments(server, entity, &attachments, coins));
+
+            match result {
+                Ok((attachments, inventory)) => MailRequest::Send {
+                    sender_alias: server
+                        .state
+                        .ecs()
+                        .read_storage::<comp::Stats>()
+                        .get(entity)
+                        .map(|stats| stats.name.clone())
+                        .unwrap_or_default(),
+                    recipient_alias: recipient.to_owned(),
+                    subject,
+                    body,
+                    attachments,
+                    coins,
+                    limits: server.settings().mail,
+                    inventory,
+                },
+                Err(error) => {
+                    notify_mail_error(server, entity, error);
+                    return;
+                },
+            }
+        },
+    };
+
+    server
+        .state
+        .ecs()
+        .write_resource::<CharacterUpdater>()
+        .mail(entity, character_id, request);
+}
+
+/// Handles the result of a mail request once it has been processed by the
+/// persistence thread
+pub fn handle_mail_response(
+    server: &mut Server,
+    entity: EcsEntity,
+    character_id: CharacterId,
+    response: MailResponse,
+) {
+    match response {
+        MailResponse::Mailbox(mailbox) => {
+            server.notify_client(entity, ServerGeneral::MailboxUpdate(mailbox));
+        },
+        MailResponse::Sent {
+            recipient_id,
+            recipient_alias,
+        } => {
+            server.notify_client(
+                entity,
+                ServerGeneral::server_msg(
+                    ChatType::CommandInfo,
+                    format!("Your mail to {} has been sent", recipient_alias),
+                ),
+            );
+
+            // Let the recipient know straight away if they are online
+            let recipient = (
+                &server.state.ecs().entities(),
+                &server.state.ecs().read_storage::<Presence>(),
+            )
+                .join()
+                .find(|(_, presence)| presence.kind == PresenceKind::Character(recipient_id))
+                .map(|(entity, _)| entity);
+            if let Some(recipient) = recipient {
+                server.notify_client(
+                    recipient,
+                    ServerGeneral::server_msg(ChatType::CommandInfo, "You have new mail"),
+                );
+                server
+                    .state
+                    .ecs()
+                    .write_resource::<CharacterUpdater>()
+                    .mail(recipient, recipient_id, MailRequest::LoadMailbox);
+            }
+        },
+        MailResponse::SendFailed {
+            error,
+            attachments,
+            coins,
+        } => {
+            notify_mail_error(server, entity, error.to_string());
+            give_attachments(server, entity, character_id, attachments, coins);
+        },
+        MailResponse::Claimed { attachments, coins } => {
+            give_attachments(server, entity, character_id, attachments, coins);
+        },
+        MailResponse::Error(error) => notify_mail_error(server, entity, error.to_string()),
+    }
+}
+
+fn character_id(server: &Server, entity: EcsEntity) -> Option<CharacterId> {
+    match server
+        .state
+        .ecs()
+        .read_storage::<Presence>()
+        .get(entity)
+        .map(|presence| presence.kind)
+    {
+        Some(PresenceKind::Character(character_id)) => Some(character_id),
+        _ => None,
+    }
+}
+
+fn notify_mail_error(server: &Server, entity: EcsEntity, error: String) {
+    if let Some(client) = server.state.ecs().read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::server_msg(ChatType::CommandError, error));
+    }
+}
+
+/// Removes the attached items and coins (plus postage) from the sender's
+/// inventory, returning them along with the inventory that is left. Nothing is
+/// removed unless every attachment is valid.
+fn take_attachments(
+    server: &Server,
+    entity: EcsEntity,
+    slots: &[InvSlotId],
+    coins: u32,
+) -> Result<(Vec<Item>, Inventory), String> {
+    if slots.len() > MAX_MAIL_ATTACHMENTS {
+        return Err(format!(
+            "You can attach at most {} items to a piece of mail",
+            MAX_MAIL_ATTACHMENTS
+        ));
+    }
+    if slots
+        .iter()
+        .enumerate()
+        .any(|(i, slot)| slots[..i].contains(slot))
+    {
+        return Err(String::from("The same item cannot be attached twice"));
+    }
+
+    let postage = server.settings().mail.postage;
+    let mut inventories = server.state.ecs().write_storage::<Inventory>();
+    let mut inventory = inventories
+        .get_mut(entity)
+        .ok_or_else(|| String::from("You have no inventory to send items from"))?;
+
+    if slots.iter().any(|slot| inventory.get(*slot).is_none()) {
+        return Err(String::from("One of the attachments could not be found"));
+    }
+    if slots.iter().any(|slot| {
+        inventory
+            .get(*slot)
+            .map_or(false, |item| item.item_definition_id() == COINS_ITEM)
+    }) {
+        return Err(String::from("Coins must be sent using the coin field"));
+    }
+    let total_coins = u64::from(coins) + u64::from(postage);
+    if coin_count(&inventory) < total_coins {
+        return Err(if postage > 0 {
+            format!(
+                "You don't have enough coins, sending mail costs {} coins in postage",
+                postage
+            )
+        } else {
+            String::from("You don't have enough coins")
+        });
+    }
+
+    remove_coins(&mut inventory, total_coins);
+    let attachments = slots
+        .iter()
+        .filter_map(|slot| inventory.remove(*slot))
+        .collect();
+    let remaining = inventory.clone();
+    drop(inventories);
+
+    server
+        .state
+        .ecs()
+        .write_storage()
+        .insert(
+            entity,
+            comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Gave),
+        )
+        .expect("Inventory entity must still exist");
+
+    Ok((attachments, remaining))
+}
+
+/// Places items and coins into a character's inventory. Anything that doesn't
+/// fit (or cannot be given because the character has logged out) is mailed
+/// back to the character.
+fn give_attachments(
+    server: &Server,
+    entity: EcsEntity,
+    character_id: CharacterId,
+    attachments: Vec<Item>,
+    coins: u32,
+) {
+    let items = attachments
+        .into_iter()
+        .chain((coins > 0).then(|| coin_stack(coins)));
+
+    let mut inventories = server.state.ecs().write_storage::<Inventory>();
+    let (leftovers, inventory) = match inventories.get_mut(entity) {
+        Some(mut inventory) => {
+            let leftovers = items
+                .filter_map(|item| inventory.push(item).err())
+                .collect::<Vec<_>>();
+            let _ = server.state.ecs().write_storage().insert(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Given),
+            );
+            (leftovers, Some(inventory.clone()))
+        },
+        None => (items.collect(), None),
+    };
+    drop(inventories);
+
+    if !leftovers.is_empty() {
+        warn!(
+            "Could not give {} mail attachments to character ID {}, returning them by mail",
+            leftovers.len(),
+            character_id
+        );
+        let (coins, attachments): (Vec<_>, Vec<_>) = leftovers
+            .into_iter()
+            .partition(|item| item.item_definition_id() == COINS_ITEM);
+        let coins = coins.iter().map(Item::amount).sum();
+        // Returned mail is not subject to the attachment limit, since it is
+        // never created by players
+        server
+            .state
+            .ecs()
+            .write_resource::<CharacterUpdater>()
+            .mail(
+                entity,
+                character_id,
+                MailRequest::Return {
+                    subject: String::from("Returned items"),
+                    attachments,
+                    coins,
+                    inventory,
+                },
+            );
+        notify_mail_error(
+            server,
+            entity,
+            String::from("Your inventory is full, the remaining items have been mailed to you"),
+        );
+    }
+}
@@ -1,8 +1,11 @@
 use crate::{
-    events::interaction::handle_tame_pet, persistence::PersistedComponents, state_ext::StateExt,
-    Server,
+    events::interaction::handle_tame_pet, farming::handle_till_soil,
+    persistence::PersistedComponents, state_ext::StateExt, Server,
+};
+use common::{
+    comp::tool::ToolKind,
+    event::{EventBus, ServerEvent},
 };
-use common::event::{EventBus, ServerEvent};
 use common_base::span;
 use entity_creation::{
     handle_beam, handle_create_npc, handle_create_ship, handle_create_waypoint,
@@ -15,27 +18,35 @@ use entity_manipulation::{
     handle_poise, handle_respawn, handle_teleport_to, handle_update_map_marker,
 };
 use group_manip::handle_group;
+use guild::handle_guild_action;
 use information::handle_site_info;
 use interaction::{
-    handle_create_sprite, handle_lantern, handle_mine_block, handle_mount, handle_npc_interaction,
-    handle_possess, handle_sound, handle_unmount,
+    handle_catch_fish, handle_command_pet, handle_create_sprite, handle_lantern, handle_mine_block,
+    handle_mount, handle_npc_interaction, handle_possess, handle_rename_pet, handle_sound,
+    handle_unmount,
 };
 use inventory_manip::handle_inventory;
 use invite::{handle_invite, handle_invite_response};
+use mail::handle_mail_action;
 use player::{handle_client_disconnect, handle_exit_ingame};
 use specs::{Builder, Entity as EcsEntity, WorldExt};
+use statistics::handle_update_statistic;
 use trade::{cancel_trade_for, handle_process_trade_action};
 
 pub use group_manip::update_map_markers;
+pub use mail::handle_mail_response;
 
-mod entity_creation;
-mod entity_manipulation;
+pub(crate) mod entity_creation;
+pub(crate) mod entity_manipulation;
 mod group_manip;
+mod guild;
 mod information;
 mod interaction;
 mod inventory_manip;
 mod invite;
+mod mail;
 mod player;
+mod statistics;
 mod trade;
 
 pub enum Event {
@@ -83,25 +94,35 @@ impl Server {
                     speed,
                     object,
                 } => handle_shoot(
-                    self, entity, pos, dir, body, light, projectile, speed, object,
+                    &mut self.state,
+                    entity,
+                    pos,
+                    dir,
+                    body,
+                    light,
+                    projectile,
+                    speed,
+                    object,
                 ),
                 ServerEvent::Shockwave {
                     properties,
                     pos,
                     ori,
-                } => handle_shockwave(self, properties, pos, ori),
+                } => handle_shockwave(&mut self.state, properties, pos, ori),
                 ServerEvent::BeamSegment {
                     properties,
                     pos,
                     ori,
-                } => handle_beam(self, properties, pos, ori),
+                } => handle_beam(&mut self.state, properties, pos, ori),
                 ServerEvent::Knockback { entity, impulse } => {
-                    handle_knockback(self, entity, impulse)
+                    handle_knockback(self.state.ecs(), entity, impulse)
                 },
                 ServerEvent::HealthChange { entity, change } => {
-                    handle_health_change(self, entity, change)
+                    handle_health_change(self.state.ecs(), entity, change)
+                },
+                ServerEvent::PoiseChange { entity, change } => {
+                    handle_poise(self.state.ecs(), entity, change)
                 },
-                ServerEvent::PoiseChange { entity, change } => handle_poise(self, entity, change),
                 ServerEvent::Delete(entity) => handle_delete(self, entity),
                 ServerEvent::Destroy { entity, cause } => handle_destroy(self, entity, cause),
                 ServerEvent::InventoryManip(entity, manip) => handle_inventory(self, entity, manip),
@@ -143,6 +164,7 @@ impl Server {
                         pets,
                         active_abilities,
                         map_marker,
+                        statistics,
                     ) = components;
                     let components = PersistedComponents {
                         body,
@@ -153,6 +175,7 @@ impl Server {
                         pets,
                         active_abilities,
                         map_marker,
+                        statistics,
                     };
                     handle_loaded_character_data(self, entity, components);
                 },
@@ -198,7 +221,8 @@ impl Server {
                     mountable,
                     agent,
                     rtsim_entity,
-                } => handle_create_ship(self, pos, ship, mountable, agent, rtsim_entity),
+                    anchor,
+                } => handle_create_ship(self, pos, ship, mountable, agent, rtsim_entity, anchor),
                 ServerEvent::CreateWaypoint(pos) => handle_create_waypoint(self, pos),
                 ServerEvent::ClientDisconnect(entity, reason) => {
                     frontend_events.push(handle_client_disconnect(self, entity, reason, false))
@@ -224,21 +248,27 @@ impl Server {
                 ServerEvent::Buff {
                     entity,
                     buff_change,
-                } => handle_buff(self, entity, buff_change),
+                } => handle_buff(self.state.ecs(), entity, buff_change),
                 ServerEvent::EnergyChange { entity, change } => {
-                    handle_energy_change(self, entity, change)
+                    handle_energy_change(self.state.ecs(), entity, change)
                 },
                 ServerEvent::ComboChange { entity, change } => {
-                    handle_combo_change(self, entity, change)
+                    handle_combo_change(self.state.ecs(), entity, change)
                 },
                 ServerEvent::Parry {
                     entity,
                     energy_cost,
-                } => handle_parry(self, entity, energy_cost),
+                } => handle_parry(self.state.ecs(), entity, energy_cost),
                 ServerEvent::RequestSiteInfo { entity, id } => handle_site_info(self, entity, id),
                 ServerEvent::MineBlock { entity, pos, tool } => {
-                    handle_mine_block(self, entity, pos, tool)
+                    // Hoes till the soil rather than breaking it
+                    if tool == Some(ToolKind::Farming) {
+                        handle_till_soil(self, pos)
+                    } else {
+                        handle_mine_block(self, entity, pos, tool)
+                    }
                 },
+                ServerEvent::CatchFish { entity, pos } => handle_catch_fish(self, entity, pos),
                 ServerEvent::TeleportTo {
                     entity,
                     target,
@@ -255,6 +285,16 @@ impl Server {
                     pet_entity,
                     owner_entity,
                 } => handle_tame_pet(self, pet_entity, owner_entity),
+                ServerEvent::CommandPet {
+                    owner_entity,
+                    pet_entity,
+                    command,
+                } => handle_command_pet(self, owner_entity, pet_entity, command),
+                ServerEvent::RenamePet {
+                    owner_entity,
+                    pet_entity,
+                    name,
+                } => handle_rename_pet(self, owner_entity, pet_entity, name),
                 ServerEvent::EntityAttackedHook { entity } => {
                     handle_entity_attacked_hook(self, entity)
                 },
@@ -267,6 +307,17 @@ impl Server {
                 ServerEvent::UpdateMapMarker { entity, update } => {
                     handle_update_map_marker(self, entity, update)
                 },
+                ServerEvent::MailAction { entity, action } => {
+                    handle_mail_action(self, entity, action)
+                },
+                ServerEvent::GuildAction { entity, action } => {
+                    handle_guild_action(self, entity, action)
+                },
+                ServerEvent::UpdateStatistic {
+                    entity,
+                    stat,
+                    amount,
+                } => handle_update_statistic(self, entity, stat, amount),
             }
         }
 
@@ -19,6 +19,8 @@ pub fn handle_exit_ingame(server: &mut Server, entity: EcsEntity) {
     span!(_guard, "handle_exit_ingame");
     let state = server.state_mut();
 
+    crate::pvp::handle_player_leave(state.ecs(), entity);
+
     // Sync the player's character data to the database. This must be done before
     // removing any components from the entity
     let entity = persist_entity(state, entity);
@@ -116,6 +118,7 @@ pub fn handle_client_disconnect(
 ) -> Event {
     span!(_guard, "handle_client_disconnect");
     cancel_trade_for(server, entity);
+    crate::pvp::handle_player_leave(server.state().ecs(), entity);
     if let Some(client) = server
         .state()
         .ecs()
@@ -231,6 +234,12 @@ fn persist_entity(state: &mut State, entity: EcsEntity) -> EcsEntity {
                     .read_storage::<common::comp::MapMarker>()
                     .get(entity)
                     .cloned();
+                let statistics = state
+                    .ecs()
+                    .read_storage::<common::comp::Statistics>()
+                    .get(entity)
+                    .cloned()
+                    .unwrap_or_default();
                 // Store last battle mode change
                 if let Some(change) = player_info.last_battlemode_change {
                     let mode = player_info.battle_mode;
@@ -267,6 +276,7 @@ fn persist_entity(state: &mut State, entity: EcsEntity) -> EcsEntity {
                         waypoint,
                         active_abilities.clone(),
                         map_marker,
+                        statistics,
                     ),
                 );
             },
@@ -0,0 +1,53 @@
+use crate::{state_ext::StateExt, Server};
+use common::{
+    achievement::default_achievement_manifest,
+    comp::{self, ChatType, Stat},
+};
+use common_net::msg::ServerGeneral;
+use specs::{world::WorldExt, Entity as EcsEntity};
+
+pub fn handle_update_statistic(server: &mut Server, entity: EcsEntity, stat: Stat, amount: u64) {
+    if amount == 0 {
+        return;
+    }
+
+    let unlocked = {
+        let mut statistics = server.state.ecs().write_storage::<comp::Statistics>();
+        // Only characters have statistics, anything else is ignored
+        let mut statistics = match statistics.get_mut(entity) {
+            Some(statistics) => statistics,
+            None => return,
+        };
+        statistics.increment(stat, amount);
+
+        let manifest = default_achievement_manifest();
+        let manifest = manifest.read();
+        let unlocked = manifest
+            .newly_unlocked(&statistics)
+            .map(|achievement| (achievement.id.clone(), achievement.title.clone()))
+            .collect::<Vec<_>>();
+        for (id, _) in unlocked.iter() {
+            statistics.unlock_achievement(id.clone());
+        }
+        unlocked
+    };
+
+    if unlocked.is_empty() {
+        return;
+    }
+
+    let name = server
+        .state
+        .ecs()
+        .read_storage::<comp::Stats>()
+        .get(entity)
+        .map(|stats| stats.name.clone())
+        .unwrap_or_default();
+    // The announcement also reaches the player who earned the achievement
+    for (_, title) in unlocked {
+        server.state.notify_players(ServerGeneral::server_msg(
+            ChatType::Meta,
+            format!("{} has earned the achievement [{}]", name, title),
+        ));
+    }
+}
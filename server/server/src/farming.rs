@@ -0,0 +1,350 @@
+//! Crops that players plant in tilled soil
+//!
+//! Soil is tilled by swinging a hoe at grass or earth, after which seeds can be
+//! planted in it. Planted crops are stored in terrain persistence so that
+//! fields survive the chunk being unloaded. The time at which each crop was
+//! planted is saved in the data directory, so crops keep growing while their
+//! chunk is unloaded or the server is down, and catch up on their growth once
+//! their chunk is loaded again (see [`crate::sys::farming`]).
+
+use crate::{time, Server};
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use common::{
+    comp::{
+        self,
+        item::{tool::AbilityMap, MaterialStatManifest},
+        slot::InvSlotId,
+        Inventory,
+    },
+    terrain::{Block, SpriteKind, TerrainChunk, TerrainGrid},
+    vol::{ReadVol, RectRasterableVol, WriteVol},
+};
+use common_state::State;
+use hashbrown::HashMap;
+use specs::WorldExt;
+use std::{
+    fs,
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+use tracing::{debug, error, info};
+use vek::*;
+
+/// The crops that are still growing, along with the time at which each was
+/// planted as a unix timestamp
+pub struct GrowingCrops {
+    path: PathBuf,
+    chunks: HashMap<Vec2<i32>, HashMap<Vec3<i32>, i64>>,
+    dirty: bool,
+}
+
+impl GrowingCrops {
+    /// Restore the crops that were growing when the server last stopped from
+    /// the data directory
+    pub fn new(data_dir: &Path) -> Self {
+        let mut this = Self {
+            path: data_dir.join("crops.ron"),
+            chunks: HashMap::new(),
+            dirty: false,
+        };
+        this.load();
+        this
+    }
+
+    fn load(&mut self) {
+        let crops: Vec<(Vec3<i32>, i64)> = match fs::File::open(&self.path) {
+            Ok(file) => match ron::de::from_reader(file) {
+                Ok(crops) => crops,
+                Err(e) => {
+                    error!(?e, ?self.path, "Failed to parse the growing crops");
+                    return;
+                },
+            },
+            // Nothing has been planted yet
+            Err(_) => return,
+        };
+        for (pos, planted) in crops {
+            self.track(pos, planted);
+        }
+        info!("Loaded the growing crops from {:?}", self.path);
+    }
+
+    /// Write the growing crops to disk, if they changed since they were last
+    /// saved
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let crops = self
+            .chunks
+            .values()
+            .flat_map(|crops| crops.iter().map(|(pos, planted)| (*pos, *planted)))
+            .collect::<Vec<_>>();
+        let ron = match ron::ser::to_string(&crops) {
+            Ok(ron) => ron,
+            Err(e) => {
+                error!(?e, "Failed to serialize the growing crops");
+                return;
+            },
+        };
+        if let Err(e) = AtomicFile::new(&self.path, OverwriteBehavior::AllowOverwrite)
+            .write(|file| file.write_all(ron.as_bytes()))
+        {
+            error!(?e, ?self.path, "Failed to save the growing crops");
+        } else {
+            self.dirty = false;
+        }
+    }
+
+    /// Start tracking a crop planted at the given time
+    pub fn track(&mut self, pos: Vec3<i32>, planted: i64) {
+        self.chunks
+            .entry(TerrainGrid::chunk_key(pos))
+            .or_default()
+            .insert(pos, planted);
+        self.dirty = true;
+    }
+
+    pub fn untrack(&mut self, pos: Vec3<i32>) {
+        let key = TerrainGrid::chunk_key(pos);
+        if let Some(crops) = self.chunks.get_mut(&key) {
+            if crops.remove(&pos).is_some() {
+                self.dirty = true;
+            }
+            if crops.is_empty() {
+                self.chunks.remove(&key);
+            }
+        }
+    }
+
+    /// The block the crop in the given block has grown into by `now`, if it
+    /// grew since the block was last updated
+    fn grown(&self, pos: Vec3<i32>, block: Block, now: i64) -> Option<Block> {
+        let (crop, growth) = block.get_crop()?;
+        let planted = *self.chunks.get(&TerrainGrid::chunk_key(pos))?.get(&pos)?;
+        let stages = (now - planted) as f64 / crop.crop_growth_time()?;
+        let target = (stages.max(0.0) as u8).min(Block::MAX_CROP_GROWTH);
+        (target > growth).then(|| block.with_crop_growth(target))
+    }
+
+    /// Stop tracking crops once they are fully grown, or if they were
+    /// harvested or destroyed
+    pub fn settle(&mut self, pos: Vec3<i32>, block: Block) {
+        if block
+            .get_crop()
+            .map_or(true, |(_, growth)| growth >= Block::MAX_CROP_GROWTH)
+        {
+            self.untrack(pos);
+        }
+    }
+
+    /// Grow the crops in a chunk that has just been loaded to the stage they
+    /// reached while it was unloaded. Returns the blocks that changed.
+    pub fn grow_chunk(
+        &mut self,
+        key: Vec2<i32>,
+        chunk: &mut TerrainChunk,
+        now: i64,
+    ) -> Vec<(Vec3<i32>, Block)> {
+        let offset = key * TerrainChunk::RECT_SIZE.map(|e| e as i32);
+        let positions = self
+            .chunks
+            .get(&key)
+            .map_or_else(Vec::new, |crops| crops.keys().copied().collect());
+        let mut grown_blocks = Vec::new();
+        for pos in positions {
+            let block = match chunk.get(pos - offset) {
+                Ok(block) => *block,
+                Err(_) => continue,
+            };
+            let block = match self.grown(pos, block, now) {
+                Some(grown) => {
+                    let _ = chunk.set(pos - offset, grown);
+                    grown_blocks.push((pos, grown));
+                    grown
+                },
+                None => block,
+            };
+            self.settle(pos, block);
+        }
+        grown_blocks
+    }
+
+    /// The crops in loaded chunks that grew since they were last updated,
+    /// along with the blocks they grew into. Call [`GrowingCrops::settle`] on
+    /// the blocks once they are set.
+    pub fn grow_loaded(&mut self, terrain: &TerrainGrid, now: i64) -> Vec<(Vec3<i32>, Block)> {
+        let positions = self
+            .chunks
+            .iter()
+            .filter(|(key, _)| terrain.get_key(**key).is_some())
+            .flat_map(|(_, crops)| crops.keys().copied())
+            .collect::<Vec<_>>();
+        let mut grown_blocks = Vec::new();
+        for pos in positions {
+            let block = match terrain.get(pos) {
+                Ok(block) => *block,
+                Err(_) => continue,
+            };
+            match self.grown(pos, block, now) {
+                Some(grown) => grown_blocks.push((pos, grown)),
+                None => self.settle(pos, block),
+            }
+        }
+        grown_blocks
+    }
+}
+
+/// Set a block that is part of a field, and record it in terrain persistence
+pub fn set_field_block(state: &State, pos: Vec3<i32>, block: Block) {
+    state.set_block(pos, block);
+    #[cfg(feature = "persistent_world")]
+    if let Some(terrain_persistence) = state
+        .ecs()
+        .try_fetch_mut::<crate::TerrainPersistence>()
+        .as_mut()
+    {
+        terrain_persistence.set_block(pos, block);
+    }
+}
+
+/// Till the soil on top of the given block, ready for seeds to be planted in
+/// it. Any grass or small plants growing on top are cleared away.
+pub fn handle_till_soil(server: &mut Server, pos: Vec3<i32>) {
+    let state = server.state();
+    let above_pos = pos + Vec3::unit_z();
+    let terrain = state.terrain();
+    let (block, above) = match (terrain.get(pos), terrain.get(above_pos)) {
+        (Ok(block), Ok(above)) => (*block, *above),
+        _ => return,
+    };
+    drop(terrain);
+
+    let is_clear = above.is_air()
+        && !above.is_collectible()
+        && above
+            .get_sprite()
+            .map_or(true, |s| s.solid_height().is_none());
+    if block.is_tillable() && is_clear && state.can_set_block(above_pos) {
+        set_field_block(state, above_pos, Block::air(SpriteKind::TilledSoil));
+    }
+}
+
+/// Plant the seed in the given inventory slot in the tilled soil at the given
+/// position
+pub fn plant_seed(state: &State, inventory: &mut Inventory, slot: InvSlotId, pos: Vec3<i32>) {
+    let crop = inventory
+        .get(slot)
+        .and_then(|item| SpriteKind::from_seed_item(item.item_definition_id()));
+
+    if let Some(crop) = crop {
+        let _ = inventory.take(
+            slot,
+            &state.ecs().read_resource::<AbilityMap>(),
+            &state.ecs().read_resource::<MaterialStatManifest>(),
+        );
+        set_field_block(state, pos, Block::seedling(crop));
+        state
+            .ecs()
+            .write_resource::<GrowingCrops>()
+            .track(pos, time::now());
+    } else {
+        debug!(
+            "Can't plant at pos={}: the item in {:?} isn't a seed",
+            pos, slot
+        );
+    }
+}
+
+/// Harvest the crop in the given block, leaving the tilled soil behind.
+/// Fully grown crops also give back a seed so that they can be planted again.
+pub fn harvest_crop(
+    state: &State,
+    pos: Vec3<i32>,
+    crop: SpriteKind,
+    growth: u8,
+) -> Option<comp::Item> {
+    state.ecs().write_resource::<GrowingCrops>().untrack(pos);
+    set_field_block(state, pos, Block::air(SpriteKind::TilledSoil));
+    crop.seed_item()
+        .filter(|_| growth >= Block::MAX_CROP_GROWTH)
+        .and_then(|seed| comp::Item::new_from_asset(seed).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::terrain::TerrainChunkMeta;
+
+    #[test]
+    fn crops_grow_from_when_they_were_planted() {
+        let pos = Vec3::new(40, -3, 120);
+        let mut crops = GrowingCrops {
+            path: PathBuf::new(),
+            chunks: HashMap::new(),
+            dirty: false,
+        };
+        crops.track(pos, 1000);
+        assert!(crops.dirty);
+
+        let seedling = Block::seedling(SpriteKind::Carrot);
+        let growth_time = SpriteKind::Carrot.crop_growth_time().unwrap() as i64;
+        // Nothing happens before the first stage is reached
+        assert_eq!(crops.grown(pos, seedling, 1000 + growth_time - 1), None);
+        assert_eq!(
+            crops.grown(pos, seedling, 1000 + growth_time * 2),
+            Some(seedling.with_crop_growth(2))
+        );
+        // However long it was unloaded, a crop doesn't grow past fully grown
+        let grown = crops
+            .grown(pos, seedling, 1000 + growth_time * 1000)
+            .unwrap();
+        assert_eq!(
+            grown.get_crop(),
+            Some((SpriteKind::Carrot, Block::MAX_CROP_GROWTH))
+        );
+
+        crops.settle(pos, seedling.with_crop_growth(2));
+        assert!(crops.chunks.contains_key(&TerrainGrid::chunk_key(pos)));
+        crops.settle(pos, grown);
+        assert!(crops.chunks.is_empty());
+    }
+
+    #[test]
+    fn crops_missing_from_loaded_chunks_are_forgotten() {
+        let key = Vec2::new(1, 0);
+        let offset = key * TerrainChunk::RECT_SIZE.map(|e| e as i32);
+        let mut crops = GrowingCrops {
+            path: PathBuf::new(),
+            chunks: HashMap::new(),
+            dirty: false,
+        };
+        let planted = offset.with_z(10) + Vec3::new(3, 4, 0);
+        let lost = offset.with_z(10) + Vec3::new(5, 6, 0);
+        crops.track(planted, 1000);
+        crops.track(lost, 1000);
+
+        // Without terrain persistence, the chunk comes back without the crops
+        // that were planted in it
+        let mut chunk = TerrainChunk::new(
+            0,
+            Block::air(SpriteKind::Empty),
+            Block::air(SpriteKind::Empty),
+            TerrainChunkMeta::void(),
+        );
+        let seedling = Block::seedling(SpriteKind::Carrot);
+        chunk.set(planted - offset, seedling).unwrap();
+
+        let growth_time = SpriteKind::Carrot.crop_growth_time().unwrap() as i64;
+        let grown = crops.grow_chunk(key, &mut chunk, 1000 + growth_time);
+        assert_eq!(grown, vec![(planted, seedling.with_crop_growth(1))]);
+        assert_eq!(
+            chunk.get(planted - offset).ok().copied(),
+            Some(seedling.with_crop_growth(1))
+        );
+        assert_eq!(
+            crops.chunks[&key].keys().copied().collect::<Vec<_>>(),
+            vec![planted]
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! Persistent player guilds
+//!
+//! Guilds are loaded from the database when the server starts and are then
+//! kept in the [`Guilds`] resource. Every change made to a guild is also sent
+//! to the persistence thread as a [`GuildRequest`].
+
+use crate::{
+    client::Client,
+    persistence::{
+        character_updater::CharacterUpdater,
+        guild::{GuildData, GuildRequest},
+    },
+    presence::Presence,
+    settings::GuildSettings,
+};
+use common::{
+    character::CharacterId,
+    comp::{GuildTag, Item, Stats},
+    guild::{GuildId, GuildInfo, GuildMember, GuildRank},
+};
+use common_net::msg::{PresenceKind, ServerGeneral};
+use hashbrown::HashMap;
+use specs::{join::Join, Entity as EcsEntity, WorldExt};
+use tracing::warn;
+
+#[derive(Clone, Debug)]
+pub struct Membership {
+    pub alias: String,
+    pub rank: GuildRank,
+}
+
+#[derive(Clone, Debug)]
+pub struct Guild {
+    pub id: GuildId,
+    pub name: String,
+    pub tag: String,
+    pub members: HashMap<CharacterId, Membership>,
+    pub bank: Vec<Item>,
+}
+
+impl Guild {
+    pub fn rank_of(&self, character_id: CharacterId) -> Option<GuildRank> {
+        self.members.get(&character_id).map(|member| member.rank)
+    }
+
+    /// Finds a member by their (case insensitive) character name
+    pub fn member_by_alias(&self, alias: &str) -> Option<(CharacterId, &Membership)> {
+        self.members
+            .iter()
+            .find(|(_, member)| member.alias.eq_ignore_ascii_case(alias))
+            .map(|(character_id, member)| (*character_id, member))
+    }
+
+    /// The highest ranked member other than `except`, who becomes the leader
+    /// if the current one leaves
+    pub fn successor(&self, except: CharacterId) -> Option<CharacterId> {
+        self.members
+            .iter()
+            .filter(|(character_id, _)| **character_id != except)
+            .max_by_key(|(character_id, member)| (member.rank, std::cmp::Reverse(**character_id)))
+            .map(|(character_id, _)| *character_id)
+    }
+}
+
+/// Resource holding every guild, along with an index of which guild each
+/// character belongs to
+#[derive(Default)]
+pub struct Guilds {
+    guilds: HashMap<GuildId, Guild>,
+    members: HashMap<CharacterId, GuildId>,
+    next_id: GuildId,
+}
+
+impl Guilds {
+    /// Builds the resource from the guilds stored in the database. Guilds
+    /// which lost their leader (because the leader's character was deleted)
+    /// are given a new one, and guilds without any members are disbanded.
+    pub fn load(data: Vec<GuildData>, character_updater: &mut CharacterUpdater) -> Self {
+        let mut guilds = Self::default();
+        for data in data {
+            guilds.next_id = guilds.next_id.max(data.id + 1);
+            let mut guild = Guild {
+                id: data.id,
+                name: data.name,
+                tag: data.tag,
+                members: data
+                    .members
+                    .into_iter()
+                    .map(|member| {
+                        (member.character_id, Membership {
+                            alias: member.alias,
+                            rank: member.rank,
+                        })
+                    })
+                    .collect(),
+                bank: data.bank,
+            };
+
+            if guild.members.is_empty() {
+                warn!("Guild {} has no members left, disbanding it", guild.name);
+                character_updater.guild(GuildRequest::Delete(guild.id));
+                continue;
+            }
+            let has_leader = guild
+                .members
+                .values()
+                .any(|member| member.rank == GuildRank::Leader);
+            if !has_leader {
+                // `CharacterId::MIN` is never a member, so anyone can succeed
+                if let Some(leader) = guild.successor(CharacterId::MIN) {
+                    warn!("Guild {} has no leader, promoting a new one", guild.name);
+                    if let Some(member) = guild.members.get_mut(&leader) {
+                        member.rank = GuildRank::Leader;
+                    }
+                    character_updater.guild(GuildRequest::SetMember {
+                        guild_id: guild.id,
+                        character_id: leader,
+                        rank: GuildRank::Leader,
+                    });
+                }
+            }
+
+            for character_id in guild.members.keys() {
+                guilds.members.insert(*character_id, guild.id);
+            }
+            guilds.guilds.insert(guild.id, guild);
+        }
+        guilds.next_id = guilds.next_id.max(1);
+        guilds
+    }
+
+    pub fn get(&self, guild_id: GuildId) -> Option<&Guild> { self.guilds.get(&guild_id) }
+
+    pub fn get_mut(&mut self, guild_id: GuildId) -> Option<&mut Guild> {
+        self.guilds.get_mut(&guild_id)
+    }
+
+    /// The guild a character belongs to, if any
+    pub fn guild_of(&self, character_id: CharacterId) -> Option<&Guild> {
+        self.members
+            .get(&character_id)
+            .and_then(|guild_id| self.guilds.get(guild_id))
+    }
+
+    pub fn guild_of_mut(&mut self, character_id: CharacterId) -> Option<&mut Guild> {
+        let guild_id = *self.members.get(&character_id)?;
+        self.guilds.get_mut(&guild_id)
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<&Guild> {
+        self.guilds
+            .values()
+            .find(|guild| guild.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Whether another guild already uses the given name or tag
+    pub fn is_taken(&self, name: &str, tag: &str) -> bool {
+        self.guilds.values().any(|guild| {
+            guild.name.eq_ignore_ascii_case(name) || guild.tag.eq_ignore_ascii_case(tag)
+        })
+    }
+
+    /// Creates a new guild led by the given character
+    pub fn create(
+        &mut self,
+        name: String,
+        tag: String,
+        leader: CharacterId,
+        leader_alias: String,
+    ) -> &Guild {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut members = HashMap::new();
+        members.insert(leader, Membership {
+            alias: leader_alias,
+            rank: GuildRank::Leader,
+        });
+        self.guilds.insert(id, Guild {
+            id,
+            name,
+            tag,
+            members,
+            bank: Vec::new(),
+        });
+        self.members.insert(leader, id);
+        &self.guilds[&id]
+    }
+
+    /// Adds a character to a guild, or changes their rank if they are already
+    /// a member of it
+    pub fn set_member(
+        &mut self,
+        guild_id: GuildId,
+        character_id: CharacterId,
+        alias: String,
+        rank: GuildRank,
+    ) {
+        if let Some(guild) = self.guilds.get_mut(&guild_id) {
+            guild
+                .members
+                .insert(character_id, Membership { alias, rank });
+            self.members.insert(character_id, guild_id);
+        }
+    }
+
+    pub fn set_rank(&mut self, character_id: CharacterId, rank: GuildRank) {
+        if let Some(member) = self
+            .guild_of_mut(character_id)
+            .and_then(|guild| guild.members.get_mut(&character_id))
+        {
+            member.rank = rank;
+        }
+    }
+
+    /// Removes a character from their guild, returning the ID of the guild
+    pub fn remove_member(&mut self, character_id: CharacterId) -> Option<GuildId> {
+        let guild_id = self.members.remove(&character_id)?;
+        if let Some(guild) = self.guilds.get_mut(&guild_id) {
+            guild.members.remove(&character_id);
+        }
+        Some(guild_id)
+    }
+
+    /// Removes a guild along with all of its memberships
+    pub fn disband(&mut self, guild_id: GuildId) -> Option<Guild> {
+        let guild = self.guilds.remove(&guild_id)?;
+        for character_id in guild.members.keys() {
+            self.members.remove(character_id);
+        }
+        Some(guild)
+    }
+}
+
+/// The character an entity is currently playing as
+pub fn character_id(ecs: &specs::World, entity: EcsEntity) -> Option<CharacterId> {
+    match ecs.read_storage::<Presence>().get(entity)?.kind {
+        PresenceKind::Character(character_id) => Some(character_id),
+        _ => None,
+    }
+}
+
+/// The entity of a character, if it is currently being played
+pub fn character_entity(ecs: &specs::World, character_id: CharacterId) -> Option<EcsEntity> {
+    (&ecs.entities(), &ecs.read_storage::<Presence>())
+        .join()
+        .find(|(_, presence)| presence.kind == PresenceKind::Character(character_id))
+        .map(|(entity, _)| entity)
+}
+
+/// Builds the information about a guild that is sent to one of its members
+pub fn guild_info(
+    ecs: &specs::World,
+    guild: &Guild,
+    rank: GuildRank,
+    settings: &GuildSettings,
+) -> GuildInfo {
+    let presences = ecs.read_storage::<Presence>();
+    let is_online = |character_id: CharacterId| {
+        presences
+            .join()
+            .any(|presence| presence.kind == PresenceKind::Character(character_id))
+    };
+    let mut members = guild
+        .members
+        .iter()
+        .map(|(character_id, member)| GuildMember {
+            name: member.alias.clone(),
+            rank: member.rank,
+            online: is_online(*character_id),
+        })
+        .collect::<Vec<_>>();
+    members.sort_by(|a, b| b.rank.cmp(&a.rank).then_with(|| a.name.cmp(&b.name)));
+
+    GuildInfo {
+        id: guild.id,
+        name: guild.name.clone(),
+        tag: guild.tag.clone(),
+        rank,
+        members,
+        bank: guild.bank.clone(),
+        bank_capacity: settings.bank_slots,
+    }
+}
+
+/// Updates the nameplate tag of a character and sends them the current state
+/// of their guild, or lets them know they are no longer in one
+pub fn refresh_member(
+    ecs: &specs::World,
+    guilds: &Guilds,
+    settings: &GuildSettings,
+    character_id: CharacterId,
+) {
+    let entity = match character_entity(ecs, character_id) {
+        Some(entity) => entity,
+        None => return,
+    };
+
+    let guild = guilds.guild_of(character_id);
+    let mut tags = ecs.write_storage::<GuildTag>();
+    match guild {
+        Some(guild) => {
+            let _ = tags.insert(entity, GuildTag(guild.tag.clone()));
+        },
+        None => {
+            tags.remove(entity);
+        },
+    }
+    drop(tags);
+
+    let info = guild.and_then(|guild| {
+        let rank = guild.rank_of(character_id)?;
+        Some(guild_info(ecs, guild, rank, settings))
+    });
+    if let Some(client) = ecs.read_storage::<Client>().get(entity) {
+        client.send_fallible(ServerGeneral::GuildUpdate(info));
+    }
+}
+
+/// Refreshes every online member of a guild
+pub fn refresh_guild(
+    ecs: &specs::World,
+    guilds: &Guilds,
+    settings: &GuildSettings,
+    guild_id: GuildId,
+) {
+    if let Some(guild) = guilds.get(guild_id) {
+        for character_id in guild.members.keys() {
+            refresh_member(ecs, guilds, settings, *character_id);
+        }
+    }
+}
+
+/// Checks whether `inviter` may invite `invitee` into their guild
+pub fn check_invite(
+    ecs: &specs::World,
+    settings: &GuildSettings,
+    inviter: EcsEntity,
+    invitee: EcsEntity,
+) -> Result<GuildId, &'static str> {
+    let guilds = ecs.read_resource::<Guilds>();
+    let guild = character_id(ecs, inviter)
+        .and_then(|character_id| {
+            let guild = guilds.guild_of(character_id)?;
+            Some((guild, guild.rank_of(character_id)?))
+        })
+        .ok_or("You are not in a guild.")
+        .and_then(|(guild, rank)| {
+            if rank.can_invite() {
+                Ok(guild)
+            } else {
+                Err("Only officers can invite new members.")
+            }
+        })?;
+    if guild.members.len() >= settings.max_members {
+        return Err("Your guild is full.");
+    }
+    match character_id(ecs, invitee) {
+        None => Err("Only characters can be invited into a guild."),
+        Some(character_id) if guilds.guild_of(character_id).is_some() => {
+            Err("This player is already in a guild.")
+        },
+        Some(_) => Ok(guild.id),
+    }
+}
+
+/// Adds `invitee` to the guild of `inviter` as a recruit, returning the name
+/// of the guild
+pub fn accept_invite(
+    ecs: &specs::World,
+    settings: &GuildSettings,
+    inviter: EcsEntity,
+    invitee: EcsEntity,
+) -> Result<String, &'static str> {
+    // Things may have changed since the invite was sent
+    let guild_id = check_invite(ecs, settings, inviter, invitee)?;
+    let character_id = character_id(ecs, invitee).ok_or("Only characters can join a guild.")?;
+    let alias = ecs
+        .read_storage::<Stats>()
+        .get(invitee)
+        .map(|stats| stats.name.clone())
+        .unwrap_or_default();
+
+    let mut guilds = ecs.write_resource::<Guilds>();
+    guilds.set_member(guild_id, character_id, alias, GuildRank::Recruit);
+    ecs.write_resource::<CharacterUpdater>()
+        .guild(GuildRequest::SetMember {
+            guild_id,
+            character_id,
+            rank: GuildRank::Recruit,
+        });
+    refresh_guild(ecs, &guilds, settings, guild_id);
+
+    Ok(guilds
+        .get(guild_id)
+        .map(|guild| guild.name.clone())
+        .unwrap_or_default())
+}
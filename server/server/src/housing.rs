@@ -0,0 +1,312 @@
+//! Town plots that characters can buy to build on
+//!
+//! Owning a plot grants the owner a personal build area covering the plot.
+//! Plots are charged rent every period and are released if their owner fails
+//! to pay or stops playing (see [`crate::sys::housing`]).
+
+use crate::{persistence::housing::HousingPlotData, settings::HousingSettings};
+use common::{character::CharacterId, comp::CanBuild, depot::Id};
+use common_state::BuildAreas;
+use hashbrown::{HashMap, HashSet};
+use specs::{Entity as EcsEntity, WorldExt};
+use vek::*;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// A plot that has been bought by a character
+#[derive(Clone, Debug)]
+pub struct OwnedPlot {
+    pub owner: CharacterId,
+    pub owner_alias: String,
+    /// The area the owner is allowed to build in
+    pub bounds: Aabb<i32>,
+    /// Unix timestamp (in seconds) until which the rent has been paid
+    pub paid_until: i64,
+    /// Unix timestamp (in seconds) of when the owner was last seen playing
+    pub last_active: i64,
+    pub build_area: Id<Aabb<i32>>,
+}
+
+impl OwnedPlot {
+    /// Plots are identified by the minimum corner of their bounds
+    pub fn key(&self) -> Vec2<i32> { self.bounds.min.xy() }
+
+    pub fn to_data(&self) -> HousingPlotData {
+        HousingPlotData {
+            bounds: self.bounds,
+            owner: self.owner,
+            owner_alias: self.owner_alias.clone(),
+            paid_until: self.paid_until,
+            last_active: self.last_active,
+        }
+    }
+}
+
+/// A plot found in a town that can be bought, if nobody owns it yet
+#[derive(Copy, Clone, Debug)]
+pub struct PlotForSale {
+    pub bounds: Aabb<i32>,
+    pub is_house: bool,
+}
+
+impl PlotForSale {
+    pub fn price(&self, settings: &HousingSettings) -> u32 {
+        if self.is_house {
+            settings.house_price
+        } else {
+            settings.tile_price
+        }
+    }
+}
+
+/// Resource holding every plot that is currently owned
+#[derive(Default)]
+pub struct HousingPlots {
+    plots: HashMap<Vec2<i32>, OwnedPlot>,
+}
+
+impl HousingPlots {
+    /// The name of the build area that is created for a plot
+    pub fn build_area_name(key: Vec2<i32>) -> String { format!("plot_{}_{}", key.x, key.y) }
+
+    pub fn iter(&self) -> impl Iterator<Item = &OwnedPlot> { self.plots.values() }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut OwnedPlot> { self.plots.values_mut() }
+
+    pub fn get(&self, key: Vec2<i32>) -> Option<&OwnedPlot> { self.plots.get(&key) }
+
+    pub fn get_mut(&mut self, key: Vec2<i32>) -> Option<&mut OwnedPlot> {
+        self.plots.get_mut(&key)
+    }
+
+    /// The owned plot that contains the given position, if any
+    pub fn get_at(&self, wpos: Vec2<i32>) -> Option<&OwnedPlot> {
+        self.plots.values().find(|plot| {
+            Aabr {
+                min: plot.bounds.min.xy(),
+                max: plot.bounds.max.xy(),
+            }
+            .contains_point(wpos)
+        })
+    }
+
+    /// Whether any owned plot overlaps the given area
+    pub fn overlaps(&self, bounds: Aabb<i32>) -> bool {
+        self.plots
+            .values()
+            .any(|plot| plot.bounds.collides_with_aabb(bounds))
+    }
+
+    /// Characters may only own one plot at a time
+    pub fn owned_by(&self, character_id: CharacterId) -> Option<&OwnedPlot> {
+        self.plots.values().find(|plot| plot.owner == character_id)
+    }
+
+    /// Registers the build area of a plot and records its ownership. Fails if
+    /// the plot overlaps one that is already owned, or if the build area name
+    /// is already taken.
+    pub fn claim(
+        &mut self,
+        build_areas: &mut BuildAreas,
+        owner: CharacterId,
+        owner_alias: String,
+        bounds: Aabb<i32>,
+        paid_until: i64,
+        last_active: i64,
+    ) -> Result<&OwnedPlot, String> {
+        if self.overlaps(bounds) {
+            return Err("Part of this plot is already owned by someone else".into());
+        }
+        let key = bounds.min.xy();
+        let build_area = build_areas.insert(Self::build_area_name(key), bounds)?;
+        let plot = OwnedPlot {
+            owner,
+            owner_alias,
+            bounds,
+            paid_until,
+            last_active,
+            build_area,
+        };
+        self.plots.insert(key, plot);
+        Ok(&self.plots[&key])
+    }
+
+    /// Removes a plot and its build area
+    pub fn release(&mut self, build_areas: &mut BuildAreas, key: Vec2<i32>) -> Option<OwnedPlot> {
+        let plot = self.plots.remove(&key)?;
+        let _ = build_areas.remove(&Self::build_area_name(key));
+        Some(plot)
+    }
+}
+
+/// The length of a rent period, in seconds
+pub fn rent_period(settings: &HousingSettings) -> i64 {
+    i64::from(settings.rent_period_days.max(1)) * SECONDS_PER_DAY
+}
+
+/// The length of time after which the plot of an inactive owner is released,
+/// in seconds
+pub fn inactivity_limit(settings: &HousingSettings) -> i64 {
+    i64::from(settings.inactivity_days) * SECONDS_PER_DAY
+}
+
+/// The number of rent periods that are owed for a plot at the given time
+pub fn periods_due(plot: &OwnedPlot, settings: &HousingSettings, now: i64) -> i64 {
+    if now < plot.paid_until {
+        0
+    } else {
+        (now - plot.paid_until) / rent_period(settings) + 1
+    }
+}
+
+/// Allows an entity to build in the build area of its plot
+pub fn grant_build_permission(ecs: &specs::World, entity: EcsEntity, build_area: Id<Aabb<i32>>) {
+    let mut can_build = ecs.write_storage::<CanBuild>();
+    if let Ok(entry) = can_build.entry(entity) {
+        let mut can_build = entry.or_insert(CanBuild {
+            enabled: false,
+            build_areas: HashSet::new(),
+        });
+        can_build.build_areas.insert(build_area);
+    }
+}
+
+/// Stops an entity from building in the build area of a plot it no longer owns
+pub fn revoke_build_permission(ecs: &specs::World, entity: EcsEntity, build_area: Id<Aabb<i32>>) {
+    if let Some(mut can_build) = ecs.write_storage::<CanBuild>().get_mut(entity) {
+        can_build.build_areas.retain(|&id| id != build_area);
+    }
+}
+
+/// Finds the plot for sale at the given position, if there is one
+#[cfg(feature = "worldgen")]
+pub fn find_plot_for_sale(
+    world: &world::World,
+    index: &world::IndexOwned,
+    settings: &HousingSettings,
+    wpos: Vec2<i32>,
+) -> Option<PlotForSale> {
+    use world::site::SiteKind;
+
+    index
+        .sites
+        .values()
+        .filter_map(|site| match &site.kind {
+            SiteKind::Refactor(site2) => Some(site2),
+            _ => None,
+        })
+        .find_map(|site2| site2.housing_plot_at(wpos))
+        .and_then(|plot| {
+            let alt = match plot.alt {
+                Some(alt) => alt,
+                None => world.sim().get_alt_approx(plot.bounds.center())? as i32,
+            };
+            Some(PlotForSale {
+                bounds: Aabb {
+                    min: plot.bounds.min.with_z(alt - settings.build_depth),
+                    max: plot.bounds.max.with_z(alt + settings.build_height),
+                },
+                is_house: plot.is_house,
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(min: Vec2<i32>, max: Vec2<i32>) -> Aabb<i32> {
+        Aabb {
+            min: min.with_z(0),
+            max: max.with_z(32),
+        }
+    }
+
+    #[test]
+    fn test_claim() {
+        let mut plots = HousingPlots::default();
+        let mut build_areas = BuildAreas::default();
+        let house = bounds(Vec2::new(0, 0), Vec2::new(11, 11));
+
+        let plot = plots
+            .claim(&mut build_areas, 1, "Alice".into(), house, 100, 0)
+            .unwrap();
+        assert_eq!(plot.key(), Vec2::new(0, 0));
+        assert!(build_areas
+            .area_names()
+            .contains_key(&HousingPlots::build_area_name(Vec2::new(0, 0))));
+        assert_eq!(plots.owned_by(1).map(OwnedPlot::key), Some(Vec2::new(0, 0)));
+        assert!(plots.owned_by(2).is_none());
+        assert!(plots.get_at(Vec2::new(11, 5)).is_some());
+        assert!(plots.get_at(Vec2::new(12, 5)).is_none());
+
+        // Plots can't be claimed twice, or overlap one that is owned
+        assert!(plots
+            .claim(&mut build_areas, 2, "Bob".into(), house, 100, 0)
+            .is_err());
+        assert!(plots
+            .claim(
+                &mut build_areas,
+                2,
+                "Bob".into(),
+                bounds(Vec2::new(6, 6), Vec2::new(17, 17)),
+                100,
+                0
+            )
+            .is_err());
+        assert!(plots
+            .claim(
+                &mut build_areas,
+                2,
+                "Bob".into(),
+                bounds(Vec2::new(12, 0), Vec2::new(17, 5)),
+                100,
+                0
+            )
+            .is_ok());
+
+        // Released plots can be bought again
+        assert!(plots.release(&mut build_areas, Vec2::new(0, 0)).is_some());
+        assert!(plots.get_at(Vec2::new(5, 5)).is_none());
+        assert!(plots
+            .claim(&mut build_areas, 2, "Bob".into(), house, 100, 0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rent() {
+        let settings = HousingSettings::default();
+        let mut plots = HousingPlots::default();
+        let mut build_areas = BuildAreas::default();
+        let paid_until = rent_period(&settings);
+        let plot = plots
+            .claim(
+                &mut build_areas,
+                1,
+                "Alice".into(),
+                bounds(Vec2::zero(), Vec2::broadcast(5)),
+                paid_until,
+                0,
+            )
+            .unwrap();
+
+        assert_eq!(periods_due(plot, &settings, 0), 0);
+        assert_eq!(periods_due(plot, &settings, paid_until - 1), 0);
+        assert_eq!(periods_due(plot, &settings, paid_until), 1);
+        assert_eq!(
+            periods_due(plot, &settings, paid_until + rent_period(&settings) - 1),
+            1
+        );
+        assert_eq!(
+            periods_due(plot, &settings, paid_until + rent_period(&settings)),
+            2
+        );
+
+        // Rent periods are never shorter than a day
+        let settings = HousingSettings {
+            rent_period_days: 0,
+            ..settings
+        };
+        assert_eq!(rent_period(&settings), SECONDS_PER_DAY);
+    }
+}
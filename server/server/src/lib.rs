@@ -18,25 +18,38 @@ mod character_creator;
 pub mod chunk_generator;
 pub mod client;
 pub mod cmd;
+pub mod combat_sim;
+mod coins;
 pub mod connection_handler;
 mod data_dir;
 pub mod error;
 pub mod events;
+pub mod farming;
+pub mod guild;
+pub mod housing;
 pub mod input;
 pub mod location;
 pub mod login_provider;
 pub mod metrics;
+pub mod pack;
 pub mod persistence;
 mod pet;
 pub mod presence;
+pub mod pvp;
 pub mod rtsim;
+pub mod schematic;
 pub mod settings;
+pub mod site_state;
 pub mod state_ext;
+pub mod statistics;
 pub mod sys;
 #[cfg(feature = "persistent_world")]
 pub mod terrain_persistence;
 #[cfg(not(feature = "worldgen"))] mod test_world;
+pub mod time;
 pub mod wiring;
+pub mod world_edit;
+pub mod world_event;
 
 // Reexports
 pub use crate::{
@@ -129,7 +142,7 @@ use common::comp::Anchor;
 #[cfg(feature = "worldgen")]
 use world::{
     sim::{FileOpts, WorldOpts, DEFAULT_WORLD_MAP},
-    IndexOwned, World,
+    IndexOwned, World, WorldgenPreset,
 };
 
 #[derive(Copy, Clone)]
@@ -246,6 +259,8 @@ impl Server {
         state.ecs_mut().insert(EventBus::<ServerEvent>::default());
         state.ecs_mut().insert(Vec::<ChunkRequest>::new());
         state.ecs_mut().insert(Locations::default());
+        state.ecs_mut().insert(farming::GrowingCrops::new(data_dir));
+        state.ecs_mut().insert(world_event::WorldEvents::default());
         state.ecs_mut().insert(LoginProvider::new(
             settings.auth_server_address.clone(),
             Arc::clone(&runtime),
@@ -317,9 +332,12 @@ impl Server {
         state.ecs_mut().register::<wiring::WiringElement>();
         state.ecs_mut().register::<wiring::Circuit>();
         state.ecs_mut().register::<comp::Anchor>();
-        state.ecs_mut().register::<comp::Pet>();
         state.ecs_mut().register::<login_provider::PendingLogin>();
         state.ecs_mut().register::<RepositionOnChunkLoad>();
+        state.ecs_mut().register::<statistics::DungeonBoss>();
+        state.ecs_mut().register::<pack::PackMember>();
+        state.ecs_mut().register::<pack::Shaken>();
+        state.ecs_mut().register::<world_edit::WorldEdit>();
 
         //Alias validator
         let banned_words_paths = &settings.banned_words_files;
@@ -352,6 +370,11 @@ impl Server {
         tracing::trace!(?banned_words);
         state.ecs_mut().insert(AliasValidator::new(banned_words));
 
+        #[cfg(feature = "worldgen")]
+        let world_preset = settings
+            .world_preset
+            .as_deref()
+            .map(WorldgenPreset::load_expect_cloned);
         #[cfg(feature = "worldgen")]
         let (world, index) = World::generate(
             settings.world_seed,
@@ -359,11 +382,15 @@ impl Server {
                 seed_elements: true,
                 world_file: if let Some(ref opts) = settings.map_file {
                     opts.clone()
+                } else if let Some(preset) = &world_preset {
+                    // Generate a new map with the size from the preset.
+                    FileOpts::Generate(preset.size.clone())
                 } else {
                     // Load default map from assets.
                     FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into())
                 },
                 calendar: Some(settings.calendar_mode.calendar_now()),
+                preset: world_preset.unwrap_or_default(),
             },
             state.thread_pool(),
         );
@@ -446,6 +473,37 @@ impl Server {
                 .expect("The initial insert should always work.");
         }
 
+        // Restore the build areas of the plots owned by characters
+        {
+            let mut housing_plots = housing::HousingPlots::default();
+            let mut build_areas = state.ecs().write_resource::<BuildAreas>();
+            for plot in
+                persistence::housing::load_housing_plots(&*database_settings.read().unwrap())
+            {
+                if let Err(e) = housing_plots.claim(
+                    &mut build_areas,
+                    plot.owner,
+                    plot.owner_alias,
+                    plot.bounds,
+                    plot.paid_until,
+                    plot.last_active,
+                ) {
+                    warn!("Could not restore the build area of plot {}", e);
+                }
+            }
+            drop(build_areas);
+            state.ecs_mut().insert(housing_plots);
+        }
+
+        let guilds = guild::Guilds::load(
+            persistence::guild::load_guilds(&*database_settings.read().unwrap()),
+            &mut state.ecs().write_resource::<CharacterUpdater>(),
+        );
+        state.ecs_mut().insert(guilds);
+        state
+            .ecs_mut()
+            .insert(pvp::Arenas::new(&settings.pvp.arenas));
+
         // Insert the world into the ECS (todo: Maybe not an Arc?)
         let world = Arc::new(world);
         state.ecs_mut().insert(Arc::clone(&world));
@@ -542,6 +600,18 @@ impl Server {
 
         let connection_handler = ConnectionHandler::new(network, &runtime);
 
+        // Track damage to sites, which rtsim needs to know which sites are abandoned
+        #[cfg(feature = "worldgen")]
+        state.ecs_mut().insert(site_state::SiteStates::new(
+            data_dir,
+            &world,
+            index.as_index_ref(),
+        ));
+        #[cfg(not(feature = "worldgen"))]
+        state
+            .ecs_mut()
+            .insert(site_state::SiteStates::empty(data_dir));
+
         // Initiate real-time world simulation
         #[cfg(feature = "worldgen")]
         rtsim::init(&mut state, &world, index.as_index_ref(), spawn_point);
@@ -666,7 +736,9 @@ impl Server {
         // 1) Build up a list of events for this frame, to be passed to the frontend.
         let mut frontend_events = Vec::new();
 
-        // 2)
+        // 2) Start, progress and settle world events, and repair damaged sites
+        world_event::tick(self);
+        site_state::tick(self);
 
         let before_new_connections = Instant::now();
 
@@ -832,6 +904,10 @@ impl Server {
             .ecs()
             .read_resource::<persistence::character_updater::CharacterUpdater>();
 
+        // Mail responses may need to modify the ECS or make further persistence requests,
+        // so they are handled once the loader and updater are no longer borrowed
+        let mut mail_responses = Vec::new();
+
         // Get character-related database responses and notify the requesting client
         character_loader
             .messages()
@@ -891,6 +967,7 @@ impl Server {
                                 pets,
                                 active_abilities,
                                 map_marker,
+                                statistics,
                             } = character_data;
                             let character_data = (
                                 body,
@@ -901,6 +978,7 @@ impl Server {
                                 pets,
                                 active_abilities,
                                 map_marker,
+                                statistics,
                             );
                             ServerEvent::UpdateCharacterData {
                                 entity: query_result.entity,
@@ -928,11 +1006,18 @@ impl Server {
                         .read_resource::<EventBus<ServerEvent>>()
                         .emit_now(message);
                 },
+                CharacterLoaderResponseKind::Mail(character_id, response) => {
+                    mail_responses.push((query_result.entity, character_id, response));
+                },
             });
 
         drop(character_loader);
         drop(character_updater);
 
+        for (entity, character_id, response) in mail_responses {
+            events::handle_mail_response(self, entity, character_id, response);
+        }
+
         {
             // Check for new chunks; cancel and regenerate all chunks if the asset has been
             // reloaded. Note that all of these assignments are no-ops, so the
@@ -1402,6 +1487,16 @@ impl Drop for Server {
         self.state
             .notify_players(ServerGeneral::Disconnect(DisconnectReason::Shutdown));
 
+        self.state
+            .ecs()
+            .write_resource::<site_state::SiteStates>()
+            .save();
+
+        self.state
+            .ecs()
+            .write_resource::<farming::GrowingCrops>()
+            .save();
+
         #[cfg(feature = "persistent_world")]
         self.state
             .ecs()
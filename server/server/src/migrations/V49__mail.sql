@@ -0,0 +1,27 @@
+-- Creates new mail table
+CREATE TABLE "mail" (
+      "mail_id" INTEGER PRIMARY KEY AUTOINCREMENT,
+      "recipient_character_id" INT NOT NULL,
+      "sender_character_id" INT,
+      "sender_alias" TEXT NOT NULL,
+      "subject" TEXT NOT NULL,
+      "body" TEXT NOT NULL,
+      "attachments" TEXT NOT NULL,
+      "coins" INT NOT NULL,
+      "sent_at" INT NOT NULL,
+      "is_read" INT NOT NULL,
+      FOREIGN KEY("recipient_character_id") REFERENCES "character"("character_id")
+);
+
+CREATE INDEX "mail_recipient_idx" ON "mail" ("recipient_character_id");
+CREATE INDEX "mail_sender_idx" ON "mail" ("sender_character_id", "sent_at");
+
+-- Records every piece of mail sent by a character, so that the daily send limit
+-- isn't reset by the recipients deleting their mail
+CREATE TABLE "mail_sent" (
+      "sender_character_id" INT NOT NULL,
+      "sent_at" INT NOT NULL,
+      FOREIGN KEY("sender_character_id") REFERENCES "character"("character_id")
+);
+
+CREATE INDEX "mail_sent_sender_idx" ON "mail_sent" ("sender_character_id", "sent_at");
@@ -0,0 +1,13 @@
+-- Creates new statistics table
+CREATE TABLE "statistics" (
+      "character_id" INT NOT NULL,
+      "stats" TEXT NOT NULL,
+      "achievements" TEXT NOT NULL,
+      PRIMARY KEY("character_id"),
+      FOREIGN KEY("character_id") REFERENCES "character"("character_id")
+);
+
+-- Inserts empty statistics for everyone
+INSERT INTO statistics
+SELECT c.character_id, '{}', '[]'
+FROM character c
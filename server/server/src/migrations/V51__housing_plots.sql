@@ -0,0 +1,16 @@
+-- Creates new housing_plot table, which holds the town plots owned by characters
+CREATE TABLE "housing_plot" (
+      "min_x" INT NOT NULL,
+      "min_y" INT NOT NULL,
+      "min_z" INT NOT NULL,
+      "max_x" INT NOT NULL,
+      "max_y" INT NOT NULL,
+      "max_z" INT NOT NULL,
+      "owner_character_id" INT NOT NULL,
+      "paid_until" INT NOT NULL,
+      "last_active" INT NOT NULL,
+      PRIMARY KEY("min_x", "min_y"),
+      FOREIGN KEY("owner_character_id") REFERENCES "character"("character_id")
+);
+
+CREATE UNIQUE INDEX "housing_plot_owner_idx" ON "housing_plot" ("owner_character_id");
@@ -0,0 +1,22 @@
+-- Creates new guild and guild_member tables
+CREATE TABLE "guild" (
+      "guild_id" INT NOT NULL,
+      "name" TEXT NOT NULL COLLATE NOCASE,
+      "tag" TEXT NOT NULL COLLATE NOCASE,
+      "bank" TEXT NOT NULL,
+      PRIMARY KEY("guild_id")
+);
+
+CREATE UNIQUE INDEX "guild_name_idx" ON "guild" ("name");
+CREATE UNIQUE INDEX "guild_tag_idx" ON "guild" ("tag");
+
+CREATE TABLE "guild_member" (
+      "character_id" INT NOT NULL,
+      "guild_id" INT NOT NULL,
+      "rank" TEXT NOT NULL,
+      PRIMARY KEY("character_id"),
+      FOREIGN KEY("character_id") REFERENCES "character"("character_id"),
+      FOREIGN KEY("guild_id") REFERENCES "guild"("guild_id")
+);
+
+CREATE INDEX "guild_member_guild_idx" ON "guild_member" ("guild_id");
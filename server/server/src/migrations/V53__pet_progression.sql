@@ -0,0 +1,4 @@
+-- Adds the level, experience and current command of pets
+ALTER TABLE pet ADD COLUMN level INT NOT NULL DEFAULT 0;
+ALTER TABLE pet ADD COLUMN experience INT NOT NULL DEFAULT 0;
+ALTER TABLE pet ADD COLUMN command TEXT NOT NULL DEFAULT 'Follow';
@@ -0,0 +1,116 @@
+//! Packs of creatures which hunt together
+//!
+//! Wild creatures like wolves and gnarlings which roam close to others of
+//! their kind band together into packs, led by the strongest of them. Members
+//! of a pack share their target, melee members surround the target while
+//! ranged members keep their distance, and losing the leader breaks the
+//! morale of the pack for a while. Packs are formed and maintained by
+//! [`crate::sys::pack`], while the agents of the members act on their
+//! [`PackMember`] component.
+
+use crate::sys::agent::tactics::TACTIC_MANIFEST;
+use common::comp::{
+    biped_small, inventory::slot::EquipSlot, quadruped_medium, quadruped_small, Body, Inventory,
+};
+use specs::{Component, Entity as EcsEntity};
+use specs_idvs::IdvStorage;
+
+/// The distance within which creatures form a pack
+pub const PACK_FORM_RADIUS: f32 = 24.0;
+/// Members straying further from their leader leave the pack
+pub const PACK_LEAVE_DIST: f32 = 80.0;
+pub const MAX_PACK_SIZE: usize = 8;
+/// How much more likely members are to flee after their leader died
+pub const LEADER_DEATH_MORALE_LOSS: f32 = 0.5;
+/// How long members stay shaken after their leader died unless they join
+/// another pack, in seconds
+pub const MORALE_RECOVERY_TIME: f64 = 60.0;
+/// How far from the target melee members wait before closing in
+pub const FLANK_DIST: f32 = 8.0;
+/// How close ranged members let their target come
+pub const RANGED_HOLD_DIST: f32 = 12.0;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PackKind {
+    Wolves,
+    Hyenas,
+    Gnarlings,
+    Adlets,
+    Gnolls,
+}
+
+impl PackKind {
+    /// Only creatures of the same kind form packs together
+    pub fn of(body: &Body) -> Option<Self> {
+        match body {
+            Body::QuadrupedMedium(body) => match body.species {
+                quadruped_medium::Species::Wolf
+                | quadruped_medium::Species::Frostfang
+                | quadruped_medium::Species::Darkhound => Some(Self::Wolves),
+                _ => None,
+            },
+            Body::QuadrupedSmall(body) => match body.species {
+                quadruped_small::Species::Hyena => Some(Self::Hyenas),
+                _ => None,
+            },
+            Body::BipedSmall(body) => match body.species {
+                biped_small::Species::Gnarling => Some(Self::Gnarlings),
+                biped_small::Species::Adlet => Some(Self::Adlets),
+                biped_small::Species::Gnoll => Some(Self::Gnolls),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PackRole {
+    Leader,
+    /// Surrounds the target before engaging it
+    Melee,
+    /// Keeps its distance from the target
+    Ranged,
+}
+
+impl PackRole {
+    /// Members fight in melee unless their weapon makes them fight from afar
+    pub fn of_member(inventory: &Inventory) -> Self {
+        let tactic = TACTIC_MANIFEST
+            .read()
+            .tactic_for(inventory.equipped(EquipSlot::ActiveMainhand));
+        if tactic.is_ranged() {
+            Self::Ranged
+        } else {
+            Self::Melee
+        }
+    }
+}
+
+/// Membership of a creature in a pack. The leader is a member of its own pack.
+#[derive(Clone, Debug)]
+pub struct PackMember {
+    pub leader: EcsEntity,
+    pub role: PackRole,
+    /// The angle from the side of the target facing the leader at which the
+    /// member takes position, in radians
+    pub flank_angle: f32,
+}
+
+impl Component for PackMember {
+    type Storage = IdvStorage<Self>;
+}
+
+/// A creature whose pack leader died, which is more likely to flee until it
+/// recovers or follows a new leader
+#[derive(Clone, Debug)]
+pub struct Shaken {
+    /// The health below which the creature fled before it was shaken
+    pub flee_health: f32,
+    /// When the creature recovers its morale
+    pub until: f64,
+}
+
+impl Component for Shaken {
+    type Storage = IdvStorage<Self>;
+}
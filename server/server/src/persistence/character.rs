@@ -15,7 +15,9 @@ use crate::{
             convert_body_from_database, convert_body_to_database_json,
             convert_character_from_database, convert_inventory_from_database_items,
             convert_items_to_database_items, convert_loadout_from_database_items,
+            convert_pet_command_from_database, convert_pet_command_to_database,
             convert_skill_groups_to_database, convert_skill_set_from_database,
+            convert_statistics_from_database, convert_statistics_to_database,
             convert_stats_from_database, convert_waypoint_from_database_json,
             convert_waypoint_to_database_json,
         },
@@ -36,6 +38,10 @@ use tracing::{debug, error, trace, warn};
 /// called--do not assume it's safe to make these public!
 mod conversions;
 
+pub(in crate::persistence) use conversions::{
+    convert_items_from_database_json, convert_items_to_database_json,
+};
+
 pub(crate) type EntityId = i64;
 
 const CHARACTER_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.character";
@@ -195,7 +201,10 @@ pub fn load_character_data(
         SELECT  p.pet_id,
                 p.name,
                 b.variant,
-                b.body_data
+                b.body_data,
+                p.level,
+                p.experience,
+                p.command
         FROM    pet p
         JOIN    body b ON (p.pet_id = b.body_id)
         WHERE   p.character_id = ?1",
@@ -208,6 +217,9 @@ pub fn load_character_data(
                 name: row.get(1)?,
                 body_variant: row.get(2)?,
                 body_data: row.get(3)?,
+                level: row.get(4)?,
+                experience: row.get(5)?,
+                command: row.get(6)?,
             })
         })?
         .filter_map(Result::ok)
@@ -224,6 +236,9 @@ pub fn load_character_data(
             {
                 let pet = comp::Pet::new_from_database(
                     NonZeroU64::new(db_pet.database_id as u64).unwrap(),
+                    convert_pet_command_from_database(&db_pet.command),
+                    db_pet.level as u16,
+                    db_pet.experience as u32,
                 );
                 let pet_stats = comp::Stats::new(db_pet.name.to_owned());
                 Some((pet, pet_body, pet_stats))
@@ -251,6 +266,22 @@ pub fn load_character_data(
         })
     })?;
 
+    let mut stmt = connection.prepare_cached(
+        "
+            SELECT  stats,
+                    achievements
+            FROM    statistics
+            WHERE   character_id = ?1",
+    )?;
+
+    let statistics_data = stmt.query_row(&[char_id], |row| {
+        Ok(CharacterStatistics {
+            character_id: char_id,
+            stats: row.get(0)?,
+            achievements: row.get(1)?,
+        })
+    })?;
+
     Ok(PersistedComponents {
         body: convert_body_from_database(&body_data.variant, &body_data.body_data)?,
         stats: convert_stats_from_database(character_data.alias),
@@ -265,6 +296,7 @@ pub fn load_character_data(
         pets,
         active_abilities: convert_active_abilities_from_database(&ability_set_data),
         map_marker: char_map_marker,
+        statistics: convert_statistics_from_database(&statistics_data)?,
     })
 }
 
@@ -359,6 +391,7 @@ pub fn create_character(
         pets: _,
         active_abilities,
         map_marker,
+        statistics,
     } = persisted_components;
 
     // Fetch new entity IDs for character, inventory and loadout
@@ -487,6 +520,23 @@ pub fn create_character(
     ])?;
     drop(stmt);
 
+    let statistics = convert_statistics_to_database(character_id, &statistics)?;
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        INSERT INTO statistics (character_id,
+                                stats,
+                                achievements)
+        VALUES (?1, ?2, ?3)",
+    )?;
+
+    stmt.execute(&[
+        &character_id as &dyn ToSql,
+        &statistics.stats,
+        &statistics.achievements,
+    ])?;
+    drop(stmt);
+
     // Insert default inventory and loadout item records
     let mut inserts = Vec::new();
 
@@ -633,6 +683,26 @@ pub fn delete_character(
     stmt.execute(&[&char_id])?;
     drop(stmt);
 
+    // Delete statistics
+    let mut stmt = transaction.prepare_cached(
+        "
+        DELETE
+        FROM    statistics
+        WHERE   character_id = ?1",
+    )?;
+
+    stmt.execute(&[&char_id])?;
+    drop(stmt);
+
+    // Delete mail addressed to the character
+    super::mail::delete_character_mail(char_id, transaction)?;
+
+    // Release any plot owned by the character
+    super::housing::delete_character_plots(char_id, transaction)?;
+
+    // Leave the character's guild
+    super::guild::delete_character_guild_membership(char_id, transaction)?;
+
     // Delete character
     let mut stmt = transaction.prepare_cached(
         "
@@ -817,9 +887,9 @@ fn get_pseudo_container_id(
     }
 }
 
-/// Stores new pets in the database, and removes pets from the database that the
-/// player no longer has. Currently there are no actual updates to pet data
-/// since we don't store any updatable data about pets in the database.
+/// Stores new pets in the database, updates the name, level and command of
+/// existing pets, and removes pets from the database that the player no longer
+/// has.
 fn update_pets(
     char_id: CharacterId,
     pets: Vec<PetPersistenceData>,
@@ -848,6 +918,30 @@ fn update_pets(
         }
     }
 
+    for (pet, stats, pet_id) in pets.iter().filter_map(|(pet, _, stats)| {
+        pet.get_database_id()
+            .load()
+            .map(|pet_id| (pet, stats, pet_id.get() as i64))
+    }) {
+        #[rustfmt::skip]
+        let mut stmt = transaction.prepare_cached("
+            UPDATE  pet
+            SET     name = ?1,
+                    level = ?2,
+                    experience = ?3,
+                    command = ?4
+            WHERE   pet_id = ?5",
+        )?;
+
+        stmt.execute(&[
+            &stats.name as &dyn ToSql,
+            &pet.level(),
+            &pet.experience(),
+            &convert_pet_command_to_database(pet.command),
+            &pet_id,
+        ])?;
+    }
+
     for (pet, body, stats) in pets
         .iter()
         .filter(|(pet, _, _)| pet.get_database_id().load().is_none())
@@ -878,11 +972,21 @@ fn update_pets(
             INTO    pet (
                     pet_id,
                     character_id,
-                    name)
-            VALUES  (?1, ?2, ?3)",
+                    name,
+                    level,
+                    experience,
+                    command)
+            VALUES  (?1, ?2, ?3, ?4, ?5, ?6)",
         )?;
 
-        stmt.execute(&[&pet_entity_id as &dyn ToSql, &char_id, &stats.name])?;
+        stmt.execute(&[
+            &pet_entity_id as &dyn ToSql,
+            &char_id,
+            &stats.name,
+            &pet.level(),
+            &pet.experience(),
+            &convert_pet_command_to_database(pet.command),
+        ])?;
         drop(stmt);
 
         pet.get_database_id()
@@ -950,11 +1054,114 @@ pub fn update(
     char_waypoint: Option<comp::Waypoint>,
     active_abilities: comp::ability::ActiveAbilities,
     map_marker: Option<comp::MapMarker>,
+    statistics: comp::Statistics,
     transaction: &mut Transaction,
 ) -> Result<(), PersistenceError> {
     // Run pet persistence
     update_pets(char_id, pets, transaction)?;
 
+    update_inventory(char_id, &inventory, transaction)?;
+
+    let db_skill_groups = convert_skill_groups_to_database(char_id, char_skill_set.skill_groups());
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        REPLACE
+        INTO    skill_group (entity_id,
+                             skill_group_kind,
+                             earned_exp,
+                             spent_exp,
+                             skills,
+                             hash_val)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?;
+
+    for skill_group in db_skill_groups {
+        stmt.execute(&[
+            &skill_group.entity_id as &dyn ToSql,
+            &skill_group.skill_group_kind,
+            &skill_group.earned_exp,
+            &skill_group.spent_exp,
+            &skill_group.skills,
+            &skill_group.hash_val,
+        ])?;
+    }
+
+    let db_waypoint = convert_waypoint_to_database_json(char_waypoint, map_marker);
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        UPDATE  character
+        SET     waypoint = ?1
+        WHERE   character_id = ?2
+    ",
+    )?;
+
+    let waypoint_count = stmt.execute(&[&db_waypoint as &dyn ToSql, &char_id])?;
+
+    if waypoint_count != 1 {
+        return Err(PersistenceError::OtherError(format!(
+            "Error updating character table for char_id {}",
+            char_id
+        )));
+    }
+
+    let ability_sets = convert_active_abilities_to_database(char_id, &active_abilities);
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        UPDATE  ability_set
+        SET     ability_sets = ?1
+        WHERE   entity_id = ?2
+    ",
+    )?;
+
+    let ability_sets_count = stmt.execute(&[
+        &ability_sets.ability_sets as &dyn ToSql,
+        &char_id as &dyn ToSql,
+    ])?;
+
+    if ability_sets_count != 1 {
+        return Err(PersistenceError::OtherError(format!(
+            "Error updating ability_set table for char_id {}",
+            char_id,
+        )));
+    }
+
+    let statistics = convert_statistics_to_database(char_id, &statistics)?;
+
+    let mut stmt = transaction.prepare_cached(
+        "
+        UPDATE  statistics
+        SET     stats = ?1,
+                achievements = ?2
+        WHERE   character_id = ?3
+    ",
+    )?;
+
+    let statistics_count = stmt.execute(&[
+        &statistics.stats as &dyn ToSql,
+        &statistics.achievements,
+        &char_id,
+    ])?;
+
+    if statistics_count != 1 {
+        return Err(PersistenceError::OtherError(format!(
+            "Error updating statistics table for char_id {}",
+            char_id,
+        )));
+    }
+
+    Ok(())
+}
+
+/// Saves the items in the inventory and loadout of a character, deleting any
+/// that are no longer in it
+pub(in crate::persistence) fn update_inventory(
+    char_id: CharacterId,
+    inventory: &comp::Inventory,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
     let pseudo_containers = get_pseudo_containers(transaction, char_id)?;
     let mut upserts = Vec::new();
     // First, get all the entity IDs for any new items, and identify which
@@ -962,7 +1169,7 @@ pub fn update(
     get_new_entity_ids(transaction, |mut next_id| {
         let upserts_ = convert_items_to_database_items(
             pseudo_containers.loadout_container_id,
-            &inventory,
+            inventory,
             pseudo_containers.inventory_container_id,
             &mut next_id,
         );
@@ -1049,71 +1256,5 @@ pub fn update(
         }
     }
 
-    let db_skill_groups = convert_skill_groups_to_database(char_id, char_skill_set.skill_groups());
-
-    let mut stmt = transaction.prepare_cached(
-        "
-        REPLACE
-        INTO    skill_group (entity_id,
-                             skill_group_kind,
-                             earned_exp,
-                             spent_exp,
-                             skills,
-                             hash_val)
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-    )?;
-
-    for skill_group in db_skill_groups {
-        stmt.execute(&[
-            &skill_group.entity_id as &dyn ToSql,
-            &skill_group.skill_group_kind,
-            &skill_group.earned_exp,
-            &skill_group.spent_exp,
-            &skill_group.skills,
-            &skill_group.hash_val,
-        ])?;
-    }
-
-    let db_waypoint = convert_waypoint_to_database_json(char_waypoint, map_marker);
-
-    let mut stmt = transaction.prepare_cached(
-        "
-        UPDATE  character
-        SET     waypoint = ?1
-        WHERE   character_id = ?2
-    ",
-    )?;
-
-    let waypoint_count = stmt.execute(&[&db_waypoint as &dyn ToSql, &char_id])?;
-
-    if waypoint_count != 1 {
-        return Err(PersistenceError::OtherError(format!(
-            "Error updating character table for char_id {}",
-            char_id
-        )));
-    }
-
-    let ability_sets = convert_active_abilities_to_database(char_id, &active_abilities);
-
-    let mut stmt = transaction.prepare_cached(
-        "
-        UPDATE  ability_set
-        SET     ability_sets = ?1
-        WHERE   entity_id = ?2
-    ",
-    )?;
-
-    let ability_sets_count = stmt.execute(&[
-        &ability_sets.ability_sets as &dyn ToSql,
-        &char_id as &dyn ToSql,
-    ])?;
-
-    if ability_sets_count != 1 {
-        return Err(PersistenceError::OtherError(format!(
-            "Error updating ability_set table for char_id {}",
-            char_id,
-        )));
-    }
-
     Ok(())
 }
@@ -1,11 +1,13 @@
 use crate::persistence::{
     character::EntityId,
-    models::{AbilitySets, Character, Item, SkillGroup},
+    models::{AbilitySets, Character, CharacterStatistics, Item, SkillGroup},
 };
 
 use crate::persistence::{
     error::PersistenceError,
-    json_models::{self, CharacterPosition, DatabaseAbilitySet, GenericBody, HumanoidBody},
+    json_models::{
+        self, CharacterPosition, DatabaseAbilitySet, DatabaseJsonItem, GenericBody, HumanoidBody,
+    },
 };
 use common::{
     character::CharacterId,
@@ -621,6 +623,105 @@ pub fn convert_active_abilities_to_database(
     }
 }
 
+pub fn convert_items_to_database_json(
+    items: &[common::comp::Item],
+) -> Result<String, PersistenceError> {
+    fn to_model(item: &common::comp::Item) -> DatabaseJsonItem {
+        DatabaseJsonItem {
+            item_definition_id: item.item_definition_id().to_owned(),
+            amount: item.amount(),
+            components: item.components().iter().map(to_model).collect(),
+        }
+    }
+
+    Ok(serde_json::to_string(
+        &items.iter().map(to_model).collect::<Vec<_>>(),
+    )?)
+}
+
+pub fn convert_items_from_database_json(
+    items: &str,
+) -> Result<Vec<common::comp::Item>, PersistenceError> {
+    fn from_model(model: &DatabaseJsonItem) -> Result<common::comp::Item, PersistenceError> {
+        let mut item = get_item_from_asset(&model.item_definition_id)?;
+        if model.amount > 1 || item.is_stackable() {
+            item.set_amount(model.amount).map_err(|_| {
+                PersistenceError::ConversionError(format!(
+                    "Invalid item amount for {}: {}",
+                    model.item_definition_id, model.amount
+                ))
+            })?;
+        }
+        for component in model.components.iter() {
+            item.add_component(
+                from_model(component)?,
+                &ABILITY_MAP,
+                &MATERIAL_STATS_MANIFEST,
+            );
+        }
+        Ok(item)
+    }
+
+    serde_json::from_str::<Vec<DatabaseJsonItem>>(items)?
+        .iter()
+        .map(from_model)
+        .collect()
+}
+
+/// Attack commands are saved as following the owner, since their target is
+/// gone by the next login
+pub fn convert_pet_command_to_database(command: PetCommand) -> &'static str {
+    match command {
+        PetCommand::Follow | PetCommand::Attack(_) => "Follow",
+        PetCommand::Stay => "Stay",
+        PetCommand::Passive => "Passive",
+    }
+}
+
+pub fn convert_pet_command_from_database(command: &str) -> PetCommand {
+    match command {
+        "Stay" => PetCommand::Stay,
+        "Passive" => PetCommand::Passive,
+        _ => PetCommand::Follow,
+    }
+}
+
+pub fn convert_statistics_to_database(
+    character_id: CharacterId,
+    statistics: &Statistics,
+) -> Result<CharacterStatistics, PersistenceError> {
+    let stats = statistics
+        .stats()
+        .map(|(stat, value)| (stat.to_key(), value))
+        .collect::<HashMap<_, _>>();
+    let achievements = statistics.achievements().collect::<Vec<_>>();
+    Ok(CharacterStatistics {
+        character_id,
+        stats: serde_json::to_string(&stats)?,
+        achievements: serde_json::to_string(&achievements)?,
+    })
+}
+
+pub fn convert_statistics_from_database(
+    statistics: &CharacterStatistics,
+) -> Result<Statistics, PersistenceError> {
+    let stats = serde_json::from_str::<HashMap<String, u64>>(&statistics.stats)?
+        .into_iter()
+        .filter_map(|(key, value)| match Stat::from_key(&key) {
+            Some(stat) => Some((stat, value)),
+            None => {
+                warn!(
+                    "Discarding unknown statistic {} for character_id {}",
+                    key, statistics.character_id
+                );
+                None
+            },
+        })
+        .collect();
+    let achievements = serde_json::from_str(&statistics.achievements)?;
+    Ok(Statistics::new(stats, achievements))
+}
+
 pub fn convert_active_abilities_from_database(
     ability_sets: &AbilitySets,
 ) -> ability::ActiveAbilities {
@@ -1,7 +1,8 @@
 use crate::persistence::{
     character::{load_character_data, load_character_list},
     error::PersistenceError,
-    establish_connection, ConnectionMode, DatabaseSettings, PersistedComponents,
+    establish_connection,
+    mail::MailResponse, ConnectionMode, DatabaseSettings, PersistedComponents,
 };
 use common::character::{CharacterId, CharacterItem};
 use crossbeam_channel::{self, TryIter};
@@ -35,6 +36,7 @@ pub enum CharacterLoaderResponseKind {
     CharacterData(Box<CharacterDataResult>),
     CharacterCreation(CharacterCreationResult),
     CharacterEdit(CharacterEditResult),
+    Mail(CharacterId, MailResponse),
 }
 
 /// Common message format dispatched in response to an update request
@@ -1,10 +1,14 @@
-use crate::comp;
+use crate::{coins::coin_stack, comp};
 use common::character::CharacterId;
 
 use crate::persistence::{
+    character::update_inventory,
     character_loader::{CharacterLoaderResponse, CharacterLoaderResponseKind},
     error::PersistenceError,
-    establish_connection, ConnectionMode, DatabaseSettings, EditableComponents,
+    establish_connection,
+    guild::{self, GuildRequest},
+    housing::{self, HousingRequest},
+    mail::{self, MailError, MailRequest, MailResponse}, ConnectionMode, DatabaseSettings, EditableComponents,
     PersistedComponents, VelorenConnection,
 };
 use crossbeam_channel::TryIter;
@@ -26,6 +30,7 @@ pub type CharacterUpdateData = (
     Option<comp::Waypoint>,
     comp::ability::ActiveAbilities,
     Option<comp::MapMarker>,
+    comp::Statistics,
 );
 
 pub type PetPersistenceData = (comp::Pet, comp::Body, comp::Stats);
@@ -51,6 +56,13 @@ pub enum CharacterUpdaterEvent {
         requesting_player_uuid: String,
         character_id: CharacterId,
     },
+    Mail {
+        entity: Entity,
+        character_id: CharacterId,
+        request: MailRequest,
+    },
+    Housing(HousingRequest),
+    Guild(GuildRequest),
     DisconnectedSuccess,
 }
 
@@ -192,6 +204,32 @@ impl CharacterUpdater {
                                 ),
                             }
                         },
+                        CharacterUpdaterEvent::Mail {
+                            entity,
+                            character_id,
+                            request,
+                        } => {
+                            for response in execute_mail_request(
+                                entity,
+                                character_id,
+                                request,
+                                &mut conn,
+                            ) {
+                                if let Err(e) = response_tx.send(response) {
+                                    error!(?e, "Could not send mail response");
+                                }
+                            }
+                        },
+                        CharacterUpdaterEvent::Housing(request) => {
+                            if let Err(e) = execute_housing_request(request, &mut conn) {
+                                error!(?e, "Error during housing plot update");
+                            }
+                        },
+                        CharacterUpdaterEvent::Guild(request) => {
+                            if let Err(e) = execute_guild_request(request, &mut conn) {
+                                error!(?e, "Error during guild update");
+                            }
+                        },
                         CharacterUpdaterEvent::DisconnectedSuccess => {
                             info!(
                                 "CharacterUpdater received DisconnectedSuccess event, resuming \
@@ -322,6 +360,49 @@ impl CharacterUpdater {
         }
     }
 
+    /// Performs a mail operation on behalf of a character. The result is
+    /// returned as a [`CharacterLoaderResponseKind::Mail`] response.
+    pub fn mail(&mut self, entity: Entity, character_id: CharacterId, request: MailRequest) {
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send(CharacterUpdaterEvent::Mail {
+                entity,
+                character_id,
+                request,
+            })
+        {
+            error!(?e, "Could not send mail request");
+        }
+    }
+
+    /// Saves or releases a housing plot. Plots are kept in memory by the
+    /// server, so no response is sent back.
+    pub fn housing(&mut self, request: HousingRequest) {
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send(CharacterUpdaterEvent::Housing(request))
+        {
+            error!(?e, "Could not send housing request");
+        }
+    }
+
+    /// Persists a change to a guild. Like housing plots, guilds are kept in
+    /// memory by the server so no response is sent back.
+    pub fn guild(&mut self, request: GuildRequest) {
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send(CharacterUpdaterEvent::Guild(request))
+        {
+            error!(?e, "Could not send guild request");
+        }
+    }
+
     /// Updates a collection of characters based on their id and components
     pub fn batch_update<'a>(
         &mut self,
@@ -334,6 +415,7 @@ impl CharacterUpdater {
                 Option<&'a comp::Waypoint>,
                 &'a comp::ability::ActiveAbilities,
                 Option<&'a comp::MapMarker>,
+                &'a comp::Statistics,
             ),
         >,
     ) {
@@ -347,6 +429,7 @@ impl CharacterUpdater {
                     waypoint,
                     active_abilities,
                     map_marker,
+                    statistics,
                 )| {
                     (
                         character_id,
@@ -357,6 +440,7 @@ impl CharacterUpdater {
                             waypoint.cloned(),
                             active_abilities.clone(),
                             map_marker.cloned(),
+                            statistics.clone(),
                         ),
                     )
                 },
@@ -399,7 +483,10 @@ fn execute_batch_update(
     transaction.set_drop_behavior(DropBehavior::Rollback);
     trace!("Transaction started for character batch update");
     updates.into_iter().try_for_each(
-        |(character_id, (stats, inventory, pets, waypoint, active_abilities, map_marker))| {
+        |(
+            character_id,
+            (stats, inventory, pets, waypoint, active_abilities, map_marker, statistics),
+        )| {
             super::character::update(
                 character_id,
                 stats,
@@ -408,6 +495,7 @@ fn execute_batch_update(
                 waypoint,
                 active_abilities,
                 map_marker,
+                statistics,
                 &mut transaction,
             )
         },
@@ -470,6 +558,157 @@ fn execute_character_delete(
     check_response(entity, transaction, result)
 }
 
+/// Executes a mail request in its own transaction. Requests that modify the
+/// mailbox of the requesting character are followed by a response containing
+/// the updated mailbox.
+fn execute_mail_request(
+    entity: Entity,
+    character_id: CharacterId,
+    request: MailRequest,
+    connection: &mut VelorenConnection,
+) -> Vec<CharacterLoaderResponse> {
+    let to_response = |response| CharacterLoaderResponse {
+        entity,
+        result: CharacterLoaderResponseKind::Mail(character_id, response),
+    };
+
+    let result: Result<Vec<MailResponse>, MailError> = (|| {
+        let mut transaction = connection.connection.transaction()?;
+        transaction.set_drop_behavior(DropBehavior::Rollback);
+
+        let (response, refresh_mailbox) = match request {
+            MailRequest::LoadMailbox => (None, true),
+            MailRequest::Send {
+                sender_alias,
+                recipient_alias,
+                subject,
+                body,
+                attachments,
+                coins,
+                limits,
+                inventory,
+            } => match mail::send_mail(
+                character_id,
+                &sender_alias,
+                &recipient_alias,
+                &subject,
+                &body,
+                &attachments,
+                coins,
+                &limits,
+                &mut transaction,
+            ) {
+                Ok(recipient_id) => {
+                    update_inventory(character_id, &inventory, &mut transaction)?;
+                    (
+                        Some(MailResponse::Sent {
+                            recipient_id,
+                            recipient_alias,
+                        }),
+                        false,
+                    )
+                },
+                Err(error) => {
+                    // The postage is refunded along with the attached coins
+                    return Ok(vec![MailResponse::SendFailed {
+                        error,
+                        attachments,
+                        coins: coins.saturating_add(limits.postage),
+                    }]);
+                },
+            },
+            MailRequest::Return {
+                subject,
+                attachments,
+                coins,
+                inventory,
+            } => {
+                if let Some(inventory) = inventory {
+                    update_inventory(character_id, &inventory, &mut transaction)?;
+                }
+                mail::return_mail(character_id, &subject, &attachments, coins, &mut transaction)?;
+                (None, true)
+            },
+            MailRequest::MarkRead(mail_id) => {
+                mail::mark_mail_read(character_id, mail_id, &mut transaction)?;
+                (None, true)
+            },
+            MailRequest::Claim {
+                mail_id,
+                mut inventory,
+            } => {
+                let (attachments, coins) = mail::claim_mail(
+                    character_id,
+                    mail_id,
+                    inventory.free_slots(),
+                    &mut transaction,
+                )?;
+                // Enough free slots were checked for, so everything fits
+                for item in attachments
+                    .iter()
+                    .cloned()
+                    .chain((coins > 0).then(|| coin_stack(coins)))
+                {
+                    let _ = inventory.push(item);
+                }
+                update_inventory(character_id, &inventory, &mut transaction)?;
+                (Some(MailResponse::Claimed { attachments, coins }), true)
+            },
+            MailRequest::Delete(mail_id) => {
+                mail::delete_mail(character_id, mail_id, &mut transaction)?;
+                (None, true)
+            },
+        };
+
+        let mailbox = if refresh_mailbox {
+            Some(mail::load_mailbox(character_id, &transaction)?)
+        } else {
+            None
+        };
+
+        transaction.commit()?;
+
+        Ok(response
+            .into_iter()
+            .chain(mailbox.map(MailResponse::Mailbox))
+            .collect())
+    })();
+
+    match result {
+        Ok(responses) => responses.into_iter().map(to_response).collect(),
+        Err(error) => {
+            if let MailError::Persistence(e) = &error {
+                error!(?e, "Error during mail request for character ID {}", character_id);
+            }
+            vec![to_response(MailResponse::Error(error))]
+        },
+    }
+}
+
+fn execute_housing_request(
+    request: HousingRequest,
+    connection: &mut VelorenConnection,
+) -> Result<(), PersistenceError> {
+    let mut transaction = connection.connection.transaction()?;
+    transaction.set_drop_behavior(DropBehavior::Rollback);
+    housing::execute_housing_request(request, &mut transaction)?;
+    transaction.commit()?;
+
+    Ok(())
+}
+
+fn execute_guild_request(
+    request: GuildRequest,
+    connection: &mut VelorenConnection,
+) -> Result<(), PersistenceError> {
+    let mut transaction = connection.connection.transaction()?;
+    transaction.set_drop_behavior(DropBehavior::Rollback);
+    guild::execute_guild_request(request, &mut transaction)?;
+    transaction.commit()?;
+
+    Ok(())
+}
+
 fn check_response(
     entity: Entity,
     transaction: Transaction,
@@ -0,0 +1,244 @@
+//! Database operations related to guilds
+//!
+//! Guilds are loaded once when the server starts, after which the server keeps
+//! them in memory and sends any changes to the persistence thread via the
+//! [`CharacterUpdater`].
+//!
+//! [`CharacterUpdater`]: super::character_updater::CharacterUpdater
+
+use super::{
+    character::{
+        convert_items_from_database_json, convert_items_to_database_json, update_inventory,
+    },
+    error::PersistenceError,
+    establish_connection, ConnectionMode, DatabaseSettings,
+};
+use common::{
+    character::CharacterId,
+    comp::{Inventory, Item},
+    guild::{GuildId, GuildRank},
+};
+use hashbrown::HashMap;
+use rusqlite::{Connection, ToSql, Transaction, NO_PARAMS};
+use tracing::{error, warn};
+
+/// A guild as it is stored in the database
+pub struct GuildData {
+    pub id: GuildId,
+    pub name: String,
+    pub tag: String,
+    pub bank: Vec<Item>,
+    pub members: Vec<GuildMemberData>,
+}
+
+pub struct GuildMemberData {
+    pub character_id: CharacterId,
+    pub alias: String,
+    pub rank: GuildRank,
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum GuildRequest {
+    Create {
+        id: GuildId,
+        name: String,
+        tag: String,
+    },
+    Delete(GuildId),
+    /// Saves the bank of a guild along with the inventory of the member who
+    /// deposited into or withdrew from it, so that items can't be duplicated
+    /// or lost if the server stops in between
+    SaveBank {
+        guild_id: GuildId,
+        bank: Vec<Item>,
+        character_id: CharacterId,
+        inventory: Inventory,
+    },
+    /// Adds a member to a guild, or changes their rank
+    SetMember {
+        guild_id: GuildId,
+        character_id: CharacterId,
+        rank: GuildRank,
+    },
+    RemoveMember(CharacterId),
+}
+
+/// Loads every guild along with its members. This is executed once during
+/// server startup, after migrations have been run.
+pub fn load_guilds(settings: &DatabaseSettings) -> Vec<GuildData> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+    load(&conn.connection).unwrap_or_else(|e| {
+        error!(?e, "Failed to load guilds");
+        Vec::new()
+    })
+}
+
+fn load(connection: &Connection) -> Result<Vec<GuildData>, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  guild_id,
+                name,
+                tag,
+                bank
+        FROM    guild",
+    )?;
+
+    let rows = stmt
+        .query_map(NO_PARAMS, |row| {
+            let id: GuildId = row.get(0)?;
+            let name: String = row.get(1)?;
+            let tag: String = row.get(2)?;
+            let bank: String = row.get(3)?;
+            Ok((id, name, tag, bank))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut guilds = HashMap::new();
+    for (id, name, tag, bank) in rows {
+        let bank = convert_items_from_database_json(&bank).unwrap_or_else(|e| {
+            warn!(?e, "Failed to load the bank of guild {}, it will be empty", name);
+            Vec::new()
+        });
+        guilds.insert(id, GuildData {
+            id,
+            name,
+            tag,
+            bank,
+            members: Vec::new(),
+        });
+    }
+
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  m.guild_id,
+                m.character_id,
+                c.alias,
+                m.rank
+        FROM    guild_member m
+        JOIN    character c ON c.character_id = m.character_id",
+    )?;
+
+    let members = stmt
+        .query_map(NO_PARAMS, |row| {
+            let guild_id: GuildId = row.get(0)?;
+            let character_id: CharacterId = row.get(1)?;
+            let alias: String = row.get(2)?;
+            let rank: String = row.get(3)?;
+            Ok((guild_id, character_id, alias, rank))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (guild_id, character_id, alias, rank) in members {
+        let rank = GuildRank::from_key(&rank).unwrap_or_else(|| {
+            warn!("Unknown guild rank {} for character ID {}", rank, character_id);
+            GuildRank::Recruit
+        });
+        if let Some(guild) = guilds.get_mut(&guild_id) {
+            guild.members.push(GuildMemberData {
+                character_id,
+                alias,
+                rank,
+            });
+        }
+    }
+
+    Ok(guilds.into_iter().map(|(_, guild)| guild).collect())
+}
+
+pub fn execute_guild_request(
+    request: GuildRequest,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    match request {
+        GuildRequest::Create { id, name, tag } => {
+            #[rustfmt::skip]
+            let mut stmt = transaction.prepare_cached("
+                INSERT INTO guild (guild_id,
+                                   name,
+                                   tag,
+                                   bank)
+                VALUES  (?1, ?2, ?3, '[]')",
+            )?;
+
+            stmt.execute(&[&id as &dyn ToSql, &name, &tag])?;
+        },
+        GuildRequest::Delete(guild_id) => {
+            #[rustfmt::skip]
+            let mut stmt = transaction.prepare_cached("
+                DELETE
+                FROM    guild_member
+                WHERE   guild_id = ?1",
+            )?;
+
+            stmt.execute(&[&guild_id])?;
+            drop(stmt);
+
+            #[rustfmt::skip]
+            let mut stmt = transaction.prepare_cached("
+                DELETE
+                FROM    guild
+                WHERE   guild_id = ?1",
+            )?;
+
+            stmt.execute(&[&guild_id])?;
+        },
+        GuildRequest::SaveBank {
+            guild_id,
+            bank,
+            character_id,
+            inventory,
+        } => {
+            update_inventory(character_id, &inventory, transaction)?;
+
+            let bank = convert_items_to_database_json(&bank)?;
+
+            #[rustfmt::skip]
+            let mut stmt = transaction.prepare_cached("
+                UPDATE  guild
+                SET     bank = ?1
+                WHERE   guild_id = ?2",
+            )?;
+
+            stmt.execute(&[&bank as &dyn ToSql, &guild_id])?;
+        },
+        GuildRequest::SetMember {
+            guild_id,
+            character_id,
+            rank,
+        } => {
+            #[rustfmt::skip]
+            let mut stmt = transaction.prepare_cached("
+                REPLACE
+                INTO    guild_member (character_id,
+                                      guild_id,
+                                      rank)
+                VALUES  (?1, ?2, ?3)",
+            )?;
+
+            stmt.execute(&[&character_id as &dyn ToSql, &guild_id, &rank.to_key()])?;
+        },
+        GuildRequest::RemoveMember(character_id) => {
+            delete_character_guild_membership(character_id, transaction)?;
+        },
+    }
+
+    Ok(())
+}
+
+/// Removes a character that is being deleted from their guild. If they were
+/// the leader, a new leader is chosen when the guilds are next loaded.
+pub fn delete_character_guild_membership(
+    character_id: CharacterId,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        DELETE
+        FROM    guild_member
+        WHERE   character_id = ?1",
+    )?;
+
+    stmt.execute(&[&character_id])?;
+
+    Ok(())
+}
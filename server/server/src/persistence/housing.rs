@@ -0,0 +1,165 @@
+//! Database operations related to the town plots owned by characters
+//!
+//! Plots are loaded once when the server starts, after which the server keeps
+//! them in memory and sends any changes to the persistence thread via the
+//! [`CharacterUpdater`].
+//!
+//! [`CharacterUpdater`]: super::character_updater::CharacterUpdater
+
+use super::{
+    character::update_inventory, error::PersistenceError, establish_connection, ConnectionMode,
+    DatabaseSettings,
+};
+use common::{character::CharacterId, comp::Inventory};
+use rusqlite::{Connection, ToSql, Transaction, NO_PARAMS};
+use tracing::error;
+use vek::*;
+
+/// A plot as it is stored in the database
+#[derive(Clone, Debug)]
+pub struct HousingPlotData {
+    /// The area the owner is allowed to build in
+    pub bounds: Aabb<i32>,
+    pub owner: CharacterId,
+    pub owner_alias: String,
+    /// Unix timestamp (in seconds) until which the rent has been paid
+    pub paid_until: i64,
+    /// Unix timestamp (in seconds) of when the owner was last seen playing
+    pub last_active: i64,
+}
+
+#[allow(clippy::large_enum_variant)]
+pub enum HousingRequest {
+    /// Inserts or updates a plot
+    Save(HousingPlotData),
+    /// Saves a plot that has just been bought or paid for along with the
+    /// inventory of its owner, so that the coins can't be lost or kept if the
+    /// server stops in between
+    Pay {
+        plot: HousingPlotData,
+        inventory: Inventory,
+    },
+    /// Releases the plot with the given minimum corner
+    Delete(Vec2<i32>),
+}
+
+/// Loads every owned plot. This is executed once during server startup, after
+/// migrations have been run.
+pub fn load_housing_plots(settings: &DatabaseSettings) -> Vec<HousingPlotData> {
+    let conn = establish_connection(settings, ConnectionMode::ReadOnly);
+    load_plots(&conn.connection).unwrap_or_else(|e| {
+        error!(?e, "Failed to load housing plots, all plots will be unowned");
+        Vec::new()
+    })
+}
+
+fn load_plots(connection: &Connection) -> Result<Vec<HousingPlotData>, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  h.min_x,
+                h.min_y,
+                h.min_z,
+                h.max_x,
+                h.max_y,
+                h.max_z,
+                h.owner_character_id,
+                c.alias,
+                h.paid_until,
+                h.last_active
+        FROM    housing_plot h
+        JOIN    character c ON c.character_id = h.owner_character_id",
+    )?;
+
+    let plots = stmt
+        .query_map(NO_PARAMS, |row| {
+            Ok(HousingPlotData {
+                bounds: Aabb {
+                    min: Vec3::new(row.get(0)?, row.get(1)?, row.get(2)?),
+                    max: Vec3::new(row.get(3)?, row.get(4)?, row.get(5)?),
+                },
+                owner: row.get(6)?,
+                owner_alias: row.get(7)?,
+                paid_until: row.get(8)?,
+                last_active: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(plots)
+}
+
+pub fn execute_housing_request(
+    request: HousingRequest,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    match request {
+        HousingRequest::Save(plot) => save_plot(&plot, transaction),
+        HousingRequest::Pay { plot, inventory } => {
+            update_inventory(plot.owner, &inventory, transaction)?;
+            save_plot(&plot, transaction)
+        },
+        HousingRequest::Delete(min) => delete_plot(min, transaction),
+    }
+}
+
+fn save_plot(plot: &HousingPlotData, transaction: &mut Transaction) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        REPLACE
+        INTO    housing_plot (min_x,
+                              min_y,
+                              min_z,
+                              max_x,
+                              max_y,
+                              max_z,
+                              owner_character_id,
+                              paid_until,
+                              last_active)
+        VALUES  (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+    )?;
+
+    stmt.execute(&[
+        &plot.bounds.min.x as &dyn ToSql,
+        &plot.bounds.min.y,
+        &plot.bounds.min.z,
+        &plot.bounds.max.x,
+        &plot.bounds.max.y,
+        &plot.bounds.max.z,
+        &plot.owner,
+        &plot.paid_until,
+        &plot.last_active,
+    ])?;
+
+    Ok(())
+}
+
+fn delete_plot(min: Vec2<i32>, transaction: &mut Transaction) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        DELETE
+        FROM    housing_plot
+        WHERE   min_x = ?1
+        AND     min_y = ?2",
+    )?;
+
+    stmt.execute(&[min.x, min.y])?;
+
+    Ok(())
+}
+
+/// Releases any plot owned by a character that is being deleted
+pub fn delete_character_plots(
+    character_id: CharacterId,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        DELETE
+        FROM    housing_plot
+        WHERE   owner_character_id = ?1",
+    )?;
+
+    stmt.execute(&[character_id])?;
+
+    Ok(())
+}
@@ -66,6 +66,17 @@ pub struct CharacterPosition {
     pub map_marker: Option<Vec2<i32>>,
 }
 
+/// An item stored as JSON rather than in the `item` table, such as mail
+/// attachments and guild banks. Modular items store their components
+/// recursively, mirroring how they are stored in the `item` table.
+#[derive(Serialize, Deserialize)]
+pub struct DatabaseJsonItem {
+    pub item_definition_id: String,
+    pub amount: u32,
+    #[serde(default)]
+    pub components: Vec<DatabaseJsonItem>,
+}
+
 pub fn skill_group_to_db_string(skill_group: comp::skillset::SkillGroupKind) -> String {
     use comp::{item::tool::ToolKind, skillset::SkillGroupKind::*};
     let skill_group_string = match skill_group {
@@ -83,6 +94,7 @@ pub fn skill_group_to_db_string(skill_group: comp::skillset::SkillGroupKind) ->
         | Weapon(ToolKind::Blowgun)
         | Weapon(ToolKind::Debug)
         | Weapon(ToolKind::Farming)
+        | Weapon(ToolKind::Fishing)
         | Weapon(ToolKind::Empty)
         | Weapon(ToolKind::Natural) => panic!(
             "Tried to add unsupported skill group to database: {:?}",
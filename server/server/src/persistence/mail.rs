@@ -0,0 +1,705 @@
+//! Database operations related to character mail
+//!
+//! As with the character operations, these are only executed on the
+//! persistence thread. Requests are made via the [`CharacterUpdater`] and the
+//! resulting [`MailResponse`]s are polled and handled each server tick.
+//!
+//! [`CharacterUpdater`]: super::character_updater::CharacterUpdater
+
+use super::{
+    character::{convert_items_from_database_json, convert_items_to_database_json},
+    error::PersistenceError,
+};
+use crate::settings::MailSettings;
+use chrono::Utc;
+use common::{
+    character::CharacterId,
+    comp::{Inventory, Item},
+    mail::{Mail, MailId},
+};
+use rusqlite::{Connection, ToSql, Transaction};
+use std::fmt;
+use tracing::warn;
+
+/// The name used as the sender of mail generated by the server itself, such as
+/// returned attachments
+pub const POSTMASTER_ALIAS: &str = "Postmaster";
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// Mail operations that can be requested for a character.
+///
+/// Requests that move items between a mailbox and an inventory carry the
+/// inventory of the character, which is saved in the same transaction as the
+/// mail so that items can't be duplicated or lost if the server stops in
+/// between.
+#[allow(clippy::large_enum_variant)]
+pub enum MailRequest {
+    LoadMailbox,
+    Send {
+        sender_alias: String,
+        recipient_alias: String,
+        subject: String,
+        body: String,
+        attachments: Vec<Item>,
+        coins: u32,
+        limits: MailSettings,
+        /// The inventory of the sender, with the attachments and coins
+        /// already taken out
+        inventory: Inventory,
+    },
+    /// Delivers attachments back to the requesting character from the
+    /// postmaster, bypassing all limits. Used when attachments could not be
+    /// sent or claimed.
+    Return {
+        subject: String,
+        attachments: Vec<Item>,
+        coins: u32,
+        /// The inventory of the character, holding whatever part of the
+        /// attachments did fit. `None` if the character has logged out.
+        inventory: Option<Inventory>,
+    },
+    MarkRead(MailId),
+    Claim {
+        mail_id: MailId,
+        /// The inventory of the claimant, which the attachments are added to
+        inventory: Inventory,
+    },
+    Delete(MailId),
+}
+
+#[derive(Debug)]
+pub enum MailResponse {
+    Mailbox(Vec<Mail>),
+    Sent {
+        recipient_id: CharacterId,
+        recipient_alias: String,
+    },
+    /// Sending failed, the attachments (and any coins spent, including
+    /// postage) are handed back so that they can be returned to the sender
+    SendFailed {
+        error: MailError,
+        attachments: Vec<Item>,
+        coins: u32,
+    },
+    Claimed {
+        attachments: Vec<Item>,
+        coins: u32,
+    },
+    Error(MailError),
+}
+
+#[derive(Debug)]
+pub enum MailError {
+    RecipientNotFound(String),
+    AmbiguousRecipient(String),
+    CannotMailSelf,
+    RecipientMailboxFull(String),
+    SendLimitReached(u32),
+    NoSuchMail,
+    HasAttachments,
+    NotEnoughSpace(usize),
+    Persistence(PersistenceError),
+}
+
+impl fmt::Display for MailError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RecipientNotFound(alias) => write!(f, "There is no character named {}", alias),
+            Self::AmbiguousRecipient(alias) => write!(
+                f,
+                "More than one character is named {}, mail cannot be delivered",
+                alias
+            ),
+            Self::CannotMailSelf => write!(f, "You cannot send mail to yourself"),
+            Self::RecipientMailboxFull(alias) => write!(f, "The mailbox of {} is full", alias),
+            Self::SendLimitReached(limit) => write!(
+                f,
+                "You can only send {} pieces of mail per day",
+                limit
+            ),
+            Self::NoSuchMail => write!(f, "That mail no longer exists"),
+            Self::HasAttachments => write!(f, "Claim the attachments before deleting this mail"),
+            Self::NotEnoughSpace(needed) => write!(
+                f,
+                "You need {} free inventory slots to claim these attachments",
+                needed
+            ),
+            Self::Persistence(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl From<PersistenceError> for MailError {
+    fn from(error: PersistenceError) -> Self { Self::Persistence(error) }
+}
+
+impl From<rusqlite::Error> for MailError {
+    fn from(error: rusqlite::Error) -> Self { Self::Persistence(error.into()) }
+}
+
+/// Loads all mail addressed to a character, newest first
+pub fn load_mailbox(
+    character_id: CharacterId,
+    connection: &Connection,
+) -> Result<Vec<Mail>, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = connection.prepare_cached("
+        SELECT  mail_id,
+                sender_alias,
+                subject,
+                body,
+                attachments,
+                coins,
+                sent_at,
+                is_read
+        FROM    mail
+        WHERE   recipient_character_id = ?1
+        ORDER BY sent_at DESC, mail_id DESC",
+    )?;
+
+    let mailbox = stmt
+        .query_map(&[character_id], |row| {
+            let mail_id: MailId = row.get(0)?;
+            let attachments: String = row.get(4)?;
+            let coins: i64 = row.get(5)?;
+            let is_read: i64 = row.get(7)?;
+            Ok(Mail {
+                id: mail_id,
+                sender: row.get(1)?,
+                subject: row.get(2)?,
+                body: row.get(3)?,
+                attachments: convert_items_from_database_json(&attachments).unwrap_or_else(|e| {
+                    // The attachments are left untouched in the database, claiming will
+                    // fail with the same error rather than losing them
+                    warn!(?e, "Failed to load attachments for mail_id {}", mail_id);
+                    Vec::new()
+                }),
+                coins: coins.clamp(0, i64::from(u32::MAX)) as u32,
+                sent_at: row.get(6)?,
+                read: is_read != 0,
+            })
+        })?
+        .filter_map(Result::ok)
+        .collect::<Vec<Mail>>();
+
+    Ok(mailbox)
+}
+
+/// Sends mail from one character to another, enforcing the limits configured
+/// for the server. Returns the character ID of the recipient.
+#[allow(clippy::too_many_arguments)]
+pub fn send_mail(
+    sender_id: CharacterId,
+    sender_alias: &str,
+    recipient_alias: &str,
+    subject: &str,
+    body: &str,
+    attachments: &[Item],
+    coins: u32,
+    limits: &MailSettings,
+    transaction: &mut Transaction,
+) -> Result<CharacterId, MailError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        SELECT  character_id
+        FROM    character
+        WHERE   alias = ?1 COLLATE NOCASE
+        LIMIT   2",
+    )?;
+
+    #[allow(clippy::needless_question_mark)]
+    let recipients = stmt
+        .query_map(&[recipient_alias], |row| Ok(row.get(0)?))?
+        .collect::<Result<Vec<CharacterId>, _>>()?;
+    drop(stmt);
+
+    let recipient_id = match recipients.as_slice() {
+        [] => return Err(MailError::RecipientNotFound(recipient_alias.to_owned())),
+        [recipient_id] => *recipient_id,
+        _ => return Err(MailError::AmbiguousRecipient(recipient_alias.to_owned())),
+    };
+
+    if recipient_id == sender_id {
+        return Err(MailError::CannotMailSelf);
+    }
+
+    let now = Utc::now().timestamp();
+
+    // Sends are counted from their own log rather than the mail itself, which
+    // the recipients are free to delete
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        SELECT  COUNT(1)
+        FROM    mail_sent
+        WHERE   sender_character_id = ?1
+        AND     sent_at > ?2",
+    )?;
+
+    #[allow(clippy::needless_question_mark)]
+    let sent_today: i64 = stmt.query_row(
+        &[&sender_id as &dyn ToSql, &(now - SECONDS_PER_DAY)],
+        |row| Ok(row.get(0)?),
+    )?;
+    drop(stmt);
+
+    if sent_today >= i64::from(limits.max_sent_per_day) {
+        return Err(MailError::SendLimitReached(limits.max_sent_per_day));
+    }
+
+    if mailbox_size(recipient_id, transaction)? >= i64::from(limits.max_mailbox_size) {
+        return Err(MailError::RecipientMailboxFull(recipient_alias.to_owned()));
+    }
+
+    insert_mail(
+        recipient_id,
+        Some(sender_id),
+        sender_alias,
+        subject,
+        body,
+        attachments,
+        coins,
+        now,
+        transaction,
+    )?;
+
+    log_sent_mail(sender_id, now, transaction)?;
+
+    Ok(recipient_id)
+}
+
+/// Records a piece of mail being sent towards the daily limit of the sender,
+/// forgetting sends that no longer count towards it
+fn log_sent_mail(
+    sender_id: CharacterId,
+    sent_at: i64,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        DELETE
+        FROM    mail_sent
+        WHERE   sender_character_id = ?1
+        AND     sent_at <= ?2",
+    )?;
+
+    stmt.execute(&[&sender_id as &dyn ToSql, &(sent_at - SECONDS_PER_DAY)])?;
+    drop(stmt);
+
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        INSERT
+        INTO    mail_sent (
+                sender_character_id,
+                sent_at)
+        VALUES  (?1, ?2)",
+    )?;
+
+    stmt.execute(&[&sender_id as &dyn ToSql, &sent_at])?;
+
+    Ok(())
+}
+
+/// Delivers mail from the postmaster to a character without checking any
+/// limits
+pub fn return_mail(
+    recipient_id: CharacterId,
+    subject: &str,
+    attachments: &[Item],
+    coins: u32,
+    transaction: &mut Transaction,
+) -> Result<(), MailError> {
+    insert_mail(
+        recipient_id,
+        None,
+        POSTMASTER_ALIAS,
+        subject,
+        "",
+        attachments,
+        coins,
+        Utc::now().timestamp(),
+        transaction,
+    )
+}
+
+pub fn mark_mail_read(
+    character_id: CharacterId,
+    mail_id: MailId,
+    transaction: &mut Transaction,
+) -> Result<(), MailError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        UPDATE  mail
+        SET     is_read = 1
+        WHERE   mail_id = ?1
+        AND     recipient_character_id = ?2",
+    )?;
+
+    match stmt.execute(&[&mail_id as &dyn ToSql, &character_id])? {
+        1 => Ok(()),
+        _ => Err(MailError::NoSuchMail),
+    }
+}
+
+/// Removes the attachments from a piece of mail and returns them so that they
+/// can be given to the claimant
+pub fn claim_mail(
+    character_id: CharacterId,
+    mail_id: MailId,
+    free_slots: usize,
+    transaction: &mut Transaction,
+) -> Result<(Vec<Item>, u32), MailError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        SELECT  attachments,
+                coins
+        FROM    mail
+        WHERE   mail_id = ?1
+        AND     recipient_character_id = ?2",
+    )?;
+
+    let (attachments, coins) = stmt
+        .query_row(&[&mail_id as &dyn ToSql, &character_id], |row| {
+            let attachments: String = row.get(0)?;
+            let coins: i64 = row.get(1)?;
+            Ok((attachments, coins))
+        })
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => MailError::NoSuchMail,
+            e => e.into(),
+        })?;
+    drop(stmt);
+
+    let attachments = convert_items_from_database_json(&attachments)?;
+    let coins = coins.clamp(0, i64::from(u32::MAX)) as u32;
+
+    // Coins may need a slot of their own if there is no existing stack to merge
+    // into, so they are counted conservatively
+    let needed_slots = attachments.len() + usize::from(coins > 0);
+    if needed_slots > free_slots {
+        return Err(MailError::NotEnoughSpace(needed_slots));
+    }
+
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        UPDATE  mail
+        SET     attachments = '[]',
+                coins = 0,
+                is_read = 1
+        WHERE   mail_id = ?1",
+    )?;
+
+    stmt.execute(&[&mail_id])?;
+
+    Ok((attachments, coins))
+}
+
+pub fn delete_mail(
+    character_id: CharacterId,
+    mail_id: MailId,
+    transaction: &mut Transaction,
+) -> Result<(), MailError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        SELECT  attachments != '[]' OR coins > 0
+        FROM    mail
+        WHERE   mail_id = ?1
+        AND     recipient_character_id = ?2",
+    )?;
+
+    let has_attachments: bool = stmt
+        .query_row(&[&mail_id as &dyn ToSql, &character_id], |row| row.get(0))
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => MailError::NoSuchMail,
+            e => e.into(),
+        })?;
+    drop(stmt);
+
+    if has_attachments {
+        return Err(MailError::HasAttachments);
+    }
+
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        DELETE
+        FROM    mail
+        WHERE   mail_id = ?1",
+    )?;
+
+    stmt.execute(&[&mail_id])?;
+
+    Ok(())
+}
+
+/// Deletes all mail addressed to a character along with its log of sent mail,
+/// used when the character itself is deleted
+pub fn delete_character_mail(
+    character_id: CharacterId,
+    transaction: &mut Transaction,
+) -> Result<(), PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        DELETE
+        FROM    mail
+        WHERE   recipient_character_id = ?1",
+    )?;
+
+    stmt.execute(&[&character_id])?;
+    drop(stmt);
+
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        DELETE
+        FROM    mail_sent
+        WHERE   sender_character_id = ?1",
+    )?;
+
+    stmt.execute(&[&character_id])?;
+
+    Ok(())
+}
+
+fn mailbox_size(
+    character_id: CharacterId,
+    transaction: &mut Transaction,
+) -> Result<i64, PersistenceError> {
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        SELECT  COUNT(1)
+        FROM    mail
+        WHERE   recipient_character_id = ?1",
+    )?;
+
+    #[allow(clippy::needless_question_mark)]
+    let count = stmt.query_row(&[&character_id], |row| Ok(row.get(0)?))?;
+
+    Ok(count)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_mail(
+    recipient_id: CharacterId,
+    sender_id: Option<CharacterId>,
+    sender_alias: &str,
+    subject: &str,
+    body: &str,
+    attachments: &[Item],
+    coins: u32,
+    sent_at: i64,
+    transaction: &mut Transaction,
+) -> Result<(), MailError> {
+    let attachments = convert_items_to_database_json(attachments)?;
+
+    #[rustfmt::skip]
+    let mut stmt = transaction.prepare_cached("
+        INSERT
+        INTO    mail (
+                recipient_character_id,
+                sender_character_id,
+                sender_alias,
+                subject,
+                body,
+                attachments,
+                coins,
+                sent_at,
+                is_read)
+        VALUES  (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 0)",
+    )?;
+
+    stmt.execute(&[
+        &recipient_id as &dyn ToSql,
+        &sender_id,
+        &sender_alias,
+        &subject,
+        &body,
+        &attachments,
+        &i64::from(coins),
+        &sent_at,
+    ])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: CharacterId = 1;
+    const BOB: CharacterId = 2;
+
+    fn test_connection() -> Connection {
+        let mut connection = Connection::open_in_memory().unwrap();
+        super::super::embedded::migrations::runner()
+            .run(&mut connection)
+            .unwrap();
+        // Characters are inserted without the bodies and items they'd normally
+        // have
+        connection
+            .pragma_update(None, "foreign_keys", &"OFF")
+            .unwrap();
+        for (character_id, alias) in [(ALICE, "Alice"), (BOB, "Bob")] {
+            connection
+                .execute(
+                    "INSERT INTO character (character_id, player_uuid, alias) VALUES (?1, '', ?2)",
+                    &[&character_id as &dyn ToSql, &alias],
+                )
+                .unwrap();
+        }
+        connection
+    }
+
+    fn send(
+        limits: &MailSettings,
+        transaction: &mut Transaction,
+    ) -> Result<CharacterId, MailError> {
+        send_mail(
+            ALICE,
+            "Alice",
+            "bob",
+            "Hello",
+            "How are you?",
+            &[],
+            10,
+            limits,
+            transaction,
+        )
+    }
+
+    #[test]
+    fn test_delivery() {
+        let mut connection = test_connection();
+        let mut transaction = connection.transaction().unwrap();
+        let limits = MailSettings::default();
+
+        assert_eq!(send(&limits, &mut transaction).unwrap(), BOB);
+        assert!(matches!(
+            send_mail(
+                ALICE,
+                "Alice",
+                "Alice",
+                "",
+                "",
+                &[],
+                0,
+                &limits,
+                &mut transaction
+            ),
+            Err(MailError::CannotMailSelf)
+        ));
+        assert!(matches!(
+            send_mail(
+                ALICE,
+                "Alice",
+                "Carol",
+                "",
+                "",
+                &[],
+                0,
+                &limits,
+                &mut transaction
+            ),
+            Err(MailError::RecipientNotFound(_))
+        ));
+
+        let mailbox = load_mailbox(BOB, &transaction).unwrap();
+        assert_eq!(mailbox.len(), 1);
+        assert_eq!(mailbox[0].sender, "Alice");
+        assert_eq!(mailbox[0].coins, 10);
+        assert!(!mailbox[0].read);
+        assert!(load_mailbox(ALICE, &transaction).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_claim_and_delete() {
+        let mut connection = test_connection();
+        let mut transaction = connection.transaction().unwrap();
+        send(&MailSettings::default(), &mut transaction).unwrap();
+        let mail_id = load_mailbox(BOB, &transaction).unwrap()[0].id;
+
+        // Mail can only be touched by its recipient
+        assert!(matches!(
+            delete_mail(ALICE, mail_id, &mut transaction),
+            Err(MailError::NoSuchMail)
+        ));
+        assert!(matches!(
+            delete_mail(BOB, mail_id, &mut transaction),
+            Err(MailError::HasAttachments)
+        ));
+        assert!(matches!(
+            claim_mail(BOB, mail_id, 0, &mut transaction),
+            Err(MailError::NotEnoughSpace(1))
+        ));
+        let (attachments, coins) = claim_mail(BOB, mail_id, 1, &mut transaction).unwrap();
+        assert!(attachments.is_empty());
+        assert_eq!(coins, 10);
+        assert!(load_mailbox(BOB, &transaction).unwrap()[0].read);
+
+        delete_mail(BOB, mail_id, &mut transaction).unwrap();
+        assert!(load_mailbox(BOB, &transaction).unwrap().is_empty());
+        assert!(matches!(
+            delete_mail(BOB, mail_id, &mut transaction),
+            Err(MailError::NoSuchMail)
+        ));
+    }
+
+    #[test]
+    fn test_send_limit() {
+        let mut connection = test_connection();
+        let mut transaction = connection.transaction().unwrap();
+        let limits = MailSettings {
+            max_sent_per_day: 2,
+            max_mailbox_size: 10,
+            postage: 0,
+        };
+
+        send(&limits, &mut transaction).unwrap();
+        send(&limits, &mut transaction).unwrap();
+        assert!(matches!(
+            send(&limits, &mut transaction),
+            Err(MailError::SendLimitReached(2))
+        ));
+
+        // The recipient throwing the mail away doesn't let more be sent
+        for mail in load_mailbox(BOB, &transaction).unwrap() {
+            claim_mail(BOB, mail.id, 1, &mut transaction).unwrap();
+            delete_mail(BOB, mail.id, &mut transaction).unwrap();
+        }
+        assert!(matches!(
+            send(&limits, &mut transaction),
+            Err(MailError::SendLimitReached(2))
+        ));
+
+        // Sends from more than a day ago no longer count
+        transaction
+            .execute(
+                "UPDATE mail_sent SET sent_at = sent_at - ?1",
+                &[&SECONDS_PER_DAY],
+            )
+            .unwrap();
+        send(&limits, &mut transaction).unwrap();
+    }
+
+    #[test]
+    fn test_mailbox_limit() {
+        let mut connection = test_connection();
+        let mut transaction = connection.transaction().unwrap();
+        let limits = MailSettings {
+            max_sent_per_day: 10,
+            max_mailbox_size: 1,
+            postage: 0,
+        };
+
+        send(&limits, &mut transaction).unwrap();
+        assert!(matches!(
+            send(&limits, &mut transaction),
+            Err(MailError::RecipientMailboxFull(_))
+        ));
+
+        // Mail from the postmaster is always delivered
+        return_mail(BOB, "Returned", &[], 5, &mut transaction).unwrap();
+        assert_eq!(load_mailbox(BOB, &transaction).unwrap().len(), 2);
+
+        delete_character_mail(BOB, &mut transaction).unwrap();
+        assert!(load_mailbox(BOB, &transaction).unwrap().is_empty());
+    }
+}
@@ -5,7 +5,10 @@ pub mod character_loader;
 pub mod character_updater;
 mod diesel_to_rusqlite;
 pub mod error;
+pub mod guild;
+pub mod housing;
 mod json_models;
+pub mod mail;
 mod models;
 
 use crate::persistence::character_updater::PetPersistenceData;
@@ -32,6 +35,7 @@ pub struct PersistedComponents {
     pub pets: Vec<PetPersistenceData>,
     pub active_abilities: comp::ActiveAbilities,
     pub map_marker: Option<comp::MapMarker>,
+    pub statistics: comp::Statistics,
 }
 
 pub type EditableComponents = (comp::Body,);
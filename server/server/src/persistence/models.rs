@@ -34,9 +34,18 @@ pub struct Pet {
     pub name: String,
     pub body_variant: String,
     pub body_data: String,
+    pub level: i64,
+    pub experience: i64,
+    pub command: String,
 }
 
 pub struct AbilitySets {
     pub entity_id: i64,
     pub ability_sets: String,
 }
+
+pub struct CharacterStatistics {
+    pub character_id: i64,
+    pub stats: String,
+    pub achievements: String,
+}
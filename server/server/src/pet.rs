@@ -1,6 +1,14 @@
-use crate::{client::Client, events::update_map_markers};
+use crate::{
+    alias_validator::{AliasValidator, ValidatorError},
+    client::Client,
+    events::update_map_markers,
+};
 use common::{
-    comp::{self, anchor::Anchor, group::GroupManager, Agent, Alignment, Pet},
+    character::MAX_NAME_LENGTH,
+    comp::{
+        self, anchor::Anchor, group::GroupManager, Agent, Alignment, ChatType, Pet, PetCommand,
+        Stats,
+    },
     uid::Uid,
 };
 use common_net::msg::ServerGeneral;
@@ -84,3 +92,77 @@ fn tame_pet_internal(ecs: &specs::World, pet_entity: Entity, owner: Entity, pet:
         },
     );
 }
+
+/// Whether the pet belongs to the given owner
+fn is_owned_by(ecs: &specs::World, pet_entity: Entity, owner: Entity) -> bool {
+    let uids = ecs.read_storage::<Uid>();
+    ecs.read_storage::<Pet>().contains(pet_entity)
+        && matches!(
+            (ecs.read_storage::<Alignment>().get(pet_entity), uids.get(owner)),
+            (Some(Alignment::Owned(owner_uid)), Some(uid)) if owner_uid == uid
+        )
+}
+
+/// Tells a pet what to do, on behalf of its owner
+pub fn command_pet(ecs: &specs::World, pet_entity: Entity, owner: Entity, command: PetCommand) {
+    if !is_owned_by(ecs, pet_entity, owner) {
+        warn!("Disallowing command of pet owned by another entity");
+        return;
+    }
+
+    if let PetCommand::Attack(target) = command {
+        // Pets don't turn on their owner or themselves
+        let uids = ecs.read_storage::<Uid>();
+        if [owner, pet_entity]
+            .iter()
+            .any(|entity| uids.get(*entity) == Some(&target))
+        {
+            return;
+        }
+    }
+
+    if let Some(mut pet) = ecs.write_storage::<Pet>().get_mut(pet_entity) {
+        pet.command = command;
+    }
+    // Forget what the pet was doing so that the command is obeyed straight away
+    if let Some(agent) = ecs.write_storage::<Agent>().get_mut(pet_entity) {
+        agent.target = None;
+    }
+}
+
+/// Gives a pet a new name, telling its owner if the name isn't allowed
+pub fn rename_pet(ecs: &specs::World, pet_entity: Entity, owner: Entity, name: &str) {
+    if !is_owned_by(ecs, pet_entity, owner) {
+        warn!("Disallowing renaming of pet owned by another entity");
+        return;
+    }
+
+    let name = name.trim();
+    let error = if name.is_empty() {
+        Some("Pets need a name".to_owned())
+    } else {
+        match ecs.read_resource::<AliasValidator>().validate(name) {
+            Ok(()) => None,
+            Err(ValidatorError::TooLong(..)) => Some(format!(
+                "Pet names can't be longer than {} characters",
+                MAX_NAME_LENGTH
+            )),
+            Err(ValidatorError::Forbidden(..)) => {
+                Some(format!("\"{}\" contains a banned word", name))
+            },
+        }
+    };
+
+    match error {
+        None => {
+            if let Some(mut stats) = ecs.write_storage::<Stats>().get_mut(pet_entity) {
+                stats.name = name.to_owned();
+            }
+        },
+        Some(error) => {
+            if let Some(client) = ecs.read_storage::<Client>().get(owner) {
+                client.send_fallible(ServerGeneral::server_msg(ChatType::CommandError, error));
+            }
+        },
+    }
+}
@@ -0,0 +1,402 @@
+//! Consensual PvP
+//!
+//! Players can challenge each other to a duel through the invite system, after
+//! which they may harm each other regardless of their battle mode until one of
+//! them is defeated, forfeits or the time limit runs out. Arenas are areas
+//! defined by admins in which PvP is always enabled, optionally split into
+//! teams. The engagements themselves are tracked with the [`PvpEngagement`]
+//! component, which is maintained by [`crate::sys::pvp`].
+
+use crate::{client::Client, settings::ArenaSettings};
+use common::{
+    comp::{ChatType, Player, Pos, PvpEngagement, Stats},
+    uid::Uid,
+};
+use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+use hashbrown::HashMap;
+use specs::{join::Join, Entity as EcsEntity, ReadStorage, WorldExt, WriteStorage};
+use vek::*;
+
+#[derive(Clone, Debug, Default)]
+pub struct ArenaScore {
+    pub team: Option<u8>,
+    pub kills: u32,
+    pub deaths: u32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Arena {
+    pub name: String,
+    pub bounds: Aabb<i32>,
+    /// The number of teams, or 0 for a free-for-all
+    pub teams: u8,
+    /// Scores of everyone who fought in the arena, by character name
+    pub scores: HashMap<String, ArenaScore>,
+}
+
+impl Arena {
+    pub fn contains(&self, pos: Vec3<f32>) -> bool {
+        self.bounds.contains_point(pos.map(|e| e.floor() as i32))
+    }
+
+    /// Picks the team with the fewest players for someone entering the arena,
+    /// given the teams of the players already inside
+    pub fn assign_team(&self, present: impl Iterator<Item = Option<u8>>) -> Option<u8> {
+        if self.teams == 0 {
+            return None;
+        }
+        let mut counts = vec![0usize; self.teams as usize];
+        for team in present.flatten() {
+            if let Some(count) = counts.get_mut(team as usize) {
+                *count += 1;
+            }
+        }
+        (0..self.teams).min_by_key(|team| counts[*team as usize])
+    }
+
+    pub fn record_kill(&mut self, killer: (&str, Option<u8>), victim: (&str, Option<u8>)) {
+        let score = self.scores.entry(killer.0.to_owned()).or_default();
+        score.team = killer.1;
+        score.kills += 1;
+        let score = self.scores.entry(victim.0.to_owned()).or_default();
+        score.team = victim.1;
+        score.deaths += 1;
+    }
+
+    pub fn scoreboard(&self) -> String {
+        let mut scores = self.scores.iter().collect::<Vec<_>>();
+        scores.sort_by(|(a_name, a), (b_name, b)| {
+            b.kills
+                .cmp(&a.kills)
+                .then(a.deaths.cmp(&b.deaths))
+                .then(a_name.cmp(b_name))
+        });
+
+        let mut text = format!("Scoreboard of arena {}:", self.name);
+        if scores.is_empty() {
+            text.push_str("\nNo fights yet.");
+        }
+        if self.teams > 0 {
+            for team in 0..self.teams {
+                let kills = scores
+                    .iter()
+                    .filter(|(_, score)| score.team == Some(team))
+                    .map(|(_, score)| score.kills)
+                    .sum::<u32>();
+                text.push_str(&format!("\nTeam {}: {} kills", team + 1, kills));
+            }
+        }
+        for (name, score) in scores {
+            text.push_str(&format!("\n{}", name));
+            if let Some(team) = score.team {
+                text.push_str(&format!(" (team {})", team + 1));
+            }
+            text.push_str(&format!(": {} kills, {} deaths", score.kills, score.deaths));
+        }
+        text
+    }
+}
+
+/// The arenas of the server. These are loaded from the settings on startup;
+/// arenas added or removed with commands only last until the server restarts.
+#[derive(Default)]
+pub struct Arenas(Vec<Arena>);
+
+impl Arenas {
+    pub fn new(settings: &[ArenaSettings]) -> Self {
+        let mut arenas = Self::default();
+        for arena in settings {
+            if !arenas.add(arena.name.clone(), arena.bounds, arena.teams) {
+                tracing::warn!("Ignoring duplicate arena {}", arena.name);
+            }
+        }
+        arenas
+    }
+
+    /// Returns false if there already is an arena with this name
+    pub fn add(&mut self, name: String, bounds: Aabb<i32>, teams: u8) -> bool {
+        if self.get(&name).is_some() {
+            return false;
+        }
+        self.0.push(Arena {
+            name,
+            bounds: bounds.made_valid(),
+            teams,
+            scores: HashMap::new(),
+        });
+        true
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Arena> {
+        let index = self.0.iter().position(|arena| arena.name == name)?;
+        Some(self.0.remove(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Arena> {
+        self.0.iter().find(|arena| arena.name == name)
+    }
+
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Arena> {
+        self.0.iter_mut().find(|arena| arena.name == name)
+    }
+
+    pub fn at(&self, pos: Vec3<f32>) -> Option<&Arena> {
+        self.0.iter().find(|arena| arena.contains(pos))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arena> { self.0.iter() }
+}
+
+fn name_of(ecs: &specs::World, entity: EcsEntity) -> String {
+    ecs.read_storage::<Stats>()
+        .get(entity)
+        .map_or_else(String::new, |stats| stats.name.clone())
+}
+
+/// Checks whether `challenger` may challenge `opponent` to a duel
+pub fn check_duel(
+    ecs: &specs::World,
+    max_distance: f32,
+    challenger: EcsEntity,
+    opponent: EcsEntity,
+) -> Result<(), &'static str> {
+    let players = ecs.read_storage::<Player>();
+    if !players.contains(challenger) || !players.contains(opponent) {
+        return Err("Only players can duel.");
+    }
+    let engagements = ecs.read_storage::<PvpEngagement>();
+    if engagements.contains(challenger) {
+        return Err("You are already fighting.");
+    }
+    if engagements.contains(opponent) {
+        return Err("This player is already fighting.");
+    }
+    let positions = ecs.read_storage::<Pos>();
+    match (positions.get(challenger), positions.get(opponent)) {
+        (Some(a), Some(b)) if a.0.distance_squared(b.0) <= max_distance.powi(2) => Ok(()),
+        _ => Err("This player is too far away to duel."),
+    }
+}
+
+/// Starts a duel between the two players after the challenge was accepted
+pub fn start_duel(
+    ecs: &specs::World,
+    max_distance: f32,
+    challenger: EcsEntity,
+    opponent: EcsEntity,
+) -> Result<(), &'static str> {
+    // Things may have changed since the challenge was sent
+    check_duel(ecs, max_distance, challenger, opponent)?;
+    let uids = ecs.read_storage::<Uid>();
+    let (challenger_uid, opponent_uid) = match (uids.get(challenger), uids.get(opponent)) {
+        (Some(a), Some(b)) => (*a, *b),
+        _ => return Err("Only players can duel."),
+    };
+    let started = *ecs.read_resource::<common::resources::Time>();
+    let mut engagements = ecs.write_storage::<PvpEngagement>();
+    let _ = engagements.insert(challenger, PvpEngagement::Duel {
+        opponent: opponent_uid,
+        started,
+    });
+    let _ = engagements.insert(opponent, PvpEngagement::Duel {
+        opponent: challenger_uid,
+        started,
+    });
+    Ok(())
+}
+
+/// Ends the duel the given duelists are in and tells them how it ended
+pub fn end_duel<'a>(
+    engagements: &mut WriteStorage<'a, PvpEngagement>,
+    clients: &ReadStorage<'a, Client>,
+    duelists: &[EcsEntity],
+    message: &str,
+) {
+    for duelist in duelists {
+        if engagements
+            .get(*duelist)
+            .map_or(false, |e| e.duel_opponent().is_some())
+        {
+            engagements.remove(*duelist);
+        }
+        if let Some(client) = clients.get(*duelist) {
+            client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, message));
+        }
+    }
+}
+
+/// Ends the duel of a player who forfeits it. Returns false if they weren't
+/// dueling.
+pub fn forfeit_duel(ecs: &specs::World, entity: EcsEntity) -> bool {
+    let opponent = match ecs
+        .read_storage::<PvpEngagement>()
+        .get(entity)
+        .and_then(|e| e.duel_opponent())
+    {
+        Some(opponent) => ecs.entity_from_uid(opponent.into()),
+        None => return false,
+    };
+    let message = format!("{} forfeited the duel.", name_of(ecs, entity));
+    end_duel(
+        &mut ecs.write_storage(),
+        &ecs.read_storage(),
+        &std::iter::once(entity).chain(opponent).collect::<Vec<_>>(),
+        &message,
+    );
+    true
+}
+
+/// Settles the duel of a player who leaves the game, in favour of their
+/// opponent
+pub fn handle_player_leave(ecs: &specs::World, entity: EcsEntity) {
+    let opponent = match ecs
+        .read_storage::<PvpEngagement>()
+        .get(entity)
+        .and_then(|e| e.duel_opponent())
+    {
+        Some(opponent) => ecs.entity_from_uid(opponent.into()),
+        None => return,
+    };
+    let message = match opponent {
+        Some(opponent) => format!(
+            "{} won the duel, {} left.",
+            name_of(ecs, opponent),
+            name_of(ecs, entity)
+        ),
+        None => "The duel is over.".to_owned(),
+    };
+    end_duel(
+        &mut ecs.write_storage(),
+        &ecs.read_storage(),
+        &std::iter::once(entity).chain(opponent).collect::<Vec<_>>(),
+        &message,
+    );
+}
+
+/// Settles the duel or arena fight that a player was killed in
+pub fn handle_player_death(ecs: &specs::World, entity: EcsEntity, killer: Option<EcsEntity>) {
+    let engagement = match ecs.read_storage::<PvpEngagement>().get(entity) {
+        Some(engagement) => engagement.clone(),
+        None => return,
+    };
+    match engagement {
+        PvpEngagement::Duel { opponent, .. } => {
+            let opponent = ecs.entity_from_uid(opponent.into());
+            let message = match opponent {
+                Some(opponent) => format!(
+                    "{} won the duel against {}!",
+                    name_of(ecs, opponent),
+                    name_of(ecs, entity)
+                ),
+                None => "The duel is over.".to_owned(),
+            };
+            end_duel(
+                &mut ecs.write_storage(),
+                &ecs.read_storage(),
+                &std::iter::once(entity).chain(opponent).collect::<Vec<_>>(),
+                &message,
+            );
+        },
+        PvpEngagement::Arena { arena, team } => {
+            let killer = match killer.filter(|killer| *killer != entity) {
+                Some(killer) => killer,
+                None => return,
+            };
+            let killer_team = match ecs.read_storage::<PvpEngagement>().get(killer) {
+                Some(PvpEngagement::Arena {
+                    arena: killer_arena,
+                    team,
+                }) if *killer_arena == arena => *team,
+                _ => return,
+            };
+            let (killer_name, victim_name) = (name_of(ecs, killer), name_of(ecs, entity));
+            if let Some(arena) = ecs.write_resource::<Arenas>().get_mut(&arena) {
+                arena.record_kill((&killer_name, killer_team), (&victim_name, team));
+            }
+            let message = format!("[{}] {} defeated {}", arena, killer_name, victim_name);
+            for (client, engagement) in (
+                &ecs.read_storage::<Client>(),
+                &ecs.read_storage::<PvpEngagement>(),
+            )
+                .join()
+            {
+                if engagement.arena() == Some(arena.as_str()) {
+                    client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, &message));
+                }
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arena(teams: u8) -> Arena {
+        Arena {
+            name: "Pit".to_owned(),
+            bounds: Aabb {
+                min: Vec3::zero(),
+                max: Vec3::broadcast(10),
+            },
+            teams,
+            scores: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_assign_team() {
+        assert_eq!(arena(0).assign_team([None, None].into_iter()), None);
+
+        let arena = arena(3);
+        assert_eq!(arena.assign_team(std::iter::empty()), Some(0));
+        assert_eq!(arena.assign_team([Some(0)].into_iter()), Some(1));
+        assert_eq!(
+            arena.assign_team([Some(0), Some(1), Some(0)].into_iter()),
+            Some(2)
+        );
+        assert_eq!(
+            arena.assign_team([Some(2), Some(1), Some(0), Some(2), Some(0)].into_iter()),
+            Some(1)
+        );
+        // Players without a team or on teams that no longer exist are ignored
+        assert_eq!(
+            arena.assign_team([None, Some(7), Some(1), Some(2)].into_iter()),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn test_record_kill() {
+        let mut arena = arena(2);
+        arena.record_kill(("Alice", Some(0)), ("Bob", Some(1)));
+        arena.record_kill(("Alice", Some(0)), ("Bob", Some(1)));
+        arena.record_kill(("Bob", Some(1)), ("Alice", Some(0)));
+
+        let alice = &arena.scores["Alice"];
+        assert_eq!((alice.team, alice.kills, alice.deaths), (Some(0), 2, 1));
+        let bob = &arena.scores["Bob"];
+        assert_eq!((bob.team, bob.kills, bob.deaths), (Some(1), 1, 2));
+    }
+
+    #[test]
+    fn test_scoreboard() {
+        let mut arena = arena(2);
+        assert_eq!(
+            arena.scoreboard(),
+            "Scoreboard of arena Pit:\nNo fights yet.\nTeam 1: 0 kills\nTeam 2: 0 kills"
+        );
+
+        arena.record_kill(("Carol", Some(1)), ("Alice", Some(0)));
+        arena.record_kill(("Bob", Some(0)), ("Carol", Some(1)));
+        arena.record_kill(("Bob", Some(0)), ("Dave", Some(1)));
+        // Players with the most kills come first, then those with the fewest
+        // deaths, then by name
+        assert_eq!(
+            arena.scoreboard(),
+            "Scoreboard of arena Pit:\nTeam 1: 2 kills\nTeam 2: 1 kills\nBob (team 1): 2 kills, \
+             0 deaths\nCarol (team 2): 1 kills, 1 deaths\nAlice (team 1): 0 kills, 1 \
+             deaths\nDave (team 2): 0 kills, 1 deaths"
+        );
+    }
+}
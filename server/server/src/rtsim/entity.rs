@@ -1,7 +1,8 @@
-use super::*;
+use super::{schedule::{self, Schedule}, *};
 use common::{
-    resources::Time,
-    rtsim::{Memory, MemoryItem},
+    calendar::Calendar,
+    resources::{Time, TimeOfDay},
+    rtsim::{Activity, Memory, MemoryItem},
     store::Id,
     terrain::TerrainGrid,
     trade, LoadoutBuilder,
@@ -34,6 +35,24 @@ pub enum RtSimEntityKind {
     Blacksmith,
     Chef,
     Alchemist,
+    Bandit,
+    Builder,
+}
+
+impl RtSimEntityKind {
+    /// Whether entities of this kind live in the town they start in, so that
+    /// the town is abandoned once none of them are left
+    pub fn is_resident(&self) -> bool {
+        matches!(
+            self,
+            Self::Villager
+                | Self::Merchant
+                | Self::Blacksmith
+                | Self::Chef
+                | Self::Alchemist
+                | Self::Builder
+        )
+    }
 }
 
 const BIRD_MEDIUM_ROSTER: &[comp::bird_medium::Species] = &[
@@ -98,7 +117,9 @@ impl Entity {
             | RtSimEntityKind::Chef
             | RtSimEntityKind::Alchemist
             | RtSimEntityKind::Blacksmith
-            | RtSimEntityKind::Merchant => {
+            | RtSimEntityKind::Merchant
+            | RtSimEntityKind::Bandit
+            | RtSimEntityKind::Builder => {
                 let species = *(&comp::humanoid::ALL_SPECIES)
                     .choose(&mut self.rng(PERM_SPECIES))
                     .unwrap();
@@ -162,7 +183,15 @@ impl Entity {
         }
     }
 
-    pub fn tick(&mut self, time: &Time, terrain: &TerrainGrid, world: &World, index: &IndexRef) {
+    pub fn tick(
+        &mut self,
+        time: &Time,
+        time_of_day: &TimeOfDay,
+        calendar: &Calendar,
+        terrain: &TerrainGrid,
+        world: &World,
+        index: &IndexRef,
+    ) {
         self.brain.route = match self.brain.route.clone() {
             Travel::Lost => {
                 match self.get_body() {
@@ -634,6 +663,48 @@ impl Entity {
                     }
                 }
             },
+            Travel::Routine { schedule } => {
+                let activity = Schedule::activity_at(*time_of_day, calendar);
+                let destination = schedule.location_of(activity);
+                let dist_sqrd = destination.map(|e| e as f32).distance_squared(self.pos.xy());
+                // Once there, villagers may move around a bit unless they are in bed. Going
+                // somewhere only ends close to the destination though, so that they don't
+                // stop at the door.
+                let radius = match (activity, &self.controller.travel_to) {
+                    (Activity::Sleep, _) | (_, Some(_)) => 3.0,
+                    _ => 16.0,
+                };
+                if dist_sqrd > radius * radius {
+                    let travel_to_alt = world
+                        .sim()
+                        .get_alt_approx(destination)
+                        .unwrap_or(0.0) as i32;
+                    let travel_to = terrain
+                        .find_space(destination.with_z(travel_to_alt))
+                        .map(|e| e as f32)
+                        + Vec3::new(0.5, 0.5, 0.0);
+
+                    self.controller.travel_to =
+                        Some((travel_to, schedule::destination_name(activity).to_owned()));
+                    self.controller.speed_factor = 0.5;
+                } else {
+                    self.controller.travel_to = None;
+                }
+                self.controller.activity = Some(activity);
+                Travel::Routine { schedule }
+            },
+            Travel::Repair { target } => {
+                if let Some(target) = target {
+                    self.controller.travel_to = Some((
+                        target.map(|e| e as f32) + Vec3::new(0.5, 0.5, 0.0),
+                        "Repairs".to_owned(),
+                    ));
+                    self.controller.speed_factor = 0.5;
+                } else {
+                    self.controller.travel_to = None;
+                }
+                Travel::Repair { target }
+            },
             Travel::Idle => Travel::Idle,
         };
 
@@ -683,6 +754,14 @@ enum Travel {
         raid_complete: bool,
         time_to_move: Option<f64>,
     },
+    // Follow the daily routine of a villager
+    Routine {
+        schedule: Schedule,
+    },
+    // Walk up to damage done to the home site to repair it, or stay put if there is none
+    Repair {
+        target: Option<Vec3<i32>>,
+    },
     // For testing purposes
     Idle,
 }
@@ -726,11 +805,12 @@ impl Brain {
         }
     }
 
-    pub fn villager(home_id: Id<Site>) -> Self {
+    /// Villagers without a schedule stay where they are
+    pub fn villager(home_id: Id<Site>, schedule: Option<Schedule>) -> Self {
         Self {
             begin: Some(home_id),
             tgt: None,
-            route: Travel::Idle,
+            route: schedule.map_or(Travel::Idle, |schedule| Travel::Routine { schedule }),
             last_visited: None,
             memories: Vec::new(),
         }
@@ -746,8 +826,35 @@ impl Brain {
         }
     }
 
+    /// Builders repair the damage done to their home site
+    pub fn builder(home_id: Id<Site>) -> Self {
+        Self {
+            begin: Some(home_id),
+            tgt: None,
+            route: Travel::Repair { target: None },
+            last_visited: None,
+            memories: Vec::new(),
+        }
+    }
+
     pub fn begin_site(&self) -> Option<Id<Site>> { self.begin }
 
+    /// The position of the house that a villager lives in, if they have one
+    pub fn home(&self) -> Option<Vec2<i32>> {
+        match &self.route {
+            Travel::Routine { schedule } => Some(schedule.home),
+            _ => None,
+        }
+    }
+
+    /// Send a builder to the damaged block at the given position, or keep them
+    /// where they are if there is nothing to repair
+    pub fn set_repair_target(&mut self, new_target: Option<Vec3<i32>>) {
+        if let Travel::Repair { target } = &mut self.route {
+            *target = new_target;
+        }
+    }
+
     pub fn add_memory(&mut self, memory: Memory) { self.memories.push(memory); }
 
     pub fn forget_enemy(&mut self, to_forget: &str) {
@@ -824,6 +931,8 @@ fn humanoid_config(kind: RtSimEntityKind, rank: TravelerRank) -> &'static str {
         RtSimEntityKind::Blacksmith => "common.entity.village.blacksmith",
         RtSimEntityKind::Chef => "common.entity.village.chef",
         RtSimEntityKind::Alchemist => "common.entity.village.alchemist",
+        RtSimEntityKind::Bandit => "common.entity.world.bandit",
+        RtSimEntityKind::Builder => "common.entity.village.builder",
     }
 }
 
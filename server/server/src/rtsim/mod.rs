@@ -3,6 +3,7 @@
 mod chunks;
 mod entity;
 mod load_chunks;
+mod schedule;
 mod tick;
 mod unload_chunks;
 
@@ -10,17 +11,23 @@ use self::chunks::Chunks;
 use common::{
     comp,
     rtsim::{Memory, RtSimController, RtSimEntity, RtSimId},
+    store::Id,
     terrain::TerrainChunk,
     vol::RectRasterableVol,
 };
 use common_ecs::{dispatch, System};
 use common_state::State;
+use hashbrown::HashMap;
 use rand::prelude::*;
 use slab::Slab;
 use specs::{DispatcherBuilder, WorldExt};
 use vek::*;
+use world::civ::Site;
 
-pub use self::entity::{Brain, Entity, RtSimEntityKind};
+pub use self::{
+    entity::{Brain, Entity, RtSimEntityKind},
+    schedule::Schedule,
+};
 
 pub struct RtSim {
     tick: u64,
@@ -93,6 +100,38 @@ impl RtSim {
             .get_mut(entity)
             .map(|entity| entity.brain.set_mood(memory));
     }
+
+    /// Whether any villager lives in a house within the given area
+    pub fn is_lived_in(&self, area: Aabr<i32>) -> bool {
+        self.entities.iter().any(|(_, entity)| {
+            entity
+                .brain
+                .home()
+                .map_or(false, |home| area.contains_point(home))
+        })
+    }
+
+    /// The builders of every site, along with the site they look after
+    pub fn builders_mut(&mut self) -> impl Iterator<Item = (Id<Site>, &mut Entity)> + '_ {
+        self.entities
+            .iter_mut()
+            .map(|(_, entity)| entity)
+            .filter(|entity| matches!(entity.kind, RtSimEntityKind::Builder))
+            .filter_map(|entity| Some((entity.brain.begin_site()?, entity)))
+    }
+
+    /// The number of residents still alive in each site
+    pub fn populations(&self) -> HashMap<Id<Site>, usize> {
+        let mut populations = HashMap::new();
+        for (_, entity) in self.entities.iter() {
+            if entity.kind.is_resident() {
+                if let Some(home) = entity.brain.begin_site() {
+                    *populations.entry(home).or_default() += 1;
+                }
+            }
+        }
+        populations
+    }
 }
 
 pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
@@ -104,6 +143,23 @@ pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     ]);
 }
 
+/// The number of builders repairing the damage done to each town
+#[cfg(feature = "worldgen")]
+const TOWN_BUILDERS: usize = 2;
+
+#[cfg(feature = "worldgen")]
+fn builder(site_id: Id<Site>, origin: Vec2<i32>) -> Entity {
+    Entity {
+        is_loaded: false,
+        pos: origin.with_z(0).map(|e| e as f32),
+        seed: thread_rng().gen(),
+        controller: RtSimController::default(),
+        last_time_ticked: 0.0,
+        kind: RtSimEntityKind::Builder,
+        brain: Brain::builder(site_id),
+    }
+}
+
 pub fn init(
     state: &mut State,
     #[cfg(feature = "worldgen")] world: &world::World,
@@ -137,13 +193,17 @@ pub fn init(
                 brain: Default::default(),
             });
         }
+        let site_states = state.ecs().read_resource::<crate::site_state::SiteStates>();
         for (site_id, site) in world
             .civs()
             .sites
             .iter()
-            .filter_map(|(site_id, site)| site.site_tmp.map(|id| (site_id, &index.sites[id])))
+            .filter_map(|(site_id, site)| Some((site_id, site.site_tmp?)))
+            // Nobody lives in or looks after abandoned sites
+            .filter(|(_, id)| !site_states.is_abandoned(id.id()))
+            .map(|(site_id, id)| (site_id, &index.sites[id]))
         {
-            use world::site::SiteKind;
+            use world::{site::SiteKind, site2::PlotKind};
             let spawn_town_id = world
                 .civs()
                 .sites
@@ -213,7 +273,10 @@ pub fn init(
                             controller: RtSimController::default(),
                             last_time_ticked: 0.0,
                             kind: RtSimEntityKind::Villager,
-                            brain: Brain::villager(site_id),
+                            brain: Brain::villager(
+                                site_id,
+                                Schedule::for_villager(site2, &mut thread_rng()),
+                            ),
                         });
                     }
 
@@ -239,6 +302,33 @@ pub fn init(
                             brain: Brain::merchant(site_id),
                         });
                     }
+
+                    for _ in 0..TOWN_BUILDERS {
+                        rtsim.entities.insert(builder(site_id, site.get_origin()));
+                    }
+                },
+                SiteKind::Settlement(_) | SiteKind::Castle(_) => {
+                    rtsim.entities.insert(builder(site_id, site.get_origin()));
+                },
+                SiteKind::BanditCamp(site2) => {
+                    let tents = site2.plots().flat_map(|plot| match plot.kind() {
+                        PlotKind::BanditCamp(camp) => camp.tents().collect(),
+                        _ => Vec::new(),
+                    });
+                    for tent in tents {
+                        for _ in 0..thread_rng().gen_range(1..=3) {
+                            rtsim.entities.insert(Entity {
+                                is_loaded: false,
+                                pos: tent.map(|e| e as f32),
+                                seed: thread_rng().gen(),
+                                controller: RtSimController::default(),
+                                last_time_ticked: 0.0,
+                                kind: RtSimEntityKind::Bandit,
+                                // Bandits stay at their camp
+                                brain: Brain::villager(site_id, None),
+                            });
+                        }
+                    }
                 },
                 _ => {},
             }
@@ -0,0 +1,113 @@
+//! Daily routines of villagers
+//!
+//! Villagers sleep in a house of their town, go to work in a workshop during
+//! the day and meet at the plaza around noon and in the evening. Which part of
+//! the routine they are in only depends on the time of day, so the routine
+//! carries on while the villager isn't loaded.
+
+use common::{
+    calendar::{Calendar, CalendarEvent},
+    resources::TimeOfDay,
+    rtsim::Activity,
+};
+use rand::prelude::*;
+use vek::*;
+use world::site2::{Plot, PlotKind, Site};
+
+/// The places a villager spends their day at, in world coordinates
+#[derive(Clone, Debug)]
+pub struct Schedule {
+    pub home: Vec2<i32>,
+    pub work: Vec2<i32>,
+    pub plaza: Vec2<i32>,
+}
+
+impl Schedule {
+    /// Picks a home and a workplace for a villager of the given town. Towns
+    /// without houses or a plaza can't house villagers.
+    pub fn for_villager(site: &Site, rng: &mut impl Rng) -> Option<Self> {
+        let plot_wpos = |plot: &Plot| site.tile_center_wpos(plot.root_tile());
+        let home = site
+            .plots()
+            .filter(|plot| matches!(plot.kind(), PlotKind::House(_)))
+            .choose(rng)
+            .map(plot_wpos)?;
+        let plaza = site
+            .plazas()
+            .choose(rng)
+            .map(|plaza| plot_wpos(site.plot(plaza)))?;
+        // Not every town has a workshop, the others work at the market
+        let work = site
+            .plots()
+            .filter(|plot| matches!(plot.kind(), PlotKind::Workshop(_)))
+            .choose(rng)
+            .map_or(plaza, plot_wpos);
+
+        Some(Self { home, work, plaza })
+    }
+
+    pub fn activity_at(time_of_day: TimeOfDay, calendar: &Calendar) -> Activity {
+        let hour = (time_of_day.0 / 3600.0).rem_euclid(24.0);
+        let holiday = calendar.is_event(CalendarEvent::Christmas);
+        match hour {
+            h if h < 6.0 => Activity::Sleep,
+            h if h < 8.0 => Activity::Rest,
+            h if (12.0..13.0).contains(&h) => Activity::Gather,
+            // Nobody works on holidays
+            h if h < 17.0 && holiday => Activity::Gather,
+            h if h < 17.0 => Activity::Work,
+            h if h < 20.0 => Activity::Gather,
+            h if h < 22.0 => Activity::Rest,
+            _ => Activity::Sleep,
+        }
+    }
+
+    pub fn location_of(&self, activity: Activity) -> Vec2<i32> {
+        match activity {
+            Activity::Sleep | Activity::Rest => self.home,
+            Activity::Work => self.work,
+            Activity::Gather => self.plaza,
+        }
+    }
+}
+
+/// How villagers refer to the place they are heading to when asked
+pub fn destination_name(activity: Activity) -> &'static str {
+    match activity {
+        Activity::Sleep => "bed",
+        Activity::Rest => "my house",
+        Activity::Work => "work",
+        Activity::Gather => "the plaza",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity_at_hour(hour: f64, calendar: &Calendar) -> Activity {
+        Schedule::activity_at(TimeOfDay(hour * 3600.0), calendar)
+    }
+
+    #[test]
+    fn follows_the_time_of_day() {
+        let calendar = Calendar::default();
+        assert_eq!(activity_at_hour(3.0, &calendar), Activity::Sleep);
+        assert_eq!(activity_at_hour(7.0, &calendar), Activity::Rest);
+        assert_eq!(activity_at_hour(10.0, &calendar), Activity::Work);
+        assert_eq!(activity_at_hour(12.5, &calendar), Activity::Gather);
+        assert_eq!(activity_at_hour(15.0, &calendar), Activity::Work);
+        assert_eq!(activity_at_hour(18.0, &calendar), Activity::Gather);
+        assert_eq!(activity_at_hour(21.0, &calendar), Activity::Rest);
+        assert_eq!(activity_at_hour(23.0, &calendar), Activity::Sleep);
+        // The time of day keeps counting up day after day
+        assert_eq!(activity_at_hour(24.0 * 5.0 + 10.0, &calendar), Activity::Work);
+    }
+
+    #[test]
+    fn no_work_on_holidays() {
+        let calendar = Calendar::from_events(vec![CalendarEvent::Christmas]);
+        assert_eq!(activity_at_hour(10.0, &calendar), Activity::Gather);
+        assert_eq!(activity_at_hour(3.0, &calendar), Activity::Sleep);
+    }
+}
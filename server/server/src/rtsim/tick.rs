@@ -3,10 +3,11 @@
 use super::*;
 use crate::sys::terrain::NpcData;
 use common::{
+    calendar::Calendar,
     comp,
     event::{EventBus, ServerEvent},
     generation::{BodyBuilder, EntityConfig, EntityInfo},
-    resources::{DeltaTime, Time},
+    resources::{DeltaTime, Time, TimeOfDay},
     terrain::TerrainGrid,
 };
 use common_ecs::{Job, Origin, Phase, System};
@@ -19,6 +20,8 @@ impl<'a> System<'a> for Sys {
     type SystemData = (
         Read<'a, Time>,
         Read<'a, DeltaTime>,
+        Read<'a, TimeOfDay>,
+        Read<'a, Calendar>,
         Read<'a, EventBus<ServerEvent>>,
         WriteExpect<'a, RtSim>,
         ReadExpect<'a, TerrainGrid>,
@@ -38,6 +41,8 @@ impl<'a> System<'a> for Sys {
         (
             time,
             _dt,
+            time_of_day,
+            calendar,
             server_event_bus,
             mut rtsim,
             terrain,
@@ -94,13 +99,27 @@ impl<'a> System<'a> for Sys {
                     entity.pos.z = alt;
                 }
             }
-            entity.tick(&time, &terrain, &world, &index.as_index_ref());
+            entity.tick(
+                &time,
+                &time_of_day,
+                &calendar,
+                &terrain,
+                &world,
+                &index.as_index_ref(),
+            );
         }
 
         // Tick entity AI each time if it's loaded
         for (_, entity) in rtsim.entities.iter_mut().filter(|(_, e)| e.is_loaded) {
             entity.last_time_ticked = time.0;
-            entity.tick(&time, &terrain, &world, &index.as_index_ref());
+            entity.tick(
+                &time,
+                &time_of_day,
+                &calendar,
+                &terrain,
+                &world,
+                &index.as_index_ref(),
+            );
         }
 
         let mut server_emitter = server_event_bus.emitter();
@@ -124,6 +143,7 @@ impl<'a> System<'a> for Sys {
                     mountable: false,
                     agent: Some(comp::Agent::from_body(&body)),
                     rtsim_entity,
+                    anchor: None,
                 }
             } else {
                 let entity_config_path = entity.get_entity_config();
@@ -0,0 +1,477 @@
+//! Schematics are boxes of blocks that players can save from the world and
+//! paste back into it, possibly rotated or mirrored.
+//!
+//! They are stored in the `schematics` directory of the server's data
+//! directory, with a directory per player holding one file per schematic, so
+//! that players can't overwrite each other's schematics.
+
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use authc::Uuid;
+use common::{
+    terrain::{
+        structure::{Structure, StructureBlock},
+        Block, BlockKind, SpriteKind, TerrainGrid,
+    },
+    vol::ReadVol,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt, fs,
+    io::{self, Write as _},
+    path::{Path, PathBuf},
+};
+use vek::*;
+
+/// The largest number of blocks a schematic may contain, to keep players from
+/// stalling the server with huge captures.
+pub const MAX_BLOCKS: usize = 1 << 21;
+
+/// The largest number of schematics each player may keep.
+pub const MAX_SCHEMATICS: usize = 32;
+
+/// Current version of [`SchematicFile`].
+const VERSION: u16 = 1;
+
+/// Color of leaves in imported structures. Worldgen picks them from the
+/// gradients of the world index, which depend on the tree and the biome.
+const LEAVES_COLOR: Rgb<u8> = Rgb::new(40, 110, 30);
+
+#[derive(Debug)]
+pub enum SchematicError {
+    InvalidName,
+    TooLarge,
+    /// The player already has as many schematics as they may keep.
+    TooMany,
+    /// The chunk containing this position isn't loaded.
+    NotLoaded(Vec3<i32>),
+    NotFound,
+    Io(io::Error),
+    Format(bincode::Error),
+    /// The blocks in the file don't add up to the size of the schematic.
+    WrongBlockCount,
+    /// The schematic was saved by a newer version of the server.
+    UnknownVersion(u16),
+}
+
+impl fmt::Display for SchematicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidName => write!(
+                f,
+                "Schematic names may only contain letters, digits, '_' and '-'"
+            ),
+            Self::TooLarge => write!(f, "Schematics may contain at most {} blocks", MAX_BLOCKS),
+            Self::TooMany => write!(
+                f,
+                "You may keep at most {} schematics, overwrite one of them instead",
+                MAX_SCHEMATICS
+            ),
+            Self::NotLoaded(pos) => write!(f, "The terrain at {} isn't loaded", pos),
+            Self::NotFound => write!(f, "No such schematic"),
+            Self::Io(err) => write!(f, "Couldn't access the schematic: {}", err),
+            Self::Format(err) => write!(f, "The schematic is corrupt: {}", err),
+            Self::WrongBlockCount => write!(
+                f,
+                "The schematic is corrupt: its blocks don't match its size"
+            ),
+            Self::UnknownVersion(version) => {
+                write!(f, "The schematic has an unknown version {}", version)
+            },
+        }
+    }
+}
+
+impl From<io::Error> for SchematicError {
+    fn from(err: io::Error) -> Self {
+        if err.kind() == io::ErrorKind::NotFound {
+            Self::NotFound
+        } else {
+            Self::Io(err)
+        }
+    }
+}
+
+impl From<bincode::Error> for SchematicError {
+    fn from(err: bincode::Error) -> Self { Self::Format(err) }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Mirror {
+    None,
+    /// Flip along the x axis, swapping east and west.
+    X,
+    /// Flip along the y axis, swapping north and south.
+    Y,
+}
+
+/// How a schematic is turned when it is pasted. Mirroring is applied before
+/// rotating.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Transform {
+    /// Quarter turns counter-clockwise.
+    pub rotation: u8,
+    pub mirror: Mirror,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            rotation: 0,
+            mirror: Mirror::None,
+        }
+    }
+}
+
+impl Transform {
+    /// Size of a box of the given size once transformed.
+    pub fn size(&self, size: Vec3<u32>) -> Vec3<u32> {
+        if self.rotation % 2 == 0 {
+            size
+        } else {
+            Vec3::new(size.y, size.x, size.z)
+        }
+    }
+
+    /// Where `pos`, in a box of the given size starting at the origin, ends up
+    /// once transformed.
+    fn apply_pos(&self, mut pos: Vec3<i32>, size: Vec3<u32>) -> Vec3<i32> {
+        let mut size = size.map(|e| e as i32);
+        match self.mirror {
+            Mirror::None => {},
+            Mirror::X => pos.x = size.x - 1 - pos.x,
+            Mirror::Y => pos.y = size.y - 1 - pos.y,
+        }
+        for _ in 0..self.rotation % 4 {
+            pos = Vec3::new(size.y - 1 - pos.y, pos.x, pos.z);
+            size = Vec3::new(size.y, size.x, size.z);
+        }
+        pos
+    }
+
+    /// Turns the sprite of a block the same way as its position.
+    fn apply_block(&self, block: Block) -> Block {
+        // Sprite orientations are counter-clockwise eighths of a turn
+        let ori = match (block.get_ori(), self.mirror) {
+            (None, _) => return block,
+            (Some(ori), Mirror::None) => ori,
+            (Some(ori), Mirror::X) => 12 - ori,
+            (Some(ori), Mirror::Y) => 8 - ori,
+        };
+        block
+            .with_ori((ori + (self.rotation % 4) * 2) & 0b111)
+            .unwrap_or(block)
+    }
+}
+
+pub struct Schematic {
+    size: Vec3<u32>,
+    /// Blocks with x varying fastest, then y, then z. `None` leaves the
+    /// existing block in place when pasting.
+    blocks: Vec<Option<Block>>,
+}
+
+impl Schematic {
+    /// Captures the blocks in the given box, bounds included.
+    pub fn capture(terrain: &TerrainGrid, aabb: Aabb<i32>) -> Result<Self, SchematicError> {
+        let aabb = aabb.made_valid();
+        let size = (aabb.max - aabb.min + 1).map(|e| e as u32);
+        check_size(size)?;
+
+        let mut blocks = Vec::with_capacity(size.product() as usize);
+        for z in aabb.min.z..=aabb.max.z {
+            for y in aabb.min.y..=aabb.max.y {
+                for x in aabb.min.x..=aabb.max.x {
+                    let pos = Vec3::new(x, y, z);
+                    let block = terrain
+                        .get(pos)
+                        .map_err(|_| SchematicError::NotLoaded(pos))?;
+                    blocks.push(Some(*block));
+                }
+            }
+        }
+
+        Ok(Self { size, blocks })
+    }
+
+    /// Converts a structure asset, such as the `.vox` files used by worldgen.
+    ///
+    /// Blocks that worldgen randomises, like fruit and leaves, are given a
+    /// fixed appearance.
+    pub fn from_structure(structure: &Structure) -> Result<Self, SchematicError> {
+        let bounds = structure.get_bounds();
+        let size = (bounds.max - bounds.min).map(|e| e.max(0) as u32);
+        check_size(size)?;
+
+        let mut blocks = Vec::with_capacity(size.product() as usize);
+        for z in bounds.min.z..bounds.max.z {
+            for y in bounds.min.y..bounds.max.y {
+                for x in bounds.min.x..bounds.max.x {
+                    let sblock = structure
+                        .get(Vec3::new(x, y, z))
+                        .copied()
+                        .unwrap_or(StructureBlock::None);
+                    blocks.push(block_from_structure(sblock));
+                }
+            }
+        }
+
+        Ok(Self { size, blocks })
+    }
+
+    pub fn size(&self) -> Vec3<u32> { self.size }
+
+    /// The blocks to set to paste the schematic with its minimum corner at
+    /// `origin`.
+    pub fn blocks(
+        &self,
+        origin: Vec3<i32>,
+        transform: Transform,
+    ) -> impl Iterator<Item = (Vec3<i32>, Block)> + '_ {
+        let size = self.size;
+        let stride = size.map(|e| e as usize);
+        self.blocks
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, block)| {
+                let pos = Vec3::new(
+                    i % stride.x,
+                    i / stride.x % stride.y,
+                    i / (stride.x * stride.y),
+                )
+                .map(|e| e as i32);
+                Some((
+                    origin + transform.apply_pos(pos, size),
+                    transform.apply_block((*block)?),
+                ))
+            })
+    }
+
+    /// Saves the schematic as `name` among the schematics of `owner`,
+    /// replacing any of their schematics of that name.
+    pub fn save(&self, data_dir: &Path, owner: Uuid, name: &str) -> Result<(), SchematicError> {
+        let path = path(data_dir, owner, name)?;
+        if !path.exists() && list(data_dir, owner)?.len() >= MAX_SCHEMATICS {
+            return Err(SchematicError::TooMany);
+        }
+        fs::create_dir_all(path.parent().expect("Schematic path has a parent"))?;
+        let bytes = bincode::serialize(&SchematicFile::from(self))?;
+        AtomicFile::new(path, OverwriteBehavior::AllowOverwrite)
+            .write(|file| file.write_all(&bytes))
+            .map_err(|err| match err {
+                atomicwrites::Error::Internal(err) | atomicwrites::Error::User(err) => {
+                    SchematicError::Io(err)
+                },
+            })
+    }
+
+    pub fn load(data_dir: &Path, owner: Uuid, name: &str) -> Result<Self, SchematicError> {
+        let bytes = fs::read(path(data_dir, owner, name)?)?;
+        bincode::deserialize::<SchematicFile>(&bytes)?.into_schematic()
+    }
+}
+
+/// Names of the schematics saved by `owner`, in alphabetical order.
+pub fn list(data_dir: &Path, owner: Uuid) -> Result<Vec<String>, SchematicError> {
+    let mut names = match fs::read_dir(dir(data_dir, owner)) {
+        Ok(entries) => entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? == "dat" {
+                    Some(path.file_stem()?.to_str()?.to_owned())
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>(),
+        // Nothing has been saved yet
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(SchematicError::Io(err)),
+    };
+    names.sort();
+    Ok(names)
+}
+
+fn dir(data_dir: &Path, owner: Uuid) -> PathBuf {
+    data_dir.join("schematics").join(owner.to_string())
+}
+
+fn path(data_dir: &Path, owner: Uuid, name: &str) -> Result<PathBuf, SchematicError> {
+    // Keep names from escaping the schematics directory
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(SchematicError::InvalidName);
+    }
+    Ok(dir(data_dir, owner).join(format!("{}.dat", name)))
+}
+
+fn check_size(size: Vec3<u32>) -> Result<(), SchematicError> {
+    let blocks = size
+        .iter()
+        .try_fold(1usize, |blocks, e| blocks.checked_mul(*e as usize));
+    match blocks {
+        Some(blocks) if blocks <= MAX_BLOCKS => Ok(()),
+        _ => Err(SchematicError::TooLarge),
+    }
+}
+
+fn block_from_structure(sblock: StructureBlock) -> Option<Block> {
+    match sblock {
+        StructureBlock::None => None,
+        StructureBlock::Hollow => Some(Block::air(SpriteKind::Empty)),
+        StructureBlock::Grass => Some(Block::new(BlockKind::Grass, Rgb::new(85, 130, 40))),
+        StructureBlock::Normal(color) => Some(Block::new(BlockKind::Misc, color)),
+        StructureBlock::Filled(kind, color) => Some(Block::new(kind, color)),
+        StructureBlock::Sprite(kind) => Some(Block::air(kind)),
+        StructureBlock::Water | StructureBlock::GreenSludge => {
+            Some(Block::water(SpriteKind::Empty))
+        },
+        StructureBlock::Liana => Some(Block::air(SpriteKind::Liana)),
+        StructureBlock::Fruit => Some(Block::air(SpriteKind::Apple)),
+        StructureBlock::Coconut => Some(Block::air(SpriteKind::Coconut)),
+        StructureBlock::Chest => Some(Block::air(SpriteKind::Chest)),
+        StructureBlock::Log => Some(Block::new(BlockKind::Wood, Rgb::new(60, 30, 0))),
+        StructureBlock::BirchWood => Some(Block::new(BlockKind::Wood, Rgb::new(220, 170, 160))),
+        StructureBlock::TemperateLeaves
+        | StructureBlock::PineLeaves
+        | StructureBlock::FrostpineLeaves
+        | StructureBlock::PalmLeavesInner
+        | StructureBlock::PalmLeavesOuter
+        | StructureBlock::Acacia
+        | StructureBlock::Mangrove
+        | StructureBlock::Chestnut
+        | StructureBlock::Baobab => Some(Block::new(BlockKind::Leaves, LEAVES_COLOR)),
+    }
+}
+
+/// Schematics as they are written to disk. Blocks are run-length encoded in
+/// the order of [`Schematic::blocks`], since schematics are mostly air.
+#[derive(Serialize, Deserialize)]
+struct SchematicFile {
+    version: u16,
+    size: (u32, u32, u32),
+    /// Numbers of repetitions of blocks, as given by [`Block::to_u32`], which
+    /// keeps their sprite and its orientation.
+    runs: Vec<(u32, Option<u32>)>,
+}
+
+impl From<&Schematic> for SchematicFile {
+    fn from(schematic: &Schematic) -> Self {
+        let mut runs: Vec<(u32, Option<u32>)> = Vec::new();
+        for block in schematic.blocks.iter().map(|b| b.map(|b| b.to_u32())) {
+            match runs.last_mut() {
+                Some((count, last)) if *last == block => *count += 1,
+                _ => runs.push((1, block)),
+            }
+        }
+        Self {
+            version: VERSION,
+            size: schematic.size.into_tuple(),
+            runs,
+        }
+    }
+}
+
+impl SchematicFile {
+    fn into_schematic(self) -> Result<Schematic, SchematicError> {
+        if self.version != VERSION {
+            return Err(SchematicError::UnknownVersion(self.version));
+        }
+        let size = Vec3::from(self.size);
+        check_size(size)?;
+        let len = size.map(|e| e as usize).product();
+        let mut blocks = Vec::with_capacity(len);
+        for (count, block) in self.runs {
+            // Don't trust the file to have the right number of blocks
+            if count as usize > len - blocks.len() {
+                return Err(SchematicError::WrongBlockCount);
+            }
+            let block = block.map(|b| Block::from_u32(b).unwrap_or_else(Block::empty));
+            blocks.extend(std::iter::repeat(block).take(count as usize));
+        }
+        if blocks.len() < len {
+            return Err(SchematicError::WrongBlockCount);
+        }
+        Ok(Schematic { size, blocks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(ori: u8) -> Block { Block::air(SpriteKind::Window1).with_ori(ori).unwrap() }
+
+    #[test]
+    fn rotation_turns_positions_and_sprites() {
+        let schematic = Schematic {
+            size: Vec3::new(2, 1, 1),
+            blocks: vec![Some(window(0)), None],
+        };
+        let transform = Transform {
+            rotation: 1,
+            mirror: Mirror::None,
+        };
+        assert_eq!(transform.size(schematic.size), Vec3::new(1, 2, 1));
+        let blocks = schematic
+            .blocks(Vec3::zero(), transform)
+            .collect::<Vec<_>>();
+        assert_eq!(blocks, vec![(Vec3::new(0, 0, 0), window(2))]);
+    }
+
+    #[test]
+    fn mirroring_flips_positions_and_sprites() {
+        let schematic = Schematic {
+            size: Vec3::new(2, 1, 1),
+            blocks: vec![Some(window(1)), None],
+        };
+        let transform = Transform {
+            rotation: 0,
+            mirror: Mirror::X,
+        };
+        let blocks = schematic
+            .blocks(Vec3::zero(), transform)
+            .collect::<Vec<_>>();
+        assert_eq!(blocks, vec![(Vec3::new(1, 0, 0), window(3))]);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let schematic = Schematic {
+            size: Vec3::new(3, 1, 1),
+            blocks: vec![Some(window(5)), None, Some(Block::empty())],
+        };
+        let loaded = SchematicFile::from(&schematic).into_schematic().unwrap();
+        assert_eq!(loaded.size, schematic.size);
+        assert_eq!(loaded.blocks, schematic.blocks);
+    }
+
+    #[test]
+    fn files_must_fill_their_size() {
+        let file = |runs| SchematicFile {
+            version: VERSION,
+            size: (2, 2, 1),
+            runs,
+        };
+        assert!(file(vec![(4, None)]).into_schematic().is_ok());
+        assert!(matches!(
+            file(vec![(3, None), (u32::MAX, None)]).into_schematic(),
+            Err(SchematicError::WrongBlockCount)
+        ));
+        assert!(matches!(
+            file(vec![(3, None)]).into_schematic(),
+            Err(SchematicError::WrongBlockCount)
+        ));
+        assert!(matches!(
+            SchematicFile {
+                version: VERSION,
+                size: (u32::MAX, u32::MAX, u32::MAX),
+                runs: Vec::new(),
+            }
+            .into_schematic(),
+            Err(SchematicError::TooLarge)
+        ));
+    }
+}
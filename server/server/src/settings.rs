@@ -16,6 +16,7 @@ pub use whitelist::{Whitelist, WhitelistInfo, WhitelistRecord};
 use chrono::Utc;
 use common::{
     calendar::{Calendar, CalendarEvent},
+    comp::WorldEventKind,
     resources::BattleMode,
 };
 use core::time::Duration;
@@ -27,6 +28,7 @@ use std::{
     path::{Path, PathBuf},
 };
 use tracing::{error, warn};
+use vek::Aabb;
 use world::sim::FileOpts;
 
 const DEFAULT_WORLD_SEED: u32 = 230;
@@ -94,6 +96,204 @@ impl CalendarMode {
     }
 }
 
+/// Limits on the in-game mail system
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MailSettings {
+    /// The number of pieces of mail a character may send in a 24 hour period
+    pub max_sent_per_day: u32,
+    /// The number of pieces of mail a mailbox can hold before the character
+    /// stops receiving mail from other players
+    pub max_mailbox_size: u32,
+    /// The number of coins it costs to send a piece of mail
+    pub postage: u32,
+}
+
+impl Default for MailSettings {
+    fn default() -> Self {
+        Self {
+            max_sent_per_day: 20,
+            max_mailbox_size: 50,
+            postage: 5,
+        }
+    }
+}
+
+/// Prices and limits for the plots that players can buy in towns
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HousingSettings {
+    pub enabled: bool,
+    /// The number of coins it costs to buy a plot with a house on it
+    pub house_price: u32,
+    /// The number of coins it costs to buy an empty tile
+    pub tile_price: u32,
+    /// The number of coins charged for each rent period
+    pub rent: u32,
+    /// The length of a rent period, in days
+    pub rent_period_days: u32,
+    /// Plots are released if their owner hasn't played for this many days
+    pub inactivity_days: u32,
+    /// The number of blocks above the ground that plot owners may build up to
+    pub build_height: i32,
+    /// The number of blocks below the ground that plot owners may dig down to
+    pub build_depth: i32,
+}
+
+impl Default for HousingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            house_price: 2000,
+            tile_price: 500,
+            rent: 100,
+            rent_period_days: 7,
+            inactivity_days: 30,
+            build_height: 48,
+            build_depth: 16,
+        }
+    }
+}
+
+/// Limits for player guilds
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildSettings {
+    /// The number of coins it costs to found a guild
+    pub creation_cost: u32,
+    pub max_members: usize,
+    /// The number of item stacks each guild bank can hold
+    pub bank_slots: usize,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        Self {
+            creation_cost: 1000,
+            max_members: 100,
+            bank_slots: 24,
+        }
+    }
+}
+
+/// An area in which PvP is always enabled
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ArenaSettings {
+    pub name: String,
+    pub bounds: Aabb<i32>,
+    /// The number of teams players entering the arena are split into, or 0 for
+    /// a free-for-all
+    #[serde(default)]
+    pub teams: u8,
+}
+
+/// Rules for duels and arenas
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PvpSettings {
+    /// Duels are forfeited by whoever strays further than this from their
+    /// opponent, in blocks
+    pub duel_max_distance: f32,
+    /// Duels that last longer than this end in a draw
+    pub duel_time_limit_secs: u64,
+    pub arenas: Vec<ArenaSettings>,
+}
+
+impl Default for PvpSettings {
+    fn default() -> Self {
+        Self {
+            duel_max_distance: 100.0,
+            duel_time_limit_secs: 300,
+            arenas: Vec::new(),
+        }
+    }
+}
+
+/// What a kind of world event spawns and rewards
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WorldEventKindSettings {
+    /// How likely this kind of event is to be picked, relative to the others
+    pub weight: f32,
+    /// Entity configs of the enemies, one of which is picked for each enemy
+    pub enemies: Vec<String>,
+    pub enemy_count: u32,
+    /// The loot table that rewards are drawn from
+    pub loot_table: String,
+    /// The number of rewards each participant receives
+    pub rewards: u32,
+}
+
+/// Events that the server starts every so often for everyone to take part in
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldEventSettings {
+    pub enabled: bool,
+    /// The time between two events is picked between these, in minutes
+    pub min_interval_mins: u64,
+    pub max_interval_mins: u64,
+    /// No more events are started while this many are going on
+    pub max_active: usize,
+    /// Events that haven't been won after this many minutes are over
+    pub duration_mins: u64,
+    /// Players coming this close to an event take part in it, in blocks
+    pub participation_radius: f32,
+    pub raid: WorldEventKindSettings,
+    pub world_boss: WorldEventKindSettings,
+    pub meteor: WorldEventKindSettings,
+}
+
+impl Default for WorldEventSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_interval_mins: 30,
+            max_interval_mins: 90,
+            max_active: 2,
+            duration_mins: 30,
+            participation_radius: 96.0,
+            raid: WorldEventKindSettings {
+                weight: 1.0,
+                enemies: vec![
+                    "common.entity.dungeon.gnarling.mugger".to_owned(),
+                    "common.entity.dungeon.gnarling.stalker".to_owned(),
+                    "common.entity.dungeon.gnarling.logger".to_owned(),
+                ],
+                enemy_count: 8,
+                loot_table: "common.loot_tables.world_event.raid".to_owned(),
+                rewards: 2,
+            },
+            world_boss: WorldEventKindSettings {
+                weight: 1.0,
+                enemies: vec![
+                    "common.entity.wild.aggressive.tarasque".to_owned(),
+                    "common.entity.wild.aggressive.mountain_troll".to_owned(),
+                    "common.entity.wild.aggressive.wendigo".to_owned(),
+                ],
+                enemy_count: 1,
+                loot_table: "common.loot_tables.world_event.world_boss".to_owned(),
+                rewards: 2,
+            },
+            meteor: WorldEventKindSettings {
+                weight: 0.5,
+                enemies: Vec::new(),
+                enemy_count: 0,
+                loot_table: "common.loot_tables.world_event.meteor".to_owned(),
+                rewards: 3,
+            },
+        }
+    }
+}
+
+impl WorldEventSettings {
+    pub fn kind(&self, kind: WorldEventKind) -> &WorldEventKindSettings {
+        match kind {
+            WorldEventKind::Raid => &self.raid,
+            WorldEventKind::WorldBoss => &self.world_boss,
+            WorldEventKind::Meteor => &self.meteor,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Settings {
@@ -108,6 +308,10 @@ pub struct Settings {
     /// When set to None, loads the default map file (if available); otherwise,
     /// uses the value of the file options to decide how to proceed.
     pub map_file: Option<FileOpts>,
+    /// Asset specifier of the worldgen preset to generate the world with.
+    /// When set without a map file, a new world of the preset's size is
+    /// generated. Loaded maps always use the preset saved with them.
+    pub world_preset: Option<String>,
     pub max_view_distance: Option<u32>,
     pub banned_words_files: Vec<PathBuf>,
     pub max_player_group_size: u32,
@@ -116,6 +320,11 @@ pub struct Settings {
     pub safe_spawn: bool,
     pub max_player_for_kill_broadcast: Option<usize>,
     pub calendar_mode: CalendarMode,
+    pub mail: MailSettings,
+    pub housing: HousingSettings,
+    pub guild: GuildSettings,
+    pub pvp: PvpSettings,
+    pub world_events: WorldEventSettings,
 
     /// Experimental feature. No guaranteed forwards-compatibility, may be
     /// removed at *any time* with no migration.
@@ -142,6 +351,7 @@ impl Default for Settings {
             battle_mode: ServerBattleMode::Global(BattleMode::PvP),
             start_time: 9.0 * 3600.0,
             map_file: None,
+            world_preset: None,
             max_view_distance: Some(65),
             banned_words_files: Vec::new(),
             max_player_group_size: 6,
@@ -150,6 +360,11 @@ impl Default for Settings {
             spawn_town: None,
             safe_spawn: true,
             max_player_for_kill_broadcast: None,
+            mail: MailSettings::default(),
+            housing: HousingSettings::default(),
+            guild: GuildSettings::default(),
+            pvp: PvpSettings::default(),
+            world_events: WorldEventSettings::default(),
             experimental_terrain_persistence: false,
         }
     }
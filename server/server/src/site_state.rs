@@ -0,0 +1,490 @@
+//! Damage to and abandonment of sites
+//!
+//! Towns and castles are generated once along with the world, but the blocks
+//! of their buildings can still be blown up or mined away. Such blocks are
+//! remembered as damage to the site they belong to, and are taken out of its
+//! chunks again whenever they are regenerated. Each site has rtsim builders
+//! that walk up to the damage one block at a time and restore what was
+//! originally generated there, unless the hole has been filled in since.
+//! Blocks that players build in a site are remembered too, so that destroying
+//! them isn't mistaken for damage to the site, and blocks removed with build
+//! mode are left alone, since build areas are the players' to change. A town
+//! whose residents have all died is abandoned: nobody comes to live there or
+//! repair it any more.
+//!
+//! The damage and abandonment of sites are saved in the data directory so that
+//! they survive restarts.
+
+use crate::{rtsim::RtSim, Server, StateExt};
+use atomicwrites::{AtomicFile, OverwriteBehavior};
+use common::{
+    comp::ChatType,
+    resources::Time,
+    terrain::{Block, TerrainChunk, TerrainChunkSize},
+    vol::{ReadVol, RectRasterableVol, RectVolSize, WriteVol},
+};
+use common_net::msg::ServerGeneral;
+use hashbrown::{HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use specs::WorldExt;
+use std::{
+    fs,
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+use tracing::{error, info, warn};
+use vek::*;
+
+/// How often builders restore a block, in seconds
+const REPAIR_INTERVAL: f64 = 2.0;
+/// How close a builder needs to be to a damaged block to restore it, in blocks
+const REPAIR_RANGE: f32 = 6.0;
+/// How often towns are checked for having lost all of their residents, in
+/// seconds
+const POPULATION_CHECK_INTERVAL: f64 = 30.0;
+/// How often changes to the state of sites are written to disk, in seconds
+const SAVE_INTERVAL: f64 = 60.0;
+
+/// What is saved about a site
+#[derive(Default, Serialize, Deserialize)]
+struct SiteRecord {
+    abandoned: bool,
+    /// Positions of destroyed blocks along with the blocks that were originally
+    /// generated there
+    damage: Vec<(Vec3<i32>, Block)>,
+    /// Positions of blocks built by players
+    #[serde(default)]
+    built: Vec<Vec3<i32>>,
+}
+
+struct TrackedSite {
+    name: String,
+    origin: Vec2<i32>,
+    radius: f32,
+    /// Whether the site is a town with rtsim residents, which can be abandoned
+    town: bool,
+    /// Whether the town was found to have residents since the server started.
+    /// Towns that never had any aren't abandoned.
+    populated: bool,
+    abandoned: bool,
+    damage: HashMap<Vec3<i32>, Block>,
+    built: HashSet<Vec3<i32>>,
+}
+
+/// The state of every site that can be damaged, keyed by the id of the site in
+/// the world index
+pub struct SiteStates {
+    path: PathBuf,
+    sites: HashMap<u64, TrackedSite>,
+    /// The sites overlapping each chunk
+    chunk_sites: HashMap<Vec2<i32>, Vec<u64>>,
+    dirty: bool,
+    next_repair: f64,
+    next_population_check: f64,
+    next_save: f64,
+}
+
+impl SiteStates {
+    /// Track the sites of the given world that can be damaged, restoring what
+    /// was saved about them in the data directory
+    #[cfg(feature = "worldgen")]
+    pub fn new(data_dir: &Path, world: &world::World, index: world::IndexRef) -> Self {
+        use world::site::SiteKind;
+
+        let mut this = Self::empty(data_dir);
+        let chunk_size = TerrainChunkSize::RECT_SIZE.map(|e| e as i32);
+        for (id, site) in index.sites.iter() {
+            let town = match &site.kind {
+                SiteKind::Refactor(_) => true,
+                SiteKind::Settlement(_) | SiteKind::Castle(_) => false,
+                _ => continue,
+            };
+            let origin = site.get_origin();
+            let radius = site.radius().ceil() as i32;
+            let min = (origin - radius).map2(chunk_size, |e, sz| e.div_euclid(sz));
+            let max = (origin + radius).map2(chunk_size, |e, sz| e.div_euclid(sz));
+            for x in min.x..=max.x {
+                for y in min.y..=max.y {
+                    if world.sim().get(Vec2::new(x, y)).is_some() {
+                        this.chunk_sites
+                            .entry(Vec2::new(x, y))
+                            .or_default()
+                            .push(id.id());
+                    }
+                }
+            }
+            this.sites.insert(
+                id.id(),
+                TrackedSite {
+                    name: site.name().to_owned(),
+                    origin,
+                    radius: site.radius(),
+                    town,
+                    populated: false,
+                    abandoned: false,
+                    damage: HashMap::new(),
+                    built: HashSet::new(),
+                },
+            );
+        }
+        this.load();
+        this
+    }
+
+    /// No sites to track, used when there is no generated world
+    pub fn empty(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join("sites.ron"),
+            sites: HashMap::new(),
+            chunk_sites: HashMap::new(),
+            dirty: false,
+            next_repair: 0.0,
+            next_population_check: 0.0,
+            next_save: 0.0,
+        }
+    }
+
+    fn load(&mut self) {
+        let records: HashMap<u64, SiteRecord> = match fs::File::open(&self.path) {
+            Ok(file) => match ron::de::from_reader(file) {
+                Ok(records) => records,
+                Err(e) => {
+                    error!(?e, ?self.path, "Failed to parse the state of sites");
+                    return;
+                },
+            },
+            // Nothing has happened to any site yet
+            Err(_) => return,
+        };
+        for (id, record) in records {
+            if let Some(site) = self.sites.get_mut(&id) {
+                site.abandoned = record.abandoned;
+                site.damage = record.damage.into_iter().collect();
+                site.built = record.built.into_iter().collect();
+            } else {
+                warn!(
+                    ?id,
+                    "Dropping the saved state of a site that no longer exists"
+                );
+            }
+        }
+        info!("Loaded the state of sites from {:?}", self.path);
+    }
+
+    /// Write the state of sites to disk, if it changed since it was last saved
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let records = self
+            .sites
+            .iter()
+            .filter(|(_, site)| site.abandoned || !site.damage.is_empty() || !site.built.is_empty())
+            .map(|(id, site)| {
+                (
+                    *id,
+                    SiteRecord {
+                        abandoned: site.abandoned,
+                        damage: site
+                            .damage
+                            .iter()
+                            .map(|(pos, block)| (*pos, *block))
+                            .collect(),
+                        built: site.built.iter().copied().collect(),
+                    },
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let ron = match ron::ser::to_string(&records) {
+            Ok(ron) => ron,
+            Err(e) => {
+                error!(?e, "Failed to serialize the state of sites");
+                return;
+            },
+        };
+        if let Err(e) = AtomicFile::new(&self.path, OverwriteBehavior::AllowOverwrite)
+            .write(|file| file.write_all(ron.as_bytes()))
+        {
+            error!(?e, ?self.path, "Failed to save the state of sites");
+        } else {
+            self.dirty = false;
+        }
+    }
+
+    pub fn is_abandoned(&self, site: u64) -> bool {
+        self.sites.get(&site).map_or(false, |site| site.abandoned)
+    }
+
+    fn site_at(&self, pos: Vec3<i32>) -> Option<u64> {
+        let chunk_key = pos
+            .xy()
+            .map2(TerrainChunkSize::RECT_SIZE, |e, sz| e.div_euclid(sz as i32));
+        self.chunk_sites
+            .get(&chunk_key)?
+            .iter()
+            .copied()
+            .find(|id| {
+                self.sites.get(id).map_or(false, |site| {
+                    site.origin
+                        .map(|e| e as f32)
+                        .distance_squared(pos.xy().map(|e| e as f32))
+                        < site.radius.powi(2)
+                })
+            })
+    }
+
+    /// Record a block being destroyed, if it was generated as part of a site.
+    /// `old_block` is the block that was destroyed.
+    pub fn record_damage(&mut self, pos: Vec3<i32>, old_block: Block) {
+        if !old_block.is_filled() {
+            return;
+        }
+        if let Some(site) = self.site_at(pos).and_then(|id| self.sites.get_mut(&id)) {
+            // Blocks are restored to what was generated rather than whatever they
+            // were last changed to
+            if site.damage.contains_key(&pos) {
+                return;
+            }
+            if !site.built.remove(&pos) {
+                site.damage.insert(pos, old_block);
+            }
+            self.dirty = true;
+        }
+    }
+
+    /// Record a player building a block, if it is within a site
+    pub fn record_build(&mut self, pos: Vec3<i32>, new_block: Block) {
+        if !new_block.is_filled() {
+            return;
+        }
+        if let Some(site) = self.site_at(pos).and_then(|id| self.sites.get_mut(&id)) {
+            // Filling in a hole doesn't make what was generated there forgotten
+            if !site.damage.contains_key(&pos) && site.built.insert(pos) {
+                self.dirty = true;
+            }
+        }
+    }
+
+    /// Take the damaged blocks of sites out of a newly generated chunk
+    pub fn apply_damage(&self, key: Vec2<i32>, chunk: &mut TerrainChunk) {
+        let offset = key * TerrainChunk::RECT_SIZE.map(|e| e as i32);
+        for site in self
+            .chunk_sites
+            .get(&key)
+            .into_iter()
+            .flatten()
+            .filter_map(|id| self.sites.get(id))
+        {
+            for (pos, block) in site.damage.iter() {
+                let rpos = pos - offset;
+                if rpos.x >= 0
+                    && rpos.y >= 0
+                    && rpos.x < TerrainChunk::RECT_SIZE.x as i32
+                    && rpos.y < TerrainChunk::RECT_SIZE.y as i32
+                {
+                    let _ = chunk.set(rpos, block.into_vacant());
+                }
+            }
+        }
+    }
+
+    /// The closest damaged block of a site to the given position
+    fn nearest_damage(&self, site: u64, pos: Vec2<f32>) -> Option<(Vec3<i32>, Block)> {
+        self.sites
+            .get(&site)?
+            .damage
+            .iter()
+            .map(|(pos, block)| (*pos, *block))
+            .min_by_key(|(block_pos, _)| {
+                block_pos.xy().map(|e| e as f32 + 0.5).distance_squared(pos) as i64
+            })
+    }
+}
+
+/// Direct the builders of sites to their damage and let them repair it, abandon
+/// towns that lost all their residents and save the state of sites every so
+/// often
+pub fn tick(server: &mut Server) {
+    let now = server.state.ecs().read_resource::<Time>().0;
+    let mut abandoned = Vec::new();
+    {
+        let ecs = server.state.ecs();
+        let mut site_states = ecs.write_resource::<SiteStates>();
+        let site_states = &mut *site_states;
+        let mut rtsim = ecs.write_resource::<RtSim>();
+
+        if now >= site_states.next_repair {
+            site_states.next_repair = now + REPAIR_INTERVAL;
+            for (home, builder) in rtsim.builders_mut() {
+                let site = match server.world.civs().sites[home].site_tmp {
+                    Some(site) => site.id(),
+                    None => continue,
+                };
+                let target = match site_states.nearest_damage(site, builder.pos.xy()) {
+                    Some(target) => target,
+                    None => {
+                        builder.brain.set_repair_target(None);
+                        continue;
+                    },
+                };
+                let (pos, block) = target;
+                if pos.xy().map(|e| e as f32 + 0.5).distance(builder.pos.xy()) > REPAIR_RANGE {
+                    builder.brain.set_repair_target(Some(pos));
+                    continue;
+                }
+                // The damage is only forgotten once the block is restored, or the hole
+                // has been filled in since
+                let current = server.state.terrain().get(pos).ok().copied();
+                match current {
+                    Some(current) if current.is_filled() => {},
+                    Some(_) if server.state.can_set_block(pos) => {
+                        server.state.set_block(pos, block);
+                        // Otherwise the hole would come back with the chunk
+                        #[cfg(feature = "persistent_world")]
+                        if let Some(terrain_persistence) =
+                            ecs.try_fetch_mut::<crate::TerrainPersistence>().as_mut()
+                        {
+                            terrain_persistence.set_block(pos, block);
+                        }
+                    },
+                    _ => continue,
+                }
+                if let Some(site) = site_states.sites.get_mut(&site) {
+                    site.damage.remove(&pos);
+                }
+                site_states.dirty = true;
+            }
+        }
+
+        if now >= site_states.next_population_check {
+            site_states.next_population_check = now + POPULATION_CHECK_INTERVAL;
+            let populations = rtsim.populations();
+            for (home, site) in server.world.civs().sites.iter() {
+                let tracked = match site
+                    .site_tmp
+                    .and_then(|id| site_states.sites.get_mut(&id.id()))
+                {
+                    Some(tracked) if tracked.town && !tracked.abandoned => tracked,
+                    _ => continue,
+                };
+                if populations.get(&home).copied().unwrap_or(0) > 0 {
+                    tracked.populated = true;
+                } else if tracked.populated {
+                    tracked.abandoned = true;
+                    abandoned.push(tracked.name.clone());
+                    site_states.dirty = true;
+                }
+            }
+        }
+
+        if now >= site_states.next_save {
+            site_states.next_save = now + SAVE_INTERVAL;
+            site_states.save();
+        }
+    }
+
+    for name in abandoned {
+        info!(?name, "Town has been abandoned");
+        server.state.notify_players(ServerGeneral::server_msg(
+            ChatType::Meta,
+            format!(
+                "The last residents of {} are gone. The town lies abandoned.",
+                name
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::terrain::{BlockKind, SpriteKind, TerrainChunkMeta};
+
+    fn stone() -> Block { Block::new(BlockKind::Rock, Rgb::new(120, 120, 120)) }
+
+    fn wood() -> Block { Block::new(BlockKind::Wood, Rgb::new(80, 50, 20)) }
+
+    /// A town in the middle of the chunk at (1, 1)
+    fn site_states(data_dir: &Path) -> SiteStates {
+        let mut site_states = SiteStates::empty(data_dir);
+        site_states.chunk_sites.insert(Vec2::new(1, 1), vec![7]);
+        site_states.sites.insert(
+            7,
+            TrackedSite {
+                name: "Testville".to_owned(),
+                origin: Vec2::new(48, 48),
+                radius: 8.0,
+                town: true,
+                populated: false,
+                abandoned: false,
+                damage: HashMap::new(),
+                built: HashSet::new(),
+            },
+        );
+        site_states
+    }
+
+    #[test]
+    fn only_generated_blocks_are_damage() {
+        let mut site_states = site_states(Path::new("."));
+        let pos = Vec3::new(50, 45, -5);
+        assert_eq!(site_states.site_at(pos), Some(7));
+        assert_eq!(site_states.site_at(Vec3::new(33, 33, -5)), None);
+        assert_eq!(site_states.site_at(Vec3::new(80, 48, -5)), None);
+
+        site_states.record_damage(pos, Block::air(SpriteKind::Empty));
+        assert!(site_states.sites[&7].damage.is_empty());
+        site_states.record_damage(pos, stone());
+        // Filling in the hole and destroying it again still restores what was generated
+        site_states.record_build(pos, wood());
+        site_states.record_damage(pos, wood());
+        assert_eq!(site_states.sites[&7].damage.get(&pos), Some(&stone()));
+
+        let built = pos + Vec3::unit_z();
+        site_states.record_build(built, wood());
+        site_states.record_damage(built, wood());
+        assert!(!site_states.sites[&7].damage.contains_key(&built));
+        assert!(site_states.sites[&7].built.is_empty());
+        assert!(site_states.dirty);
+    }
+
+    #[test]
+    fn damage_is_taken_out_of_generated_chunks() {
+        let mut site_states = site_states(Path::new("."));
+        let pos = Vec3::new(50, 45, -5);
+        site_states.record_damage(pos, stone());
+
+        let mut chunk = TerrainChunk::new(
+            0,
+            stone(),
+            Block::air(SpriteKind::Empty),
+            TerrainChunkMeta::void(),
+        );
+        site_states.apply_damage(Vec2::new(1, 1), &mut chunk);
+        let rpos = pos - Vec3::new(32, 32, 0);
+        assert!(!chunk.get(rpos).unwrap().is_filled());
+        assert!(chunk.get(rpos - Vec3::unit_x()).unwrap().is_filled());
+    }
+
+    #[test]
+    fn save_and_load() {
+        let data_dir = std::env::temp_dir().join(format!("veloren_sites_{}", std::process::id()));
+        fs::create_dir_all(&data_dir).unwrap();
+
+        let mut site_states = site_states(&data_dir);
+        site_states.record_damage(Vec3::new(50, 45, -5), stone());
+        site_states.record_build(Vec3::new(47, 49, 3), wood());
+        site_states.sites.get_mut(&7).unwrap().abandoned = true;
+        site_states.save();
+        assert!(!site_states.dirty);
+
+        let mut loaded = self::site_states(&data_dir);
+        loaded.load();
+        let (site, loaded_site) = (&site_states.sites[&7], &loaded.sites[&7]);
+        assert!(loaded_site.abandoned);
+        assert_eq!(loaded_site.damage, site.damage);
+        assert_eq!(loaded_site.built, site.built);
+
+        let _ = fs::remove_dir_all(&data_dir);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::{
     client::Client,
     events::update_map_markers,
+    guild::{self, Guilds},
     persistence::PersistedComponents,
     pet::restore_pet,
     presence::{Presence, RepositionOnChunkLoad},
@@ -200,12 +201,15 @@ impl StateExt for State {
                     .write_storage::<comp::Buffs>()
                     .get_mut(entity)
                     .map(|mut buffs| {
-                        buffs.insert(comp::Buff::new(
-                            buff.kind,
-                            buff.data,
-                            buff.cat_ids,
-                            comp::BuffSource::Item,
-                        ))
+                        buffs.insert(
+                            comp::Buff::new(
+                                buff.kind,
+                                buff.data,
+                                buff.cat_ids,
+                                comp::BuffSource::Item,
+                            ),
+                            &comp::buff::BUFF_MANIFEST.read(),
+                        )
                     });
             },
         }
@@ -532,6 +536,7 @@ impl StateExt for State {
             pets,
             active_abilities,
             map_marker,
+            statistics,
         } = components;
 
         if let Some(player_uid) = self.read_component_copied::<Uid>(entity) {
@@ -563,6 +568,7 @@ impl StateExt for State {
             self.write_component_ignore_entity_dead(entity, comp::Poise::new(body));
             self.write_component_ignore_entity_dead(entity, stats);
             self.write_component_ignore_entity_dead(entity, active_abilities);
+            self.write_component_ignore_entity_dead(entity, statistics);
             self.write_component_ignore_entity_dead(entity, skill_set);
             self.write_component_ignore_entity_dead(entity, inventory);
             self.write_component_ignore_entity_dead(
@@ -795,6 +801,35 @@ impl StateExt for State {
                     }
                 }
             },
+            comp::ChatType::GuildMeta(s) => send_to_guild(s, ecs, &resolved_msg),
+            comp::ChatType::Guild(from, s) => {
+                // The sender may have left the guild since choosing guild chat
+                let guilds = ecs.read_resource::<Guilds>();
+                let is_member = ecs
+                    .entity_from_uid(from.0)
+                    .and_then(|entity| guild::character_id(ecs, entity))
+                    .and_then(|character_id| guilds.guild_of(character_id))
+                    .map_or(false, |guild| &guild.name == s);
+                drop(guilds);
+                if !is_member {
+                    let reply = comp::ChatMsg {
+                        chat_type: comp::ChatType::CommandError,
+                        message: "You are using guild chat but do not belong to that guild. Use \
+                                  /world or /region to change chat."
+                            .into(),
+                    };
+
+                    if let Some((client, _)) =
+                        (&ecs.read_storage::<Client>(), &ecs.read_storage::<Uid>())
+                            .join()
+                            .find(|(_, uid)| *uid == from)
+                    {
+                        client.send_fallible(ServerGeneral::ChatMsg(reply));
+                    }
+                    return;
+                }
+                send_to_guild(s, ecs, &resolved_msg);
+            },
             comp::ChatType::Group(from, g) => {
                 if group_info.is_none() {
                     // group not found, reply with command error
@@ -964,6 +999,26 @@ fn send_to_group(g: &comp::Group, ecs: &specs::World, msg: &comp::ChatMsg) {
     }
 }
 
+fn send_to_guild(name: &str, ecs: &specs::World, msg: &comp::ChatMsg) {
+    let guilds = ecs.read_resource::<Guilds>();
+    let guild = match guilds.by_name(name) {
+        Some(guild) => guild,
+        None => return,
+    };
+    for (client, presence) in (
+        &ecs.read_storage::<Client>(),
+        &ecs.read_storage::<Presence>(),
+    )
+        .join()
+    {
+        if let PresenceKind::Character(character_id) = presence.kind {
+            if guild.members.contains_key(&character_id) {
+                client.send_fallible(ServerGeneral::ChatMsg(msg.clone()));
+            }
+        }
+    }
+}
+
 fn capsule(body: &comp::Body) -> comp::Collider {
     let (p0, p1, radius) = body.sausage();
 
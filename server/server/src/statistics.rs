@@ -0,0 +1,26 @@
+//! Server-side helpers for tracking character statistics
+
+use common::lottery::LootSpec;
+use specs::{Component, NullStorage};
+
+/// Marks the boss of a dungeon, so that clearing the dungeon can be credited
+/// to everyone who helped defeat it
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DungeonBoss;
+
+impl Component for DungeonBoss {
+    type Storage = NullStorage<Self>;
+}
+
+impl DungeonBoss {
+    /// Dungeon bosses are the only entities that drop loot from the boss loot
+    /// table of a dungeon tier
+    pub fn is_boss_loot(loot: &LootSpec<String>) -> bool {
+        match loot {
+            LootSpec::LootTable(table) => {
+                table.starts_with("common.loot_tables.dungeon.") && table.ends_with(".boss")
+            },
+            _ => false,
+        }
+    }
+}
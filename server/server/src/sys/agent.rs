@@ -1,20 +1,23 @@
 pub mod attack;
+pub mod behavior_tree;
 pub mod consts;
 pub mod data;
+pub mod tactics;
 pub mod util;
 
 use crate::{
     rtsim::RtSim,
     sys::agent::{
+        behavior_tree::BehaviorData,
         consts::{
-            AVG_FOLLOW_DIST, AWARENESS_INVESTIGATE_THRESHOLD, DAMAGE_MEMORY_DURATION,
-            DEFAULT_ATTACK_RANGE, FLEE_DURATION, HEALING_ITEM_THRESHOLD,
-            IDLE_HEALING_ITEM_THRESHOLD, MAX_FLEE_DIST, MAX_FOLLOW_DIST, PARTIAL_PATH_DIST,
-            RETARGETING_THRESHOLD_SECONDS, SEPARATION_BIAS, SEPARATION_DIST,
+            AVG_FOLLOW_DIST, AWARENESS_INVESTIGATE_THRESHOLD, DEFAULT_ATTACK_RANGE,
+            IDLE_HEALING_ITEM_THRESHOLD, MAX_FOLLOW_DIST, PARTIAL_PATH_DIST, SEPARATION_BIAS,
+            SEPARATION_DIST,
         },
         data::{AgentData, AttackData, ReadData, Tactic, TargetData},
+        tactics::TACTIC_MANIFEST,
         util::{
-            aim_projectile, can_see_tgt, get_entity_by_id, is_dead, is_dead_or_invulnerable,
+            aim_projectile, can_see_tgt, get_entity_by_id, is_dead_or_invulnerable,
             is_invulnerable, try_owner_alignment,
         },
     },
@@ -32,10 +35,7 @@ use common::{
         dialogue::{MoodContext, MoodState, Subject},
         inventory::{item::ItemTag, slot::EquipSlot},
         invite::{InviteKind, InviteResponse},
-        item::{
-            tool::{AbilitySpec, ToolKind},
-            ConsumableKind, Item, ItemDesc, ItemKind,
-        },
+        item::{ConsumableKind, Item, ItemDesc, ItemKind},
         projectile::ProjectileConstructor,
         Agent, Alignment, BehaviorState, Body, CharacterState, ControlAction, ControlEvent,
         Controller, Health, HealthChange, InputKind, Inventory, InventoryAction, Pos, Scale, Stats,
@@ -44,7 +44,7 @@ use common::{
     effect::{BuffEffect, Effect},
     event::{Emitter, EventBus, ServerEvent},
     path::TraversalConfig,
-    rtsim::{Memory, MemoryItem, RtSimEvent},
+    rtsim::{Activity, Memory, MemoryItem, RtSimEvent},
     states::basic_beam,
     terrain::{Block, TerrainGrid},
     time::DayPeriod,
@@ -54,7 +54,7 @@ use common::{
 };
 use common_base::prof_span;
 use common_ecs::{Job, Origin, ParMode, Phase, System};
-use rand::{thread_rng, Rng};
+use rand::{rngs::SmallRng, Rng};
 use rayon::iter::ParallelIterator;
 use specs::{
     saveload::{Marker, MarkerAllocator},
@@ -131,7 +131,8 @@ impl<'a> System<'a> for Sys {
                     _,
                 )| {
                     let mut event_emitter = event_bus.emitter();
-                    let mut rng = thread_rng();
+                    let mut rng: SmallRng =
+                        read_data.rng_seed.rng(*read_data.time, u64::from(*uid));
 
                     // Hack, replace with better system when groups are more sophisticated
                     // Override alignment if in a group unless entity is owned already
@@ -248,149 +249,21 @@ impl<'a> System<'a> for Sys {
                     // Each tick should arrive at one (1) action node which
                     // then determines what the agent does. If this makes you
                     // uncomfortable, consider dt the response time of the
-                    // NPC. To make the tree easier to read, subtrees can be
-                    // created as methods on `AgentData`. Action nodes are
-                    // also methods on the `AgentData` struct. Action nodes
-                    // are the only parts of this tree that should provide
-                    // inputs.
-
-                    // Falling damage starts from 30.0 as of time of writing
-                    // But keep in mind our 25 m/s gravity
-                    let is_falling_dangerous = data.vel.0.z < -20.0;
-
-                    let is_on_fire = read_data
-                        .buffs
-                        .get(entity)
-                        .map_or(false, |b| b.kinds.contains_key(&BuffKind::Burning));
-
-                    // If falling velocity is critical, throw everything
-                    // and save yourself!
-                    //
-                    // If can fly - fly.
-                    // If have glider - glide.
-                    // Else, rest in peace.
-                    if is_falling_dangerous && data.traversal_config.can_fly {
-                        data.fly_upward(controller)
-                    } else if is_falling_dangerous && data.glider_equipped {
-                        data.glider_fall(controller);
-                    // If on fire and able, stop, drop, and roll
-                    } else if is_on_fire
-                        && data.body.map_or(false, |b| b.is_humanoid())
-                        && data.physics_state.on_ground.is_some()
-                        && rng.gen_bool((2.0 * read_data.dt.0).into())
-                    {
-                        controller.inputs.move_dir = ori
-                            .look_vec()
-                            .xy()
-                            .try_normalized()
-                            .unwrap_or_else(Vec2::zero);
-                        controller.push_basic_input(InputKind::Roll);
-                    } else {
-                        // Target an entity that's attacking us if the attack
-                        // was recent and we have a health component
-                        match health {
-                            Some(health)
-                                if read_data.time.0 - health.last_change.time.0
-                                    < DAMAGE_MEMORY_DURATION =>
-                            {
-                                if let Some(by) = health.last_change.damage_by() {
-                                    if let Some(attacker) =
-                                        read_data.uid_allocator.retrieve_entity_internal(by.uid().0)
-                                    {
-                                        // If target is dead or invulnerable (for now, this only
-                                        // means safezone), untarget them and idle.
-                                        if is_dead_or_invulnerable(attacker, &read_data) {
-                                            agent.target = None;
-                                        } else if let Some(tgt_pos) =
-                                            read_data.positions.get(attacker)
-                                        {
-                                            if agent.target.is_none() {
-                                                controller.push_event(ControlEvent::Utterance(
-                                                    UtteranceKind::Angry,
-                                                ));
-                                            }
-
-                                            // Determine whether the new target should be a priority
-                                            // over the old one (i.e: because it's either close or
-                                            // because they attacked us)
-                                            let more_dangerous_than_old_target =
-                                                agent.target.map_or(true, |old_tgt| {
-                                                    if let Some(old_tgt_pos) =
-                                                        read_data.positions.get(old_tgt.target)
-                                                    {
-                                                        // Fuzzy factor that makes it harder for
-                                                        // players to cheese enemies by making them
-                                                        // quickly flip aggro between two players.
-                                                        // It
-                                                        // does this by only switching aggro if the
-                                                        // new target is closer to the enemy by a
-                                                        // specific proportional threshold.
-                                                        const FUZZY_DIST_COMPARISON: f32 = 0.8;
-                                                        // Only switch to new target if it is closer
-                                                        // than the old target, or if the old target
-                                                        // had not triggered aggro (the new target
-                                                        // has because damage always triggers it)
-                                                        let old_tgt_not_threat = !old_tgt.aggro_on;
-                                                        let old_tgt_further =
-                                                            tgt_pos.0.distance(pos.0)
-                                                                < old_tgt_pos.0.distance(pos.0)
-                                                                    * FUZZY_DIST_COMPARISON;
-                                                        let new_tgt_hostile = read_data
-                                                            .alignments
-                                                            .get(attacker)
-                                                            .zip(alignment)
-                                                            .map_or(false, |(attacker, us)| {
-                                                                us.hostile_towards(*attacker)
-                                                            });
-                                                        old_tgt_not_threat
-                                                            || (old_tgt_further && new_tgt_hostile)
-                                                    } else {
-                                                        true
-                                                    }
-                                                });
-
-                                            // Select the attacker as the new target
-                                            if more_dangerous_than_old_target {
-                                                agent.target = Some(Target {
-                                                    target: attacker,
-                                                    hostile: true,
-                                                    selected_at: read_data.time.0,
-                                                    aggro_on: true,
-                                                });
-                                            }
-
-                                            // Remember this attack if we're an RtSim entity
-                                            if let Some(tgt_stats) =
-                                                data.rtsim_entity.and(read_data.stats.get(attacker))
-                                            {
-                                                agent.add_enemy(&tgt_stats.name, read_data.time.0);
-                                            }
-                                        }
-                                    }
-                                }
-                            },
-                            _ => {},
-                        }
-
-                        if let Some(target_info) = agent.target {
-                            data.react_to_target(
-                                agent,
-                                controller,
-                                &read_data,
-                                &mut event_emitter,
-                                target_info,
-                                &mut rng,
-                            );
-                        } else {
-                            data.idle_tree(
-                                agent,
-                                controller,
-                                &read_data,
-                                &mut event_emitter,
-                                &mut rng,
-                            );
-                        }
-                    }
+                    // NPC. The trees themselves live in `behavior_tree`,
+                    // where each kind of body gets its own tree. Action
+                    // nodes mostly call methods on the `AgentData` struct,
+                    // and are the only parts of the tree that should
+                    // provide inputs.
+
+                    let mut behavior_data = BehaviorData {
+                        agent_data: &data,
+                        agent,
+                        controller,
+                        read_data: &read_data,
+                        event_emitter: &mut event_emitter,
+                        rng: &mut rng,
+                    };
+                    behavior_tree::tree_for(body).tick(&mut behavior_data);
 
                     debug_assert!(controller.inputs.move_dir.map(|e| !e.is_nan()).reduce_and());
                     debug_assert!(controller.inputs.look_dir.map(|e| !e.is_nan()).reduce_and());
@@ -421,45 +294,6 @@ impl<'a> AgentData<'a> {
     ////////////////////////////////////////
     // Subtrees
     ////////////////////////////////////////
-    fn react_to_target(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        read_data: &ReadData,
-        event_emitter: &mut Emitter<'_, ServerEvent>,
-        target_info: Target,
-        rng: &mut impl Rng,
-    ) {
-        let Target {
-            target, hostile, ..
-        } = target_info;
-        if let Some(tgt_health) = read_data.healths.get(target) {
-            // If target is dead, forget them
-            if tgt_health.is_dead {
-                if let Some(tgt_stats) = self.rtsim_entity.and(read_data.stats.get(target)) {
-                    agent.forget_enemy(&tgt_stats.name);
-                }
-                agent.target = None;
-            // Else, if target is hostile, hostile tree
-            } else if hostile {
-                self.hostile_tree(agent, controller, read_data, event_emitter, rng);
-            // Else, if owned, act as pet to them
-            } else if let Some(Alignment::Owned(uid)) = self.alignment {
-                if read_data.uids.get(target) == Some(uid) {
-                    self.react_as_pet(agent, controller, read_data, event_emitter, target, rng);
-                } else {
-                    agent.target = None;
-                    self.idle_tree(agent, controller, read_data, event_emitter, rng);
-                };
-            } else {
-                self.idle_tree(agent, controller, read_data, event_emitter, rng);
-            }
-        } else {
-            agent.target = None;
-            self.idle_tree(agent, controller, read_data, event_emitter, rng);
-        }
-    }
-
     fn react_as_pet(
         &self,
         agent: &mut Agent,
@@ -581,100 +415,79 @@ impl<'a> AgentData<'a> {
         }
     }
 
-    fn hostile_tree(
+    ////////////////////////////////////////
+    // Action Nodes
+    ////////////////////////////////////////
+
+    /// Targets the entity which attacked us last, if it is more of a threat
+    /// than the current target
+    fn target_recent_attacker(
         &self,
         agent: &mut Agent,
         controller: &mut Controller,
         read_data: &ReadData,
-        event_emitter: &mut Emitter<'_, ServerEvent>,
-        rng: &mut impl Rng,
     ) {
-        if self.damage < HEALING_ITEM_THRESHOLD && self.heal_self(agent, controller, false) {
-            agent.action_state.timer = 0.01;
-            return;
-        }
+        let attacker = match self
+            .health
+            .and_then(|health| health.last_change.damage_by())
+            .and_then(|by| read_data.uid_allocator.retrieve_entity_internal(by.uid().0))
+        {
+            Some(attacker) => attacker,
+            None => return,
+        };
 
-        if let Some(AgentEvent::Hurt) = agent.inbox.pop_front() {
-            // Hurt utterances at random upon receiving damage
-            if rng.gen::<f32>() < 0.4 {
-                controller.push_utterance(UtteranceKind::Hurt);
+        // If target is dead or invulnerable (for now, this only means safezone),
+        // untarget them and idle.
+        if is_dead_or_invulnerable(attacker, read_data) {
+            agent.target = None;
+        } else if let Some(tgt_pos) = read_data.positions.get(attacker) {
+            if agent.target.is_none() {
+                controller.push_event(ControlEvent::Utterance(UtteranceKind::Angry));
             }
-        }
 
-        if let Some(Target {
-            target,
-            selected_at,
-            aggro_on,
-            ..
-        }) = &mut agent.target
-        {
-            let target = *target;
-            let selected_at = *selected_at;
-
-            if let Some(tgt_pos) = read_data.positions.get(target) {
-                let dist_sqrd = self.pos.0.distance_squared(tgt_pos.0);
-                let in_aggro_range = agent
-                    .psyche
-                    .aggro_dist
-                    .map_or(true, |ad| dist_sqrd < ad.powi(2));
-
-                if in_aggro_range {
-                    *aggro_on = true;
-                }
-                let aggro_on = *aggro_on;
-
-                let should_flee = self.damage.min(1.0) < agent.psyche.flee_health;
-                if should_flee {
-                    let has_opportunity_to_flee = agent.action_state.timer < FLEE_DURATION;
-                    let within_flee_distance = dist_sqrd < MAX_FLEE_DIST.powi(2);
-
-                    // FIXME: Using the action state timer to see if an agent is allowed to speak is
-                    // a hack.
-                    if agent.action_state.timer == 0.0 {
-                        self.cry_out(agent, read_data.time.0, event_emitter);
-                        agent.action_state.timer = 0.01;
-                    } else if within_flee_distance && has_opportunity_to_flee {
-                        self.flee(agent, controller, &read_data.terrain, tgt_pos);
-                        agent.action_state.timer += read_data.dt.0;
-                    } else {
-                        agent.action_state.timer = 0.0;
-                        agent.target = None;
-                        self.idle(agent, controller, read_data, rng);
-                    }
-                } else if is_dead(target, read_data) {
-                    self.exclaim_relief_about_enemy_dead(agent, event_emitter);
-                    agent.target = None;
-                    self.idle(agent, controller, read_data, rng);
-                } else if is_invulnerable(target, read_data) {
-                    agent.target = None;
-                    self.idle(agent, controller, read_data, rng);
+            // Determine whether the new target should be a priority over the old one
+            // (i.e: because it's either close or because they attacked us)
+            let more_dangerous_than_old_target = agent.target.map_or(true, |old_tgt| {
+                if let Some(old_tgt_pos) = read_data.positions.get(old_tgt.target) {
+                    // Fuzzy factor that makes it harder for players to cheese enemies by
+                    // making them quickly flip aggro between two players. It does this by
+                    // only switching aggro if the new target is closer to the enemy by a
+                    // specific proportional threshold.
+                    const FUZZY_DIST_COMPARISON: f32 = 0.8;
+                    // Only switch to new target if it is closer than the old target, or if
+                    // the old target had not triggered aggro (the new target has because
+                    // damage always triggers it)
+                    let old_tgt_not_threat = !old_tgt.aggro_on;
+                    let old_tgt_further = tgt_pos.0.distance(self.pos.0)
+                        < old_tgt_pos.0.distance(self.pos.0) * FUZZY_DIST_COMPARISON;
+                    let new_tgt_hostile = read_data
+                        .alignments
+                        .get(attacker)
+                        .zip(self.alignment)
+                        .map_or(false, |(attacker, us)| us.hostile_towards(*attacker));
+                    old_tgt_not_threat || (old_tgt_further && new_tgt_hostile)
                 } else {
-                    let is_time_to_retarget =
-                        read_data.time.0 - selected_at > RETARGETING_THRESHOLD_SECONDS;
+                    true
+                }
+            });
 
-                    if !in_aggro_range && is_time_to_retarget {
-                        self.choose_target(agent, controller, read_data, event_emitter);
-                    }
+            // Select the attacker as the new target
+            if more_dangerous_than_old_target {
+                agent.target = Some(Target {
+                    target: attacker,
+                    hostile: true,
+                    selected_at: read_data.time.0,
+                    aggro_on: true,
+                });
+            }
 
-                    if aggro_on {
-                        let target_data = TargetData::new(
-                            tgt_pos,
-                            read_data.bodies.get(target),
-                            read_data.scales.get(target),
-                        );
-                        self.attack(agent, controller, &target_data, read_data, rng);
-                    } else {
-                        self.menacing(agent, target, controller, read_data, event_emitter, rng);
-                    }
-                }
+            // Remember this attack if we're an RtSim entity
+            if let Some(tgt_stats) = self.rtsim_entity.and(read_data.stats.get(attacker)) {
+                agent.add_enemy(&tgt_stats.name, read_data.time.0);
             }
         }
     }
 
-    ////////////////////////////////////////
-    // Action Nodes
-    ////////////////////////////////////////
-
     fn glider_fall(&self, controller: &mut Controller) {
         controller.push_action(ControlAction::GlideWield);
 
@@ -794,8 +607,12 @@ impl<'a> AgentData<'a> {
                                 let height_approx = self.pos.0.z
                                     - read_data
                                         .world
-                                        .sim()
-                                        .get_alt_approx(self.pos.0.xy().map(|x: f32| x as i32))
+                                        .as_ref()
+                                        .and_then(|world| {
+                                            world.sim().get_alt_approx(
+                                                self.pos.0.xy().map(|x: f32| x as i32),
+                                            )
+                                        })
                                         .unwrap_or(0.0);
                                 #[cfg(not(feature = "worldgen"))]
                                 let height_approx = self.pos.0.z;
@@ -853,6 +670,12 @@ impl<'a> AgentData<'a> {
                     controller.push_action(ControlAction::Unwield);
                 }
             }
+        } else if agent.rtsim_controller.activity == Some(Activity::Sleep) {
+            // Villagers go to sleep once they got home
+            controller.inputs.move_dir = Vec2::zero();
+            if !matches!(self.char_state, CharacterState::Sit) {
+                controller.push_action(ControlAction::Sit);
+            }
         } else {
             agent.bearing += Vec2::new(rng.gen::<f32>() - 0.5, rng.gen::<f32>() - 0.5) * 0.1
                 - agent.bearing * 0.003
@@ -1698,88 +1521,15 @@ impl<'a> AgentData<'a> {
         read_data: &ReadData,
         rng: &mut impl Rng,
     ) {
-        let tool_tactic = |tool_kind| match tool_kind {
-            ToolKind::Bow => Tactic::Bow,
-            ToolKind::Staff => Tactic::Staff,
-            ToolKind::Sceptre => Tactic::Sceptre,
-            ToolKind::Hammer => Tactic::Hammer,
-            ToolKind::Sword | ToolKind::Blowgun => Tactic::Sword,
-            ToolKind::Axe => Tactic::Axe,
-            _ => Tactic::SimpleMelee,
+        let tactic_manifest = TACTIC_MANIFEST.read();
+        let tactic =
+            tactic_manifest.tactic_for(self.inventory.equipped(EquipSlot::ActiveMainhand));
+        // Custom tactics missing from the manifest fall back to simple melee
+        let custom_tactic = match &tactic {
+            Tactic::Custom(name) => tactic_manifest.tactics.get(name),
+            _ => None,
         };
 
-        let tactic = self
-            .inventory
-            .equipped(EquipSlot::ActiveMainhand)
-            .as_ref()
-            .map(|item| {
-                if let Some(ability_spec) = item.ability_spec() {
-                    match ability_spec {
-                        AbilitySpec::Custom(spec) => match spec.as_str() {
-                            "Oni" | "Sword Simple" => Tactic::Sword,
-                            "Staff Simple" => Tactic::Staff,
-                            "Bow Simple" => Tactic::Bow,
-                            "Stone Golem" => Tactic::StoneGolem,
-                            "Quad Med Quick" => Tactic::CircleCharge {
-                                radius: 3,
-                                circle_time: 2,
-                            },
-                            "Quad Med Jump" => Tactic::QuadMedJump,
-                            "Quad Med Charge" => Tactic::CircleCharge {
-                                radius: 6,
-                                circle_time: 1,
-                            },
-                            "Quad Med Basic" => Tactic::QuadMedBasic,
-                            "Asp" | "Maneater" => Tactic::QuadLowRanged,
-                            "Quad Low Breathe" | "Quad Low Beam" | "Basilisk" => {
-                                Tactic::QuadLowBeam
-                            },
-                            "Quad Low Tail" | "Husk Brute" => Tactic::TailSlap,
-                            "Quad Low Quick" => Tactic::QuadLowQuick,
-                            "Quad Low Basic" => Tactic::QuadLowBasic,
-                            "Theropod Basic" | "Theropod Bird" => Tactic::Theropod,
-                            "Arthropod Basic" => Tactic::ArthropodBasic,
-                            "Arthropod Charge" => Tactic::ArthropodCharge,
-                            "Arthropod Ranged" => Tactic::ArthropodRanged,
-                            "Arthropod Leap" => Tactic::ArthropodLeap,
-                            "Theropod Charge" => Tactic::CircleCharge {
-                                radius: 6,
-                                circle_time: 1,
-                            },
-                            "Turret" => Tactic::Turret,
-                            "Haniwa Sentry" => Tactic::RotatingTurret,
-                            "Bird Large Breathe" => Tactic::BirdLargeBreathe,
-                            "Bird Large Fire" => Tactic::BirdLargeFire,
-                            "Bird Large Basic" => Tactic::BirdLargeBasic,
-                            "Mindflayer" => Tactic::Mindflayer,
-                            "Minotaur" => Tactic::Minotaur,
-                            "Clay Golem" => Tactic::ClayGolem,
-                            "Tidal Warrior" => Tactic::TidalWarrior,
-                            "Tidal Totem"
-                            | "Tornado"
-                            | "Gnarling Totem Red"
-                            | "Gnarling Totem Green"
-                            | "Gnarling Totem White" => Tactic::RadialTurret,
-                            "Yeti" => Tactic::Yeti,
-                            "Harvester" => Tactic::Harvester,
-                            "Gnarling Dagger" => Tactic::SimpleBackstab,
-                            "Gnarling Blowgun" => Tactic::ElevatedRanged,
-                            "Deadwood" => Tactic::Deadwood,
-                            "Mandragora" => Tactic::Mandragora,
-                            "Wood Golem" => Tactic::WoodGolem,
-                            "Gnarling Chieftain" => Tactic::GnarlingChieftain,
-                            _ => Tactic::SimpleMelee,
-                        },
-                        AbilitySpec::Tool(tool_kind) => tool_tactic(*tool_kind),
-                    }
-                } else if let ItemKind::Tool(tool) = &item.kind() {
-                    tool_tactic(tool.kind)
-                } else {
-                    Tactic::SimpleMelee
-                }
-            })
-            .unwrap_or(Tactic::SimpleMelee);
-
         // Wield the weapon as running towards the target
         controller.push_action(ControlAction::Wield);
 
@@ -1812,7 +1562,7 @@ impl<'a> AgentData<'a> {
                    } else if matches!(tactic, Tactic::QuadLowRanged) {
                        -1.0
                    } else {
-                       0.0
+                       custom_tactic.map_or(0.0, |tactic| tactic.aim_offset)
                    };
 
         // FIXME:
@@ -1945,6 +1695,25 @@ impl<'a> AgentData<'a> {
         // Match on tactic. Each tactic has different controls
         // depending on the distance from the agent to the target
         match tactic {
+            Tactic::Custom(_) => match custom_tactic {
+                Some(custom_tactic) => self.handle_custom_tactic(
+                    agent,
+                    controller,
+                    &attack_data,
+                    tgt_data,
+                    read_data,
+                    custom_tactic,
+                    rng,
+                ),
+                None => self.handle_simple_melee(
+                    agent,
+                    controller,
+                    &attack_data,
+                    tgt_data,
+                    read_data,
+                    rng,
+                ),
+            },
             Tactic::SimpleMelee => {
                 self.handle_simple_melee(agent, controller, &attack_data, tgt_data, read_data, rng)
             },
@@ -1997,20 +1766,6 @@ impl<'a> AgentData<'a> {
             Tactic::TailSlap => {
                 self.handle_tail_slap_attack(agent, controller, &attack_data, tgt_data, read_data)
             },
-            Tactic::QuadLowQuick => self.handle_quadlow_quick_attack(
-                agent,
-                controller,
-                &attack_data,
-                tgt_data,
-                read_data,
-            ),
-            Tactic::QuadLowBasic => self.handle_quadlow_basic_attack(
-                agent,
-                controller,
-                &attack_data,
-                tgt_data,
-                read_data,
-            ),
             Tactic::QuadMedJump => self.handle_quadmed_jump_attack(
                 agent,
                 controller,
@@ -2018,13 +1773,6 @@ impl<'a> AgentData<'a> {
                 tgt_data,
                 read_data,
             ),
-            Tactic::QuadMedBasic => self.handle_quadmed_basic_attack(
-                agent,
-                controller,
-                &attack_data,
-                tgt_data,
-                read_data,
-            ),
             Tactic::QuadLowBeam => self.handle_quadlow_beam_attack(
                 agent,
                 controller,
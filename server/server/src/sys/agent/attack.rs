@@ -996,64 +996,6 @@ impl<'a> AgentData<'a> {
         }
     }
 
-    pub fn handle_quadlow_quick_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 90.0
-            && attack_data.dist_sqrd < (1.5 * attack_data.min_attack_dist).powi(2)
-        {
-            controller.inputs.move_dir = Vec2::zero();
-            controller.push_basic_input(InputKind::Secondary);
-        } else if attack_data.dist_sqrd < (3.0 * attack_data.min_attack_dist).powi(2)
-            && attack_data.dist_sqrd > (2.0 * attack_data.min_attack_dist).powi(2)
-            && attack_data.angle < 90.0
-        {
-            controller.push_basic_input(InputKind::Primary);
-            controller.inputs.move_dir = (tgt_data.pos.0 - self.pos.0)
-                .xy()
-                .rotated_z(-0.47 * PI)
-                .try_normalized()
-                .unwrap_or_else(Vec2::unit_y);
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, false, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, false, None);
-        }
-    }
-
-    pub fn handle_quadlow_basic_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 70.0
-            && attack_data.dist_sqrd < (1.3 * attack_data.min_attack_dist).powi(2)
-        {
-            controller.inputs.move_dir = Vec2::zero();
-            if agent.action_state.timer > 5.0 {
-                agent.action_state.timer = 0.0;
-            } else if agent.action_state.timer > 2.0 {
-                controller.push_basic_input(InputKind::Secondary);
-                agent.action_state.timer += read_data.dt.0;
-            } else {
-                controller.push_basic_input(InputKind::Primary);
-                agent.action_state.timer += read_data.dt.0;
-            }
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, false, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, false, None);
-        }
-    }
-
     pub fn handle_quadmed_jump_attack(
         &self,
         agent: &mut Agent,
@@ -1088,32 +1030,6 @@ impl<'a> AgentData<'a> {
         }
     }
 
-    pub fn handle_quadmed_basic_attack(
-        &self,
-        agent: &mut Agent,
-        controller: &mut Controller,
-        attack_data: &AttackData,
-        tgt_data: &TargetData,
-        read_data: &ReadData,
-    ) {
-        if attack_data.angle < 90.0 && attack_data.in_min_range() {
-            controller.inputs.move_dir = Vec2::zero();
-            if agent.action_state.timer < 2.0 {
-                controller.push_basic_input(InputKind::Secondary);
-                agent.action_state.timer += read_data.dt.0;
-            } else if agent.action_state.timer < 3.0 {
-                controller.push_basic_input(InputKind::Primary);
-                agent.action_state.timer += read_data.dt.0;
-            } else {
-                agent.action_state.timer = 0.0;
-            }
-        } else if attack_data.dist_sqrd < MAX_PATH_DIST.powi(2) {
-            self.path_toward_target(agent, controller, tgt_data, read_data, true, false, None);
-        } else {
-            self.path_toward_target(agent, controller, tgt_data, read_data, false, false, None);
-        }
-    }
-
     pub fn handle_quadlow_beam_attack(
         &self,
         agent: &mut Agent,
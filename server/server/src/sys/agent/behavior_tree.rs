@@ -0,0 +1,1129 @@
+//! Behaviour trees which make the decisions of agents
+//!
+//! A tree is made of [`Node`]s. Conditions look at the world without changing
+//! anything, while actions make the agent do something. Trees are static and
+//! only ever see the world through the [`AgentWorld`] trait, which is
+//! implemented by [`BehaviorData`] in the game and by mock worlds in tests.
+//!
+//! Each tick should lead to one action which decides what the agent does.
+//! Actions which only prepare the decision, like remembering who attacked the
+//! agent, fail so that the tree moves on to the next node.
+
+use crate::{
+    pack::{PackMember, PackRole, FLANK_DIST, RANGED_HOLD_DIST},
+    sys::agent::{
+        consts::{
+            DAMAGE_MEMORY_DURATION, FLEE_DURATION, HEALING_ITEM_THRESHOLD, MAX_FLEE_DIST,
+            RETARGETING_THRESHOLD_SECONDS,
+        },
+        util::{get_entity_by_id, is_dead, is_invulnerable},
+        AgentData, ReadData, TargetData,
+    },
+};
+use common::{
+    comp::{
+        agent::{AgentEvent, Target},
+        buff::BuffKind,
+        Agent, Alignment, Body, Controller, InputKind, PetCommand, Pos, UtteranceKind,
+    },
+    event::{Emitter, ServerEvent},
+};
+use rand::{rngs::SmallRng, Rng};
+use vek::*;
+
+pub enum Node {
+    /// Ticks its children in order until one of them succeeds
+    Selector(&'static [Node]),
+    /// Ticks its children in order until one of them fails
+    Sequence(&'static [Node]),
+    /// Succeeds when its child fails, and fails when it succeeds
+    Invert(&'static Node),
+    /// Ticks its child and succeeds no matter the outcome
+    Succeed(&'static Node),
+    Condition(fn(&dyn AgentWorld) -> bool),
+    /// Returns whether the action was taken
+    Action(fn(&mut dyn AgentWorld) -> bool),
+}
+
+impl Node {
+    pub fn tick(&self, world: &mut dyn AgentWorld) -> bool {
+        match self {
+            Node::Selector(children) => children.iter().any(|child| child.tick(world)),
+            Node::Sequence(children) => children.iter().all(|child| child.tick(world)),
+            Node::Invert(child) => !child.tick(world),
+            Node::Succeed(child) => {
+                child.tick(world);
+                true
+            },
+            Node::Condition(condition) => condition(world),
+            Node::Action(action) => action(world),
+        }
+    }
+}
+
+/// Everything the behaviour trees of agents can check and do
+pub trait AgentWorld {
+    // Conditions
+    fn is_falling_dangerously(&self) -> bool;
+    fn can_fly(&self) -> bool;
+    fn has_glider(&self) -> bool;
+    fn is_on_fire(&self) -> bool;
+    fn is_on_ground(&self) -> bool;
+    /// Rolls the dice for whether to roll on the ground this tick. As this
+    /// uses up randomness it is used as an action.
+    fn wants_to_roll(&mut self) -> bool;
+    fn has_target(&self) -> bool;
+    fn target_has_health(&self) -> bool;
+    fn target_is_dead(&self) -> bool;
+    fn target_is_hostile(&self) -> bool;
+    fn target_is_invulnerable(&self) -> bool;
+    fn target_position_known(&self) -> bool;
+    fn is_owned(&self) -> bool;
+    fn is_owned_by_target(&self) -> bool;
+    fn needs_healing(&self) -> bool;
+    fn should_flee(&self) -> bool;
+    fn has_cried_out(&self) -> bool;
+    fn can_keep_fleeing(&self) -> bool;
+    fn should_retarget(&self) -> bool;
+    fn is_aggro(&self) -> bool;
+    /// Whether the agent is a pack member which should take position around
+    /// the target before closing in
+    fn should_flank(&self) -> bool;
+    /// Whether the agent is a ranged pack member which the target came too
+    /// close to
+    fn should_keep_distance(&self) -> bool;
+    /// Whether the agent is a pet told not to fight
+    fn is_passive(&self) -> bool;
+    /// Whether the agent is a pet told to stay where it is
+    fn is_told_to_stay(&self) -> bool;
+
+    // Actions
+    fn fly_upward(&mut self) -> bool;
+    fn glide(&mut self) -> bool;
+    fn roll(&mut self) -> bool;
+    /// Targets whoever attacked the agent recently. This never ends the tick.
+    fn target_attacker(&mut self) -> bool;
+    /// Reacts to being hurt. This never ends the tick.
+    fn utter_if_hurt(&mut self) -> bool;
+    /// Becomes aggressive once the target is in range. This never ends the
+    /// tick.
+    fn update_aggro(&mut self) -> bool;
+    fn heal_self(&mut self) -> bool;
+    fn cry_out(&mut self) -> bool;
+    fn flee(&mut self) -> bool;
+    fn give_up_fleeing(&mut self) -> bool;
+    fn celebrate_dead_target(&mut self) -> bool;
+    fn forget_target(&mut self) -> bool;
+    /// Looks for a new target. This never ends the tick.
+    fn choose_target(&mut self) -> bool;
+    fn attack(&mut self) -> bool;
+    fn flank(&mut self) -> bool;
+    fn back_off(&mut self) -> bool;
+    fn menacing(&mut self) -> bool;
+    fn react_as_pet(&mut self) -> bool;
+    /// Targets whoever the owner of the pet told it to attack. This never ends
+    /// the tick.
+    fn target_commanded(&mut self) -> bool;
+    fn follow_owner(&mut self) -> bool;
+    fn stay(&mut self) -> bool;
+    fn idle(&mut self) -> bool;
+    /// The full idle behaviour: reacting to sounds, talking, picking targets
+    /// and wandering around
+    fn idle_tree(&mut self) -> bool;
+}
+
+const FLEE: Node = Node::Selector(&[
+    Node::Sequence(&[
+        Node::Invert(&Node::Condition(|w| w.has_cried_out())),
+        Node::Action(|w| w.cry_out()),
+    ]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.can_keep_fleeing()),
+        Node::Action(|w| w.flee()),
+    ]),
+    Node::Action(|w| w.give_up_fleeing()),
+]);
+
+/// Packs surround their target, while their ranged members keep their distance
+const ENGAGE: Node = Node::Selector(&[
+    Node::Sequence(&[
+        Node::Condition(|w| w.should_flank()),
+        Node::Action(|w| w.flank()),
+    ]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.should_keep_distance()),
+        Node::Action(|w| w.attack()),
+        Node::Action(|w| w.back_off()),
+    ]),
+    Node::Action(|w| w.attack()),
+]);
+
+const FIGHT: Node = Node::Selector(&[
+    Node::Sequence(&[
+        Node::Condition(|w| w.should_retarget()),
+        Node::Action(|w| w.choose_target()),
+    ]),
+    Node::Sequence(&[Node::Condition(|w| w.is_aggro()), ENGAGE]),
+    Node::Action(|w| w.menacing()),
+]);
+
+const HOSTILE: Node = Node::Selector(&[
+    Node::Sequence(&[
+        Node::Condition(|w| w.needs_healing()),
+        Node::Action(|w| w.heal_self()),
+    ]),
+    Node::Action(|w| w.utter_if_hurt()),
+    // Nothing to do if the target can't be found
+    Node::Invert(&Node::Condition(|w| w.target_position_known())),
+    Node::Action(|w| w.update_aggro()),
+    Node::Sequence(&[Node::Condition(|w| w.should_flee()), FLEE]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.target_is_dead()),
+        Node::Action(|w| w.celebrate_dead_target()),
+        Node::Action(|w| w.forget_target()),
+        Node::Action(|w| w.idle()),
+    ]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.target_is_invulnerable()),
+        Node::Action(|w| w.forget_target()),
+        Node::Action(|w| w.idle()),
+    ]),
+    FIGHT,
+]);
+
+const REACT_TO_TARGET: Node = Node::Selector(&[
+    Node::Sequence(&[
+        Node::Invert(&Node::Condition(|w| w.target_has_health())),
+        Node::Action(|w| w.forget_target()),
+        Node::Action(|w| w.idle_tree()),
+    ]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.target_is_dead()),
+        Node::Action(|w| w.forget_target()),
+    ]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.target_is_hostile()),
+        Node::Succeed(&HOSTILE),
+    ]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.is_owned_by_target()),
+        Node::Action(|w| w.react_as_pet()),
+    ]),
+    Node::Sequence(&[
+        Node::Condition(|w| w.is_owned()),
+        Node::Action(|w| w.forget_target()),
+        Node::Action(|w| w.idle_tree()),
+    ]),
+    Node::Action(|w| w.idle_tree()),
+]);
+
+/// Saves the agent when falling fast enough to get hurt
+const SURVIVE_FALL: Node = Node::Sequence(&[
+    Node::Condition(|w| w.is_falling_dangerously()),
+    Node::Selector(&[
+        Node::Sequence(&[
+            Node::Condition(|w| w.can_fly()),
+            Node::Action(|w| w.fly_upward()),
+        ]),
+        Node::Sequence(&[
+            Node::Condition(|w| w.has_glider()),
+            Node::Action(|w| w.glide()),
+        ]),
+    ]),
+]);
+
+/// Stop, drop and roll
+const EXTINGUISH: Node = Node::Sequence(&[
+    Node::Condition(|w| w.is_on_fire()),
+    Node::Condition(|w| w.is_on_ground()),
+    Node::Action(|w| w.wants_to_roll()),
+    Node::Action(|w| w.roll()),
+]);
+
+/// Pets do what their owner told them to
+const OBEY_OWNER: Node = Node::Selector(&[
+    Node::Sequence(&[
+        Node::Condition(|w| w.is_passive()),
+        Node::Action(|w| w.forget_target()),
+        Node::Action(|w| w.follow_owner()),
+    ]),
+    Node::Action(|w| w.target_commanded()),
+    Node::Sequence(&[
+        Node::Condition(|w| w.is_told_to_stay()),
+        Node::Invert(&Node::Condition(|w| w.target_is_hostile())),
+        Node::Action(|w| w.stay()),
+    ]),
+]);
+
+const TARGET_OR_IDLE: Node = Node::Selector(&[
+    Node::Action(|w| w.target_attacker()),
+    OBEY_OWNER,
+    Node::Sequence(&[Node::Condition(|w| w.has_target()), REACT_TO_TARGET]),
+    Node::Action(|w| w.idle_tree()),
+]);
+
+pub static HUMANOID_TREE: Node = Node::Selector(&[SURVIVE_FALL, EXTINGUISH, TARGET_OR_IDLE]);
+
+pub static CREATURE_TREE: Node = Node::Selector(&[SURVIVE_FALL, TARGET_OR_IDLE]);
+
+/// The tree that makes the decisions of agents with the given body
+pub fn tree_for(body: Option<&Body>) -> &'static Node {
+    match body {
+        Some(body) if body.is_humanoid() => &HUMANOID_TREE,
+        _ => &CREATURE_TREE,
+    }
+}
+
+/// The world as seen by the agent whose turn it is
+pub struct BehaviorData<'a, 'b, 'c> {
+    pub agent_data: &'a AgentData<'b>,
+    pub agent: &'a mut Agent,
+    pub controller: &'a mut Controller,
+    pub read_data: &'a ReadData<'b>,
+    pub event_emitter: &'a mut Emitter<'c, ServerEvent>,
+    pub rng: &'a mut SmallRng,
+}
+
+impl<'a, 'b, 'c> BehaviorData<'a, 'b, 'c> {
+    fn target(&self) -> Option<Target> { self.agent.target }
+
+    fn target_pos(&self) -> Option<Vec3<f32>> {
+        self.target()
+            .and_then(|target| self.read_data.positions.get(target.target))
+            .map(|pos| pos.0)
+    }
+
+    fn pet_command(&self) -> Option<PetCommand> {
+        self.read_data
+            .pets
+            .get(*self.agent_data.entity)
+            .map(|pet| pet.command)
+    }
+
+    fn owner(&self) -> Option<specs::Entity> {
+        match self.agent_data.alignment {
+            Some(Alignment::Owned(owner)) => get_entity_by_id(owner.id(), self.read_data),
+            _ => None,
+        }
+    }
+
+    fn pack_member(&self) -> Option<&PackMember> {
+        self.read_data.pack_members.get(*self.agent_data.entity)
+    }
+
+    /// Where a melee pack member waits before closing in on the target
+    fn flank_pos(&self) -> Option<Vec3<f32>> {
+        let member = self
+            .pack_member()
+            .filter(|member| member.role == PackRole::Melee)?;
+        let tgt_pos = self.target_pos()?;
+        let leader_pos = self.read_data.positions.get(member.leader)?.0;
+        let front = (leader_pos - tgt_pos)
+            .xy()
+            .try_normalized()
+            .unwrap_or_else(Vec2::unit_x);
+        Some(tgt_pos + Vec3::from(front.rotated_z(member.flank_angle)) * FLANK_DIST)
+    }
+}
+
+impl<'a, 'b, 'c> AgentWorld for BehaviorData<'a, 'b, 'c> {
+    fn is_falling_dangerously(&self) -> bool {
+        // Falling damage starts from 30.0 as of time of writing
+        // But keep in mind our 25 m/s gravity
+        self.agent_data.vel.0.z < -20.0
+    }
+
+    fn can_fly(&self) -> bool { self.agent_data.traversal_config.can_fly }
+
+    fn has_glider(&self) -> bool { self.agent_data.glider_equipped }
+
+    fn is_on_fire(&self) -> bool {
+        self.read_data
+            .buffs
+            .get(*self.agent_data.entity)
+            .map_or(false, |b| b.kinds.contains_key(&BuffKind::Burning))
+    }
+
+    fn is_on_ground(&self) -> bool { self.agent_data.physics_state.on_ground.is_some() }
+
+    fn wants_to_roll(&mut self) -> bool { self.rng.gen_bool((2.0 * self.read_data.dt.0).into()) }
+
+    fn has_target(&self) -> bool { self.agent.target.is_some() }
+
+    fn target_has_health(&self) -> bool {
+        self.target()
+            .map_or(false, |target| self.read_data.healths.contains(target.target))
+    }
+
+    fn target_is_dead(&self) -> bool {
+        self.target()
+            .map_or(false, |target| is_dead(target.target, self.read_data))
+    }
+
+    fn target_is_hostile(&self) -> bool { self.target().map_or(false, |target| target.hostile) }
+
+    fn target_is_invulnerable(&self) -> bool {
+        self.target()
+            .map_or(false, |target| is_invulnerable(target.target, self.read_data))
+    }
+
+    fn target_position_known(&self) -> bool { self.target_pos().is_some() }
+
+    fn is_owned(&self) -> bool { matches!(self.agent_data.alignment, Some(Alignment::Owned(_))) }
+
+    fn is_owned_by_target(&self) -> bool {
+        match (self.agent_data.alignment, self.target()) {
+            (Some(Alignment::Owned(owner)), Some(target)) => {
+                self.read_data.uids.get(target.target) == Some(owner)
+            },
+            _ => false,
+        }
+    }
+
+    fn needs_healing(&self) -> bool { self.agent_data.damage < HEALING_ITEM_THRESHOLD }
+
+    fn should_flee(&self) -> bool {
+        self.agent_data.damage.min(1.0) < self.agent.psyche.flee_health
+    }
+
+    // FIXME: Using the action state timer to see if an agent is allowed to speak is
+    // a hack.
+    fn has_cried_out(&self) -> bool { self.agent.action_state.timer != 0.0 }
+
+    fn can_keep_fleeing(&self) -> bool {
+        self.agent.action_state.timer < FLEE_DURATION
+            && self.target_pos().map_or(false, |tgt_pos| {
+                self.agent_data.pos.0.distance_squared(tgt_pos) < MAX_FLEE_DIST.powi(2)
+            })
+    }
+
+    fn should_retarget(&self) -> bool {
+        match (self.target(), self.target_pos()) {
+            (Some(target), Some(tgt_pos)) => {
+                let in_aggro_range = self.agent.psyche.aggro_dist.map_or(true, |ad| {
+                    self.agent_data.pos.0.distance_squared(tgt_pos) < ad.powi(2)
+                });
+                !in_aggro_range
+                    && self.read_data.time.0 - target.selected_at > RETARGETING_THRESHOLD_SECONDS
+            },
+            _ => false,
+        }
+    }
+
+    fn is_aggro(&self) -> bool { self.target().map_or(false, |target| target.aggro_on) }
+
+    fn should_flank(&self) -> bool {
+        match (self.flank_pos(), self.target_pos()) {
+            (Some(flank_pos), Some(tgt_pos)) => {
+                let pos = self.agent_data.pos.0;
+                // Close in once in position, or when the target came close anyway
+                pos.distance_squared(flank_pos) > 3.0_f32.powi(2)
+                    && pos.distance_squared(tgt_pos) > (FLANK_DIST * 0.75).powi(2)
+            },
+            _ => false,
+        }
+    }
+
+    fn should_keep_distance(&self) -> bool {
+        self.pack_member()
+            .filter(|member| member.role == PackRole::Ranged)
+            .zip(self.target_pos())
+            .map_or(false, |(_, tgt_pos)| {
+                self.agent_data.pos.0.distance_squared(tgt_pos) < RANGED_HOLD_DIST.powi(2)
+            })
+    }
+
+    fn is_passive(&self) -> bool { self.pet_command() == Some(PetCommand::Passive) }
+
+    fn is_told_to_stay(&self) -> bool { self.pet_command() == Some(PetCommand::Stay) }
+
+    fn fly_upward(&mut self) -> bool {
+        self.agent_data.fly_upward(self.controller);
+        true
+    }
+
+    fn glide(&mut self) -> bool {
+        self.agent_data.glider_fall(self.controller);
+        true
+    }
+
+    fn roll(&mut self) -> bool {
+        self.controller.inputs.move_dir = self
+            .agent_data
+            .ori
+            .look_vec()
+            .xy()
+            .try_normalized()
+            .unwrap_or_else(Vec2::zero);
+        self.controller.push_basic_input(InputKind::Roll);
+        true
+    }
+
+    fn target_attacker(&mut self) -> bool {
+        // Target an entity that's attacking us if the attack was recent and we have a
+        // health component
+        if let Some(health) = self.agent_data.health {
+            if self.read_data.time.0 - health.last_change.time.0 < DAMAGE_MEMORY_DURATION {
+                self.agent_data
+                    .target_recent_attacker(self.agent, self.controller, self.read_data);
+            }
+        }
+        false
+    }
+
+    fn utter_if_hurt(&mut self) -> bool {
+        if let Some(AgentEvent::Hurt) = self.agent.inbox.pop_front() {
+            // Hurt utterances at random upon receiving damage
+            if self.rng.gen::<f32>() < 0.4 {
+                self.controller.push_utterance(UtteranceKind::Hurt);
+            }
+        }
+        false
+    }
+
+    fn update_aggro(&mut self) -> bool {
+        let in_aggro_range = match self.target_pos() {
+            Some(tgt_pos) => self.agent.psyche.aggro_dist.map_or(true, |ad| {
+                self.agent_data.pos.0.distance_squared(tgt_pos) < ad.powi(2)
+            }),
+            None => false,
+        };
+        if let Some(target) = self.agent.target.as_mut() {
+            target.aggro_on |= in_aggro_range;
+        }
+        false
+    }
+
+    fn heal_self(&mut self) -> bool {
+        if self.agent_data.heal_self(self.agent, self.controller, false) {
+            self.agent.action_state.timer = 0.01;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn cry_out(&mut self) -> bool {
+        self.agent_data
+            .cry_out(self.agent, self.read_data.time.0, self.event_emitter);
+        self.agent.action_state.timer = 0.01;
+        true
+    }
+
+    fn flee(&mut self) -> bool {
+        let read_data = self.read_data;
+        match self.target().and_then(|t| read_data.positions.get(t.target)) {
+            Some(tgt_pos) => {
+                self.agent_data
+                    .flee(self.agent, self.controller, &read_data.terrain, tgt_pos);
+                self.agent.action_state.timer += read_data.dt.0;
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn give_up_fleeing(&mut self) -> bool {
+        self.agent.action_state.timer = 0.0;
+        self.agent.target = None;
+        self.idle()
+    }
+
+    fn celebrate_dead_target(&mut self) -> bool {
+        self.agent_data
+            .exclaim_relief_about_enemy_dead(self.agent, self.event_emitter);
+        true
+    }
+
+    fn forget_target(&mut self) -> bool {
+        let read_data = self.read_data;
+        if let Some(target) = self.target() {
+            if let Some(tgt_stats) = self
+                .agent_data
+                .rtsim_entity
+                .and(read_data.stats.get(target.target))
+                .filter(|_| is_dead(target.target, read_data))
+            {
+                self.agent.forget_enemy(&tgt_stats.name);
+            }
+        }
+        self.agent.target = None;
+        true
+    }
+
+    fn choose_target(&mut self) -> bool {
+        self.agent_data.choose_target(
+            self.agent,
+            self.controller,
+            self.read_data,
+            self.event_emitter,
+        );
+        false
+    }
+
+    fn attack(&mut self) -> bool {
+        let read_data = self.read_data;
+        let target = match self.target() {
+            Some(target) => target.target,
+            None => return false,
+        };
+        match read_data.positions.get(target) {
+            Some(tgt_pos) => {
+                let target_data = TargetData::new(
+                    tgt_pos,
+                    read_data.bodies.get(target),
+                    read_data.scales.get(target),
+                );
+                self.agent_data.attack(
+                    self.agent,
+                    self.controller,
+                    &target_data,
+                    read_data,
+                    self.rng,
+                );
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn flank(&mut self) -> bool {
+        match self.flank_pos() {
+            Some(flank_pos) => {
+                let flank_pos = Pos(flank_pos);
+                self.agent_data.path_toward_target(
+                    self.agent,
+                    self.controller,
+                    &TargetData::new(&flank_pos, None, None),
+                    self.read_data,
+                    true,
+                    false,
+                    None,
+                );
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn back_off(&mut self) -> bool {
+        match self.target_pos() {
+            Some(tgt_pos) => {
+                self.controller.inputs.move_dir = (self.agent_data.pos.0 - tgt_pos)
+                    .xy()
+                    .try_normalized()
+                    .unwrap_or_else(Vec2::zero);
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn menacing(&mut self) -> bool {
+        match self.target() {
+            Some(target) => {
+                self.agent_data.menacing(
+                    self.agent,
+                    target.target,
+                    self.controller,
+                    self.read_data,
+                    self.event_emitter,
+                    self.rng,
+                );
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn react_as_pet(&mut self) -> bool {
+        match self.target() {
+            Some(target) => {
+                self.agent_data.react_as_pet(
+                    self.agent,
+                    self.controller,
+                    self.read_data,
+                    self.event_emitter,
+                    target.target,
+                    self.rng,
+                );
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn target_commanded(&mut self) -> bool {
+        let read_data = self.read_data;
+        if let Some(PetCommand::Attack(target)) = self.pet_command() {
+            match get_entity_by_id(target.id(), read_data) {
+                Some(target)
+                    if !is_dead(target, read_data)
+                        && self.target().map_or(true, |t| t.target != target) =>
+                {
+                    self.agent.target = Some(Target::new(target, true, read_data.time.0, true));
+                },
+                _ => {},
+            }
+        }
+        false
+    }
+
+    fn follow_owner(&mut self) -> bool {
+        match self
+            .owner()
+            .and_then(|owner| self.read_data.positions.get(owner))
+        {
+            Some(owner_pos) => {
+                self.agent_data.follow(
+                    self.agent,
+                    self.controller,
+                    &self.read_data.terrain,
+                    owner_pos,
+                );
+                true
+            },
+            None => self.idle(),
+        }
+    }
+
+    fn stay(&mut self) -> bool {
+        // The controller was reset this tick, so there's nothing left to do
+        true
+    }
+
+    fn idle(&mut self) -> bool {
+        self.agent_data
+            .idle(self.agent, self.controller, self.read_data, self.rng);
+        true
+    }
+
+    fn idle_tree(&mut self) -> bool {
+        self.agent_data.idle_tree(
+            self.agent,
+            self.controller,
+            self.read_data,
+            self.event_emitter,
+            self.rng,
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A world described by flags, which records the actions taken
+    #[derive(Default)]
+    struct MockWorld {
+        falling: bool,
+        can_fly: bool,
+        has_glider: bool,
+        on_fire: bool,
+        on_ground: bool,
+        attacked: bool,
+        target: Option<MockTarget>,
+        owned: bool,
+        hurt: bool,
+        low_health: bool,
+        has_healing_item: bool,
+        cried_out: bool,
+        out_of_position: bool,
+        too_close: bool,
+        passive: bool,
+        staying: bool,
+        commanded_target: bool,
+        actions: Vec<&'static str>,
+    }
+
+    #[derive(Clone, Copy, Default)]
+    struct MockTarget {
+        hostile: bool,
+        dead: bool,
+        invulnerable: bool,
+        owner: bool,
+        aggro: bool,
+        far_away: bool,
+        retarget: bool,
+    }
+
+    impl MockWorld {
+        fn act(&mut self, action: &'static str) -> bool {
+            self.actions.push(action);
+            true
+        }
+
+        fn run(mut self, tree: &Node) -> Vec<&'static str> {
+            tree.tick(&mut self);
+            self.actions
+        }
+    }
+
+    impl AgentWorld for MockWorld {
+        fn is_falling_dangerously(&self) -> bool { self.falling }
+
+        fn can_fly(&self) -> bool { self.can_fly }
+
+        fn has_glider(&self) -> bool { self.has_glider }
+
+        fn is_on_fire(&self) -> bool { self.on_fire }
+
+        fn is_on_ground(&self) -> bool { self.on_ground }
+
+        fn wants_to_roll(&mut self) -> bool { true }
+
+        fn has_target(&self) -> bool { self.target.is_some() }
+
+        fn target_has_health(&self) -> bool { self.target.is_some() }
+
+        fn target_is_dead(&self) -> bool { self.target.map_or(false, |t| t.dead) }
+
+        fn target_is_hostile(&self) -> bool { self.target.map_or(false, |t| t.hostile) }
+
+        fn target_is_invulnerable(&self) -> bool { self.target.map_or(false, |t| t.invulnerable) }
+
+        fn target_position_known(&self) -> bool { self.target.is_some() }
+
+        fn is_owned(&self) -> bool { self.owned }
+
+        fn is_owned_by_target(&self) -> bool {
+            self.owned && self.target.map_or(false, |t| t.owner)
+        }
+
+        fn needs_healing(&self) -> bool { self.low_health }
+
+        fn should_flee(&self) -> bool { self.low_health }
+
+        fn has_cried_out(&self) -> bool { self.cried_out }
+
+        fn can_keep_fleeing(&self) -> bool { self.target.map_or(false, |t| !t.far_away) }
+
+        fn should_retarget(&self) -> bool { self.target.map_or(false, |t| t.retarget) }
+
+        fn is_aggro(&self) -> bool { self.target.map_or(false, |t| t.aggro) }
+
+        fn should_flank(&self) -> bool { self.out_of_position }
+
+        fn should_keep_distance(&self) -> bool { self.too_close }
+
+        fn is_passive(&self) -> bool { self.passive }
+
+        fn is_told_to_stay(&self) -> bool { self.staying }
+
+        fn fly_upward(&mut self) -> bool { self.act("fly_upward") }
+
+        fn glide(&mut self) -> bool { self.act("glide") }
+
+        fn roll(&mut self) -> bool { self.act("roll") }
+
+        fn target_attacker(&mut self) -> bool {
+            if self.attacked {
+                self.actions.push("target_attacker");
+                self.target = Some(MockTarget {
+                    hostile: true,
+                    aggro: true,
+                    ..MockTarget::default()
+                });
+            }
+            false
+        }
+
+        fn utter_if_hurt(&mut self) -> bool {
+            if self.hurt {
+                self.actions.push("utter");
+            }
+            false
+        }
+
+        fn update_aggro(&mut self) -> bool { false }
+
+        fn heal_self(&mut self) -> bool { self.has_healing_item && self.act("heal_self") }
+
+        fn cry_out(&mut self) -> bool { self.act("cry_out") }
+
+        fn flee(&mut self) -> bool { self.act("flee") }
+
+        fn give_up_fleeing(&mut self) -> bool { self.act("give_up_fleeing") }
+
+        fn celebrate_dead_target(&mut self) -> bool { self.act("celebrate") }
+
+        fn forget_target(&mut self) -> bool {
+            self.target = None;
+            self.act("forget_target")
+        }
+
+        fn choose_target(&mut self) -> bool {
+            self.actions.push("choose_target");
+            false
+        }
+
+        fn attack(&mut self) -> bool { self.act("attack") }
+
+        fn flank(&mut self) -> bool { self.act("flank") }
+
+        fn back_off(&mut self) -> bool { self.act("back_off") }
+
+        fn menacing(&mut self) -> bool { self.act("menacing") }
+
+        fn react_as_pet(&mut self) -> bool { self.act("react_as_pet") }
+
+        fn target_commanded(&mut self) -> bool {
+            if self.commanded_target {
+                self.actions.push("target_commanded");
+                self.target = Some(MockTarget {
+                    hostile: true,
+                    aggro: true,
+                    ..MockTarget::default()
+                });
+            }
+            false
+        }
+
+        fn follow_owner(&mut self) -> bool { self.act("follow_owner") }
+
+        fn stay(&mut self) -> bool { self.act("stay") }
+
+        fn idle(&mut self) -> bool { self.act("idle") }
+
+        fn idle_tree(&mut self) -> bool { self.act("idle_tree") }
+    }
+
+    fn hostile(target: MockTarget) -> Option<MockTarget> {
+        Some(MockTarget {
+            hostile: true,
+            ..target
+        })
+    }
+
+    #[test]
+    fn idles_without_target() {
+        assert_eq!(MockWorld::default().run(&CREATURE_TREE), ["idle_tree"]);
+    }
+
+    #[test]
+    fn saves_itself_when_falling() {
+        let flyer = MockWorld {
+            falling: true,
+            can_fly: true,
+            has_glider: true,
+            ..MockWorld::default()
+        };
+        assert_eq!(flyer.run(&CREATURE_TREE), ["fly_upward"]);
+
+        let glider = MockWorld {
+            falling: true,
+            has_glider: true,
+            ..MockWorld::default()
+        };
+        assert_eq!(glider.run(&HUMANOID_TREE), ["glide"]);
+
+        // Without a way to save itself the agent carries on as usual
+        let doomed = MockWorld {
+            falling: true,
+            ..MockWorld::default()
+        };
+        assert_eq!(doomed.run(&CREATURE_TREE), ["idle_tree"]);
+    }
+
+    #[test]
+    fn only_humanoids_stop_drop_and_roll() {
+        let burning = || MockWorld {
+            on_fire: true,
+            on_ground: true,
+            ..MockWorld::default()
+        };
+        assert_eq!(burning().run(&HUMANOID_TREE), ["roll"]);
+        assert_eq!(burning().run(&CREATURE_TREE), ["idle_tree"]);
+    }
+
+    #[test]
+    fn fights_back_when_attacked() {
+        let world = MockWorld {
+            attacked: true,
+            ..MockWorld::default()
+        };
+        assert_eq!(world.run(&CREATURE_TREE), ["target_attacker", "attack"]);
+    }
+
+    #[test]
+    fn menaces_until_aggravated() {
+        let world = MockWorld {
+            target: hostile(MockTarget::default()),
+            ..MockWorld::default()
+        };
+        assert_eq!(world.run(&CREATURE_TREE), ["menacing"]);
+
+        let world = MockWorld {
+            target: hostile(MockTarget {
+                aggro: true,
+                retarget: true,
+                ..MockTarget::default()
+            }),
+            ..MockWorld::default()
+        };
+        assert_eq!(world.run(&CREATURE_TREE), ["choose_target", "attack"]);
+    }
+
+    #[test]
+    fn packs_surround_their_target() {
+        let world = || MockWorld {
+            target: hostile(MockTarget {
+                aggro: true,
+                ..MockTarget::default()
+            }),
+            ..MockWorld::default()
+        };
+        assert_eq!(
+            MockWorld {
+                out_of_position: true,
+                ..world()
+            }
+            .run(&CREATURE_TREE),
+            ["flank"]
+        );
+        assert_eq!(
+            MockWorld {
+                too_close: true,
+                ..world()
+            }
+            .run(&HUMANOID_TREE),
+            ["attack", "back_off"]
+        );
+        // Members which aren't aggravated yet only menace the target
+        assert_eq!(
+            MockWorld {
+                out_of_position: true,
+                target: hostile(MockTarget::default()),
+                ..world()
+            }
+            .run(&CREATURE_TREE),
+            ["menacing"]
+        );
+    }
+
+    #[test]
+    fn heals_before_fleeing() {
+        let world = || MockWorld {
+            target: hostile(MockTarget::default()),
+            low_health: true,
+            hurt: true,
+            ..MockWorld::default()
+        };
+        assert_eq!(
+            MockWorld {
+                has_healing_item: true,
+                ..world()
+            }
+            .run(&CREATURE_TREE),
+            ["heal_self"]
+        );
+        assert_eq!(world().run(&CREATURE_TREE), ["utter", "cry_out"]);
+        assert_eq!(
+            MockWorld {
+                cried_out: true,
+                ..world()
+            }
+            .run(&CREATURE_TREE),
+            ["utter", "flee"]
+        );
+        assert_eq!(
+            MockWorld {
+                cried_out: true,
+                target: hostile(MockTarget {
+                    far_away: true,
+                    ..MockTarget::default()
+                }),
+                ..world()
+            }
+            .run(&CREATURE_TREE),
+            ["utter", "give_up_fleeing"]
+        );
+    }
+
+    #[test]
+    fn forgets_dead_and_invulnerable_targets() {
+        let world = MockWorld {
+            target: hostile(MockTarget {
+                dead: true,
+                ..MockTarget::default()
+            }),
+            ..MockWorld::default()
+        };
+        assert_eq!(world.run(&CREATURE_TREE), ["forget_target"]);
+
+        let world = MockWorld {
+            target: hostile(MockTarget {
+                invulnerable: true,
+                aggro: true,
+                ..MockTarget::default()
+            }),
+            ..MockWorld::default()
+        };
+        assert_eq!(world.run(&CREATURE_TREE), ["forget_target", "idle"]);
+    }
+
+    #[test]
+    fn pets_follow_their_owner() {
+        let pet = MockWorld {
+            owned: true,
+            target: Some(MockTarget {
+                owner: true,
+                ..MockTarget::default()
+            }),
+            ..MockWorld::default()
+        };
+        assert_eq!(pet.run(&CREATURE_TREE), ["react_as_pet"]);
+
+        let stray = MockWorld {
+            owned: true,
+            target: Some(MockTarget::default()),
+            ..MockWorld::default()
+        };
+        assert_eq!(stray.run(&CREATURE_TREE), ["forget_target", "idle_tree"]);
+    }
+
+    #[test]
+    fn pets_obey_their_owner() {
+        let pet = || MockWorld {
+            owned: true,
+            ..MockWorld::default()
+        };
+        // Passive pets don't even fight back
+        assert_eq!(
+            MockWorld {
+                passive: true,
+                attacked: true,
+                ..pet()
+            }
+            .run(&CREATURE_TREE),
+            ["target_attacker", "forget_target", "follow_owner"]
+        );
+        // Pets told to stay only leave their spot to fight back
+        assert_eq!(
+            MockWorld {
+                staying: true,
+                ..pet()
+            }
+            .run(&CREATURE_TREE),
+            ["stay"]
+        );
+        assert_eq!(
+            MockWorld {
+                staying: true,
+                attacked: true,
+                ..pet()
+            }
+            .run(&CREATURE_TREE),
+            ["target_attacker", "attack"]
+        );
+        assert_eq!(
+            MockWorld {
+                commanded_target: true,
+                ..pet()
+            }
+            .run(&CREATURE_TREE),
+            ["target_commanded", "attack"]
+        );
+    }
+}
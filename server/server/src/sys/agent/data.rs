@@ -1,17 +1,18 @@
-use crate::rtsim::Entity as RtSimData;
+use crate::{pack::PackMember, rtsim::Entity as RtSimData};
 use common::{
     comp::{
         buff::Buffs, group, ActiveAbilities, Alignment, Body, CharacterState, Combo, Energy,
-        Health, Inventory, LightEmitter, Ori, PhysicsState, Pos, Scale, SkillSet, Stats, Vel,
+        Health, Inventory, LightEmitter, Ori, Pet, PhysicsState, Pos, Scale, SkillSet, Stats, Vel,
     },
     link::Is,
     mounting::Mount,
     path::TraversalConfig,
-    resources::{DeltaTime, Time, TimeOfDay},
+    resources::{DeltaTime, RngSeed, Time, TimeOfDay},
     rtsim::RtSimEntity,
     terrain::TerrainGrid,
     uid::{Uid, UidAllocator},
 };
+use serde::Deserialize;
 use specs::{
     shred::ResourceId, Entities, Entity as EcsEntity, Read, ReadExpect, ReadStorage, SystemData,
     World,
@@ -67,10 +68,13 @@ impl AttackData {
     pub fn in_min_range(&self) -> bool { self.dist_sqrd < self.min_attack_dist.powi(2) }
 }
 
-#[derive(Eq, PartialEq)]
-// When adding a new variant, first decide if it should instead fall under one
-// of the pre-exisitng tactics
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+// When adding a new variant, first decide if the tactic could instead be
+// written as a `Custom` tactic in the tactic manifest
 pub enum Tactic {
+    /// A tactic defined in the tactic manifest
+    Custom(String),
+
     // General tactics
     SimpleMelee,
     SimpleBackstab,
@@ -92,11 +96,8 @@ pub enum Tactic {
     CircleCharge { radius: u32, circle_time: u32 },
     QuadLowRanged,
     TailSlap,
-    QuadLowQuick,
-    QuadLowBasic,
     QuadLowBeam,
     QuadMedJump,
-    QuadMedBasic,
     Theropod,
     BirdLargeBreathe,
     BirdLargeFire,
@@ -120,12 +121,28 @@ pub enum Tactic {
     GnarlingChieftain,
 }
 
+impl Tactic {
+    /// Whether the tactic attacks from afar
+    pub fn is_ranged(&self) -> bool {
+        matches!(
+            self,
+            Tactic::Bow
+                | Tactic::Staff
+                | Tactic::Sceptre
+                | Tactic::ElevatedRanged
+                | Tactic::QuadLowRanged
+                | Tactic::ArthropodRanged
+        )
+    }
+}
+
 #[derive(SystemData)]
 pub struct ReadData<'a> {
     pub entities: Entities<'a>,
     pub uid_allocator: Read<'a, UidAllocator>,
     pub dt: Read<'a, DeltaTime>,
     pub time: Read<'a, Time>,
+    pub rng_seed: Read<'a, RngSeed>,
     pub cached_spatial_grid: Read<'a, common::CachedSpatialGrid>,
     pub group_manager: Read<'a, group::GroupManager>,
     pub energies: ReadStorage<'a, Energy>,
@@ -147,10 +164,13 @@ pub struct ReadData<'a> {
     pub is_mounts: ReadStorage<'a, Is<Mount>>,
     pub time_of_day: Read<'a, TimeOfDay>,
     pub light_emitter: ReadStorage<'a, LightEmitter>,
+    /// Missing in headless simulations, which run without a generated world
     #[cfg(feature = "worldgen")]
-    pub world: ReadExpect<'a, Arc<world::World>>,
+    pub world: Option<Read<'a, Arc<world::World>>>,
     pub rtsim_entities: ReadStorage<'a, RtSimEntity>,
     pub buffs: ReadStorage<'a, Buffs>,
     pub combos: ReadStorage<'a, Combo>,
     pub active_abilities: ReadStorage<'a, ActiveAbilities>,
+    pub pack_members: ReadStorage<'a, PackMember>,
+    pub pets: ReadStorage<'a, Pet>,
 }
@@ -0,0 +1,262 @@
+//! Combat tactics defined in assets
+//!
+//! The tactic an NPC uses is chosen by the ability set of the weapon it holds,
+//! as listed in the `server.manifests.npc_tactics` manifest. A tactic is either
+//! one of the hand-written [`Tactic`]s, or a [`TacticDef`] from the same
+//! manifest which is interpreted by [`AgentData::handle_custom_tactic`]. New
+//! creature behaviours should be written as a `TacticDef` where possible.
+
+use crate::sys::agent::{
+    consts::MAX_PATH_DIST, util::can_see_tgt, AgentData, AttackData, ReadData, Tactic, TargetData,
+};
+use common::comp::{
+    item::tool::{AbilitySpec, ToolKind},
+    Agent, Controller, InputKind, Item, ItemKind,
+};
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use rand::Rng;
+use serde::Deserialize;
+use vek::*;
+
+/// Which tactic NPCs use, and the definitions of the tactics written in RON
+#[derive(Debug, Deserialize)]
+pub struct TacticManifest {
+    /// Tactics by the name of the custom ability set of the held weapon
+    pub abilities: HashMap<String, Tactic>,
+    /// Tactics by the kind of the held tool, for weapons without a custom
+    /// ability set
+    pub tools: HashMap<ToolKind, Tactic>,
+    /// The tactic used when nothing else matches
+    pub default: Tactic,
+    pub tactics: HashMap<String, TacticDef>,
+}
+
+impl assets::Asset for TacticManifest {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+lazy_static! {
+    pub static ref TACTIC_MANIFEST: assets::AssetHandle<TacticManifest> =
+        assets::AssetExt::load_expect("server.manifests.npc_tactics");
+}
+
+impl TacticManifest {
+    pub fn tactic_for(&self, main_hand: Option<&Item>) -> Tactic {
+        let tool_tactic = |tool_kind| self.tools.get(tool_kind).unwrap_or(&self.default);
+        main_hand
+            .map_or(&self.default, |item| match item.ability_spec() {
+                Some(AbilitySpec::Custom(spec)) => {
+                    self.abilities.get(spec).unwrap_or(&self.default)
+                },
+                Some(AbilitySpec::Tool(tool_kind)) => tool_tactic(tool_kind),
+                None => match item.kind() {
+                    ItemKind::Tool(tool) => tool_tactic(&tool.kind),
+                    _ => &self.default,
+                },
+            })
+            .clone()
+    }
+}
+
+/// A tactic made of rules, which are checked in order every tick. The first
+/// rule whose conditions all hold decides what the NPC does.
+#[derive(Clone, Debug, Deserialize)]
+pub struct TacticDef {
+    pub rules: Vec<TacticRule>,
+    /// What to do when no rule applies
+    #[serde(default = "default_fallback")]
+    pub fallback: Movement,
+    /// Offset added to the height the NPC aims at, relative to the eyes of the
+    /// target. Negative values aim at the feet.
+    #[serde(default)]
+    pub aim_offset: f32,
+}
+
+fn default_fallback() -> Movement { Movement::Chase }
+
+impl TacticDef {
+    /// The longest period of the phases the tactic cycles through, after which
+    /// the time spent fighting starts over
+    fn cycle(&self) -> Option<f32> {
+        self.rules
+            .iter()
+            .flat_map(|rule| rule.conditions.iter())
+            .filter_map(|condition| match condition {
+                Condition::Phase { period, .. } => Some(period.max(0.01)),
+                _ => None,
+            })
+            .reduce(f32::max)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TacticRule {
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    /// The ability input pressed while the rule applies
+    #[serde(default)]
+    pub input: Option<InputKind>,
+    #[serde(default)]
+    pub movement: Movement,
+}
+
+/// Distances are given in multiples of the minimum attack distance, which
+/// takes the size of both the NPC and its target into account.
+#[derive(Clone, Debug, Deserialize)]
+pub enum Condition {
+    DistanceBelow(f32),
+    DistanceAbove(f32),
+    /// The angle between where the NPC looks and the target, in degrees
+    AngleBelow(f32),
+    EnergyAbove(f32),
+    /// Fraction of the maximum health of the NPC
+    HealthBelow(f32),
+    /// Fraction of the maximum health of the NPC
+    HealthAbove(f32),
+    /// Holds while the time spent fighting, modulo `period`, is within
+    /// `from..to`. This is used to cycle between attacks.
+    Phase { period: f32, from: f32, to: f32 },
+    CanSeeTarget,
+    /// Holds with the given probability each tick
+    Chance(f32),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum Movement {
+    Stand,
+    /// Path towards the target
+    Chase,
+    /// Move at an angle to the direction of the target, in degrees. 90 and -90
+    /// circle the target, 180 runs away from it.
+    Strafe(f32),
+    /// Back away from the target while it is closer than the given distance
+    Kite(f32),
+}
+
+impl Default for Movement {
+    fn default() -> Self { Movement::Stand }
+}
+
+impl<'a> AgentData<'a> {
+    fn tactic_condition_holds(
+        &self,
+        condition: &Condition,
+        agent: &Agent,
+        attack_data: &AttackData,
+        tgt_data: &TargetData,
+        read_data: &ReadData,
+        rng: &mut impl Rng,
+    ) -> bool {
+        let dist = |factor: f32| (factor * attack_data.min_attack_dist).powi(2);
+        match condition {
+            Condition::DistanceBelow(d) => attack_data.dist_sqrd < dist(*d),
+            Condition::DistanceAbove(d) => attack_data.dist_sqrd > dist(*d),
+            Condition::AngleBelow(angle) => attack_data.angle < *angle,
+            Condition::EnergyAbove(energy) => self.energy.current() > *energy,
+            Condition::HealthBelow(fraction) => {
+                self.health.map_or(false, |h| h.fraction() < *fraction)
+            },
+            Condition::HealthAbove(fraction) => {
+                self.health.map_or(true, |h| h.fraction() > *fraction)
+            },
+            Condition::Phase { period, from, to } => {
+                let phase = agent.action_state.timer % period.max(0.01);
+                phase >= *from && phase < *to
+            },
+            Condition::CanSeeTarget => can_see_tgt(
+                &*read_data.terrain,
+                self.pos,
+                tgt_data.pos,
+                attack_data.dist_sqrd,
+            ),
+            Condition::Chance(chance) => rng.gen_bool(chance.clamp(0.0, 1.0) as f64),
+        }
+    }
+
+    /// Executes a tactic defined in the tactic manifest
+    pub fn handle_custom_tactic(
+        &self,
+        agent: &mut Agent,
+        controller: &mut Controller,
+        attack_data: &AttackData,
+        tgt_data: &TargetData,
+        read_data: &ReadData,
+        tactic: &TacticDef,
+        rng: &mut impl Rng,
+    ) {
+        // The timer is only used for phases, so it is wrapped around to keep it
+        // from growing for as long as the fight lasts
+        agent.action_state.timer = match tactic.cycle() {
+            Some(cycle) => (agent.action_state.timer + read_data.dt.0) % cycle,
+            None => 0.0,
+        };
+
+        let rule = tactic.rules.iter().find(|rule| {
+            rule.conditions.iter().all(|condition| {
+                self.tactic_condition_holds(
+                    condition,
+                    agent,
+                    attack_data,
+                    tgt_data,
+                    read_data,
+                    rng,
+                )
+            })
+        });
+        if let Some(input) = rule.and_then(|rule| rule.input) {
+            controller.push_basic_input(input);
+        }
+
+        let to_target = (tgt_data.pos.0 - self.pos.0).xy();
+        match rule.map_or(&tactic.fallback, |rule| &rule.movement) {
+            Movement::Stand => controller.inputs.move_dir = Vec2::zero(),
+            Movement::Chase => {
+                let full_path = attack_data.dist_sqrd < MAX_PATH_DIST.powi(2);
+                self.path_toward_target(
+                    agent, controller, tgt_data, read_data, full_path, false, None,
+                );
+            },
+            Movement::Strafe(angle) => {
+                controller.inputs.move_dir = to_target
+                    .rotated_z(angle.to_radians())
+                    .try_normalized()
+                    .unwrap_or_else(Vec2::unit_y);
+            },
+            Movement::Kite(distance) => {
+                controller.inputs.move_dir =
+                    if attack_data.dist_sqrd < (distance * attack_data.min_attack_dist).powi(2) {
+                        -to_target.try_normalized().unwrap_or_else(Vec2::unit_y)
+                    } else {
+                        Vec2::zero()
+                    };
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_tactics_exist() {
+        let manifest = TACTIC_MANIFEST.read();
+        for tactic in manifest
+            .abilities
+            .values()
+            .chain(manifest.tools.values())
+            .chain(std::iter::once(&manifest.default))
+        {
+            if let Tactic::Custom(name) = tactic {
+                assert!(
+                    manifest.tactics.contains_key(name),
+                    "Custom tactic {} is not defined in the tactic manifest",
+                    name
+                );
+            }
+        }
+    }
+}
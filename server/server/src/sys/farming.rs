@@ -0,0 +1,57 @@
+use crate::{
+    farming::GrowingCrops,
+    sys::{terrain::TerrainPersistenceData, SysScheduler},
+    time,
+};
+use common::terrain::TerrainGrid;
+use common_ecs::{Job, Origin, Phase, System};
+use common_state::BlockChange;
+use specs::{ReadExpect, Write, WriteExpect};
+
+/// This system grows the crops that players have planted in loaded chunks to
+/// the stage they have reached since being planted, and saves the crops that
+/// are still growing.
+#[derive(Default)]
+pub struct Sys;
+
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        ReadExpect<'a, TerrainGrid>,
+        Write<'a, BlockChange>,
+        WriteExpect<'a, GrowingCrops>,
+        TerrainPersistenceData<'a>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "farming";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            terrain,
+            mut block_change,
+            mut growing_crops,
+            mut _terrain_persistence,
+            mut scheduler,
+        ): Self::SystemData,
+    ) {
+        if !scheduler.should_run() {
+            return;
+        }
+
+        for (pos, grown) in growing_crops.grow_loaded(&terrain, time::now()) {
+            // If something else changed the block this tick, it grows on the next run
+            if block_change.try_set(pos, grown).is_some() {
+                #[cfg(feature = "persistent_world")]
+                if let Some(terrain_persistence) = _terrain_persistence.as_mut() {
+                    terrain_persistence.set_block(pos, grown);
+                }
+                growing_crops.settle(pos, grown);
+            }
+        }
+
+        growing_crops.save();
+    }
+}
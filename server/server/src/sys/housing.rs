@@ -0,0 +1,159 @@
+use crate::{
+    client::Client,
+    coins::{coin_count, remove_coins},
+    housing::{self, HousingPlots},
+    persistence::{character_updater::CharacterUpdater, housing::HousingRequest},
+    presence::Presence,
+    sys::SysScheduler,
+    time, Settings,
+};
+use common::comp::{CanBuild, ChatType, Inventory, InventoryUpdate, InventoryUpdateEvent};
+use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::{PresenceKind, ServerGeneral};
+use common_state::BuildAreas;
+use hashbrown::HashMap;
+use specs::{Entities, Join, ReadExpect, ReadStorage, Write, WriteExpect, WriteStorage};
+use tracing::info;
+
+/// How often the time a plot owner was last active is saved, in seconds
+const LAST_ACTIVE_SAVE_INTERVAL: i64 = 60 * 60;
+
+/// This system charges rent for the plots owned by characters, and releases
+/// plots whose owners can't afford the rent or haven't played for too long.
+///
+/// Rent is only taken while the owner is online; rent that fell due while they
+/// were offline is charged when they return.
+#[derive(Default)]
+pub struct Sys;
+
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Entities<'a>,
+        ReadExpect<'a, Settings>,
+        ReadStorage<'a, Presence>,
+        ReadStorage<'a, Client>,
+        WriteStorage<'a, Inventory>,
+        WriteStorage<'a, InventoryUpdate>,
+        WriteStorage<'a, CanBuild>,
+        WriteExpect<'a, HousingPlots>,
+        WriteExpect<'a, BuildAreas>,
+        WriteExpect<'a, CharacterUpdater>,
+        Write<'a, SysScheduler<Self>>,
+    );
+
+    const NAME: &'static str = "housing";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            entities,
+            settings,
+            presences,
+            clients,
+            mut inventories,
+            mut inventory_updates,
+            mut can_build,
+            mut housing_plots,
+            mut build_areas,
+            mut updater,
+            mut scheduler,
+        ): Self::SystemData,
+    ) {
+        if !scheduler.should_run() {
+            return;
+        }
+
+        let settings = settings.housing;
+        let now = time::now();
+        let owners_online = (&entities, &presences)
+            .join()
+            .filter_map(|(entity, presence)| match presence.kind {
+                PresenceKind::Character(character_id) => Some((character_id, entity)),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+        let notify = |entity, msg: String| {
+            if let Some(client) = clients.get(entity) {
+                client.send_fallible(ServerGeneral::server_msg(ChatType::CommandInfo, msg));
+            }
+        };
+
+        let mut released = Vec::new();
+        for plot in housing_plots.iter_mut() {
+            let owner = owners_online.get(&plot.owner).copied();
+            let mut changed = false;
+            // The inventory of the owner after paying rent, saved along with the plot
+            let mut paid = None;
+
+            if let Some(owner) = owner {
+                if now - plot.last_active > LAST_ACTIVE_SAVE_INTERVAL {
+                    changed = true;
+                }
+                plot.last_active = now;
+
+                let periods_due = housing::periods_due(plot, &settings, now);
+                if periods_due > 0 {
+                    let rent = u64::from(settings.rent) * periods_due as u64;
+                    match inventories
+                        .get_mut(owner)
+                        .filter(|inventory| coin_count(inventory) >= rent)
+                    {
+                        Some(mut inventory) => {
+                            remove_coins(&mut inventory, rent);
+                            let _ = inventory_updates
+                                .insert(owner, InventoryUpdate::new(InventoryUpdateEvent::Gave));
+                            plot.paid_until += periods_due * housing::rent_period(&settings);
+                            paid = Some(inventory.clone());
+                            notify(owner, format!("You paid {} coins of rent for your plot", rent));
+                        },
+                        None => {
+                            released.push(plot.key());
+                            notify(
+                                owner,
+                                format!(
+                                    "You could not afford the {} coins of rent for your plot, so \
+                                     it has been released",
+                                    rent
+                                ),
+                            );
+                            continue;
+                        },
+                    }
+                }
+            } else if settings.inactivity_days > 0
+                && now - plot.last_active > housing::inactivity_limit(&settings)
+            {
+                info!(
+                    "Releasing the plot of inactive character ID {} at {}",
+                    plot.owner,
+                    plot.key()
+                );
+                released.push(plot.key());
+                continue;
+            }
+
+            if let Some(inventory) = paid {
+                updater.housing(HousingRequest::Pay {
+                    plot: plot.to_data(),
+                    inventory,
+                });
+            } else if changed {
+                updater.housing(HousingRequest::Save(plot.to_data()));
+            }
+        }
+
+        for key in released {
+            if let Some(plot) = housing_plots.release(&mut build_areas, key) {
+                updater.housing(HousingRequest::Delete(key));
+                if let Some(mut can_build) = owners_online
+                    .get(&plot.owner)
+                    .and_then(|owner| can_build.get_mut(*owner))
+                {
+                    can_build.build_areas.retain(|&id| id != plot.build_area);
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,17 @@
 pub mod agent;
 pub mod entity_sync;
+pub mod farming;
+pub mod housing;
 pub mod invite_timeout;
 pub mod metrics;
 pub mod msg;
 pub mod object;
+pub mod pack;
 pub mod persistence;
 pub mod pets;
+pub mod pvp;
 pub mod sentinel;
+pub mod statistics;
 pub mod subscription;
 pub mod terrain;
 pub mod terrain_sync;
@@ -27,10 +32,15 @@ pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     dispatch::<melee::Sys>(dispatch_builder, &[&projectile::Sys::sys_name()]);
     //Note: server should not depend on interpolation system
     dispatch::<agent::Sys>(dispatch_builder, &[]);
+    dispatch::<pack::Sys>(dispatch_builder, &[&agent::Sys::sys_name()]);
     dispatch::<terrain::Sys>(dispatch_builder, &[&msg::terrain::Sys::sys_name()]);
     dispatch::<waypoint::Sys>(dispatch_builder, &[]);
     dispatch::<invite_timeout::Sys>(dispatch_builder, &[]);
     dispatch::<persistence::Sys>(dispatch_builder, &[]);
+    dispatch::<statistics::Sys>(dispatch_builder, &[]);
+    dispatch::<housing::Sys>(dispatch_builder, &[]);
+    dispatch::<farming::Sys>(dispatch_builder, &[]);
+    dispatch::<pvp::Sys>(dispatch_builder, &[]);
     dispatch::<object::Sys>(dispatch_builder, &[]);
     dispatch::<wiring::Sys>(dispatch_builder, &[]);
 }
@@ -1,6 +1,6 @@
 #[cfg(feature = "persistent_world")]
 use crate::TerrainPersistence;
-use crate::{client::Client, presence::Presence, Settings};
+use crate::{client::Client, presence::Presence, site_state::SiteStates, Settings};
 use common::{
     comp::{
         Admin, CanBuild, ControlEvent, Controller, ForceUpdate, Health, Ori, Player, Pos, SkillSet,
@@ -16,7 +16,7 @@ use common::{
 use common_ecs::{Job, Origin, Phase, System};
 use common_net::msg::{ClientGeneral, PresenceKind, ServerGeneral};
 use common_state::{BlockChange, BuildAreas};
-use specs::{Entities, Join, Read, ReadExpect, ReadStorage, Write, WriteStorage};
+use specs::{Entities, Join, Read, ReadExpect, ReadStorage, Write, WriteExpect, WriteStorage};
 use tracing::{debug, trace, warn};
 use vek::*;
 
@@ -47,6 +47,7 @@ impl Sys {
         build_areas: &Read<'_, BuildAreas>,
         player_physics_settings: &mut Write<'_, PlayerPhysicsSettings>,
         _terrain_persistence: &mut TerrainPersistenceData<'_>,
+        site_states: &mut WriteExpect<'_, SiteStates>,
         maybe_player: &Option<&Player>,
         maybe_admin: &Option<&Admin>,
         msg: ClientGeneral,
@@ -211,9 +212,11 @@ impl Sys {
                                 .and_then(|_| terrain.get(pos).ok())
                             {
                                 let new_block = old_block.into_vacant();
-                                let _was_set = block_changes.try_set(pos, new_block).is_some();
-                                #[cfg(feature = "persistent_world")]
-                                if _was_set {
+                                let was_set = block_changes.try_set(pos, new_block).is_some();
+                                // Blocks removed on purpose inside a build area are not
+                                // damage for sites to repair
+                                if was_set {
+                                    #[cfg(feature = "persistent_world")]
                                     if let Some(terrain_persistence) = _terrain_persistence.as_mut()
                                     {
                                         terrain_persistence.set_block(pos, new_block);
@@ -236,9 +239,10 @@ impl Sys {
                                 .filter(|aabb| aabb.contains_point(pos))
                                 .is_some()
                             {
-                                let _was_set = block_changes.try_set(pos, new_block).is_some();
-                                #[cfg(feature = "persistent_world")]
-                                if _was_set {
+                                let was_set = block_changes.try_set(pos, new_block).is_some();
+                                if was_set {
+                                    site_states.record_build(pos, new_block);
+                                    #[cfg(feature = "persistent_world")]
                                     if let Some(terrain_persistence) = _terrain_persistence.as_mut()
                                     {
                                         terrain_persistence.set_block(pos, new_block);
@@ -288,6 +292,16 @@ impl Sys {
             ClientGeneral::UpdateMapMarker(update) => {
                 server_emitter.emit(ServerEvent::UpdateMapMarker { entity, update });
             },
+            ClientGeneral::MailAction(action) => {
+                if matches!(presence.kind, PresenceKind::Character(_)) {
+                    server_emitter.emit(ServerEvent::MailAction { entity, action });
+                }
+            },
+            ClientGeneral::GuildAction(action) => {
+                if matches!(presence.kind, PresenceKind::Character(_)) {
+                    server_emitter.emit(ServerEvent::GuildAction { entity, action });
+                }
+            },
             ClientGeneral::RequestCharacterList
             | ClientGeneral::CreateCharacter { .. }
             | ClientGeneral::EditCharacter { .. }
@@ -328,6 +342,7 @@ impl<'a> System<'a> for Sys {
         Read<'a, BuildAreas>,
         Write<'a, PlayerPhysicsSettings>,
         TerrainPersistenceData<'a>,
+        WriteExpect<'a, SiteStates>,
         ReadStorage<'a, Player>,
         ReadStorage<'a, Admin>,
     );
@@ -358,6 +373,7 @@ impl<'a> System<'a> for Sys {
             build_areas,
             mut player_physics_settings,
             mut terrain_persistence,
+            mut site_states,
             players,
             admins,
         ): Self::SystemData,
@@ -394,6 +410,7 @@ impl<'a> System<'a> for Sys {
                     &build_areas,
                     &mut player_physics_settings,
                     &mut terrain_persistence,
+                    &mut site_states,
                     &player,
                     &maybe_admin,
                     msg,
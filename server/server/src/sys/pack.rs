@@ -0,0 +1,231 @@
+use crate::pack::{
+    PackKind, PackMember, PackRole, Shaken, LEADER_DEATH_MORALE_LOSS, MAX_PACK_SIZE,
+    MORALE_RECOVERY_TIME, PACK_FORM_RADIUS, PACK_LEAVE_DIST,
+};
+use common::{
+    comp::{agent::Target, Agent, Alignment, Body, Health, Inventory, Pos},
+    resources::Time,
+    CachedSpatialGrid,
+};
+use common_ecs::{Job, Origin, Phase, System};
+use hashbrown::{HashMap, HashSet};
+use specs::{Entities, Entity as EcsEntity, Join, Read, ReadStorage, WriteStorage};
+use std::cmp::Ordering;
+
+/// This system forms packs out of creatures roaming close to each other, lets
+/// the members of a pack share their target and breaks packs up when their
+/// leader dies.
+#[derive(Default)]
+pub struct Sys;
+
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        Read<'a, CachedSpatialGrid>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Body>,
+        ReadStorage<'a, Health>,
+        ReadStorage<'a, Alignment>,
+        ReadStorage<'a, Inventory>,
+        WriteStorage<'a, Agent>,
+        WriteStorage<'a, PackMember>,
+        WriteStorage<'a, Shaken>,
+    );
+
+    const NAME: &'static str = "pack";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            entities,
+            time,
+            spatial_grid,
+            positions,
+            bodies,
+            healths,
+            alignments,
+            inventories,
+            mut agents,
+            mut pack_members,
+            mut shaken,
+        ): Self::SystemData,
+    ) {
+        let is_alive = |entity| {
+            entities.is_alive(entity) && healths.get(entity).map_or(true, |h| !h.is_dead)
+        };
+
+        // Leave the pack when the leader died or got too far away
+        let mut leavers = Vec::new();
+        for (entity, pos, member) in (&entities, &positions, &pack_members).join() {
+            if !is_alive(member.leader) {
+                leavers.push((entity, true));
+            } else if positions.get(member.leader).map_or(true, |leader_pos| {
+                leader_pos.0.distance_squared(pos.0) > PACK_LEAVE_DIST.powi(2)
+            }) {
+                leavers.push((entity, false));
+            }
+        }
+        for (entity, leader_died) in leavers {
+            pack_members.remove(entity);
+            if leader_died {
+                if let Some(agent) = agents.get_mut(entity) {
+                    let until = time.0 + MORALE_RECOVERY_TIME;
+                    match shaken.get_mut(entity) {
+                        Some(shaken) => shaken.until = until,
+                        None => {
+                            let _ = shaken.insert(entity, Shaken {
+                                flee_health: agent.psyche.flee_health,
+                                until,
+                            });
+                            agent.psyche.flee_health =
+                                (agent.psyche.flee_health + LEADER_DEATH_MORALE_LOSS).min(1.0);
+                        },
+                    }
+                }
+            }
+        }
+
+        // Morale recovers over time, or once a new leader was found
+        let recovered = (&entities, &shaken)
+            .join()
+            .filter(|(entity, shaken)| time.0 >= shaken.until || pack_members.contains(*entity))
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for entity in recovered {
+            if let (Some(shaken), Some(agent)) = (shaken.remove(entity), agents.get_mut(entity)) {
+                agent.psyche.flee_health = shaken.flee_health;
+            }
+        }
+
+        // A leader without followers is no leader
+        let mut pack_sizes = HashMap::<EcsEntity, usize>::new();
+        for member in (&pack_members).join() {
+            *pack_sizes.entry(member.leader).or_default() += 1;
+        }
+        let loners = (&entities, &pack_members)
+            .join()
+            .filter(|(_, member)| pack_sizes.get(&member.leader).map_or(true, |size| *size < 2))
+            .map(|(entity, _)| entity)
+            .collect::<Vec<_>>();
+        for loner in loners {
+            pack_members.remove(loner);
+        }
+
+        // Members call the rest of the pack for help once they found a target, but the
+        // target of the leader comes first
+        let mut pack_targets = HashMap::<EcsEntity, Target>::new();
+        for (member, agent) in (&pack_members, &agents).join() {
+            if let Some(target) = agent
+                .target
+                .filter(|target| target.hostile && target.aggro_on && is_alive(target.target))
+            {
+                if member.role == PackRole::Leader {
+                    pack_targets.insert(member.leader, target);
+                } else {
+                    pack_targets.entry(member.leader).or_insert(target);
+                }
+            }
+        }
+        for (member, agent) in (&pack_members, &mut agents).join() {
+            if let Some(pack_target) = pack_targets.get(&member.leader) {
+                if !agent.target.map_or(false, |target| target.hostile) {
+                    agent.target = Some(Target {
+                        selected_at: time.0,
+                        ..*pack_target
+                    });
+                }
+            }
+        }
+
+        // Form packs out of creatures of the same kind roaming close to each other
+        let mut recruited = HashSet::new();
+        let mut new_packs = Vec::new();
+        for (entity, pos, body, alignment, _, _) in (
+            &entities,
+            &positions,
+            &bodies,
+            &alignments,
+            &agents,
+            !&pack_members,
+        )
+            .join()
+        {
+            if recruited.contains(&entity)
+                || !matches!(alignment, Alignment::Wild | Alignment::Enemy)
+                || !is_alive(entity)
+            {
+                continue;
+            }
+            let kind = match PackKind::of(body) {
+                Some(kind) => kind,
+                None => continue,
+            };
+            // This includes the creature itself
+            let pack = spatial_grid
+                .0
+                .in_circle_aabr(pos.0.xy(), PACK_FORM_RADIUS)
+                .filter(|other| {
+                    !recruited.contains(other)
+                        && !pack_members.contains(*other)
+                        && agents.contains(*other)
+                        && is_alive(*other)
+                        && alignments.get(*other) == Some(alignment)
+                        && bodies.get(*other).and_then(PackKind::of) == Some(kind)
+                        && positions.get(*other).map_or(false, |other_pos| {
+                            other_pos.0.distance_squared(pos.0) < PACK_FORM_RADIUS.powi(2)
+                        })
+                })
+                .take(MAX_PACK_SIZE)
+                .collect::<Vec<_>>();
+            if pack.len() >= 2 {
+                recruited.extend(pack.iter().copied());
+                new_packs.push(pack);
+            }
+        }
+
+        let max_health = |entity| healths.get(entity).map_or(0.0, Health::maximum);
+        for pack in new_packs {
+            // The strongest leads the pack
+            let leader = match pack.iter().copied().max_by(|a, b| {
+                max_health(*a)
+                    .partial_cmp(&max_health(*b))
+                    .unwrap_or(Ordering::Equal)
+            }) {
+                Some(leader) => leader,
+                None => continue,
+            };
+            let roles = pack
+                .iter()
+                .map(|member| {
+                    if *member == leader {
+                        PackRole::Leader
+                    } else {
+                        inventories
+                            .get(*member)
+                            .map_or(PackRole::Melee, PackRole::of_member)
+                    }
+                })
+                .collect::<Vec<_>>();
+            // Melee members spread out evenly around the target, leaving its front to the
+            // leader
+            let flankers = roles.iter().filter(|role| **role == PackRole::Melee).count();
+            let mut flanker = 0;
+            for (member, role) in pack.iter().zip(roles) {
+                let flank_angle = if role == PackRole::Melee {
+                    flanker += 1;
+                    std::f32::consts::TAU * flanker as f32 / (flankers + 1) as f32
+                } else {
+                    0.0
+                };
+                let _ = pack_members.insert(*member, PackMember {
+                    leader,
+                    role,
+                    flank_angle,
+                });
+            }
+        }
+    }
+}
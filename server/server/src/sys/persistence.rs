@@ -2,7 +2,8 @@ use crate::{persistence::character_updater, presence::Presence, sys::SysSchedule
 use common::{
     comp::{
         pet::{is_tameable, Pet},
-        ActiveAbilities, Alignment, Body, Inventory, MapMarker, SkillSet, Stats, Waypoint,
+        ActiveAbilities, Alignment, Body, Inventory, MapMarker, SkillSet, Statistics, Stats,
+        Waypoint,
     },
     uid::Uid,
 };
@@ -26,6 +27,7 @@ impl<'a> System<'a> for Sys {
         ReadStorage<'a, Pet>,
         ReadStorage<'a, Stats>,
         ReadStorage<'a, ActiveAbilities>,
+        ReadStorage<'a, Statistics>,
         WriteExpect<'a, character_updater::CharacterUpdater>,
         Write<'a, SysScheduler<Self>>,
     );
@@ -48,6 +50,7 @@ impl<'a> System<'a> for Sys {
             pets,
             stats,
             active_abilities,
+            statistics,
             mut updater,
             mut scheduler,
         ): Self::SystemData,
@@ -62,6 +65,7 @@ impl<'a> System<'a> for Sys {
                     player_waypoints.maybe(),
                     &active_abilities,
                     map_markers.maybe(),
+                    &statistics,
                 )
                     .join()
                     .filter_map(
@@ -73,6 +77,7 @@ impl<'a> System<'a> for Sys {
                             waypoint,
                             active_abilities,
                             map_marker,
+                            statistics,
                         )|  match presence.kind {
                             PresenceKind::Character(id) => {
                                 let pets = (&alignments, &bodies, &stats, &pets)
@@ -98,6 +103,7 @@ impl<'a> System<'a> for Sys {
                                     waypoint,
                                     active_abilities,
                                     map_marker,
+                                    statistics,
                                 ))
                             },
                             PresenceKind::Spectator => None,
@@ -1,5 +1,5 @@
 use common::{
-    comp::{Alignment, Pet, PhysicsState, Pos},
+    comp::{Alignment, Pet, PetCommand, PhysicsState, Pos},
     terrain::TerrainGrid,
     uid::UidAllocator,
 };
@@ -35,6 +35,8 @@ impl<'a> System<'a> for Sys {
         // Find pets that are too far away from their owner
         let lost_pets: Vec<(Entity, Pos)> = (&entities, &positions, &alignments, &pets)
             .join()
+            // Pets told to stay are left where they are
+            .filter(|(_, _, _, pet)| pet.command != PetCommand::Stay)
             .filter_map(|(entity, pos, alignment, _)| match alignment {
                 Alignment::Owned(owner_uid) => Some((entity, pos, owner_uid)),
                 _ => None,
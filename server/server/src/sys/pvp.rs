@@ -0,0 +1,147 @@
+use crate::{client::Client, pvp::Arenas, Settings};
+use common::{
+    comp::{ChatType, Player, Pos, PvpEngagement, Stats},
+    resources::Time,
+    uid::UidAllocator,
+};
+use common_ecs::{Job, Origin, Phase, System};
+use common_net::msg::ServerGeneral;
+use specs::{
+    saveload::MarkerAllocator, Entities, Join, Read, ReadExpect, ReadStorage, WriteStorage,
+};
+
+/// This system puts players into and out of arenas as they enter and leave
+/// them, and ends duels whose duelists stray too far from each other, leave
+/// the server or run out of time.
+#[derive(Default)]
+pub struct Sys;
+
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        Read<'a, UidAllocator>,
+        ReadExpect<'a, Settings>,
+        ReadExpect<'a, Arenas>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, Pos>,
+        ReadStorage<'a, Stats>,
+        ReadStorage<'a, Client>,
+        WriteStorage<'a, PvpEngagement>,
+    );
+
+    const NAME: &'static str = "pvp";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        _job: &mut Job<Self>,
+        (
+            entities,
+            time,
+            uid_allocator,
+            settings,
+            arenas,
+            players,
+            positions,
+            stats,
+            clients,
+            mut engagements,
+        ): Self::SystemData,
+    ) {
+        let settings = &settings.pvp;
+        let name_of = |entity| stats.get(entity).map_or("", |stats| stats.name.as_str());
+        let notify = |entity, msg: String| {
+            if let Some(client) = clients.get(entity) {
+                client.send_fallible(ServerGeneral::server_msg(ChatType::Meta, msg));
+            }
+        };
+
+        // Duels
+        let mut ended_duels = Vec::new();
+        for (entity, pos, engagement) in (&entities, &positions, &engagements).join() {
+            let (opponent, started) = match engagement {
+                PvpEngagement::Duel { opponent, started } => (opponent, started),
+                PvpEngagement::Arena { .. } => continue,
+            };
+            let opponent = uid_allocator.retrieve_entity_internal(opponent.0);
+            let opponent_pos = opponent.and_then(|opponent| positions.get(opponent));
+            let message = match opponent_pos {
+                None => format!("{} won the duel, their opponent left.", name_of(entity)),
+                Some(opponent_pos)
+                    if pos.0.distance_squared(opponent_pos.0)
+                        > settings.duel_max_distance.powi(2) =>
+                {
+                    // Both duelists see the other one as being too far away, but only one of
+                    // them needs to end the duel
+                    if opponent.map_or(false, |opponent| opponent.id() < entity.id()) {
+                        continue;
+                    }
+                    "The duelists moved too far apart, the duel is over.".to_owned()
+                },
+                Some(_) if time.0 - started.0 > settings.duel_time_limit_secs as f64 => {
+                    if opponent.map_or(false, |opponent| opponent.id() < entity.id()) {
+                        continue;
+                    }
+                    "The time ran out, the duel ended in a draw.".to_owned()
+                },
+                Some(_) => continue,
+            };
+            ended_duels.push((entity, opponent, message));
+        }
+        for (entity, opponent, message) in ended_duels {
+            crate::pvp::end_duel(
+                &mut engagements,
+                &clients,
+                &std::iter::once(entity).chain(opponent).collect::<Vec<_>>(),
+                &message,
+            );
+        }
+
+        // Arenas
+        let mut changes = Vec::new();
+        for (entity, pos, _) in (&entities, &positions, &players).join() {
+            let current = match engagements.get(entity) {
+                Some(PvpEngagement::Duel { .. }) => continue,
+                Some(PvpEngagement::Arena { arena, .. }) => Some(arena.as_str()),
+                None => None,
+            };
+            let arena = arenas.at(pos.0);
+            if arena.map(|arena| arena.name.as_str()) != current {
+                changes.push((entity, arena.map(|arena| arena.name.clone())));
+            }
+        }
+        for (entity, arena) in changes {
+            match arena.and_then(|arena| arenas.get(&arena)) {
+                Some(arena) => {
+                    let team = arena.assign_team(
+                        (&engagements).join().filter_map(|engagement| match engagement {
+                            PvpEngagement::Arena { arena: name, team } if *name == arena.name => {
+                                Some(*team)
+                            },
+                            _ => None,
+                        }),
+                    );
+                    let team_msg = team.map_or_else(String::new, |team| {
+                        format!(" You are on team {}.", team + 1)
+                    });
+                    notify(
+                        entity,
+                        format!(
+                            "You entered the arena {}, PvP is enabled here.{}",
+                            arena.name, team_msg
+                        ),
+                    );
+                    let _ = engagements.insert(entity, PvpEngagement::Arena {
+                        arena: arena.name.clone(),
+                        team,
+                    });
+                },
+                None => {
+                    engagements.remove(entity);
+                    notify(entity, "You left the arena.".to_owned());
+                },
+            }
+        }
+    }
+}
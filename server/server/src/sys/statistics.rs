@@ -0,0 +1,60 @@
+use common::{
+    comp::{CharacterState, Stat, Statistics, Vel},
+    event::{EventBus, ServerEvent},
+    resources::DeltaTime,
+};
+use common_ecs::{Job, Origin, Phase, System};
+use hashbrown::HashMap;
+use specs::{Entities, Entity, Join, Read, ReadStorage};
+
+/// This system tracks the statistics that change continuously rather than as
+/// a result of a discrete event, such as the distance glided
+#[derive(Default)]
+pub struct Sys {
+    /// Distance glided by each entity that hasn't yet been added to its
+    /// statistics, since they are only updated one whole block at a time
+    glided: HashMap<Entity, f32>,
+}
+
+impl<'a> System<'a> for Sys {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, DeltaTime>,
+        Read<'a, EventBus<ServerEvent>>,
+        ReadStorage<'a, CharacterState>,
+        ReadStorage<'a, Vel>,
+        ReadStorage<'a, Statistics>,
+    );
+
+    const NAME: &'static str = "statistics";
+    const ORIGIN: Origin = Origin::Server;
+    const PHASE: Phase = Phase::Create;
+
+    fn run(
+        job: &mut Job<Self>,
+        (entities, dt, server_bus, character_states, velocities, statistics): Self::SystemData,
+    ) {
+        let mut server_emitter = server_bus.emitter();
+        let glided = &mut job.own.glided;
+
+        for (entity, character_state, vel, _) in
+            (&entities, &character_states, &velocities, &statistics).join()
+        {
+            if matches!(character_state, CharacterState::Glide(_)) {
+                let distance = glided.entry(entity).or_insert(0.0);
+                *distance += vel.0.magnitude() * dt.0;
+                if *distance >= 1.0 {
+                    let whole = distance.floor();
+                    *distance -= whole;
+                    server_emitter.emit(ServerEvent::UpdateStatistic {
+                        entity,
+                        stat: Stat::DistanceGlided,
+                        amount: whole as u64,
+                    });
+                }
+            }
+        }
+
+        glided.retain(|entity, _| entities.is_alive(*entity));
+    }
+}
@@ -8,10 +8,12 @@ use world::{IndexOwned, World};
 use crate::{
     chunk_generator::ChunkGenerator,
     client::Client,
+    farming::GrowingCrops,
     metrics::NetworkRequestMetrics,
     presence::{Presence, RepositionOnChunkLoad},
     rtsim::RtSim,
     settings::Settings,
+    site_state::SiteStates,
     ChunkRequest, SpawnPoint, Tick,
 };
 use common::{
@@ -124,6 +126,8 @@ impl<'a> System<'a> for Sys {
         Write<'a, Vec<ChunkRequest>>,
         WriteExpect<'a, RtSim>,
         TerrainPersistenceData<'a>,
+        WriteExpect<'a, GrowingCrops>,
+        ReadExpect<'a, SiteStates>,
         WriteStorage<'a, Pos>,
         ReadStorage<'a, Presence>,
         ReadStorage<'a, Client>,
@@ -157,6 +161,8 @@ impl<'a> System<'a> for Sys {
             mut chunk_requests,
             mut rtsim,
             mut _terrain_persistence,
+            mut growing_crops,
+            site_states,
             mut positions,
             presences,
             clients,
@@ -212,6 +218,20 @@ impl<'a> System<'a> for Sys {
                 terrain_persistence.apply_changes(key, &mut chunk);
             }
 
+            // Catch up on the growth of crops planted in the chunk while it was unloaded.
+            // Without terrain persistence the crops are gone once their chunk is unloaded,
+            // and are only forgotten here.
+            let _grown = growing_crops.grow_chunk(key, &mut chunk, crate::time::now());
+            #[cfg(feature = "persistent_world")]
+            if let Some(terrain_persistence) = _terrain_persistence.as_mut() {
+                for (pos, grown) in _grown {
+                    terrain_persistence.set_block(pos, grown);
+                }
+            }
+
+            // Take out the destroyed blocks of sites that haven't been repaired yet
+            site_states.apply_damage(key, &mut chunk);
+
             // Arcify the chunk
             let chunk = Arc::new(chunk);
 
@@ -238,6 +258,19 @@ impl<'a> System<'a> for Sys {
                     "Chunk spawned entity that wasn't nearby",
                 );
 
+                // Ships aren't NPCs: they need a collider to stand on and can be mounted
+                if let comp::Body::Ship(ship) = entity.body {
+                    server_emitter.emit(ServerEvent::CreateShip {
+                        pos: Pos(entity.pos),
+                        ship,
+                        mountable: true,
+                        agent: None,
+                        rtsim_entity: None,
+                        anchor: Some(comp::Anchor::Chunk(key)),
+                    });
+                    continue;
+                }
+
                 let data = NpcData::from_entity_info(entity);
                 match data {
                     NpcData::Waypoint(pos) => {
@@ -0,0 +1,5 @@
+//! Wall clock helpers for state that has to keep track of time while the
+//! server is stopped, such as rent and growing crops
+
+/// The current time as a unix timestamp, in seconds
+pub fn now() -> i64 { chrono::Utc::now().timestamp() }
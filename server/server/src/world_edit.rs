@@ -0,0 +1,183 @@
+//! Per-player state of the world-edit tools used by builders: the selected
+//! region, the clipboard and the history of edits that can be undone.
+
+use crate::schematic::Schematic;
+use common::terrain::{Block, SpriteKind};
+use specs::Component;
+use specs_idvs::IdvStorage;
+use std::collections::VecDeque;
+use vek::*;
+
+/// How many edits each player can undo.
+pub const MAX_UNDO: usize = 16;
+
+/// How many blocks the edits that each player can undo may replace in total.
+/// Edits replacing more blocks than this can't be undone.
+pub const MAX_UNDO_BLOCKS: usize = 1 << 18;
+
+#[derive(Default)]
+pub struct WorldEdit {
+    /// The two corners of the selected region, bounds included.
+    pub corners: [Option<Vec3<i32>>; 2],
+    /// Copied blocks, along with the offset of their minimum corner from the
+    /// player that copied them.
+    pub clipboard: Option<(Schematic, Vec3<i32>)>,
+    /// The blocks that each edit replaced, the latest edit last.
+    history: VecDeque<Vec<(Vec3<i32>, Block)>>,
+    /// The total number of blocks in `history`.
+    history_blocks: usize,
+}
+
+impl Component for WorldEdit {
+    type Storage = IdvStorage<Self>;
+}
+
+impl WorldEdit {
+    pub fn selection(&self) -> Option<Aabb<i32>> {
+        match self.corners {
+            [Some(a), Some(b)] => Some(Aabb { min: a, max: b }.made_valid()),
+            _ => None,
+        }
+    }
+
+    /// Remembers the blocks replaced by an edit, forgetting the oldest edits
+    /// if the history is full. Returns false if the edit is too large to be
+    /// undone, in which case the history is left as it was.
+    pub fn push_history(&mut self, replaced: Vec<(Vec3<i32>, Block)>) -> bool {
+        if replaced.len() > MAX_UNDO_BLOCKS {
+            return false;
+        }
+        while self.history.len() >= MAX_UNDO
+            || self.history_blocks + replaced.len() > MAX_UNDO_BLOCKS
+        {
+            match self.history.pop_front() {
+                Some(oldest) => self.history_blocks -= oldest.len(),
+                None => break,
+            }
+        }
+        self.history_blocks += replaced.len();
+        self.history.push_back(replaced);
+        true
+    }
+
+    /// The blocks to set to undo the latest edit.
+    pub fn pop_history(&mut self) -> Option<Vec<(Vec3<i32>, Block)>> {
+        let replaced = self.history.pop_back()?;
+        self.history_blocks -= replaced.len();
+        Some(replaced)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// Every block of the region.
+    Fill,
+    /// The six faces of the region, emptying the inside.
+    Hollow,
+    /// The four vertical sides of the region, leaving the inside untouched.
+    Walls,
+}
+
+impl Shape {
+    /// The block to set at `pos` to build this shape over `aabb` out of
+    /// `block`, if any.
+    pub fn block_at(&self, aabb: Aabb<i32>, pos: Vec3<i32>, block: Block) -> Option<Block> {
+        let on_side = pos.x == aabb.min.x
+            || pos.x == aabb.max.x
+            || pos.y == aabb.min.y
+            || pos.y == aabb.max.y;
+        let on_face = on_side || pos.z == aabb.min.z || pos.z == aabb.max.z;
+        match self {
+            Shape::Fill => Some(block),
+            Shape::Hollow if on_face => Some(block),
+            Shape::Hollow => Some(Block::air(SpriteKind::Empty)),
+            Shape::Walls if on_side => Some(block),
+            Shape::Walls => None,
+        }
+    }
+}
+
+/// Every position in `aabb`, bounds included.
+pub fn positions(aabb: Aabb<i32>) -> impl Iterator<Item = Vec3<i32>> {
+    (aabb.min.z..=aabb.max.z).flat_map(move |z| {
+        (aabb.min.y..=aabb.max.y)
+            .flat_map(move |y| (aabb.min.x..=aabb.max.x).map(move |x| Vec3::new(x, y, z)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::terrain::BlockKind;
+
+    fn edit(len: usize, z: i32) -> Vec<(Vec3<i32>, Block)> {
+        vec![(Vec3::new(0, 0, z), Block::empty()); len]
+    }
+
+    #[test]
+    fn undo_latest_edit_first() {
+        let mut world_edit = WorldEdit::default();
+        for z in 0..MAX_UNDO as i32 + 2 {
+            world_edit.push_history(edit(1, z));
+        }
+        for z in (2..MAX_UNDO as i32 + 2).rev() {
+            assert_eq!(world_edit.pop_history(), Some(edit(1, z)));
+        }
+        // The oldest edits were forgotten
+        assert_eq!(world_edit.pop_history(), None);
+        assert_eq!(world_edit.history_blocks, 0);
+    }
+
+    #[test]
+    fn history_is_capped_by_blocks() {
+        let mut world_edit = WorldEdit::default();
+        assert!(world_edit.push_history(edit(MAX_UNDO_BLOCKS / 2, 0)));
+        assert!(world_edit.push_history(edit(MAX_UNDO_BLOCKS / 2, 1)));
+        assert!(world_edit.push_history(edit(1, 2)));
+        assert_eq!(world_edit.history.len(), 2);
+        assert_eq!(world_edit.history_blocks, MAX_UNDO_BLOCKS / 2 + 1);
+
+        // Edits too large to undo leave the earlier edits undoable
+        assert!(!world_edit.push_history(edit(MAX_UNDO_BLOCKS + 1, 3)));
+        assert_eq!(world_edit.pop_history(), Some(edit(1, 2)));
+        assert_eq!(world_edit.history_blocks, MAX_UNDO_BLOCKS / 2);
+    }
+
+    #[test]
+    fn shapes() {
+        let aabb = Aabb {
+            min: Vec3::new(0, 0, 0),
+            max: Vec3::new(2, 2, 2),
+        };
+        let stone = Block::new(BlockKind::Rock, Rgb::new(100, 100, 100));
+        let air = Block::air(SpriteKind::Empty);
+        let block_at = |shape: Shape, pos| shape.block_at(aabb, pos, stone);
+
+        let centre = Vec3::new(1, 1, 1);
+        let floor = Vec3::new(1, 1, 0);
+        let side = Vec3::new(0, 1, 1);
+        assert_eq!(block_at(Shape::Fill, centre), Some(stone));
+        assert_eq!(block_at(Shape::Hollow, centre), Some(air));
+        assert_eq!(block_at(Shape::Hollow, floor), Some(stone));
+        assert_eq!(block_at(Shape::Walls, centre), None);
+        assert_eq!(block_at(Shape::Walls, floor), None);
+        assert_eq!(block_at(Shape::Walls, side), Some(stone));
+    }
+
+    #[test]
+    fn positions_cover_the_box_x_first() {
+        let aabb = Aabb {
+            min: Vec3::new(-1, 0, 5),
+            max: Vec3::new(0, 1, 5),
+        };
+        assert_eq!(
+            positions(aabb).collect::<Vec<_>>(),
+            vec![
+                Vec3::new(-1, 0, 5),
+                Vec3::new(0, 0, 5),
+                Vec3::new(-1, 1, 5),
+                Vec3::new(0, 1, 5),
+            ]
+        );
+    }
+}
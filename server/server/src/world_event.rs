@@ -0,0 +1,735 @@
+//! World events
+//!
+//! Every so often the server starts an event somewhere in the world for all
+//! players to take part in: monsters raiding a town, a world boss appearing at
+//! a point of interest, or a meteor striking the ground. Events are announced
+//! in chat and marked on the map, and their enemies are spawned once a player
+//! coming close has loaded the terrain around them. Everyone who comes close to
+//! an event or helps defeat its enemies takes part in it, and is rewarded with
+//! loot when it is won. Events are scheduled, started and settled by [`tick`],
+//! which runs once per server tick.
+
+use crate::{settings::WorldEventSettings, sys::terrain::NpcData, Server, Settings, StateExt};
+use common::{
+    assets::AssetExt,
+    combat::Reagent,
+    comp::{
+        self, inventory::item::Item, Alignment, ChatType, Health, Inventory, MapMarkerChange,
+        MapMarkerUpdate, Player, Pos, WorldEventKind,
+    },
+    generation::{EntityConfig, EntityInfo},
+    lottery::{LootSpec, Lottery},
+    outcome::Outcome,
+    resources::Time,
+    terrain::{Block, SpriteKind, TerrainChunkSize},
+    uid::Uid,
+};
+use common_net::{msg::ServerGeneral, sync::WorldSyncExt};
+use hashbrown::HashSet;
+use rand::prelude::*;
+use specs::{Builder, Entity as EcsEntity, Join, WorldExt};
+use std::f32::consts::TAU;
+use tracing::{debug, warn};
+use vek::*;
+use world::{civ::SiteKind, IndexRef, World};
+
+/// How far outside of a town raiders gather before attacking it, in blocks
+const RAID_DISTANCE: f32 = 48.0;
+/// Enemies spawn this far apart from each other at most, in blocks
+const SPAWN_SPREAD: i32 = 8;
+/// Radius of the crater left by a meteor, in blocks
+const CRATER_RADIUS: i32 = 10;
+/// The chance of each block of the floor of a crater holding velorite
+const VELORITE_CHANCE: f64 = 0.08;
+/// The number of random chunks tried when looking for a place for an event
+const PLACEMENT_ATTEMPTS: usize = 512;
+
+#[derive(Clone, Debug)]
+pub struct WorldEvent {
+    pub id: u64,
+    pub kind: WorldEventKind,
+    /// Name of the town the event takes place at or near
+    pub location: String,
+    /// Where the event is marked on the map
+    pub pos: Vec2<i32>,
+    /// Where the enemies spawn or the meteor strikes
+    spawn_pos: Vec2<i32>,
+    /// Time after which the event is over if it hasn't been won
+    pub ends: f64,
+    /// Whether the enemies have been spawned or the meteor has struck
+    started: bool,
+    /// Events with enemies are won once all of them are defeated. Those without
+    /// are won by everyone who came close before they ended.
+    has_enemies: bool,
+    /// Entity configs of the enemies yet to be spawned
+    pending: Vec<String>,
+    /// Enemies which are spawned and haven't been defeated yet, along with
+    /// their entity configs
+    enemies: Vec<(Uid, String)>,
+    /// Everyone who took part in the event
+    participants: HashSet<Uid>,
+}
+
+impl WorldEvent {
+    pub fn name(&self) -> String {
+        match self.kind {
+            WorldEventKind::Raid => format!("the raid on {}", self.location),
+            WorldEventKind::WorldBoss => format!("the world boss near {}", self.location),
+            WorldEventKind::Meteor => format!("the meteor strike near {}", self.location),
+        }
+    }
+
+    /// Whether all the enemies of the event have been defeated
+    fn is_won(&self) -> bool {
+        self.has_enemies && self.pending.is_empty() && self.enemies.is_empty()
+    }
+
+    /// Whether the event was won by the time it ended
+    fn was_won(&self) -> bool {
+        if self.has_enemies {
+            self.is_won()
+        } else {
+            self.started && !self.participants.is_empty()
+        }
+    }
+
+    /// Enemies that are gone without being defeated, such as those unloaded
+    /// along with their chunk, are spawned again once the area is loaded
+    fn respawn_missing_enemies(&mut self, exists: impl Fn(Uid) -> bool) {
+        let (enemies, missing) = std::mem::take(&mut self.enemies)
+            .into_iter()
+            .partition(|(uid, _)| exists(*uid));
+        self.enemies = enemies;
+        self.pending
+            .extend(missing.into_iter().map(|(_, config)| config));
+    }
+
+    fn marker(&self, change: MapMarkerChange) -> ServerGeneral {
+        ServerGeneral::MapMarker(MapMarkerUpdate::WorldEvent(self.id, self.kind, change))
+    }
+}
+
+/// The world events going on, and when the next one is due
+#[derive(Default)]
+pub struct WorldEvents {
+    next_id: u64,
+    /// The time at which the next event starts
+    next_start: Option<f64>,
+    events: Vec<WorldEvent>,
+}
+
+impl WorldEvents {
+    pub fn iter(&self) -> impl Iterator<Item = &WorldEvent> { self.events.iter() }
+}
+
+pub fn parse_kind(kind: &str) -> Option<WorldEventKind> {
+    match kind {
+        "raid" => Some(WorldEventKind::Raid),
+        "world_boss" => Some(WorldEventKind::WorldBoss),
+        "meteor" => Some(WorldEventKind::Meteor),
+        _ => None,
+    }
+}
+
+fn next_interval(settings: &WorldEventSettings, rng: &mut impl Rng) -> f64 {
+    let min = settings.min_interval_mins.min(settings.max_interval_mins);
+    (rng.gen_range(min..=settings.max_interval_mins) * 60) as f64
+}
+
+/// Towns with their names and centres
+fn towns<'a>(
+    world: &'a World,
+    index: IndexRef<'a>,
+) -> impl Iterator<Item = (&'a world::site::Site, Vec2<i32>)> + 'a {
+    world
+        .civs()
+        .sites()
+        .filter(|site| matches!(site.kind, SiteKind::Settlement | SiteKind::Refactor))
+        .filter_map(move |site| {
+            let site = &index.sites[site.site_tmp?];
+            Some((site, site.get_origin()))
+        })
+}
+
+fn nearest_town(world: &World, index: IndexRef, wpos: Vec2<i32>) -> String {
+    towns(world, index)
+        .min_by_key(|(_, center)| center.distance_squared(wpos))
+        .map_or_else(
+            || "the wilds".to_owned(),
+            |(site, _)| site.name().to_owned(),
+        )
+}
+
+/// Picks a random chunk fitting the given predicate
+fn find_chunk(
+    world: &World,
+    rng: &mut impl Rng,
+    f: impl Fn(&world::sim::SimChunk) -> bool,
+) -> Option<Vec2<i32>> {
+    let size = world.sim().get_size().map(|e| e as i32);
+    (0..PLACEMENT_ATTEMPTS)
+        .map(|_| Vec2::new(rng.gen_range(0..size.x), rng.gen_range(0..size.y)))
+        .find(|chunk_pos| world.sim().get(*chunk_pos).map_or(false, &f))
+}
+
+/// Finds where an event may take place: the name of the nearest town, where to
+/// mark it on the map and where its enemies spawn
+fn choose_place(
+    world: &World,
+    index: IndexRef,
+    kind: WorldEventKind,
+    rng: &mut impl Rng,
+) -> Option<(String, Vec2<i32>, Vec2<i32>)> {
+    match kind {
+        WorldEventKind::Raid => {
+            let (site, center) = towns(world, index).choose(rng)?;
+            let angle = rng.gen_range(0.0..TAU);
+            let dist = site.radius() + RAID_DISTANCE;
+            let spawn_pos = center + Vec2::new(angle.cos(), angle.sin()).map(|e| (e * dist) as i32);
+            Some((site.name().to_owned(), center, spawn_pos))
+        },
+        WorldEventKind::WorldBoss => {
+            let chunk_pos = find_chunk(world, rng, |chunk| chunk.spot.is_some())?;
+            let wpos = TerrainChunkSize::center_wpos(chunk_pos);
+            Some((nearest_town(world, index, wpos), wpos, wpos))
+        },
+        WorldEventKind::Meteor => {
+            let chunk_pos = find_chunk(world, rng, |chunk| {
+                chunk.sites.is_empty()
+                    && chunk.spot.is_none()
+                    && !chunk.is_underwater()
+                    && !chunk.river.near_water()
+            })?;
+            let wpos = TerrainChunkSize::center_wpos(chunk_pos);
+            Some((nearest_town(world, index, wpos), wpos, wpos))
+        },
+    }
+}
+
+/// Starts a world event of the given kind, or of a random one. On success,
+/// returns the name of the event.
+pub fn start(server: &mut Server, kind: Option<WorldEventKind>) -> Result<String, String> {
+    let mut rng = thread_rng();
+    let (kind, pending, loot_table, duration) = {
+        let settings = &server.state.ecs().read_resource::<Settings>().world_events;
+        let kind = match kind {
+            Some(kind) => kind,
+            None => *[
+                WorldEventKind::Raid,
+                WorldEventKind::WorldBoss,
+                WorldEventKind::Meteor,
+            ]
+            .choose_weighted(&mut rng, |kind| settings.kind(*kind).weight.max(0.0))
+            .map_err(|_| "No kind of world event may be started".to_owned())?,
+        };
+        let kind_settings = settings.kind(kind);
+        let pending = (0..kind_settings.enemy_count)
+            .filter_map(|_| kind_settings.enemies.choose(&mut rng).cloned())
+            .collect::<Vec<_>>();
+        (
+            kind,
+            pending,
+            kind_settings.loot_table.clone(),
+            settings.duration_mins * 60,
+        )
+    };
+    // Check the assets now, rather than when the event is under way
+    if let Some(config) = pending
+        .iter()
+        .find(|config| EntityConfig::load(config).is_err())
+    {
+        return Err(format!("Failed to load the entity config {}", config));
+    }
+    if Lottery::<LootSpec<String>>::load(&loot_table).is_err() {
+        return Err(format!("Failed to load the loot table {}", loot_table));
+    }
+
+    let (location, pos, spawn_pos) =
+        choose_place(&server.world, server.index.as_index_ref(), kind, &mut rng)
+            .ok_or_else(|| "Could not find a place for the world event".to_owned())?;
+
+    let time = server.state.ecs().read_resource::<Time>().0;
+    let mut events = server.state.ecs().write_resource::<WorldEvents>();
+    let event = WorldEvent {
+        id: events.next_id,
+        kind,
+        location,
+        pos,
+        spawn_pos,
+        ends: time + duration as f64,
+        started: false,
+        has_enemies: !pending.is_empty(),
+        pending,
+        enemies: Vec::new(),
+        participants: HashSet::new(),
+    };
+    events.next_id += 1;
+
+    let announcement = match kind {
+        WorldEventKind::Raid => format!(
+            "Monsters are gathering to raid {}! Help defend the town.",
+            event.location
+        ),
+        WorldEventKind::WorldBoss => format!(
+            "A world boss has appeared near {}! Defeat it for a reward.",
+            event.location
+        ),
+        WorldEventKind::Meteor => format!(
+            "A meteor is falling near {}! Reach the crater for a share of its riches.",
+            event.location
+        ),
+    };
+    server
+        .state
+        .notify_players(ServerGeneral::server_msg(ChatType::Meta, announcement));
+    server
+        .state
+        .notify_in_game_clients(event.marker(MapMarkerChange::Update(event.pos)));
+
+    let name = event.name();
+    events.events.push(event);
+    Ok(name)
+}
+
+/// Spawns one of the enemies of an event, returning its [`Uid`]
+fn spawn_enemy(
+    server: &mut Server,
+    config: &str,
+    pos: Vec3<f32>,
+    patrol_origin: Vec3<f32>,
+) -> Option<Uid> {
+    let entity_config = match EntityConfig::load(config) {
+        Ok(asset) => asset.read().clone(),
+        Err(e) => {
+            warn!(
+                ?e,
+                "Failed to load the entity config {} of a world event", config
+            );
+            return None;
+        },
+    };
+    let entity_info =
+        EntityInfo::at(pos).with_entity_config(entity_config, Some(config), &mut thread_rng());
+
+    match NpcData::from_entity_info(entity_info) {
+        NpcData::Waypoint(_) => None,
+        NpcData::Data {
+            pos,
+            stats,
+            skill_set,
+            health,
+            poise,
+            inventory,
+            agent,
+            body,
+            alignment,
+            scale,
+            loot,
+        } => {
+            let mut entity_builder = server
+                .state
+                .create_npc(pos, stats, skill_set, health, poise, inventory, body)
+                .with(alignment)
+                .with(scale);
+            if let Some(agent) = agent {
+                entity_builder = entity_builder.with(agent.with_patrol_origin(patrol_origin));
+            }
+            if let Some(drop_item) = loot.to_item() {
+                entity_builder = entity_builder.with(comp::ItemDrop(drop_item));
+            }
+            if let Alignment::Enemy = alignment {
+                entity_builder = entity_builder.with(comp::group::ENEMY);
+            }
+            let entity = entity_builder.build();
+            server
+                .state
+                .ecs()
+                .read_storage::<Uid>()
+                .get(entity)
+                .copied()
+        },
+    }
+}
+
+/// Leaves a crater with velorite strewn over its floor where a meteor struck
+fn strike_meteor(server: &mut Server, pos: Vec3<i32>) {
+    let mut rng = thread_rng();
+    let in_crater = |offset: Vec3<i32>| offset.magnitude_squared() <= CRATER_RADIUS.pow(2);
+    for x in -CRATER_RADIUS..=CRATER_RADIUS {
+        for y in -CRATER_RADIUS..=CRATER_RADIUS {
+            for z in -CRATER_RADIUS..=CRATER_RADIUS {
+                let offset = Vec3::new(x, y, z);
+                if !in_crater(offset) || !server.state.can_set_block(pos + offset) {
+                    continue;
+                }
+                // Velorite is strewn over the bottom of the crater, which is below the ground
+                let is_bottom = z < 0 && !in_crater(offset - Vec3::unit_z());
+                let sprite = if is_bottom && rng.gen_bool(VELORITE_CHANCE) {
+                    SpriteKind::Velorite
+                } else {
+                    SpriteKind::Empty
+                };
+                server.state.set_block(pos + offset, Block::air(sprite));
+            }
+        }
+    }
+
+    server
+        .state
+        .ecs()
+        .write_resource::<Vec<Outcome>>()
+        .push(Outcome::Explosion {
+            pos: pos.map(|e| e as f32),
+            power: 1.0,
+            radius: CRATER_RADIUS as f32,
+            is_attack: false,
+            reagent: Some(Reagent::Red),
+        });
+}
+
+/// Spawns the enemies of an event, or makes its meteor strike, once the
+/// terrain where it takes place is loaded
+fn begin(server: &mut Server, event: &mut WorldEvent) {
+    let ground = {
+        let terrain = server.state.terrain();
+        let chunk = match terrain.get_key(terrain.pos_key(event.spawn_pos.with_z(0))) {
+            Some(chunk) => chunk,
+            None => return,
+        };
+        let alt = server
+            .world
+            .sim()
+            .get_alt_approx(event.spawn_pos)
+            .unwrap_or(0.0) as i32;
+        terrain
+            .try_find_space(event.spawn_pos.with_z(alt))
+            .unwrap_or_else(|| chunk.find_accessible_pos(event.spawn_pos, false).as_())
+    };
+
+    if !event.started {
+        event.started = true;
+        if let WorldEventKind::Meteor = event.kind {
+            strike_meteor(server, ground);
+        }
+    }
+
+    // Raiders head for the town, other enemies stay where they appeared
+    let patrol_origin = match event.kind {
+        WorldEventKind::Raid => event.pos.map(|e| e as f32).with_z(ground.z as f32),
+        _ => ground.map(|e| e as f32),
+    };
+    let mut rng = thread_rng();
+    for config in std::mem::take(&mut event.pending) {
+        let offset = Vec2::new(
+            rng.gen_range(-SPAWN_SPREAD..=SPAWN_SPREAD),
+            rng.gen_range(-SPAWN_SPREAD..=SPAWN_SPREAD),
+        );
+        let pos = server
+            .state
+            .terrain()
+            .find_space(ground + offset.with_z(0))
+            .map(|e| e as f32)
+            + Vec3::new(0.5, 0.5, 0.0);
+        if let Some(uid) = spawn_enemy(server, &config, pos, patrol_origin) {
+            event.enemies.push((uid, config));
+        }
+    }
+}
+
+/// Gives the rewards of a won event to the players who took part in it,
+/// returning how many there were
+fn reward(server: &mut Server, event: &WorldEvent) -> usize {
+    let (loot_table, rewards) = {
+        let settings = &server.state.ecs().read_resource::<Settings>().world_events;
+        let settings = settings.kind(event.kind);
+        (
+            LootSpec::LootTable(settings.loot_table.clone()),
+            settings.rewards,
+        )
+    };
+
+    let mut rewarded = 0;
+    for uid in &event.participants {
+        let entity = match server.state.ecs().entity_from_uid((*uid).into()) {
+            Some(entity) if server.state.ecs().read_storage::<Player>().contains(entity) => entity,
+            _ => continue,
+        };
+        let items = (0..rewards)
+            .filter_map(|_| loot_table.to_item())
+            .collect::<Vec<Item>>();
+        let names = items
+            .iter()
+            .map(|item| item.name().to_owned())
+            .collect::<Vec<_>>();
+
+        let dropped = {
+            let mut inventories = server.state.ecs().write_storage::<Inventory>();
+            match inventories.get_mut(entity) {
+                Some(inventory) => items
+                    .into_iter()
+                    .filter_map(|item| inventory.push(item).err())
+                    .collect::<Vec<_>>(),
+                None => continue,
+            }
+        };
+        // Rewards that don't fit in the inventory are dropped at the player's feet
+        let pos = server
+            .state
+            .ecs()
+            .read_storage::<Pos>()
+            .get(entity)
+            .copied();
+        if let Some(pos) = pos {
+            for item in dropped {
+                server.state.create_item_drop(pos, &item).with(item).build();
+            }
+        }
+        let _ = server
+            .state
+            .ecs()
+            .write_storage::<comp::InventoryUpdate>()
+            .insert(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Given),
+            );
+
+        rewarded += 1;
+        let message = if names.is_empty() {
+            format!("Thank you for taking part in {}.", event.name())
+        } else {
+            format!(
+                "For taking part in {}, you received: {}",
+                event.name(),
+                names.join(", ")
+            )
+        };
+        server.notify_client(
+            entity,
+            ServerGeneral::server_msg(ChatType::CommandInfo, message),
+        );
+    }
+    rewarded
+}
+
+/// Announces the end of an event, rewarding its participants if it was won
+fn settle(server: &mut Server, event: WorldEvent) {
+    let won = event.was_won();
+
+    // Enemies of events that weren't won in time leave
+    for (uid, _) in &event.enemies {
+        if let Some(entity) = server.state.ecs().entity_from_uid((*uid).into()) {
+            if let Err(e) = server.state.delete_entity_recorded(entity) {
+                warn!(?e, "Failed to delete an enemy of a world event");
+            }
+        }
+    }
+
+    let message = if won {
+        let rewarded = reward(server, &event);
+        format!(
+            "Victory! {} adventurers took part in {} and were rewarded.",
+            rewarded,
+            event.name()
+        )
+    } else {
+        format!("Time ran out for {}.", event.name())
+    };
+    server
+        .state
+        .notify_players(ServerGeneral::server_msg(ChatType::Meta, message));
+    server
+        .state
+        .notify_in_game_clients(event.marker(MapMarkerChange::Remove));
+}
+
+pub fn tick(server: &mut Server) {
+    let time = server.state.ecs().read_resource::<Time>().0;
+
+    // Start the next event once it's due
+    let due = {
+        let settings = &server.state.ecs().read_resource::<Settings>().world_events;
+        let mut events = server.state.ecs().write_resource::<WorldEvents>();
+        if settings.enabled {
+            let next_start = *events
+                .next_start
+                .get_or_insert_with(|| time + next_interval(settings, &mut thread_rng()));
+            if time >= next_start {
+                events.next_start = None;
+                events.events.len() < settings.max_active
+            } else {
+                false
+            }
+        } else {
+            false
+        }
+    };
+    if due {
+        if let Err(e) = start(server, None) {
+            debug!("Failed to start a world event: {}", e);
+        }
+    }
+
+    let mut events = std::mem::take(&mut server.state.ecs().write_resource::<WorldEvents>().events);
+    if events.is_empty() {
+        return;
+    }
+    let radius = server
+        .state
+        .ecs()
+        .read_resource::<Settings>()
+        .world_events
+        .participation_radius;
+
+    for event in &mut events {
+        let ecs = server.state.ecs();
+        event.respawn_missing_enemies(|uid| ecs.entity_from_uid(uid.into()).is_some());
+
+        if !event.started || !event.pending.is_empty() {
+            begin(server, event);
+        }
+
+        let ecs = server.state.ecs();
+        for (uid, pos, _) in (
+            &ecs.read_storage::<Uid>(),
+            &ecs.read_storage::<Pos>(),
+            &ecs.read_storage::<Player>(),
+        )
+            .join()
+        {
+            let wpos = pos.0.xy().map(|e| e as i32);
+            if [event.pos, event.spawn_pos]
+                .iter()
+                .any(|p| p.distance_squared(wpos) < (radius as i32).pow(2))
+            {
+                event.participants.insert(*uid);
+            }
+        }
+    }
+
+    let (finished, ongoing) = events
+        .into_iter()
+        .partition::<Vec<_>, _>(|event| event.is_won() || time >= event.ends);
+    server.state.ecs().write_resource::<WorldEvents>().events = ongoing;
+    for event in finished {
+        settle(server, event);
+    }
+}
+
+/// Marks an enemy of a world event as defeated, crediting everyone who helped
+/// defeat it with taking part in the event
+pub fn handle_npc_death(ecs: &specs::World, entity: EcsEntity) {
+    let uid = match ecs.read_storage::<Uid>().get(entity) {
+        Some(uid) => *uid,
+        None => return,
+    };
+    let mut events = ecs.write_resource::<WorldEvents>();
+    if let Some(event) = events
+        .events
+        .iter_mut()
+        .find(|event| event.enemies.iter().any(|(enemy, _)| *enemy == uid))
+    {
+        event.enemies.retain(|(enemy, _)| *enemy != uid);
+        if let Some(health) = ecs.read_storage::<Health>().get(entity) {
+            event.participants.extend(
+                health
+                    .damage_contributions()
+                    .map(|(contributor, _)| contributor.uid()),
+            );
+        }
+    }
+}
+
+/// Shows the events going on to a player who just joined
+pub fn notify_joined(server: &Server, entity: EcsEntity) {
+    for event in server.state.ecs().read_resource::<WorldEvents>().iter() {
+        server.notify_client(entity, event.marker(MapMarkerChange::Update(event.pos)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MUGGER: &str = "common.entity.dungeon.gnarling.mugger";
+
+    fn event(pending: &[&str], enemies: &[u64]) -> WorldEvent {
+        WorldEvent {
+            id: 0,
+            kind: WorldEventKind::Raid,
+            location: "Testville".to_owned(),
+            pos: Vec2::zero(),
+            spawn_pos: Vec2::zero(),
+            ends: 100.0,
+            started: true,
+            has_enemies: !pending.is_empty() || !enemies.is_empty(),
+            pending: pending.iter().map(|config| config.to_string()).collect(),
+            enemies: enemies
+                .iter()
+                .map(|uid| (Uid(*uid), MUGGER.to_owned()))
+                .collect(),
+            participants: HashSet::new(),
+        }
+    }
+
+    #[test]
+    fn events_with_enemies_are_won_once_all_are_gone() {
+        let mut raid = event(&[], &[1, 2]);
+        assert!(!raid.was_won());
+        raid.enemies.retain(|(uid, _)| *uid != Uid(2));
+        assert!(!raid.was_won());
+        raid.enemies.clear();
+        assert!(raid.was_won());
+
+        // Enemies yet to be spawned have to be defeated too
+        let raid = event(&[MUGGER], &[]);
+        assert!(!raid.was_won());
+    }
+
+    #[test]
+    fn unloaded_enemies_are_spawned_again() {
+        let mut raid = event(&[], &[1, 2]);
+        raid.respawn_missing_enemies(|uid| uid == Uid(1));
+        assert_eq!(raid.enemies, vec![(Uid(1), MUGGER.to_owned())]);
+        assert_eq!(raid.pending, vec![MUGGER.to_owned()]);
+        assert!(!raid.is_won());
+
+        // Once every enemy is unloaded, none count as defeated
+        raid.respawn_missing_enemies(|_| false);
+        assert!(raid.enemies.is_empty());
+        assert_eq!(raid.pending.len(), 2);
+        assert!(!raid.was_won());
+    }
+
+    #[test]
+    fn events_without_enemies_are_won_by_participants() {
+        let mut meteor = event(&[], &[]);
+        assert!(!meteor.is_won());
+        assert!(!meteor.was_won());
+        meteor.participants.insert(Uid(7));
+        assert!(meteor.was_won());
+        meteor.started = false;
+        assert!(!meteor.was_won());
+    }
+
+    #[test]
+    fn intervals_are_within_the_settings() {
+        let mut rng = thread_rng();
+        let settings = WorldEventSettings {
+            min_interval_mins: 2,
+            max_interval_mins: 3,
+            ..WorldEventSettings::default()
+        };
+        for _ in 0..32 {
+            let interval = next_interval(&settings, &mut rng);
+            assert!((120.0..=180.0).contains(&interval));
+        }
+
+        // A minimum above the maximum is ignored
+        let settings = WorldEventSettings {
+            min_interval_mins: 10,
+            max_interval_mins: 5,
+            ..WorldEventSettings::default()
+        };
+        assert_eq!(next_interval(&settings, &mut rng), 300.0);
+    }
+}
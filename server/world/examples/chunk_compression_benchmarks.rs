@@ -678,6 +678,7 @@ fn main() {
             seed_elements: true,
             world_file: FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into()),
             calendar: None,
+            ..WorldOpts::default()
         },
         &pool,
     );
@@ -30,6 +30,7 @@ fn main() -> Result {
             seed_elements: true,
             world_file: FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into()),
             calendar: None,
+            ..WorldOpts::default()
         },
         &pool,
     );
@@ -50,6 +50,7 @@ fn main() {
             /* world_file: sim::FileOpts::Load(_map_file),
              * world_file: sim::FileOpts::Save(sim::SizeOpts::default()), */
             calendar: None,
+            ..WorldOpts::default()
         },
         &threadpool,
     );
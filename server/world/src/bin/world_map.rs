@@ -0,0 +1,242 @@
+use common::{assets::AssetExt, terrain::TerrainChunkSize, vol::RectVolSize};
+use common_net::msg::world_msg::{PoiKind, SiteKind, WorldMapMsg};
+use image::{
+    imageops::{self, FilterType},
+    RgbaImage,
+};
+use rayon::prelude::*;
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf};
+use structopt::StructOpt;
+use vek::*;
+use veloren_world::{
+    sim::{FileOpts, WorldOpts, DEFAULT_WORLD_MAP},
+    util::NEIGHBORS,
+    World, WorldgenPreset,
+};
+
+/// Renders the map of a world without running a server, along with a tile
+/// pyramid and the sites, points of interest and roads of the world as
+/// GeoJSON. It can be run with
+/// `cargo run --release --bin world_map --features bin_world_map -- <out>`.
+///
+/// Tiles are written to `<out>/tiles/<zoom>/<x>/<y>.png`, with tile (0, 0) in
+/// the north west corner of the world. GeoJSON coordinates are world block
+/// positions, with y pointing north.
+#[derive(StructOpt)]
+struct Cli {
+    /// Directory to write the map, tiles and GeoJSON to
+    out: PathBuf,
+    /// Seed of the world
+    #[structopt(long, default_value = "230")]
+    seed: u32,
+    /// World file to load, instead of the default world map
+    #[structopt(long)]
+    map_file: Option<PathBuf>,
+    /// Asset specifier of a worldgen preset to generate a new world with,
+    /// instead of loading the default world map
+    #[structopt(long)]
+    preset: Option<String>,
+    /// Width and height of each tile, in pixels
+    #[structopt(long, default_value = "256")]
+    tile_size: u32,
+    /// Zoom levels to render beyond the full resolution of the map, with the
+    /// map scaled up
+    #[structopt(long, default_value = "2")]
+    extra_zoom: u32,
+}
+
+fn main() {
+    let cli = Cli::from_args();
+
+    let preset = cli
+        .preset
+        .as_deref()
+        .map(WorldgenPreset::load_expect_cloned);
+    let world_file = match (&cli.map_file, &preset) {
+        (Some(path), _) => FileOpts::Load(path.clone()),
+        (None, Some(preset)) => FileOpts::Generate(preset.size.clone()),
+        (None, None) => FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into()),
+    };
+
+    let threadpool = rayon::ThreadPoolBuilder::new().build().unwrap();
+    println!("Generating world...");
+    let (world, index) = World::generate(
+        cli.seed,
+        WorldOpts {
+            seed_elements: true,
+            world_file,
+            preset: preset.unwrap_or_default(),
+            ..WorldOpts::default()
+        },
+        &threadpool,
+    );
+    let map = world.get_map_data(index.as_index_ref(), &threadpool);
+
+    fs::create_dir_all(&cli.out).expect("Couldn't create output directory");
+
+    println!("Rendering map...");
+    let image = map_image(&map);
+    image
+        .save(cli.out.join("map.png"))
+        .expect("Couldn't write map image");
+
+    println!("Rendering tiles...");
+    write_tiles(&cli, &image);
+
+    println!("Writing features...");
+    let features = features(&world, &map);
+    fs::write(
+        cli.out.join("features.geojson"),
+        serde_json::to_string_pretty(&features).expect("Couldn't serialize features"),
+    )
+    .expect("Couldn't write features");
+
+    println!("Done, written to {}", cli.out.display());
+}
+
+/// The map at its full resolution, of one pixel per chunk
+fn map_image(map: &WorldMapMsg) -> RgbaImage {
+    let size = map.rgba.size().map(|e| e as u32);
+    let raw = map
+        .rgba
+        .raw()
+        .iter()
+        .flat_map(|rgba| {
+            let [r, g, b, _] = rgba.to_le_bytes();
+            [r, g, b, 255]
+        })
+        .collect();
+    let image = RgbaImage::from_raw(size.x, size.y, raw).expect("Map has the wrong size");
+    // The map is stored with y pointing north, while images go from the top down
+    imageops::flip_vertical(&image)
+}
+
+fn write_tiles(cli: &Cli, image: &RgbaImage) {
+    let tile_size = cli.tile_size.max(1);
+    let size = Vec2::new(image.width(), image.height());
+    // The zoom level at which one map pixel is one tile pixel
+    let full_zoom = (size.reduce_max() as f32 / tile_size as f32)
+        .log2()
+        .ceil()
+        .max(0.0) as u32;
+
+    for zoom in 0..=full_zoom + cli.extra_zoom {
+        let scale = 2.0f32.powi(zoom as i32 - full_zoom as i32);
+        let zoom_size = size.map(|e| ((e as f32 * scale).ceil() as u32).max(1));
+        // Scale up without smoothing to keep the chunks crisp
+        let filter = if scale >= 1.0 {
+            FilterType::Nearest
+        } else {
+            FilterType::Triangle
+        };
+        let zoomed = imageops::resize(image, zoom_size.x, zoom_size.y, filter);
+        let tiles = zoom_size.map(|e| (e + tile_size - 1) / tile_size);
+
+        (0..tiles.x)
+            .into_par_iter()
+            .flat_map(|x| (0..tiles.y).into_par_iter().map(move |y| (x, y)))
+            .for_each(|(x, y)| {
+                let dir = cli
+                    .out
+                    .join("tiles")
+                    .join(zoom.to_string())
+                    .join(x.to_string());
+                fs::create_dir_all(&dir).expect("Couldn't create tile directory");
+                // Tiles on the edge of the map are padded with transparency
+                let mut tile = RgbaImage::new(tile_size, tile_size);
+                let part =
+                    imageops::crop_imm(&zoomed, x * tile_size, y * tile_size, tile_size, tile_size);
+                imageops::replace(&mut tile, &part.to_image(), 0, 0);
+                tile.save(dir.join(format!("{}.png", y)))
+                    .expect("Couldn't write tile");
+            });
+    }
+}
+
+fn point(wpos: Vec2<i32>, properties: Value) -> Value {
+    json!({
+        "type": "Feature",
+        "geometry": { "type": "Point", "coordinates": [wpos.x, wpos.y] },
+        "properties": properties,
+    })
+}
+
+/// Sites (including caves), points of interest and roads of the world
+fn features(world: &World, map: &WorldMapMsg) -> Value {
+    let sites = map.sites.iter().map(|site| {
+        let (kind, difficulty) = match site.kind {
+            SiteKind::Town => ("town", None),
+            SiteKind::Dungeon { difficulty } => ("dungeon", Some(difficulty)),
+            SiteKind::Castle => ("castle", None),
+            SiteKind::Cave => ("cave", None),
+            SiteKind::Tree => ("tree", None),
+            SiteKind::Gnarling => ("gnarling", None),
+            SiteKind::Mine => ("mine", None),
+            SiteKind::Port => ("port", None),
+            SiteKind::BanditCamp => ("bandit_camp", None),
+        };
+        point(
+            site.wpos,
+            json!({
+                "layer": "sites",
+                "id": site.id,
+                "kind": kind,
+                "name": site.name,
+                "difficulty": difficulty,
+            }),
+        )
+    });
+
+    let pois = map.pois.iter().map(|poi| {
+        let (kind, size) = match poi.kind {
+            PoiKind::Peak(alt) => ("peak", alt),
+            PoiKind::Lake(size) => ("lake", size),
+        };
+        point(
+            poi.wpos,
+            json!({ "layer": "pois", "kind": kind, "name": poi.name, "size": size }),
+        )
+    });
+
+    // Each road connection is set on the chunks at both of its ends, so only
+    // half of the directions are needed to find every connection once.
+    let chunk_size = TerrainChunkSize::RECT_SIZE.map(|e| e as i32);
+    let sim = world.sim();
+    let way_wpos = |chunk_pos: Vec2<i32>| {
+        sim.get(chunk_pos).map(|chunk| {
+            chunk_pos * chunk_size + chunk_size / 2 + chunk.path.0.offset.map(i32::from)
+        })
+    };
+    let roads = (0..sim.get_size().x as i32)
+        .flat_map(|x| (0..sim.get_size().y as i32).map(move |y| Vec2::new(x, y)))
+        .filter_map(|chunk_pos| Some((chunk_pos, sim.get(chunk_pos)?.path.0)))
+        .flat_map(|(chunk_pos, way)| {
+            NEIGHBORS
+                .iter()
+                .enumerate()
+                .take(4)
+                .filter(move |(i, _)| way.neighbors & (1 << i) != 0)
+                .filter_map(move |(_, dir)| {
+                    let start = way_wpos(chunk_pos)?;
+                    let end = way_wpos(chunk_pos + *dir)?;
+                    Some([[start.x, start.y], [end.x, end.y]])
+                })
+        })
+        .collect::<Vec<_>>();
+    let roads = json!({
+        "type": "Feature",
+        "geometry": { "type": "MultiLineString", "coordinates": roads },
+        "properties": { "layer": "roads" },
+    });
+
+    let world_size = sim.get_size().map(|e| e as i32) * chunk_size;
+    json!({
+        "type": "FeatureCollection",
+        "bbox": [0, 0, world_size.x, world_size.y],
+        "features": sites
+            .chain(pois)
+            .chain(std::iter::once(roads))
+            .collect::<Vec<_>>(),
+    })
+}
@@ -3,7 +3,7 @@
 mod econ;
 
 use crate::{
-    config::CONFIG,
+    config::{WorldgenPreset, CONFIG},
     sim::WorldSim,
     site::{namegen::NameGen, Castle, Settlement, Site as WorldSite, Tree},
     site2,
@@ -76,7 +76,12 @@ impl<'a, R: Rng> GenCtx<'a, R> {
 }
 
 impl Civs {
-    pub fn generate(seed: u32, sim: &mut WorldSim, index: &mut Index) -> Self {
+    pub fn generate(
+        seed: u32,
+        sim: &mut WorldSim,
+        index: &mut Index,
+        preset: &WorldgenPreset,
+    ) -> Self {
         let mut this = Self::default();
         let rng = ChaChaRng::from_seed(seed_expan::rng_state(seed));
         let initial_civ_count = initial_civ_count(sim.map_size_lg());
@@ -86,10 +91,15 @@ impl Civs {
         info!("starting biome naming");
         this.name_biomes(&mut ctx);
 
-        for _ in 0..ctx.sim.get_size().product() / 10_000 {
-            this.generate_cave(&mut ctx);
+        if preset.caves {
+            for _ in 0..ctx.sim.get_size().product() / 10_000 {
+                this.generate_cave(&mut ctx);
+            }
         }
 
+        // Worlds without sites have no civilisations either
+        let initial_civ_count = if preset.sites { initial_civ_count } else { 0 };
+
         let mut start_locations: Vec<Vec2<i32>> = Vec::new();
         for _ in 0..initial_civ_count {
             debug!("Creating civilisation...");
@@ -106,9 +116,15 @@ impl Civs {
         let mut dungeon_enemies: Vec<Vec2<i32>> = start_locations.clone();
         let mut tree_enemies: Vec<Vec2<i32>> = start_locations.clone();
         let mut castle_enemies: Vec<Vec2<i32>> = Vec::new();
-        for _ in 0..initial_civ_count * 3 {
+        let mut mine_enemies: Vec<Vec2<i32>> = Vec::new();
+        let mut port_enemies: Vec<Vec2<i32>> = start_locations.clone();
+        let mut bandit_enemies: Vec<Vec2<i32>> = start_locations.clone();
+        // Mines, ports and bandit camps were added on top of the other sites, with 12
+        // of the 76 rolls. There are as many more attempts, so that the other
+        // sites are as common as before.
+        for _ in 0..initial_civ_count * 3 * 76 / 64 {
             attempt(5, || {
-                let (kind, size, avoid) = match ctx.rng.gen_range(0..64) {
+                let (kind, size, avoid) = match ctx.rng.gen_range(0..76) {
                     0..=5 => (SiteKind::Castle, 3, (&castle_enemies, 20)),
                     28..=31 => {
                         if index.features().site2_giant_trees {
@@ -118,6 +134,9 @@ impl Civs {
                         }
                     },
                     32..=37 => (SiteKind::Gnarling, 5, (&gnarling_enemies, 20)),
+                    64..=67 => (SiteKind::Mine, 0, (&mine_enemies, 20)),
+                    68..=71 => (SiteKind::Port, 2, (&port_enemies, 30)),
+                    72..=75 => (SiteKind::BanditCamp, 3, (&bandit_enemies, 20)),
                     _ => (SiteKind::Dungeon, 0, (&dungeon_enemies, 20)),
                 };
                 let loc = find_site_loc(&mut ctx, avoid, size, kind)?;
@@ -127,16 +146,33 @@ impl Civs {
                         dungeon_enemies.push(loc);
                         tree_enemies.push(loc);
                         castle_enemies.push(loc);
+                        bandit_enemies.push(loc);
                     },
                     SiteKind::Gnarling => {
                         castle_enemies.push(loc);
                         dungeon_enemies.push(loc);
                         gnarling_enemies.push(loc);
+                        bandit_enemies.push(loc);
                     },
                     SiteKind::Dungeon => {
                         gnarling_enemies.push(loc);
                         dungeon_enemies.push(loc);
                         castle_enemies.push(loc);
+                        mine_enemies.push(loc);
+                    },
+                    SiteKind::Mine => {
+                        dungeon_enemies.push(loc);
+                        mine_enemies.push(loc);
+                    },
+                    SiteKind::Port => {
+                        port_enemies.push(loc);
+                        bandit_enemies.push(loc);
+                    },
+                    SiteKind::BanditCamp => {
+                        castle_enemies.push(loc);
+                        gnarling_enemies.push(loc);
+                        port_enemies.push(loc);
+                        bandit_enemies.push(loc);
                     },
                     _ => (),
                 }
@@ -164,6 +200,9 @@ impl Civs {
                 SiteKind::Tree => (12i32, 8.0),
                 SiteKind::GiantTree => (12i32, 8.0),
                 SiteKind::Gnarling => (16i32, 10.0),
+                SiteKind::Mine => (6i32, 3.0),
+                SiteKind::Port => (8i32, 4.0),
+                SiteKind::BanditCamp => (12i32, 6.0),
             };
 
             let (raise, raise_dist, make_waypoint): (f32, i32, bool) = match &site.kind {
@@ -252,6 +291,21 @@ impl Civs {
                     &mut rng,
                     wpos,
                 )),
+                SiteKind::Mine => WorldSite::mine(site2::Site::generate_mine(
+                    &Land::from_sim(ctx.sim),
+                    &mut rng,
+                    wpos,
+                )),
+                SiteKind::Port => WorldSite::port(site2::Site::generate_port(
+                    &Land::from_sim(ctx.sim),
+                    &mut rng,
+                    wpos,
+                )),
+                SiteKind::BanditCamp => WorldSite::bandit_camp(site2::Site::generate_camp(
+                    &Land::from_sim(ctx.sim),
+                    &mut rng,
+                    wpos,
+                )),
             });
             sim_site.site_tmp = Some(site);
             let site_ref = &index.sites[site];
@@ -1152,6 +1206,9 @@ pub enum SiteKind {
     Tree,
     GiantTree,
     Gnarling,
+    Mine,
+    Port,
+    BanditCamp,
 }
 
 impl SiteKind {
@@ -1159,6 +1216,27 @@ impl SiteKind {
         sim.get(loc).map_or(false, |chunk| match self {
             SiteKind::Gnarling => (-0.3..0.4).contains(&chunk.temp) && chunk.tree_density > 0.75,
             SiteKind::GiantTree | SiteKind::Tree => chunk.tree_density > 0.4,
+            // Mines are dug down into a cave passing deep enough below
+            SiteKind::Mine => {
+                chunk.cave.0.is_way()
+                    && (32.0..160.0).contains(&(chunk.alt - chunk.cave.1.alt))
+                    && !chunk.river.near_water()
+            },
+            SiteKind::Port => {
+                const OCEAN_RADIUS: i32 = 2;
+                if chunk.alt - CONFIG.sea_level > 12.0 || chunk.near_cliffs() {
+                    return false;
+                }
+                (-OCEAN_RADIUS..=OCEAN_RADIUS)
+                    .flat_map(|x| (-OCEAN_RADIUS..=OCEAN_RADIUS).map(move |y| Vec2::new(x, y)))
+                    .any(|rpos| sim.get(loc + rpos).map_or(false, |c| c.river.is_ocean()))
+            },
+            SiteKind::BanditCamp => {
+                (-0.3..0.6).contains(&chunk.temp)
+                    && chunk.tree_density > 0.3
+                    && !chunk.river.near_water()
+                    && !chunk.near_cliffs()
+            },
             SiteKind::Castle => {
                 if chunk.tree_density > 0.4 || chunk.river.near_water() || chunk.near_cliffs() {
                     return false;
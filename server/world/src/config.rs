@@ -1,7 +1,13 @@
+use crate::sim::SizeOpts;
 use common::assets;
-use serde::Deserialize;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing::warn;
 use vek::*;
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub sea_level: f32,
     pub mountain_scale: f32,
@@ -56,28 +62,109 @@ pub struct Config {
     pub ice_color: Rgb<u8>,
 }
 
-pub const CONFIG: Config = Config {
-    sea_level: 140.0,
-    mountain_scale: 2048.0,
-    // temperature
-    snow_temp: -0.8,
-    temperate_temp: -0.4,
-    tropical_temp: 0.4,
-    desert_temp: 0.8,
-    // humidity
-    desert_hum: 0.15,
-    forest_hum: 0.5,
-    jungle_hum: 0.75,
-    // water
-    rainfall_chunk_rate: 1.0 / (512.0 * 32.0 * 32.0),
-    river_roughness: 0.06125,
-    river_max_width: 2.0,
-    river_min_height: 0.25,
-    river_width_to_depth: 8.0,
-    ice_color: Rgb::new(140, 175, 255),
-};
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            sea_level: 140.0,
+            mountain_scale: 2048.0,
+            // temperature
+            snow_temp: -0.8,
+            temperate_temp: -0.4,
+            tropical_temp: 0.4,
+            desert_temp: 0.8,
+            // humidity
+            desert_hum: 0.15,
+            forest_hum: 0.5,
+            jungle_hum: 0.75,
+            // water
+            rainfall_chunk_rate: 1.0 / (512.0 * 32.0 * 32.0),
+            river_roughness: 0.06125,
+            river_max_width: 2.0,
+            river_min_height: 0.25,
+            river_width_to_depth: 8.0,
+            ice_color: Rgb::new(140, 175, 255),
+        }
+    }
+}
+
+lazy_static! {
+    static ref PENDING_CONFIG: Mutex<Option<Config>> = Mutex::new(None);
+    /// The parameters of the world being generated, taken from the preset it
+    /// is generated with (see [`set_config`]).
+    pub static ref CONFIG: Config = PENDING_CONFIG
+        .lock()
+        .expect("Poisoned worldgen config lock")
+        .take()
+        .unwrap_or_default();
+}
+
+/// Set the parameters for the world that is about to be generated.
+///
+/// Worldgen relies on these parameters everywhere, so they can only be set
+/// once per process, before the first world is generated. Later calls with
+/// different parameters have no effect.
+pub fn set_config(config: Config) {
+    *PENDING_CONFIG
+        .lock()
+        .expect("Poisoned worldgen config lock") = Some(config.clone());
+    if *CONFIG != config {
+        warn!(
+            "The worldgen config has already been set for this process, so the world will be \
+             generated with the first config instead"
+        );
+    }
+}
+
+/// A set of worldgen parameters that a server can pick to generate its world
+/// with. The preset a world was generated with is saved in its world file, so
+/// that it always regenerates the same way.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorldgenPreset {
+    /// The size of newly generated worlds
+    pub size: SizeOpts,
+    pub config: Config,
+    /// Whether civilisations and the sites that come with them are generated
+    pub sites: bool,
+    /// Whether caves are generated
+    pub caves: bool,
+    /// Multiplies the wildlife density from the world features
+    pub wildlife_density: f32,
+}
+
+impl Default for WorldgenPreset {
+    fn default() -> Self {
+        Self {
+            size: SizeOpts::default(),
+            config: Config::default(),
+            sites: true,
+            caves: true,
+            wildlife_density: 1.0,
+        }
+    }
+}
 
-#[derive(Deserialize)]
+impl WorldgenPreset {
+    /// The world features with those that the preset disables turned off
+    pub fn apply_to(&self, features: &Features) -> Features {
+        Features {
+            caverns: features.caverns && self.caves,
+            caves: features.caves && self.caves,
+            site2_towns: features.site2_towns && self.sites,
+            site2_giant_trees: features.site2_giant_trees && self.sites,
+            wildlife_density: features.wildlife_density * self.wildlife_density,
+            ..features.clone()
+        }
+    }
+}
+
+impl assets::Asset for WorldgenPreset {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+#[derive(Clone, Debug, Deserialize)]
 pub struct Features {
     pub caverns: bool,
     pub caves: bool,
@@ -97,3 +184,15 @@ impl assets::Asset for Features {
 
     const EXTENSION: &'static str = "ron";
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assets::AssetExt;
+
+    #[test]
+    fn default_preset_matches_the_defaults() {
+        let preset = WorldgenPreset::load_expect_cloned("world.preset.default");
+        assert_eq!(preset, WorldgenPreset::default());
+    }
+}
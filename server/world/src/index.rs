@@ -1,7 +1,7 @@
 use crate::{
     layer::wildlife::{self, DensityFn, SpawnEntry},
     site::{economy::TradeInformation, Site},
-    Colors, Features,
+    Colors, Features, WorldgenPreset,
 };
 use common::{
     assets::{AssetExt, AssetHandle},
@@ -22,6 +22,9 @@ pub struct Index {
     pub sites: Store<Site>,
     pub trade: TradeInformation,
     pub wildlife_spawns: Vec<(AssetHandle<SpawnEntry>, DensityFn)>,
+    /// The preset the world was generated with, which can turn some of the
+    /// world features off.
+    pub preset: WorldgenPreset,
     colors: AssetHandle<Arc<Colors>>,
     features: AssetHandle<Arc<Features>>,
 }
@@ -77,6 +80,7 @@ impl Index {
             sites: Store::default(),
             trade: Default::default(),
             wildlife_spawns,
+            preset: WorldgenPreset::default(),
             colors,
             features,
         }
@@ -97,7 +101,7 @@ impl Index {
 impl IndexOwned {
     pub fn new(index: Index) -> Self {
         let colors = index.colors.cloned();
-        let features = index.features.cloned();
+        let features = Arc::new(index.preset.apply_to(&index.features.read()));
 
         Self {
             index: Arc::new(index),
@@ -118,7 +122,7 @@ impl IndexOwned {
         reloaded.then(move || {
             // Reload the fields from the asset handle, which is updated automatically
             self.colors = self.index.colors.cloned();
-            self.features = self.index.features.cloned();
+            self.features = Arc::new(self.index.preset.apply_to(&self.index.features.read()));
             // Update wildlife spawns which is based on base_density in features
             reload(self)
         })
@@ -36,4 +36,12 @@ impl<'a> Land<'a> {
     pub fn get_chunk_wpos(&self, wpos: Vec2<i32>) -> Option<&sim::SimChunk> {
         self.sim.and_then(|sim| sim.get_wpos(wpos))
     }
+
+    /// The distance to the nearest point of a cave, that point and the cave
+    /// there.
+    pub fn get_nearest_cave(&self, wpos: Vec2<i32>) -> Option<(f32, Vec2<f32>, sim::Cave)> {
+        self.sim
+            .and_then(|sim| sim.get_nearest_cave(wpos))
+            .map(|(dist, pos, cave, _)| (dist, pos, cave))
+    }
 }
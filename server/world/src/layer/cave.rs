@@ -0,0 +1,133 @@
+use common::{
+    assets::{self, AssetExt, AssetHandle},
+    lottery::Lottery,
+    terrain::{BlockKind, SpriteKind},
+};
+use serde::Deserialize;
+use vek::*;
+
+const CAVE_LAYERS: &str = "world.cave.layers";
+
+/// A layer of the underground, with its own look and inhabitants
+///
+/// Caves take on the layer found at their depth below the surface, which
+/// decides the blocks they are made of, what grows and lights them, which ores
+/// can be mined in them and what lives in them. Lotteries and spawn tables
+/// are given as asset specifiers.
+#[derive(Clone, Debug, Deserialize)]
+pub struct CaveLayer {
+    /// User-facing info for wiki, statistical tools, etc.
+    pub name: String,
+    /// Depth below the surface that the layer starts at, in blocks
+    pub min_depth: f32,
+    /// Kind of the blocks making up the floor of the caves
+    pub floor_kind: BlockKind,
+    pub floor_color: (u8, u8, u8),
+    pub roof_color: (u8, u8, u8),
+    pub stalactite_color: (u8, u8, u8),
+    pub vein_color: (u8, u8, u8),
+    /// Whether caves have stalactites hanging from their roof
+    pub stalactites: bool,
+    /// Whether caves have pits of lava in their floor
+    pub lava_pits: bool,
+    /// Whether caves have glowing veins of ore running under their floor
+    pub veins: bool,
+    /// If set, caves are flooded up to this height above their centre line, in
+    /// blocks
+    #[serde(default)]
+    pub flood_level: Option<f32>,
+    /// Lottery of sprites growing on the floor, and how densely they do
+    pub floor_sprites: (String, f32),
+    /// Lottery of sprites hanging from the roof, and how densely they do
+    pub ceiling_sprites: (String, f32),
+    /// Lottery of sprites lighting the caves, and how densely they do
+    pub light_sprites: (String, f32),
+    /// Lottery of ores found on the floor and in veins, and how densely they
+    /// are found on the floor
+    pub ores: (String, f32),
+    /// Lottery of entity configs of the wildlife, and the chance of each
+    /// column spawning some
+    pub wildlife: (String, f32),
+}
+
+impl CaveLayer {
+    pub fn floor_sprites(&self) -> AssetHandle<Lottery<SpriteKind>> {
+        Lottery::load_expect(&self.floor_sprites.0)
+    }
+
+    pub fn ceiling_sprites(&self) -> AssetHandle<Lottery<SpriteKind>> {
+        Lottery::load_expect(&self.ceiling_sprites.0)
+    }
+
+    pub fn light_sprites(&self) -> AssetHandle<Lottery<SpriteKind>> {
+        Lottery::load_expect(&self.light_sprites.0)
+    }
+
+    pub fn ores(&self) -> AssetHandle<Lottery<SpriteKind>> { Lottery::load_expect(&self.ores.0) }
+
+    pub fn wildlife(&self) -> AssetHandle<Lottery<String>> {
+        Lottery::load_expect(&self.wildlife.0)
+    }
+
+    pub fn floor_color(&self) -> Rgb<u8> { self.floor_color.into() }
+
+    pub fn roof_color(&self) -> Rgb<u8> { self.roof_color.into() }
+
+    pub fn stalactite_color(&self) -> Rgb<u8> { self.stalactite_color.into() }
+
+    pub fn vein_color(&self) -> Rgb<u8> { self.vein_color.into() }
+}
+
+/// The layers of the underground, from the surface down
+#[derive(Clone, Debug, Deserialize)]
+pub struct CaveLayers(Vec<CaveLayer>);
+
+impl assets::Asset for CaveLayers {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl CaveLayers {
+    pub fn get() -> AssetHandle<Self> { Self::load_expect(CAVE_LAYERS) }
+
+    /// The layer at the given depth below the surface
+    pub fn at_depth(&self, depth: f32) -> &CaveLayer {
+        self.0
+            .iter()
+            .rev()
+            .find(|layer| depth >= layer.min_depth)
+            .or_else(|| self.0.first())
+            .expect("There must be at least one cave layer")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::generation::EntityInfo;
+
+    #[test]
+    fn test_load_layers() {
+        let layers = CaveLayers::get().read();
+        assert!(
+            layers
+                .0
+                .windows(2)
+                .all(|pair| pair[0].min_depth < pair[1].min_depth),
+            "Cave layers must be ordered from the surface down"
+        );
+        let mut dummy_rng = rand::thread_rng();
+        for layer in layers.0.iter() {
+            std::mem::drop(layer.floor_sprites());
+            std::mem::drop(layer.ceiling_sprites());
+            std::mem::drop(layer.light_sprites());
+            std::mem::drop(layer.ores());
+            for (_, config) in layer.wildlife().read().iter() {
+                std::mem::drop(
+                    EntityInfo::at(Vec3::zero()).with_asset_expect(config, &mut dummy_rng),
+                );
+            }
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod cave;
 pub mod scatter;
 pub mod shrub;
 pub mod spot;
@@ -15,9 +16,7 @@ use crate::{
     Canvas, IndexRef,
 };
 use common::{
-    assets::AssetExt,
     generation::{ChunkSupplement, EntityInfo},
-    lottery::Lottery,
     terrain::{Block, BlockKind, SpriteKind},
     vol::{BaseVol, ReadVol, RectSizedVol, WriteVol},
 };
@@ -34,13 +33,9 @@ use vek::*;
 #[derive(Deserialize)]
 pub struct Colors {
     pub bridge: (u8, u8, u8),
-    pub stalactite: (u8, u8, u8),
-    pub cave_floor: (u8, u8, u8),
-    pub cave_roof: (u8, u8, u8),
     pub dirt: (u8, u8, u8),
     pub scaffold: (u8, u8, u8),
     pub lava: (u8, u8, u8),
-    pub vein: (u8, u8, u8),
 }
 
 const EMPTY_AIR: Block = Block::air(SpriteKind::Empty);
@@ -120,6 +115,8 @@ pub fn apply_paths_to(canvas: &mut Canvas) {
 
 pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
     let info = canvas.info();
+    let layers = cave::CaveLayers::get();
+    let layers = layers.read();
     canvas.foreach_col(|canvas, wpos2d, col| {
         let surface_z = col.alt.floor() as i32;
 
@@ -130,6 +127,7 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
 
             // Relative units
             let cave_depth = (col.alt - cave.alt).max(0.0);
+            let layer = layers.at_depth(cave_depth);
 
             let cave_floor = 0.0 - 0.5 * (1.0 - cave_x.powi(2)).max(0.0).sqrt() * cave.width;
             let cave_height = (1.0 - cave_x.powi(2)).max(0.0).sqrt() * cave.width;
@@ -167,12 +165,12 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
             };
 
             let ridge_condition = cave_depth % 10.0 > 8.0 && cave_depth > 10.0;
-            let pit_condition = cave_depth % 42.0 > 37.0 && cave_x > 0.6 && cave_depth > 200.0;
+            let pit_condition = layer.lava_pits && cave_depth % 42.0 > 37.0 && cave_x > 0.6;
             let pit_depth = 30;
             let floor_dist = pit_condition as i32 * pit_depth as i32;
             let vein_condition =
-                cave_depth % 12.0 > 11.5 && cave_x > 0.1 && cave_x < 0.6 && cave_depth > 200.0;
-            let stalactite_condition = cave_depth > 150.0;
+                layer.veins && cave_depth % 12.0 > 11.5 && cave_x > 0.1 && cave_x < 0.6;
+            let stalactite_condition = layer.stalactites;
             let vein_depth = 3;
             let vein_floor = cave_base - vein_depth;
             // Stalagtites
@@ -201,14 +199,14 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
                         Vec3::new(wpos2d.x, wpos2d.y, z),
                         Block::new(
                             BlockKind::WeakRock,
-                            noisy_color(info.index().colors.layer.stalactite.into(), 8),
+                            noisy_color(layer.stalactite_color(), 8),
                         ),
                     );
                 }
             }
 
             let ground_colors = if cave_roof - cave_base > 23 {
-                noisy_color(info.index().colors.layer.cave_floor.into(), 8)
+                noisy_color(layer.floor_color(), 8)
             } else {
                 noisy_color(info.index().colors.layer.dirt.into(), 8)
             };
@@ -258,7 +256,7 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
                         Vec3::new(wpos2d.x, wpos2d.y, z),
                         Block::new(
                             BlockKind::GlowingWeakRock,
-                            noisy_color(info.index().colors.layer.vein.into(), 16),
+                            noisy_color(layer.vein_color(), 16),
                         ),
                     );
                 }
@@ -270,15 +268,13 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
                         Vec3::new(wpos2d.x, wpos2d.y, z),
                         Block::new(
                             BlockKind::GlowingWeakRock,
-                            noisy_color(info.index().colors.layer.vein.into(), 16),
+                            noisy_color(layer.vein_color(), 16),
                         ),
                     );
                 }
             }
             if vein_condition && rng.gen::<f32>() > 0.7 {
-                let kind = *Lottery::<SpriteKind>::load_expect("common.cave_scatter.vein")
-                    .read()
-                    .choose();
+                let kind = *layer.ores().read().choose();
                 canvas.map(Vec3::new(wpos2d.x, wpos2d.y, vein_floor), |block| {
                     block.with_sprite(kind)
                 });
@@ -293,7 +289,7 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
                 {
                     canvas.set(
                         Vec3::new(wpos2d.x, wpos2d.y, z),
-                        Block::new(BlockKind::WeakRock, ground_colors),
+                        Block::new(layer.floor_kind, ground_colors),
                     );
                 }
             }
@@ -302,10 +298,7 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
                 if cave_depth > 30.0 && (cave_roof - cave_base) > 10 {
                     canvas.set(
                         Vec3::new(wpos2d.x, wpos2d.y, z),
-                        Block::new(
-                            BlockKind::WeakRock,
-                            noisy_color(info.index().colors.layer.cave_roof.into(), 8),
-                        ),
+                        Block::new(BlockKind::WeakRock, noisy_color(layer.roof_color(), 8)),
                     );
                 }
             }
@@ -334,77 +327,50 @@ pub fn apply_caves_to(canvas: &mut Canvas, rng: &mut impl Rng) {
                 cave_base - floor_dist
             };
 
-            // Scatter things in caves
-            if cave_depth > 40.0 && cave_depth < 80.0 {
-                if rng.gen::<f32>() < 0.14 * (cave_x.max(0.5).powf(4.0)) && !vein_condition {
-                    let kind =
-                        *Lottery::<SpriteKind>::load_expect("common.cave_scatter.shallow_floor")
-                            .read()
-                            .choose();
-                    canvas.map(
-                        Vec3::new(wpos2d.x, wpos2d.y, cave_floor_adjusted),
-                        |block| block.with_sprite(kind),
-                    );
-                }
-                if rng.gen::<f32>() < 0.3 * (cave_x.max(0.5).powf(4.0)) && !ridge_condition {
-                    let kind =
-                        *Lottery::<SpriteKind>::load_expect("common.cave_scatter.shallow_ceiling")
-                            .read()
-                            .choose();
-                    canvas.map(
-                        Vec3::new(wpos2d.x, wpos2d.y, cave_roof_adjusted - 1),
-                        |block| block.with_sprite(kind),
-                    );
-                }
-            } else if cave_depth < 200.0 && cave_depth > 80.0 {
-                if rng.gen::<f32>() < 0.065 * (cave_x.max(0.5).powf(4.0)) && !vein_condition {
-                    let kind =
-                        *Lottery::<SpriteKind>::load_expect("common.cave_scatter.deep_floor")
-                            .read()
-                            .choose();
-                    canvas.map(
-                        Vec3::new(wpos2d.x, wpos2d.y, cave_floor_adjusted),
-                        |block| block.with_sprite(kind),
-                    );
-                }
-                if rng.gen::<f32>() < 0.3 * (cave_x.max(0.5).powf(4.0)) && !ridge_condition {
-                    let kind =
-                        *Lottery::<SpriteKind>::load_expect("common.cave_scatter.deep_ceiling")
-                            .read()
-                            .choose();
-                    canvas.map(
-                        Vec3::new(wpos2d.x, wpos2d.y, cave_roof_adjusted - 1),
-                        |block| block.with_sprite(kind),
-                    );
+            // Flood the bottom of caves
+            if let Some(flood_level) = layer.flood_level {
+                for z in cave_floor_adjusted..(cave.alt + flood_level) as i32 {
+                    canvas.map(Vec3::new(wpos2d.x, wpos2d.y, z), |block| {
+                        if block.kind() == BlockKind::Air {
+                            Block::water(SpriteKind::Empty)
+                        } else {
+                            block
+                        }
+                    });
                 }
-            } else {
-                if rng.gen::<f32>() < 0.08 * (cave_x.max(0.5).powf(4.0))
-                    && cave_depth > 40.0
-                    && !vein_condition
+            }
+
+            // Scatter things in caves
+            if cave_depth > 40.0 {
+                let floor_chance = rng.gen::<f32>() / cave_x.max(0.5).powf(4.0);
+                let floor_lottery = if floor_chance < layer.floor_sprites.1 {
+                    Some(layer.floor_sprites())
+                } else if floor_chance < layer.floor_sprites.1 + layer.ores.1 {
+                    Some(layer.ores())
+                } else if floor_chance
+                    < layer.floor_sprites.1 + layer.ores.1 + layer.light_sprites.1
                 {
-                    let kind =
-                        *Lottery::<SpriteKind>::load_expect("common.cave_scatter.dark_floor")
-                            .read()
-                            .choose();
+                    Some(layer.light_sprites())
+                } else {
+                    None
+                };
+                if let Some(lottery) = floor_lottery.filter(|_| !vein_condition) {
+                    let kind = *lottery.read().choose();
                     canvas.map(
                         Vec3::new(wpos2d.x, wpos2d.y, cave_floor_adjusted),
                         |block| block.with_sprite(kind),
                     );
                 }
-                if rng.gen::<f32>() < 0.02 * (cave_x.max(0.5).powf(4.0))
+                if rng.gen::<f32>() < layer.ceiling_sprites.1 * (cave_x.max(0.5).powf(4.0))
                     && !ridge_condition
-                    && cave_depth > 40.0
                 {
-                    let kind =
-                        *Lottery::<SpriteKind>::load_expect("common.cave_scatter.dark_ceiling")
-                            .read()
-                            .choose();
+                    let kind = *layer.ceiling_sprites().read().choose();
                     canvas.map(
                         Vec3::new(wpos2d.x, wpos2d.y, cave_roof_adjusted - 1),
                         |block| block.with_sprite(kind),
                     );
                 }
-            };
+            }
         }
     });
 }
@@ -417,6 +383,8 @@ pub fn apply_caves_supplement<'a>(
     index: IndexRef,
     supplement: &mut ChunkSupplement,
 ) {
+    let layers = cave::CaveLayers::get();
+    let layers = layers.read();
     for y in 0..vol.size_xy().y as i32 {
         for x in 0..vol.size_xy().x as i32 {
             let offs = Vec2::new(x, y);
@@ -445,6 +413,7 @@ pub fn apply_caves_supplement<'a>(
 
                 // slightly different from earlier cave depth?
                 let cave_depth = (col_sample.alt - cave.alt).max(0.0);
+                let layer = layers.at_depth(cave_depth);
 
                 // Scatter things in caves
                 if let Some(z) = (-4..8).map(|z| cave_base + z).find(|z| {
@@ -453,48 +422,16 @@ pub fn apply_caves_supplement<'a>(
                             .map_or(true, |b| b.is_fluid())
                     })
                 }) {
-                    if RandomField::new(index.seed).chance(wpos2d.into(), 0.0014)
+                    if RandomField::new(index.seed).chance(wpos2d.into(), layer.wildlife.1)
                         && cave_base < surface_z as i32 - 40
                     {
-                        let entity = EntityInfo::at(wpos2d.map(|e| e as f32).with_z(z as f32));
-                        let entity = {
-                            let asset = if cave_depth < 70.0 {
-                                match dynamic_rng.gen_range(0..4) {
-                                    0 => "common.entity.wild.peaceful.truffler",
-                                    1 => "common.entity.wild.aggressive.dodarock",
-                                    2 => "common.entity.wild.peaceful.holladon",
-                                    _ => "common.entity.wild.aggressive.batfox",
-                                }
-                            } else if cave_depth < 120.0 {
-                                match dynamic_rng.gen_range(0..6) {
-                                    0 => "common.entity.wild.aggressive.rocksnapper",
-                                    1 => "common.entity.wild.aggressive.cave_salamander",
-                                    2 => "common.entity.wild.aggressive.cave_spider",
-                                    3 => "common.entity.wild.aggressive.antlion",
-                                    4 => "common.entity.wild.peaceful.crawler_molten",
-                                    _ => "common.entity.wild.aggressive.asp",
-                                }
-                            } else if cave_depth < 190.0 {
-                                match dynamic_rng.gen_range(0..4) {
-                                    0 => "common.entity.wild.aggressive.rocksnapper",
-                                    1 => "common.entity.wild.aggressive.lavadrake",
-                                    2 => "common.entity.wild.aggressive.black_widow",
-                                    _ => "common.entity.wild.aggressive.basilisk",
-                                }
-                            } else {
-                                match dynamic_rng.gen_range(0..5) {
-                                    0 => "common.entity.wild.aggressive.ogre",
-                                    1 => "common.entity.wild.aggressive.cyclops",
-                                    2 => "common.entity.wild.aggressive.wendigo",
-                                    3 => match dynamic_rng.gen_range(0..2) {
-                                        0 => "common.entity.wild.aggressive.blue_oni",
-                                        _ => "common.entity.wild.aggressive.red_oni",
-                                    },
-                                    _ => "common.entity.wild.aggressive.cave_troll",
-                                }
-                            };
-                            entity.with_asset_expect(asset, dynamic_rng)
-                        };
+                        let asset = layer
+                            .wildlife()
+                            .read()
+                            .choose_seeded(dynamic_rng.gen())
+                            .clone();
+                        let entity = EntityInfo::at(wpos2d.map(|e| e as f32).with_z(z as f32))
+                            .with_asset_expect(&asset, dynamic_rng);
 
                         supplement.add_entity(entity);
                     }
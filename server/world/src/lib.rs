@@ -33,7 +33,7 @@ pub mod util;
 // Reexports
 pub use crate::{
     canvas::{Canvas, CanvasInfo},
-    config::{Features, CONFIG},
+    config::{Features, WorldgenPreset, CONFIG},
     land::Land,
 };
 pub use block::BlockGen;
@@ -102,7 +102,9 @@ impl World {
 
             let mut sim = sim::WorldSim::generate(seed, opts, threadpool);
 
-            let civs = civ::Civs::generate(seed, &mut sim, &mut index);
+            index.preset = sim.preset().clone();
+            let preset = index.preset.clone();
+            let civs = civ::Civs::generate(seed, &mut sim, &mut index, &preset);
 
             sim2::simulate(&mut index, &mut sim);
 
@@ -158,6 +160,9 @@ impl World {
                                 civ::SiteKind::Tree | civ::SiteKind::GiantTree => world_msg::SiteKind::Tree,
                                 // TODO: Maybe change?
                                 civ::SiteKind::Gnarling => world_msg::SiteKind::Gnarling,
+                                civ::SiteKind::Mine => world_msg::SiteKind::Mine,
+                                civ::SiteKind::Port => world_msg::SiteKind::Port,
+                                civ::SiteKind::BanditCamp => world_msg::SiteKind::BanditCamp,
                             },
                             wpos: site.center * TerrainChunkSize::RECT_SIZE.map(|e| e as i32),
                         }
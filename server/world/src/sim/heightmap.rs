@@ -0,0 +1,216 @@
+//! Importing hand-authored heightmaps as the base altitude of a world.
+//!
+//! Heightmaps are images with one pixel per chunk, so their width and height
+//! must be powers of two and decide the size of the map.  The top row of each
+//! image is the northern edge of the world, matching how the world map is
+//! displayed.
+
+use super::Alt;
+use common::terrain::{uniform_idx_as_vec2, MapSizeLg};
+use image::{GenericImageView, ImageError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use vek::*;
+
+/// How far below sea level chunks marked by the water mask are sunk, at least.
+const MIN_WATER_DEPTH: Alt = 4.0;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct HeightmapOpts {
+    /// Path to a grayscale image, where brighter pixels are higher.
+    pub path: PathBuf,
+    /// Altitude, relative to sea level, of black pixels.
+    pub min_alt: f32,
+    /// Altitude, relative to sea level, of white pixels.
+    pub max_alt: f32,
+    /// Optional grayscale image marking water; chunks under bright pixels are
+    /// sunk below sea level, to become oceans or lakes.
+    pub water_mask: Option<PathBuf>,
+    /// Optional color image steering the climate, and so the biomes, of the
+    /// world: redder pixels are warmer and greener pixels are more humid.
+    pub biome_mask: Option<PathBuf>,
+    /// Number of erosion steps to run on top of the heightmap, or 0 to use it
+    /// unchanged.
+    pub erosion_steps: usize,
+    /// See `SizeOpts::scale`.
+    pub scale: f64,
+    /// Whether to save the imported world, as with `FileOpts::Save`, so that
+    /// it can be loaded later without importing it again.
+    pub save: bool,
+}
+
+impl Default for HeightmapOpts {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("heightmap.png"),
+            min_alt: -128.0,
+            max_alt: 1536.0,
+            water_mask: None,
+            biome_mask: None,
+            erosion_steps: 0,
+            scale: 2.0,
+            save: false,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum HeightmapError {
+    Image(PathBuf, ImageError),
+    /// The heightmap size isn't a valid map size.
+    SizeInvalid(Vec2<u32>),
+    /// A mask is not the same size as the heightmap.
+    MaskSizeMismatch(PathBuf),
+}
+
+/// A heightmap loaded from [`HeightmapOpts`], with one entry per chunk.
+pub struct Heightmap {
+    pub map_size_lg: MapSizeLg,
+    /// Altitude relative to sea level.
+    pub alt: Box<[Alt]>,
+    /// Temperature and humidity from the biome mask, both from 0 to 1.
+    pub climate: Option<Box<[(f32, f32)]>>,
+}
+
+impl HeightmapOpts {
+    pub fn load(&self) -> Result<Heightmap, HeightmapError> {
+        let image = open_image(&self.path)?;
+        let (width, height) = image.dimensions();
+        let map_size_lg = (width.is_power_of_two() && height.is_power_of_two())
+            .then(|| Vec2::new(width.trailing_zeros(), height.trailing_zeros()))
+            .and_then(|size_lg| MapSizeLg::new(size_lg).ok())
+            .ok_or(HeightmapError::SizeInvalid(Vec2::new(width, height)))?;
+
+        // Image rows go from north to south, while chunk positions go from south to
+        // north.
+        let pixel = |posi| {
+            let pos = uniform_idx_as_vec2(map_size_lg, posi);
+            (pos.x as u32, height - 1 - pos.y as u32)
+        };
+
+        let heights = image.into_luma16();
+        let mut alt = (0..map_size_lg.chunks_len())
+            .map(|posi| {
+                let (x, y) = pixel(posi);
+                let brightness = heights.get_pixel(x, y).0[0] as f32 / u16::MAX as f32;
+                Lerp::lerp(self.min_alt, self.max_alt, brightness) as Alt
+            })
+            .collect::<Box<[_]>>();
+
+        if let Some(path) = &self.water_mask {
+            let water = open_mask(path, (width, height))?.into_luma8();
+            alt.iter_mut().enumerate().for_each(|(posi, alt)| {
+                let (x, y) = pixel(posi);
+                if water.get_pixel(x, y).0[0] >= 128 {
+                    // Keep any depth that the heightmap already gives the water
+                    *alt = alt.min(-MIN_WATER_DEPTH);
+                }
+            });
+        }
+
+        let climate = self
+            .biome_mask
+            .as_ref()
+            .map(|path| -> Result<Box<[_]>, HeightmapError> {
+                let biomes = open_mask(path, (width, height))?.into_rgb8();
+                Ok((0..map_size_lg.chunks_len())
+                    .map(|posi| {
+                        let (x, y) = pixel(posi);
+                        let image::Rgb([r, g, _]) = *biomes.get_pixel(x, y);
+                        (r as f32 / 255.0, g as f32 / 255.0)
+                    })
+                    .collect())
+            })
+            .transpose()?;
+
+        Ok(Heightmap {
+            map_size_lg,
+            alt,
+            climate,
+        })
+    }
+}
+
+fn open_image(path: &Path) -> Result<image::DynamicImage, HeightmapError> {
+    image::open(path).map_err(|e| HeightmapError::Image(path.to_owned(), e))
+}
+
+fn open_mask(path: &Path, size: (u32, u32)) -> Result<image::DynamicImage, HeightmapError> {
+    let mask = open_image(path)?;
+    if mask.dimensions() == size {
+        Ok(mask)
+    } else {
+        Err(HeightmapError::MaskSizeMismatch(path.to_owned()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    /// Save a black image with a white top-left pixel, returning its path
+    fn save_image(name: &str, width: u32, height: u32) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "veloren_heightmap_{}_{}.png",
+            std::process::id(),
+            name
+        ));
+        GrayImage::from_fn(width, height, |x, y| {
+            Luma([if (x, y) == (0, 0) { 255 } else { 0 }])
+        })
+        .save(&path)
+        .unwrap();
+        path
+    }
+
+    #[test]
+    fn heightmap_is_scaled_to_altitudes() {
+        let opts = HeightmapOpts {
+            path: save_image("scaled", 2, 4),
+            min_alt: -100.0,
+            max_alt: 300.0,
+            ..HeightmapOpts::default()
+        };
+        let heightmap = opts.load().unwrap();
+        assert_eq!(heightmap.map_size_lg.vec(), Vec2::new(1, 2));
+        assert_eq!(heightmap.alt.len(), 8);
+        // The top-left pixel is the north-western chunk
+        let north_west = 3 * 2;
+        for (posi, alt) in heightmap.alt.iter().enumerate() {
+            let expected = if posi == north_west { 300.0 } else { -100.0 };
+            assert!((alt - expected).abs() < 1e-3, "{} at {}", alt, posi);
+        }
+        assert!(heightmap.climate.is_none());
+    }
+
+    #[test]
+    fn invalid_heightmaps_are_rejected() {
+        let missing = HeightmapOpts {
+            path: std::env::temp_dir().join("veloren_heightmap_missing.png"),
+            ..HeightmapOpts::default()
+        };
+        assert!(matches!(missing.load(), Err(HeightmapError::Image(..))));
+
+        let not_power_of_two = HeightmapOpts {
+            path: save_image("uneven", 3, 4),
+            ..HeightmapOpts::default()
+        };
+        assert!(matches!(
+            not_power_of_two.load(),
+            Err(HeightmapError::SizeInvalid(size)) if size == Vec2::new(3, 4)
+        ));
+
+        let mask = save_image("mask", 4, 4);
+        let mismatched_mask = HeightmapOpts {
+            path: save_image("masked", 2, 4),
+            water_mask: Some(mask.clone()),
+            ..HeightmapOpts::default()
+        };
+        assert!(matches!(
+            mismatched_mask.load(),
+            Err(HeightmapError::MaskSizeMismatch(path)) if path == mask
+        ));
+    }
+}
@@ -1,5 +1,6 @@
 mod diffusion;
 mod erosion;
+mod heightmap;
 mod location;
 mod map;
 mod util;
@@ -9,6 +10,7 @@ mod way;
 use self::erosion::Compute;
 pub use self::{
     diffusion::diffusion,
+    heightmap::{Heightmap, HeightmapError, HeightmapOpts},
     location::Location,
     map::{sample_pos, sample_wpos},
     util::get_horizon_map,
@@ -36,7 +38,7 @@ use crate::{
         seed_expan, DHashSet, FastNoise, FastNoise2d, RandomField, Sampler, StructureGen2d,
         CARDINALS, LOCALITY, NEIGHBORS,
     },
-    IndexRef, CONFIG,
+    IndexRef, WorldgenPreset, CONFIG,
 };
 use common::{
     assets::{self, AssetExt},
@@ -90,6 +92,9 @@ const DEFAULT_WORLD_CHUNKS_LG: MapSizeLg =
 struct GenCdf {
     humid_base: InverseCdf,
     temp_base: InverseCdf,
+    /// Temperature and humidity from a biome mask, which replace the noise in
+    /// `temp_base` and `humid_base`.
+    climate: Option<Box<[(f32, f32)]>>,
     chaos: InverseCdf,
     alt: Box<[Alt]>,
     basement: Box<[Alt]>,
@@ -103,6 +108,21 @@ struct GenCdf {
     rivers: Box<[RiverData]>,
 }
 
+/// The uniform temperature and humidity that the climate of a chunk is based
+/// on. A biome mask gives them directly, since ranking its values like noise
+/// would throw away how warm or humid each part of it was painted.
+fn base_climate(
+    temp_base: (f32, f32),
+    humid_base: (f32, f32),
+    mask: Option<(f32, f32)>,
+) -> (f32, f32) {
+    match mask {
+        // Pure water chunks are left out of the noise, and so of the mask
+        Some(mask) if !temp_base.1.is_nan() => mask,
+        _ => (temp_base.0, humid_base.0),
+    }
+}
+
 pub(crate) struct GenCtx {
     pub turb_x_nz: SuperSimplex,
     pub turb_y_nz: SuperSimplex,
@@ -135,12 +155,15 @@ pub(crate) struct GenCtx {
     pub uplift_nz: Worley,
 }
 
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
 #[serde(default)]
 pub struct SizeOpts {
-    x_lg: u32,
-    y_lg: u32,
-    scale: f64,
+    /// Base 2 logarithm of the number of chunks along the x axis
+    pub x_lg: u32,
+    /// Base 2 logarithm of the number of chunks along the y axis
+    pub y_lg: u32,
+    /// How spread out continents are, see `continent_scale_hack`
+    pub scale: f64,
 }
 
 impl Default for SizeOpts {
@@ -168,6 +191,9 @@ pub enum FileOpts {
     LoadLegacy(PathBuf),
     /// If set, load the world file from this path (errors if path not found).
     Load(PathBuf),
+    /// If set, import a hand-authored heightmap as the base altitude of the
+    /// world, falling back to generating it if the heightmap can't be loaded.
+    Heightmap(HeightmapOpts),
     /// If set, look for  the world file at this asset specifier (errors if
     /// asset is not found).
     ///
@@ -185,6 +211,9 @@ pub struct WorldOpts {
     pub seed_elements: bool,
     pub world_file: FileOpts,
     pub calendar: Option<Calendar>,
+    /// The preset to generate the world with. Worlds loaded from a file use
+    /// the preset recorded in it instead.
+    pub preset: WorldgenPreset,
 }
 
 impl Default for WorldOpts {
@@ -193,6 +222,7 @@ impl Default for WorldOpts {
             seed_elements: true,
             world_file: Default::default(),
             calendar: None,
+            preset: WorldgenPreset::default(),
         }
     }
 }
@@ -234,6 +264,25 @@ pub struct WorldMap_0_7_0 {
     pub basement: Box<[Alt]>,
 }
 
+/// Version of the world map intended for use in Veloren 0.10.0.
+#[derive(Serialize, Deserialize)]
+#[repr(C)]
+pub struct WorldMap_0_10_0 {
+    /// Saved map size.
+    pub map_size_lg: Vec2<u32>,
+    /// Saved continent_scale hack, to try to better approximate the correct
+    /// seed according to varying map size.
+    ///
+    /// TODO: Remove when generating new maps becomes more principled.
+    pub continent_scale_hack: f64,
+    /// Saved altitude height map.
+    pub alt: Box<[Alt]>,
+    /// Saved basement height map.
+    pub basement: Box<[Alt]>,
+    /// Saved worldgen preset the map was generated with.
+    pub preset: WorldgenPreset,
+}
+
 /// Errors when converting a map to the most recent type (currently,
 /// shared by the various map types, but at some point we might switch to
 /// version-specific errors if it feels worthwhile).
@@ -273,6 +322,7 @@ pub enum WorldFileError {
 pub enum WorldFile {
     Veloren0_5_0(WorldMap_0_5_0) = 0,
     Veloren0_7_0(WorldMap_0_7_0) = 1,
+    Veloren0_10_0(WorldMap_0_10_0) = 2,
 }
 
 impl assets::Asset for WorldFile {
@@ -283,7 +333,7 @@ impl assets::Asset for WorldFile {
 
 /// Data for the most recent map type.  Update this when you add a new map
 /// version.
-pub type ModernMap = WorldMap_0_7_0;
+pub type ModernMap = WorldMap_0_10_0;
 
 /// The default world map.
 ///
@@ -343,6 +393,32 @@ impl WorldMap_0_5_0 {
 }
 
 impl WorldMap_0_7_0 {
+    #[inline]
+    pub fn into_modern(self) -> Result<ModernMap, WorldFileError> {
+        // Maps from before presets existed were all generated with the default
+        // parameters
+        let preset = WorldgenPreset {
+            size: SizeOpts {
+                x_lg: self.map_size_lg.x,
+                y_lg: self.map_size_lg.y,
+                scale: self.continent_scale_hack,
+            },
+            ..WorldgenPreset::default()
+        };
+
+        let map = WorldMap_0_10_0 {
+            map_size_lg: self.map_size_lg,
+            continent_scale_hack: self.continent_scale_hack,
+            alt: self.alt,
+            basement: self.basement,
+            preset,
+        };
+
+        map.into_modern()
+    }
+}
+
+impl WorldMap_0_10_0 {
     #[inline]
     pub fn into_modern(self) -> Result<ModernMap, WorldFileError> {
         if self.alt.len() != self.basement.len()
@@ -362,7 +438,7 @@ impl WorldFile {
     /// variant we construct here to make sure we're using the latest map
     /// version.
 
-    pub fn new(map: ModernMap) -> Self { WorldFile::Veloren0_7_0(map) }
+    pub fn new(map: ModernMap) -> Self { WorldFile::Veloren0_10_0(map) }
 
     #[inline]
     /// Turns a WorldFile into the latest version.  Whenever a new map version
@@ -371,6 +447,7 @@ impl WorldFile {
         match self {
             WorldFile::Veloren0_5_0(map) => map.into_modern(),
             WorldFile::Veloren0_7_0(map) => map.into_modern(),
+            WorldFile::Veloren0_10_0(map) => map.into_modern(),
         }
     }
 }
@@ -390,6 +467,8 @@ pub struct WorldSim {
     pub rng: ChaChaRng,
 
     pub(crate) calendar: Option<Calendar>,
+    /// The preset the world was generated with.
+    preset: WorldgenPreset,
 }
 
 impl WorldSim {
@@ -464,7 +543,9 @@ impl WorldSim {
                         return None;
                     },
                 },
-                FileOpts::Generate { .. } | FileOpts::Save { .. } => return None,
+                FileOpts::Generate { .. } | FileOpts::Save { .. } | FileOpts::Heightmap { .. } => {
+                    return None;
+                },
             };
 
             match map {
@@ -479,6 +560,19 @@ impl WorldSim {
                 },
             }
         })();
+        let heightmap = match world_file {
+            FileOpts::Heightmap(ref heightmap_opts) => match heightmap_opts.load() {
+                Ok(heightmap) => Some(heightmap),
+                Err(e) => {
+                    warn!(
+                        ?e,
+                        "Couldn't import heightmap, generating the world instead"
+                    );
+                    None
+                },
+            },
+            _ => None,
+        };
 
         // NOTE: Change 1.0 to 4.0 for a 4x
         // improvement in world detail.  We also use this to automatically adjust
@@ -506,6 +600,9 @@ impl WorldSim {
                             DEFAULT_WORLD_CHUNKS_LG
                         })
                     },
+                    FileOpts::Heightmap(_) => heightmap
+                        .as_ref()
+                        .map_or(DEFAULT_WORLD_CHUNKS_LG, |heightmap| heightmap.map_size_lg),
                     _ => DEFAULT_WORLD_CHUNKS_LG,
                 };
                 (None, size_lg)
@@ -513,13 +610,43 @@ impl WorldSim {
         let continent_scale_hack = if let Some(map) = &parsed_world_file {
             map.continent_scale_hack
         } else if let FileOpts::Generate(SizeOpts { scale, .. })
-        | FileOpts::Save(SizeOpts { scale, .. }) = world_file
+        | FileOpts::Save(SizeOpts { scale, .. })
+        | FileOpts::Heightmap(HeightmapOpts { scale, .. }) = world_file
         {
             scale
         } else {
             continent_scale_hack
         };
 
+        // A loaded map always regenerates with the preset it was generated with,
+        // while a newly generated map records the size it was actually given.
+        let preset = match &parsed_world_file {
+            Some(map) => {
+                // Only the size of a loaded map comes from the map itself
+                let requested = WorldgenPreset {
+                    size: map.preset.size.clone(),
+                    ..opts.preset.clone()
+                };
+                if map.preset != requested {
+                    warn!(
+                        "The loaded map was generated with a different worldgen preset, so its \
+                         own preset will be used instead"
+                    );
+                }
+                map.preset.clone()
+            },
+            None => WorldgenPreset {
+                size: SizeOpts {
+                    x_lg: map_size_lg.vec().x,
+                    y_lg: map_size_lg.vec().y,
+                    scale: continent_scale_hack,
+                },
+                ..opts.preset
+            },
+        };
+        // This must happen before anything reads `CONFIG`.
+        crate::config::set_config(preset.config.clone());
+
         let mut rng = ChaChaRng::from_seed(seed_expan::rng_state(seed));
         let continent_scale = continent_scale_hack
             * 5_000.0f64
@@ -1084,6 +1211,40 @@ impl WorldSim {
 
         let (alt, basement) = if let Some(map) = parsed_world_file {
             (map.alt, map.basement)
+        } else if let Some(heightmap) = &heightmap {
+            let erosion_steps = match world_file {
+                FileOpts::Heightmap(HeightmapOpts { erosion_steps, .. }) => erosion_steps,
+                _ => 0,
+            };
+            if erosion_steps == 0 {
+                (heightmap.alt.clone(), heightmap.alt.clone())
+            } else {
+                // Erode the heightmap in the same way as a loaded map, since its
+                // altitudes are already final
+                let is_ocean = get_oceans(map_size_lg, |posi| heightmap.alt[posi]);
+                do_erosion(
+                    map_size_lg,
+                    1.0f32,
+                    erosion_steps,
+                    river_seed,
+                    &rock_strength_nz,
+                    |posi| heightmap.alt[posi] as f32,
+                    |posi| heightmap.alt[posi] as f32,
+                    |posi: usize| is_ocean[posi],
+                    |posi| uplift_fn(posi) * (1.0 / max_erosion_per_delta_t),
+                    n_func,
+                    theta_func,
+                    kf_func,
+                    kd_func,
+                    g_func,
+                    epsilon_0_func,
+                    alpha_func,
+                    height_scale,
+                    k_d_scale(n_approx),
+                    k_da_scale,
+                    threadpool,
+                )
+            }
         } else {
             let (alt, basement) = do_erosion(
                 map_size_lg,
@@ -1144,9 +1305,12 @@ impl WorldSim {
             map_size_lg: map_size_lg.vec(),
             alt,
             basement,
+            preset: preset.clone(),
         });
         (|| {
-            if let FileOpts::Save { .. } = world_file {
+            if let FileOpts::Save { .. } | FileOpts::Heightmap(HeightmapOpts { save: true, .. }) =
+                world_file
+            {
                 use std::time::SystemTime;
                 // Check if folder exists and create it if it does not
                 let mut path = PathBuf::from("./maps");
@@ -1186,6 +1350,7 @@ impl WorldSim {
             map_size_lg: _,
             alt,
             basement,
+            preset: _,
         } = map.into_modern().unwrap();
 
         // Additional small-scale erosion after map load, only used during testing.
@@ -1425,6 +1590,7 @@ impl WorldSim {
         let gen_cdf = GenCdf {
             humid_base,
             temp_base,
+            climate: heightmap.and_then(|heightmap| heightmap.climate),
             chaos,
             alt,
             basement,
@@ -1450,6 +1616,7 @@ impl WorldSim {
             gen_ctx,
             rng,
             calendar,
+            preset,
         };
 
         this.generate_cliffs();
@@ -1466,6 +1633,9 @@ impl WorldSim {
 
     pub fn get_size(&self) -> Vec2<u32> { self.map_size_lg().chunks().map(u32::from) }
 
+    /// The worldgen preset that the world was generated with
+    pub fn preset(&self) -> &WorldgenPreset { &self.preset }
+
     /// Draw a map of the world based on chunk information.  Returns a buffer of
     /// u32s.
     pub fn get_map(&self, index: IndexRef, calendar: Option<&Calendar>) -> WorldMapMsg {
@@ -2213,8 +2383,11 @@ impl SimChunk {
         // test one of the four in order to find out whether this is the case.
         let (flux_uniform, /* flux_non_uniform */ _) = gen_cdf.pure_flux[posi];
         let (alt_uniform, _) = gen_cdf.alt_no_water[posi];
-        let (temp_uniform, _) = gen_cdf.temp_base[posi];
-        let (humid_uniform, _) = gen_cdf.humid_base[posi];
+        let (temp_uniform, humid_uniform) = base_climate(
+            gen_cdf.temp_base[posi],
+            gen_cdf.humid_base[posi],
+            gen_cdf.climate.as_ref().map(|climate| climate[posi]),
+        );
 
         /* // Vertical difference from the equator (NOTE: "uniform" with much lower granularity than
         // other uniform quantities, but hopefully this doesn't matter *too* much--if it does, we
@@ -2487,3 +2660,60 @@ impl SimChunk {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn old_map(map_size_lg: Vec2<u32>, len: usize) -> WorldMap_0_7_0 {
+        WorldMap_0_7_0 {
+            map_size_lg,
+            continent_scale_hack: 0.5,
+            alt: vec![0.0; len].into_boxed_slice(),
+            basement: vec![0.0; len].into_boxed_slice(),
+        }
+    }
+
+    #[test]
+    fn old_maps_use_the_default_preset() {
+        let map = old_map(Vec2::new(2, 3), 32).into_modern().unwrap();
+        assert_eq!(
+            map.preset.size,
+            SizeOpts {
+                x_lg: 2,
+                y_lg: 3,
+                scale: 0.5,
+            }
+        );
+        assert_eq!(map.preset.config, Config::default());
+        assert!(map.preset.sites && map.preset.caves);
+    }
+
+    #[test]
+    fn flat_biome_masks_keep_their_climate() {
+        let map_size_lg = MapSizeLg::new(Vec2::new(2, 2)).unwrap();
+        // The first chunk is pure water
+        let (flat, _) = uniform_noise(map_size_lg, |posi, _| (posi != 0).then(|| 0.5f32));
+        // Ranking a flat mask would spread it over the whole range
+        assert!(flat[1].0 < flat[map_size_lg.chunks_len() - 1].0);
+
+        let mask = (0.8, 0.3);
+        assert_eq!(base_climate(flat[0], flat[0], Some(mask)), (0.0, 0.0));
+        for posi in 1..map_size_lg.chunks_len() {
+            assert_eq!(base_climate(flat[posi], flat[posi], Some(mask)), mask);
+            assert_eq!(
+                base_climate(flat[posi], flat[posi], None),
+                (flat[posi].0, flat[posi].0)
+            );
+        }
+    }
+
+    #[test]
+    fn old_maps_of_the_wrong_size_are_rejected() {
+        assert!(matches!(
+            old_map(Vec2::new(2, 3), 31).into_modern(),
+            Err(WorldFileError::WorldSizeInvalid)
+        ));
+    }
+}
@@ -209,6 +209,7 @@ fn simulate_return(index: &mut Index, world: &mut WorldSim) -> Result<(), std::i
                 SiteKind::Refactor(_) => towns += site.economy.pop,
                 SiteKind::GiantTree(_) => (),
                 SiteKind::Gnarling(_) => {},
+                SiteKind::Mine(_) | SiteKind::Port(_) | SiteKind::BanditCamp(_) => {},
             }
         }
         if towns.valid() {
@@ -1194,6 +1195,7 @@ mod tests {
             world_file: sim::FileOpts::LoadAsset(sim::DEFAULT_WORLD_MAP.into()),
             //sim::FileOpts::LoadAsset("world.map.economy_8x8".into()),
             calendar: None,
+            ..Default::default()
         };
         let mut index = crate::index::Index::new(seed);
         info!("Index created");
@@ -1201,7 +1203,8 @@ mod tests {
         info!("World loaded");
         let regenerate_input = false;
         if regenerate_input {
-            let _civs = crate::civ::Civs::generate(seed, &mut sim, &mut index);
+            let preset = sim.preset().clone();
+            let _civs = crate::civ::Civs::generate(seed, &mut sim, &mut index, &preset);
             info!("Civs created");
             let mut outarr: Vec<EconomySetup> = Vec::new();
             for i in index.sites.values() {
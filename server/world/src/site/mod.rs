@@ -67,6 +67,9 @@ pub enum SiteKind {
     Tree(tree::Tree),
     GiantTree(site2::Site),
     Gnarling(site2::Site),
+    Mine(site2::Site),
+    Port(site2::Site),
+    BanditCamp(site2::Site),
 }
 
 impl Site {
@@ -91,6 +94,27 @@ impl Site {
         }
     }
 
+    pub fn mine(m: site2::Site) -> Self {
+        Self {
+            kind: SiteKind::Mine(m),
+            economy: Economy::default(),
+        }
+    }
+
+    pub fn port(p: site2::Site) -> Self {
+        Self {
+            kind: SiteKind::Port(p),
+            economy: Economy::default(),
+        }
+    }
+
+    pub fn bandit_camp(b: site2::Site) -> Self {
+        Self {
+            kind: SiteKind::BanditCamp(b),
+            economy: Economy::default(),
+        }
+    }
+
     pub fn castle(c: Castle) -> Self {
         Self {
             kind: SiteKind::Castle(c),
@@ -128,6 +152,9 @@ impl Site {
             SiteKind::Tree(t) => t.radius(),
             SiteKind::GiantTree(gt) => gt.radius(),
             SiteKind::Gnarling(g) => g.radius(),
+            SiteKind::Mine(m) => m.radius(),
+            SiteKind::Port(p) => p.radius(),
+            SiteKind::BanditCamp(b) => b.radius(),
         }
     }
 
@@ -140,6 +167,9 @@ impl Site {
             SiteKind::Tree(t) => t.origin,
             SiteKind::GiantTree(gt) => gt.origin,
             SiteKind::Gnarling(g) => g.origin,
+            SiteKind::Mine(m) => m.origin,
+            SiteKind::Port(p) => p.origin,
+            SiteKind::BanditCamp(b) => b.origin,
         }
     }
 
@@ -152,6 +182,9 @@ impl Site {
             SiteKind::Tree(t) => t.spawn_rules(wpos),
             SiteKind::GiantTree(gt) => gt.spawn_rules(wpos),
             SiteKind::Gnarling(g) => g.spawn_rules(wpos),
+            SiteKind::Mine(m) => m.spawn_rules(wpos),
+            SiteKind::Port(p) => p.spawn_rules(wpos),
+            SiteKind::BanditCamp(b) => b.spawn_rules(wpos),
         }
     }
 
@@ -164,6 +197,9 @@ impl Site {
             SiteKind::Tree(_) => "Giant Tree",
             SiteKind::GiantTree(gt) => gt.name(),
             SiteKind::Gnarling(g) => g.name(),
+            SiteKind::Mine(m) => m.name(),
+            SiteKind::Port(p) => p.name(),
+            SiteKind::BanditCamp(b) => b.name(),
         }
     }
 
@@ -198,6 +234,9 @@ impl Site {
             SiteKind::Tree(t) => t.render(canvas, dynamic_rng),
             SiteKind::GiantTree(gt) => gt.render(canvas, dynamic_rng),
             SiteKind::Gnarling(g) => g.render(canvas, dynamic_rng),
+            SiteKind::Mine(m) => m.render(canvas, dynamic_rng),
+            SiteKind::Port(p) => p.render(canvas, dynamic_rng),
+            SiteKind::BanditCamp(b) => b.render(canvas, dynamic_rng),
         }
     }
 
@@ -223,6 +262,9 @@ impl Site {
             SiteKind::Tree(_) => {},
             SiteKind::GiantTree(gt) => gt.apply_supplement(dynamic_rng, wpos2d, supplement),
             SiteKind::Gnarling(g) => g.apply_supplement(dynamic_rng, wpos2d, supplement),
+            SiteKind::Mine(m) => m.apply_supplement(dynamic_rng, wpos2d, supplement),
+            SiteKind::Port(p) => p.apply_supplement(dynamic_rng, wpos2d, supplement),
+            SiteKind::BanditCamp(b) => b.apply_supplement(dynamic_rng, wpos2d, supplement),
         }
     }
 
@@ -37,6 +37,20 @@ use vek::*;
 /// easier to maintain and less liable to breaking changes.
 fn reseed(rng: &mut impl Rng) -> impl Rng { ChaChaRng::from_seed(rng.gen::<[u8; 32]>()) }
 
+/// How many buildings described by plot templates each town tries to place.
+const TEMPLATE_PLOTS: usize = 10;
+
+/// An area of a town that can be claimed by a player to build in
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HousingPlot {
+    /// The (inclusive) bounds of the area in world coordinates
+    pub bounds: Aabr<i32>,
+    /// Whether the area is a house plot, rather than a single empty tile
+    pub is_house: bool,
+    /// The altitude of the house, if there is one
+    pub alt: Option<i32>,
+}
+
 #[derive(Default)]
 pub struct Site {
     pub(crate) origin: Vec2<i32>,
@@ -100,6 +114,8 @@ impl Site {
             .filter_map(|plot| match &plot.kind {
                 PlotKind::Dungeon(d) => Some(d.spawn_rules(wpos)),
                 PlotKind::Gnarling(g) => Some(g.spawn_rules(wpos)),
+                PlotKind::Mine(m) => Some(m.spawn_rules(wpos)),
+                PlotKind::BanditCamp(b) => Some(b.spawn_rules(wpos)),
                 _ => None,
             })
             .fold(base_spawn_rules, |a, b| a.combine(b))
@@ -442,6 +458,79 @@ impl Site {
         site
     }
 
+    pub fn generate_mine(land: &Land, rng: &mut impl Rng, origin: Vec2<i32>) -> Self {
+        let mut rng = reseed(rng);
+        let mut site = Site {
+            origin,
+            ..Site::default()
+        };
+        site.demarcate_obstacles(land);
+        let mine = plot::Mine::generate(land, &mut rng, origin);
+        site.name = mine.name().to_string();
+        // The shaft may be dug a little away from the site, right above the cave
+        let bounds = mine.bounds();
+        let aabr = Aabr {
+            min: site.wpos_tile_pos(bounds.min),
+            max: site.wpos_tile_pos(bounds.max) + 1,
+        };
+        site.create_single_plot(PlotKind::Mine(mine), aabr, &mut rng);
+        site
+    }
+
+    pub fn generate_port(land: &Land, rng: &mut impl Rng, origin: Vec2<i32>) -> Self {
+        let mut rng = reseed(rng);
+        let mut site = Site {
+            origin,
+            ..Site::default()
+        };
+        site.demarcate_obstacles(land);
+        let port = plot::Port::generate(land, &mut rng, origin);
+        site.name = port.name().to_string();
+        let bounds = port.bounds();
+        let aabr = Aabr {
+            min: site.wpos_tile_pos(bounds.min),
+            max: site.wpos_tile_pos(bounds.max) + 1,
+        };
+        site.create_single_plot(PlotKind::Port(port), aabr, &mut rng);
+        site
+    }
+
+    pub fn generate_camp(land: &Land, rng: &mut impl Rng, origin: Vec2<i32>) -> Self {
+        let mut rng = reseed(rng);
+        let mut site = Site {
+            origin,
+            ..Site::default()
+        };
+        site.demarcate_obstacles(land);
+        let camp = plot::BanditCamp::generate(land, &mut rng, origin);
+        site.name = camp.name().to_string();
+        let size = (camp.radius() as f32 / tile::TILE_SIZE as f32).ceil() as i32;
+        let aabr = Aabr {
+            min: Vec2::broadcast(-size),
+            max: Vec2::broadcast(size) + 1,
+        };
+        site.create_single_plot(PlotKind::BanditCamp(camp), aabr, &mut rng);
+        site
+    }
+
+    /// Covers `aabr` with a single plot, for sites that are one structure.
+    fn create_single_plot(&mut self, kind: PlotKind, aabr: Aabr<i32>, rng: &mut impl Rng) {
+        let plot = self.create_plot(Plot {
+            kind,
+            root_tile: aabr.center(),
+            tiles: aabr_tiles(aabr).collect(),
+            seed: rng.gen(),
+        });
+        self.blit_aabr(
+            aabr,
+            Tile {
+                kind: TileKind::Building,
+                plot: Some(plot),
+                hard_alt: None,
+            },
+        );
+    }
+
     pub fn generate_city(land: &Land, rng: &mut impl Rng, origin: Vec2<i32>) -> Self {
         let mut rng = reseed(rng);
 
@@ -739,6 +828,48 @@ impl Site {
             }
         }
 
+        // Buildings described by plot templates fill in the space left beside the
+        // roads. They are placed last so that they don't change the layout of the
+        // other plots of towns generated from the same seed.
+        for _ in 0..TEMPLATE_PLOTS {
+            let template = match plot::PlotTemplates::choose(&mut rng) {
+                Some(template) => template,
+                None => break,
+            };
+            let (min_size, max_size) = template.size;
+            // Smaller plots don't fit beside roads
+            let size = rng.gen_range(min_size..=max_size.max(min_size)).max(2);
+            if let Some((aabr, door_tile, door_dir)) = attempt(32, || {
+                site.find_roadside_aabr(&mut rng, 4..(size + 1).pow(2), Extent2::broadcast(size))
+            }) {
+                let building = plot::TemplatePlot::generate(
+                    template,
+                    land,
+                    &mut reseed(&mut rng),
+                    &site,
+                    door_tile,
+                    door_dir,
+                    aabr,
+                );
+                let building_alt = building.alt;
+                let plot = site.create_plot(Plot {
+                    kind: PlotKind::Template(building),
+                    root_tile: aabr.center(),
+                    tiles: aabr_tiles(aabr).collect(),
+                    seed: rng.gen(),
+                });
+
+                site.blit_aabr(
+                    aabr,
+                    Tile {
+                        kind: TileKind::Building,
+                        plot: Some(plot),
+                        hard_alt: Some(building_alt),
+                    },
+                );
+            }
+        }
+
         site
     }
 
@@ -758,6 +889,44 @@ impl Site {
         self.origin + tile * tile::TILE_SIZE as i32 + tile::TILE_SIZE as i32 / 2
     }
 
+    /// Finds the area at the given position that a player could claim as a
+    /// housing plot: either a house plot, or a single empty tile within the
+    /// bounds of the town. Whether the area is already owned is not checked.
+    pub fn housing_plot_at(&self, wpos2d: Vec2<i32>) -> Option<HousingPlot> {
+        // Only towns have plots for sale
+        if self.plazas.is_empty() {
+            return None;
+        }
+
+        let tpos = self.wpos_tile_pos(wpos2d);
+        let tile = self.tiles.get(tpos);
+        let tile_bounds_to_wpos = |tile_aabr: Aabr<i32>| Aabr {
+            min: self.tile_wpos(tile_aabr.min),
+            max: self.tile_wpos(tile_aabr.max + 1) - 1,
+        };
+        match tile.plot.map(|plot| &self.plots[plot]) {
+            Some(plot) => match &plot.kind {
+                PlotKind::House(house) => Some(HousingPlot {
+                    bounds: tile_bounds_to_wpos(plot.find_bounds()),
+                    is_house: true,
+                    alt: Some(house.alt),
+                }),
+                _ => None,
+            },
+            None if tile.is_empty() && self.tiles.bounds.contains_point(tpos) => {
+                Some(HousingPlot {
+                    bounds: tile_bounds_to_wpos(Aabr {
+                        min: tpos,
+                        max: tpos,
+                    }),
+                    is_house: false,
+                    alt: None,
+                })
+            },
+            None => None,
+        }
+    }
+
     pub fn render_tile(&self, canvas: &mut Canvas, _dynamic_rng: &mut impl Rng, tpos: Vec2<i32>) {
         let tile = self.tiles.get(tpos);
         let twpos = self.tile_wpos(tpos);
@@ -991,6 +1160,10 @@ impl Site {
                 PlotKind::Dungeon(dungeon) => dungeon.render_collect(self, canvas),
                 PlotKind::Gnarling(gnarling) => gnarling.render_collect(self, canvas),
                 PlotKind::GiantTree(giant_tree) => giant_tree.render_collect(self, canvas),
+                PlotKind::Template(building) => building.render_collect(self, canvas),
+                PlotKind::Mine(mine) => mine.render_collect(self, canvas),
+                PlotKind::Port(port) => port.render_collect(self, canvas),
+                PlotKind::BanditCamp(camp) => camp.render_collect(self, canvas),
                 _ => continue,
             };
 
@@ -1038,6 +1211,7 @@ impl Site {
             match &plot.kind {
                 PlotKind::Dungeon(d) => d.apply_supplement(dynamic_rng, wpos2d, supplement),
                 PlotKind::Gnarling(g) => g.apply_supplement(dynamic_rng, wpos2d, supplement),
+                PlotKind::Port(p) => p.apply_supplement(dynamic_rng, wpos2d, supplement),
                 _ => {},
             }
         }
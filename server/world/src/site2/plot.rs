@@ -1,13 +1,25 @@
+mod bandit_camp;
 mod castle;
 pub mod dungeon;
 mod giant_tree;
 mod gnarling;
 mod house;
+mod mine;
+mod port;
+pub mod template;
 mod workshop;
 
 pub use self::{
-    castle::Castle, dungeon::Dungeon, giant_tree::GiantTree, gnarling::GnarlingFortification,
-    house::House, workshop::Workshop,
+    bandit_camp::BanditCamp,
+    castle::Castle,
+    dungeon::Dungeon,
+    giant_tree::GiantTree,
+    gnarling::GnarlingFortification,
+    house::House,
+    mine::Mine,
+    port::Port,
+    template::{PlotTemplate, PlotTemplates, TemplatePlot},
+    workshop::Workshop,
 };
 
 use super::*;
@@ -52,4 +64,9 @@ pub enum PlotKind {
     Dungeon(Dungeon),
     Gnarling(GnarlingFortification),
     GiantTree(GiantTree),
+    /// A building described by a [`PlotTemplate`] asset
+    Template(TemplatePlot),
+    Mine(Mine),
+    Port(Port),
+    BanditCamp(BanditCamp),
 }
@@ -0,0 +1,157 @@
+use super::*;
+use crate::Land;
+use common::terrain::{Block, BlockKind, SpriteKind};
+use rand::prelude::*;
+use std::f32::consts::TAU;
+use vek::*;
+
+/// Half the angle of the gap in the palisade, in radians.
+const GATE_HALF_ANGLE: f32 = 0.25;
+
+/// Tents around a campfire, behind a palisade of stakes. The bandits living
+/// here are rtsim entities, spawned at the tents.
+pub struct BanditCamp {
+    name: String,
+    center: Vec3<i32>,
+    /// Radius of the palisade
+    radius: i32,
+    /// Direction of the gap in the palisade, in radians
+    gate_angle: f32,
+    /// Centre of each tent, on the ground, and the way to the fire
+    tents: Vec<(Vec3<i32>, Dir)>,
+}
+
+impl BanditCamp {
+    pub fn generate(land: &Land, rng: &mut impl Rng, wpos: Vec2<i32>) -> Self {
+        let name = format!("{} Hideout", NameGen::location(rng).generate());
+        let radius = rng.gen_range(16..24);
+        let gate_angle = rng.gen_range(0.0..TAU);
+
+        // Tents in a ring around the fire, leaving the way in from the gate clear
+        let tent_count = rng.gen_range(3..6);
+        let tents = (0..tent_count)
+            .map(|i| {
+                let angle = gate_angle + TAU * (i + 1) as f32 / (tent_count + 1) as f32;
+                let pos = wpos
+                    + Vec2::new(angle.cos(), angle.sin()).map(|e| (e * radius as f32 * 0.6) as i32);
+                (
+                    pos.with_z(land.get_alt_approx(pos) as i32),
+                    Dir::from_vector(wpos - pos),
+                )
+            })
+            .collect();
+
+        Self {
+            name,
+            center: wpos.with_z(land.get_alt_approx(wpos) as i32),
+            radius,
+            gate_angle,
+            tents,
+        }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn radius(&self) -> i32 { self.radius + 2 }
+
+    /// Where the bandits of each tent sleep.
+    pub fn tents(&self) -> impl Iterator<Item = Vec3<i32>> + '_ {
+        self.tents.iter().map(|(pos, _)| *pos)
+    }
+
+    pub fn spawn_rules(&self, wpos: Vec2<i32>) -> SpawnRules {
+        SpawnRules {
+            trees: wpos.distance_squared(self.center.xy()) > self.radius().pow(2),
+            waypoints: false,
+            ..SpawnRules::default()
+        }
+    }
+}
+
+impl Structure for BanditCamp {
+    fn render(&self, _site: &Site, land: &Land, painter: &Painter) {
+        let stake = Fill::Block(Block::new(BlockKind::Wood, Rgb::new(78, 50, 25)));
+        let canvas = Fill::Brick(BlockKind::Misc, Rgb::new(126, 104, 72), 16);
+
+        // Palisade of uneven stakes, with a gap for the gate
+        let stakes = (TAU * self.radius as f32) as i32;
+        for i in 0..stakes {
+            let angle = i as f32 / stakes as f32 * TAU;
+            let from_gate = (angle - self.gate_angle).rem_euclid(TAU);
+            if from_gate.min(TAU - from_gate) < GATE_HALF_ANGLE {
+                continue;
+            }
+            let pos = self.center.xy()
+                + Vec2::new(angle.cos(), angle.sin()).map(|e| (e * self.radius as f32) as i32);
+            let alt = land.get_alt_approx(pos) as i32;
+            painter
+                .aabb(Aabb {
+                    min: pos.with_z(alt - 2),
+                    max: (pos + 1).with_z(alt + 4 + i % 3),
+                })
+                .fill(stake.clone());
+        }
+
+        // Campfire with benches around it
+        let fire = self.center + Vec3::unit_z();
+        painter.sprite(fire, SpriteKind::Ember);
+        for dir in CARDINALS {
+            painter.sprite(fire + (dir * 3).with_z(0), SpriteKind::Bench);
+        }
+
+        // Open-ended tents facing the fire
+        for (pos, dir) in &self.tents {
+            let floor = pos.z + 1;
+            let (length, width) = if dir.is_y() {
+                (Vec2::unit_y() * 3, Vec2::unit_x() * 2)
+            } else {
+                (Vec2::unit_x() * 3, Vec2::unit_y() * 2)
+            };
+            let bounds = Aabr {
+                min: pos.xy() - length - width,
+                max: pos.xy() + length + width + 1,
+            };
+            let ridge = if dir.is_y() { Dir::Y } else { Dir::X };
+            painter
+                .gable(aabr_with_z(bounds, floor..floor + 4), 3, ridge)
+                .fill(canvas.clone());
+            painter
+                .gable(
+                    aabr_with_z(
+                        Aabr {
+                            min: bounds.min + width.map(|e| e.signum()),
+                            max: bounds.max - width.map(|e| e.signum()),
+                        },
+                        floor..floor + 3,
+                    ),
+                    2,
+                    ridge,
+                )
+                .clear();
+            painter.sprite(pos.xy().with_z(floor), SpriteKind::Bed);
+            painter.sprite(
+                (pos.xy() - dir.to_vec2() * 2).with_z(floor),
+                SpriteKind::Crate,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn tents_are_inside_the_palisade() {
+        let wpos = Vec2::new(500, -200);
+        for seed in 0..32 {
+            let camp =
+                BanditCamp::generate(&Land::empty(), &mut ChaChaRng::seed_from_u64(seed), wpos);
+            assert!((3..6).contains(&camp.tents.len()));
+            for tent in camp.tents() {
+                assert!(tent.xy().distance_squared(wpos) < camp.radius.pow(2));
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use super::*;
+use crate::{
+    util::{RandomField, Sampler},
+    Land,
+};
+use common::terrain::{Block, BlockKind, SpriteKind};
+use rand::prelude::*;
+use std::f32::consts::TAU;
+use vek::*;
+
+/// How far from the site a cave may pass for the shaft to be dug down to it.
+const MAX_CAVE_DIST: f32 = 24.0;
+/// Radius of the open shaft, without its lining.
+const SHAFT_RADIUS: f32 = 5.0;
+/// Radius of the chamber at the bottom of the shaft.
+const CHAMBER_RADIUS: f32 = 8.0;
+/// Steps of the spiral staircase in each turn.
+const STEPS_PER_TURN: i32 = 16;
+
+/// A headframe over a shaft with a spiral staircase, leading down into the
+/// cave network.
+pub struct Mine {
+    name: String,
+    seed: u32,
+    /// Centre of the shaft, at the surface
+    entrance: Vec3<i32>,
+    /// Floor of the chamber at the bottom of the shaft
+    bottom: i32,
+    /// Height down to which the shaft is lined with stone
+    lining_bottom: i32,
+}
+
+impl Mine {
+    pub fn generate(land: &Land, rng: &mut impl Rng, wpos: Vec2<i32>) -> Self {
+        let name = format!("{} Mine", NameGen::location(rng).generate());
+        // Dig right above the cave, if there's one close enough
+        let cave = land
+            .get_nearest_cave(wpos)
+            .filter(|(dist, ..)| *dist < MAX_CAVE_DIST);
+        let center = cave.map_or(wpos, |(_, pos, _)| pos.map(|e| e as i32));
+        let alt = land.get_alt_approx(center) as i32;
+        let (bottom, lining_bottom) = match cave {
+            // The floor of a cave is half its width below its altitude, and its roof a full
+            // width above
+            Some((_, _, cave)) => (
+                (cave.alt - cave.width * 0.5) as i32,
+                ((cave.alt + cave.width) as i32).min(alt - 4),
+            ),
+            None => (alt - 40, alt - 40),
+        };
+
+        Self {
+            name,
+            seed: rng.gen(),
+            entrance: center.with_z(alt),
+            bottom,
+            lining_bottom,
+        }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+
+    pub fn radius(&self) -> i32 { CHAMBER_RADIUS as i32 + 2 }
+
+    /// The area covered by the mine, around its shaft.
+    pub fn bounds(&self) -> Aabr<i32> {
+        Aabr {
+            min: self.entrance.xy() - self.radius(),
+            max: self.entrance.xy() + self.radius() + 1,
+        }
+    }
+
+    pub fn spawn_rules(&self, wpos: Vec2<i32>) -> SpawnRules {
+        SpawnRules {
+            trees: wpos.distance_squared(self.entrance.xy()) > self.radius().pow(2),
+            waypoints: false,
+            ..SpawnRules::default()
+        }
+    }
+}
+
+impl Structure for Mine {
+    fn render(&self, _site: &Site, _land: &Land, painter: &Painter) {
+        let stone = Fill::Brick(BlockKind::Rock, Rgb::new(90, 86, 80), 24);
+        let wood = Fill::Block(Block::new(BlockKind::Wood, Rgb::new(71, 43, 19)));
+        let planks = Fill::Brick(BlockKind::Wood, Rgb::new(105, 68, 34), 12);
+        let roof = Fill::Brick(BlockKind::Wood, Rgb::new(60, 36, 20), 16);
+
+        let center = self.entrance.xy();
+        let floor = self.entrance.z + 1;
+        let frame_top = floor + 10;
+
+        // Line the shaft with stone, so that it stays a shaft through loose ground
+        painter
+            .cylinder_with_radius(
+                center.with_z(self.lining_bottom),
+                SHAFT_RADIUS + 1.0,
+                (floor - self.lining_bottom) as f32,
+            )
+            .fill(stone);
+
+        // Chamber at the bottom, opening onto the cave
+        painter
+            .cylinder_with_radius(center.with_z(self.bottom - 1), CHAMBER_RADIUS, 1.0)
+            .fill(planks.clone());
+        painter
+            .cylinder_with_radius(center.with_z(self.bottom), CHAMBER_RADIUS, 6.0)
+            .clear();
+
+        // The shaft itself, up through the headframe
+        painter
+            .cylinder_with_radius(
+                center.with_z(self.bottom),
+                SHAFT_RADIUS,
+                (frame_top - self.bottom) as f32,
+            )
+            .clear();
+
+        // Spiral staircase around a central post
+        painter
+            .cylinder_with_radius(
+                center.with_z(self.bottom),
+                1.0,
+                (frame_top - self.bottom) as f32,
+            )
+            .fill(wood.clone());
+        for z in self.bottom..floor {
+            let angle = z as f32 * TAU / STEPS_PER_TURN as f32;
+            let step = center + Vec2::new(angle.cos(), angle.sin()).map(|e| (e * 3.0) as i32);
+            painter
+                .aabb(Aabb {
+                    min: (step - 1).with_z(z - 1),
+                    max: (step + 1).with_z(z),
+                })
+                .fill(planks.clone());
+        }
+
+        // Ore left along the walls of the chamber
+        let field = RandomField::new(self.seed);
+        for i in 0..8 {
+            let angle = i as f32 * TAU / 8.0;
+            let pos = center
+                + Vec2::new(angle.cos(), angle.sin()).map(|e| (e * (CHAMBER_RADIUS - 1.0)) as i32);
+            let sprite = match field.get(pos.with_z(self.bottom)) % 4 {
+                0 => SpriteKind::Iron,
+                1 => SpriteKind::Coal,
+                2 => SpriteKind::Crate,
+                _ => continue,
+            };
+            painter.sprite(pos.with_z(self.bottom), sprite);
+        }
+
+        // Headframe: a platform around the mouth of the shaft...
+        let frame = Aabr {
+            min: center - 6,
+            max: center + 7,
+        };
+        painter
+            .aabb(aabr_with_z(frame, floor - 1..floor))
+            .without(painter.cylinder_with_radius(center.with_z(floor - 1), SHAFT_RADIUS, 1.0))
+            .fill(planks);
+        // ...with a post at each corner...
+        for corner in SQUARE_4 {
+            let post = frame.min + corner * (frame.max - frame.min - 1);
+            painter
+                .aabb(Aabb {
+                    min: post.with_z(floor),
+                    max: (post + 1).with_z(frame_top),
+                })
+                .fill(wood.clone());
+            painter.sprite(
+                (post + (center - post).map(|e| e.signum())).with_z(floor),
+                SpriteKind::Lantern,
+            );
+        }
+        // ...holding up the winding drum and a roof
+        painter
+            .line(
+                Vec2::new(frame.min.x, center.y).with_z(frame_top - 2),
+                Vec2::new(frame.max.x - 1, center.y).with_z(frame_top - 2),
+                1.0,
+            )
+            .fill(wood);
+        painter
+            .gable(aabr_with_z(frame, frame_top..frame_top + 4), 4, Dir::X)
+            .fill(roof);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn shaft_is_dug_down_without_a_cave() {
+        let wpos = Vec2::new(-30, 70);
+        let mine = Mine::generate(&Land::empty(), &mut ChaChaRng::seed_from_u64(0), wpos);
+        assert_eq!(mine.entrance.xy(), wpos);
+        assert!(mine.bottom < mine.entrance.z);
+        assert!(mine.bounds().contains_point(wpos));
+    }
+}
@@ -0,0 +1,270 @@
+use super::*;
+use crate::{Land, CONFIG};
+use common::{
+    comp::{self, ship},
+    generation::{ChunkSupplement, EntityInfo},
+    terrain::{Block, BlockKind, SpriteKind},
+};
+use rand::prelude::*;
+use vek::*;
+
+/// Furthest from the site that the shore is looked for.
+const MAX_SHORE_DIST: i32 = 64;
+/// Distance from the pier of the centre of the ships moored to it, enough for
+/// the longest ship to clear the pier whichever way it faces.
+const SHIP_CLEARANCE: i32 = 20;
+/// Half the width of the harbour office.
+const OFFICE_RADIUS: i32 = 4;
+/// Space between the posts holding up the pier.
+const POST_SPACING: usize = 6;
+
+/// A stone quay and a wooden pier leading out to sea, with a harbour office on
+/// the shore and ships moored past the end of the pier.
+pub struct Port {
+    name: String,
+    /// Where the pier leaves the land
+    shore: Vec2<i32>,
+    /// Direction from the shore out to sea
+    dir: Vec2<i32>,
+    pier_len: i32,
+    /// Altitude of the deck of the pier and quay, a little above the sea
+    deck_alt: i32,
+    /// Altitude of the floor of the harbour office
+    office_alt: i32,
+    /// Where ships moor, on the surface of the sea
+    berths: Vec<(Vec2<i32>, ship::Body)>,
+}
+
+impl Port {
+    pub fn generate(land: &Land, rng: &mut impl Rng, wpos: Vec2<i32>) -> Self {
+        let name = format!("Port {}", NameGen::location(rng).generate_town());
+        let is_sea = |wpos| land.get_alt_approx(wpos) < CONFIG.sea_level;
+
+        // Build towards the nearest open water
+        let (dir, shore_dist) = CARDINALS
+            .iter()
+            .filter_map(|dir| {
+                (0..MAX_SHORE_DIST)
+                    .find(|dist| is_sea(wpos + *dir * *dist))
+                    .map(|dist| (*dir, dist))
+            })
+            .min_by_key(|(_, dist)| *dist)
+            .unwrap_or((CARDINALS[0], 0));
+        let shore = wpos + dir * shore_dist;
+        let pier_len = rng.gen_range(24..40);
+        let deck_alt = CONFIG.sea_level as i32 + 2;
+        let office = shore - dir * (OFFICE_RADIUS + 4);
+        let office_alt = (land.get_alt_approx(office) as i32).max(deck_alt);
+
+        let end = shore + dir * pier_len;
+        let side = Vec2::new(-dir.y, dir.x);
+        let mut berths = Vec::new();
+        for pos in [
+            end + dir * SHIP_CLEARANCE,
+            end - dir * (pier_len / 3) + side * SHIP_CLEARANCE,
+            end - dir * (pier_len / 3) - side * SHIP_CLEARANCE,
+        ] {
+            if is_sea(pos) && rng.gen_bool(0.75) {
+                berths.push((pos, ship::Body::random_ship_with(rng)));
+            }
+        }
+
+        Self {
+            name,
+            shore,
+            dir,
+            pier_len,
+            deck_alt,
+            office_alt,
+            berths,
+        }
+    }
+
+    pub fn name(&self) -> &str { &self.name }
+
+    fn office(&self) -> Vec2<i32> { self.shore - self.dir * (OFFICE_RADIUS + 4) }
+
+    fn end(&self) -> Vec2<i32> { self.shore + self.dir * self.pier_len }
+
+    /// The area covered by the port, including the ships, so that the chunks
+    /// they spawn in belong to the site.
+    pub fn bounds(&self) -> Aabr<i32> {
+        let office = self.office();
+        self.berths.iter().fold(
+            Aabr {
+                min: office - OFFICE_RADIUS,
+                max: office + OFFICE_RADIUS + 1,
+            }
+            .union(rect(self.end() - 8, self.end() + 8)),
+            |bounds, (pos, _)| bounds.union(rect(*pos - SHIP_CLEARANCE, *pos + SHIP_CLEARANCE)),
+        )
+    }
+
+    // TODO: Find a better way of spawning entities in site2
+    pub fn apply_supplement(
+        &self,
+        // NOTE: Used only for dynamic elements like chests and entities!
+        _dynamic_rng: &mut impl Rng,
+        wpos2d: Vec2<i32>,
+        supplement: &mut ChunkSupplement,
+    ) {
+        let area = Aabr {
+            min: wpos2d,
+            max: wpos2d + TerrainChunkSize::RECT_SIZE.map(|e| e as i32),
+        };
+
+        for (pos, ship) in &self.berths {
+            if area.contains_point(*pos) {
+                supplement.add_entity(
+                    EntityInfo::at(pos.map(|e| e as f32).with_z(CONFIG.sea_level + 1.0))
+                        .with_body(comp::Body::Ship(*ship)),
+                );
+            }
+        }
+    }
+}
+
+/// The area between two corners, both included.
+fn rect(a: Vec2<i32>, b: Vec2<i32>) -> Aabr<i32> {
+    let aabr = Aabr { min: a, max: b }.made_valid();
+    Aabr {
+        min: aabr.min,
+        max: aabr.max + 1,
+    }
+}
+
+impl Structure for Port {
+    fn render(&self, _site: &Site, land: &Land, painter: &Painter) {
+        let stone = Fill::Brick(BlockKind::Rock, Rgb::new(110, 104, 96), 24);
+        let wood = Fill::Block(Block::new(BlockKind::Wood, Rgb::new(71, 43, 19)));
+        let planks = Fill::Brick(BlockKind::Wood, Rgb::new(112, 76, 40), 12);
+        let roof = Fill::Brick(BlockKind::Wood, Rgb::new(120, 45, 30), 16);
+
+        let side = Vec2::new(-self.dir.y, self.dir.x);
+        let end = self.end();
+        // Deep enough for the foundations to reach the seabed
+        let seabed = |wpos| (land.get_alt_approx(wpos) as i32 - 2).min(self.deck_alt - 4);
+
+        // Quay along the shore
+        let quay = rect(
+            self.shore - self.dir * 2 - side * 8,
+            self.shore + self.dir * 2 + side * 8,
+        );
+        painter
+            .aabb(aabr_with_z(quay, seabed(self.shore)..self.deck_alt))
+            .fill(stone.clone());
+        painter
+            .aabb(aabr_with_z(quay, self.deck_alt..self.deck_alt + 6))
+            .clear();
+        for offset in [-6, 6] {
+            painter.sprite(
+                (self.shore + side * offset).with_z(self.deck_alt),
+                SpriteKind::Crate,
+            );
+        }
+
+        // Pier out to sea, on posts
+        painter
+            .aabb(aabr_with_z(
+                rect(self.shore - side * 2, end + side * 2),
+                self.deck_alt - 1..self.deck_alt,
+            ))
+            .fill(planks.clone());
+        for dist in (0..=self.pier_len).step_by(POST_SPACING) {
+            for offset in [-2, 2] {
+                let post = self.shore + self.dir * dist + side * offset;
+                painter
+                    .aabb(Aabb {
+                        min: post.with_z(seabed(post)),
+                        max: (post + 1).with_z(self.deck_alt + 1),
+                    })
+                    .fill(wood.clone());
+            }
+        }
+        for offset in [-2, 2] {
+            painter.sprite(
+                (end + side * offset).with_z(self.deck_alt + 1),
+                SpriteKind::Lantern,
+            );
+        }
+
+        // Harbour office, with its door facing the sea
+        let office = self.office();
+        let floor = self.office_alt + 1;
+        let office_aabr = Aabr {
+            min: office - OFFICE_RADIUS,
+            max: office + OFFICE_RADIUS + 1,
+        };
+        painter
+            .aabb(aabr_with_z(office_aabr, seabed(office)..floor))
+            .fill(stone);
+        painter
+            .aabb(aabr_with_z(office_aabr, floor..floor + 5))
+            .fill(planks);
+        painter
+            .aabb(aabr_with_z(
+                Aabr {
+                    min: office_aabr.min + 1,
+                    max: office_aabr.max - 1,
+                },
+                floor..floor + 5,
+            ))
+            .clear();
+        let door = office + self.dir * OFFICE_RADIUS;
+        painter
+            .aabb(aabr_with_z(
+                rect(door - side, door + side),
+                floor..floor + 3,
+            ))
+            .clear();
+        for corner in SQUARE_4 {
+            let post = office_aabr.min + corner * (office_aabr.max - office_aabr.min - 1);
+            painter
+                .aabb(aabr_with_z(rect(post, post), floor..floor + 5))
+                .fill(wood.clone());
+        }
+        painter.sprite(
+            (office - self.dir * (OFFICE_RADIUS - 2)).with_z(floor),
+            SpriteKind::Bench,
+        );
+        painter
+            .gable(
+                aabr_with_z(
+                    Aabr {
+                        min: office_aabr.min - 1,
+                        max: office_aabr.max + 1,
+                    },
+                    floor + 5..floor + 9,
+                ),
+                5,
+                if self.dir.x == 0 { Dir::X } else { Dir::Y },
+            )
+            .fill(roof);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::ChaChaRng;
+
+    #[test]
+    fn ships_moor_inside_the_port() {
+        // Everywhere is below sea level without a world
+        for seed in 0..32 {
+            let port = Port::generate(
+                &Land::empty(),
+                &mut ChaChaRng::seed_from_u64(seed),
+                Vec2::new(100, 100),
+            );
+            for (pos, ship) in &port.berths {
+                assert!(port.bounds().contains_point(*pos));
+                // Clear of the pier
+                assert!(!rect(port.end() - 8, port.end() + 8)
+                    .intersection(rect(*pos - 2, *pos + 2))
+                    .is_valid());
+                assert!(ship::ALL_SHIPS.contains(ship));
+            }
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use super::*;
+use crate::Land;
+use common::{
+    assets::{self, AssetExt},
+    terrain::{Block, BlockKind, SpriteKind},
+};
+use hashbrown::HashMap;
+use rand::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use vek::*;
+
+/// The plot templates that towns may build, along with their relative
+/// weights.
+const TEMPLATES_MANIFEST: &str = "world.plot.manifest";
+
+#[derive(Deserialize)]
+pub struct PlotTemplates(Vec<(String, f32)>);
+
+impl assets::Asset for PlotTemplates {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+impl PlotTemplates {
+    /// Picks the template of a plot to build, if there are any.
+    pub fn choose(rng: &mut impl Rng) -> Option<PlotTemplate> {
+        let templates = Self::load_expect(TEMPLATES_MANIFEST).read();
+        let (specifier, _) = templates.0.choose_weighted(rng, |(_, w)| *w).ok()?;
+        Some(PlotTemplate::load_expect_cloned(specifier))
+    }
+}
+
+/// A building described by an asset rather than by code, as a list of layers
+/// of primitives painted one after the other.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PlotTemplate {
+    /// Smallest and largest width and depth of the plot, in tiles, of at
+    /// least 2.
+    pub size: (u32, u32),
+    /// Integers picked from an inclusive range for each plot, which
+    /// coordinates can refer to by name. `height` gives the top of the plot.
+    // Ordered, so that the same seed always picks the same values
+    #[serde(default)]
+    pub params: BTreeMap<String, (i32, i32)>,
+    pub layers: Vec<Layer>,
+}
+
+impl assets::Asset for PlotTemplate {
+    type Loader = assets::RonLoader;
+
+    const EXTENSION: &'static str = "ron";
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Layer {
+    pub shape: Shape,
+    pub fill: TemplateFill,
+}
+
+/// A position along one axis, relative to the plot. Along z, the minimum of
+/// the plot is its floor and the maximum is the floor raised by the `height`
+/// parameter.
+#[derive(Clone, Debug, Deserialize)]
+pub enum Coord {
+    Min(i32),
+    Max(i32),
+    Center(i32),
+    /// The minimum of the plot moved by the sum of the named parameters, and
+    /// then by the offset.
+    Param(Vec<String>, i32),
+}
+
+/// A box of the plot. Like the rest of site2, the maximum is exclusive.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Bounds {
+    pub min: (Coord, Coord, Coord),
+    pub max: (Coord, Coord, Coord),
+}
+
+/// A tree of [`Primitive`]s.
+#[derive(Clone, Debug, Deserialize)]
+pub enum Shape {
+    Aabb(Bounds),
+    Pyramid(Bounds),
+    Gable {
+        bounds: Bounds,
+        inset: i32,
+        dir: Dir,
+    },
+    Ramp {
+        bounds: Bounds,
+        inset: i32,
+        dir: Dir,
+    },
+    Cylinder(Bounds),
+    Cone(Bounds),
+    Sphere(Bounds),
+    Superquadric {
+        bounds: Bounds,
+        degree: f32,
+    },
+    Union(Vec<Shape>),
+    Intersect(Box<Shape>, Box<Shape>),
+    Without(Box<Shape>, Box<Shape>),
+    Translate(Box<Shape>, (i32, i32, i32)),
+    Repeat(Box<Shape>, (i32, i32, i32), i32),
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum TemplateFill {
+    /// Empties the shape.
+    Clear,
+    Block(BlockKind, (u8, u8, u8)),
+    Brick(BlockKind, (u8, u8, u8), u8),
+    Sprite(SpriteKind),
+    RotatedSprite(SpriteKind, u8),
+}
+
+impl From<&TemplateFill> for Fill {
+    fn from(fill: &TemplateFill) -> Self {
+        match fill {
+            TemplateFill::Clear => Fill::Block(Block::empty()),
+            TemplateFill::Block(kind, color) => Fill::Block(Block::new(*kind, Rgb::from(*color))),
+            TemplateFill::Brick(kind, color, range) => {
+                Fill::Brick(*kind, Rgb::from(*color), *range)
+            },
+            TemplateFill::Sprite(sprite) => Fill::Sprite(*sprite),
+            TemplateFill::RotatedSprite(sprite, ori) => Fill::RotatedSprite(*sprite, *ori),
+        }
+    }
+}
+
+/// A plot built from a [`PlotTemplate`].
+pub struct TemplatePlot {
+    template: PlotTemplate,
+    /// Axis aligned bounding region for the plot
+    bounds: Aabr<i32>,
+    /// Approximate altitude of the door tile
+    pub(crate) alt: i32,
+    /// Values picked for the parameters of the template
+    params: HashMap<String, i32>,
+}
+
+impl TemplatePlot {
+    pub fn generate(
+        template: PlotTemplate,
+        land: &Land,
+        rng: &mut impl Rng,
+        site: &Site,
+        door_tile: Vec2<i32>,
+        door_dir: Vec2<i32>,
+        tile_aabr: Aabr<i32>,
+    ) -> Self {
+        let bounds = Aabr {
+            min: site.tile_wpos(tile_aabr.min),
+            max: site.tile_wpos(tile_aabr.max),
+        };
+        let params = template
+            .params
+            .iter()
+            .map(|(name, (min, max))| (name.clone(), rng.gen_range(*min..=(*max).max(*min))))
+            .collect();
+
+        Self {
+            template,
+            bounds,
+            alt: land.get_alt_approx(site.tile_center_wpos(door_tile + door_dir)) as i32,
+            params,
+        }
+    }
+
+    fn param(&self, name: &str) -> i32 { self.params.get(name).copied().unwrap_or(0) }
+
+    fn coord(&self, coord: &Coord, min: i32, max: i32) -> i32 {
+        match coord {
+            Coord::Min(offset) => min + offset,
+            Coord::Max(offset) => max + offset,
+            Coord::Center(offset) => (min + max) / 2 + offset,
+            Coord::Param(names, offset) => {
+                min + names.iter().map(|name| self.param(name)).sum::<i32>() + offset
+            },
+        }
+    }
+
+    fn bounds(&self, bounds: &Bounds) -> Aabb<i32> {
+        let floor = self.alt + 1;
+        let roof = floor + self.param("height");
+        let pos = |(x, y, z): &(Coord, Coord, Coord)| {
+            Vec3::new(
+                self.coord(x, self.bounds.min.x, self.bounds.max.x),
+                self.coord(y, self.bounds.min.y, self.bounds.max.y),
+                self.coord(z, floor, roof),
+            )
+        };
+        Aabb {
+            min: pos(&bounds.min),
+            max: pos(&bounds.max),
+        }
+    }
+
+    fn shape<'a>(&self, painter: &'a Painter, shape: &Shape) -> PrimitiveRef<'a> {
+        match shape {
+            Shape::Aabb(bounds) => painter.aabb(self.bounds(bounds)),
+            Shape::Pyramid(bounds) => painter.pyramid(self.bounds(bounds)),
+            Shape::Gable { bounds, inset, dir } => painter.gable(self.bounds(bounds), *inset, *dir),
+            Shape::Ramp { bounds, inset, dir } => painter.ramp(self.bounds(bounds), *inset, *dir),
+            Shape::Cylinder(bounds) => painter.cylinder(self.bounds(bounds)),
+            Shape::Cone(bounds) => painter.cone(self.bounds(bounds)),
+            Shape::Sphere(bounds) => painter.sphere(self.bounds(bounds)),
+            Shape::Superquadric { bounds, degree } => {
+                painter.superquadric(self.bounds(bounds), *degree)
+            },
+            Shape::Union(shapes) => shapes
+                .iter()
+                .map(|shape| self.shape(painter, shape))
+                .reduce(|a, b| a.union(b))
+                .unwrap_or_else(|| painter.empty()),
+            Shape::Intersect(a, b) => self.shape(painter, a).intersect(self.shape(painter, b)),
+            Shape::Without(a, b) => self.shape(painter, a).without(self.shape(painter, b)),
+            Shape::Translate(shape, offset) => {
+                self.shape(painter, shape).translate(Vec3::from(*offset))
+            },
+            Shape::Repeat(shape, offset, count) => self
+                .shape(painter, shape)
+                .repeat(Vec3::from(*offset), *count),
+        }
+    }
+}
+
+impl Structure for TemplatePlot {
+    fn render(&self, _site: &Site, _land: &Land, painter: &Painter) {
+        for layer in &self.template.layers {
+            self.shape(painter, &layer.shape)
+                .fill(Fill::from(&layer.fill));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn templates_load() {
+        let templates = PlotTemplates::load_expect(TEMPLATES_MANIFEST).read();
+        assert!(!templates.0.is_empty());
+        for (specifier, _) in &templates.0 {
+            let template = PlotTemplate::load_expect_cloned(specifier);
+            assert!(template.size.0 > 0 && template.size.0 <= template.size.1);
+        }
+    }
+}
@@ -1,10 +1,11 @@
 pub mod gradient;
 
 use rand::Rng;
+use serde::Deserialize;
 use vek::*;
 
 /// A 2d direction.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
 pub enum Dir {
     X,
     Y,